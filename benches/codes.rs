@@ -0,0 +1,186 @@
+//! Read and write throughput of the instantaneous codes used by BVGraph, for
+//! both endiannesses.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use dsi_bitstream::prelude::*;
+use rand::prelude::*;
+use webgraph::graph::bvgraph::{read_gamma_table, read_unary_burst};
+
+/// The number of codes written or read in each iteration.
+const NUM_VALUES: usize = 1 << 16;
+
+/// Return values with a roughly geometric distribution, as gaps in successor
+/// lists are.
+fn values() -> Vec<u64> {
+    let mut rng = SmallRng::seed_from_u64(0);
+    (0..NUM_VALUES)
+        .map(|_| (-(1.0 - rng.gen::<f64>()).ln() * 20.0) as u64)
+        .collect()
+}
+
+/// Return huge values, whose unary codes, or the unary parts of their γ
+/// codes, span several words.
+fn huge_values() -> Vec<u64> {
+    let mut rng = SmallRng::seed_from_u64(0);
+    (0..NUM_VALUES / 64)
+        .map(|_| rng.gen_range(1 << 8..1 << 12))
+        .collect()
+}
+
+macro_rules! bench_code {
+    ($c:expr, $values:expr, $endianness:ty, $code:literal, $write:ident, $read:ident) => {{
+        let values: &[u64] = $values;
+        let mut group = $c.benchmark_group(format!(
+            "{}/{}",
+            $code,
+            stringify!($endianness).to_lowercase()
+        ));
+        group.throughput(Throughput::Elements(values.len() as u64));
+
+        let mut buffer = Vec::<u64>::new();
+        group.bench_function("write", |b| {
+            b.iter(|| {
+                buffer.clear();
+                let mut writer = <BufferedBitStreamWrite<$endianness, _>>::new(
+                    MemWordWriteVec::new(&mut buffer),
+                );
+                for &value in values {
+                    writer.$write(value).unwrap();
+                }
+                writer.flush().unwrap();
+            })
+        });
+
+        // the reader needs a slice of u32, as when reading a memory-mapped
+        // graph
+        let data: &[u32] = unsafe {
+            core::slice::from_raw_parts(
+                buffer.as_ptr() as *const u32,
+                buffer.len() * (core::mem::size_of::<u64>() / core::mem::size_of::<u32>()),
+            )
+        };
+        group.bench_function("read", |b| {
+            b.iter(|| {
+                let mut reader = <BufferedBitStreamRead<$endianness, u64, _>>::new(
+                    MemWordReadInfinite::new(data),
+                );
+                for _ in 0..values.len() {
+                    black_box(reader.$read().unwrap());
+                }
+            })
+        });
+        group.finish();
+    }};
+}
+
+/// Read a γ code using a table of 12 bits.
+fn read_gamma_table_12<E: Endianness, CR: ReadCodes<E>>(reader: &mut CR) -> u64 {
+    read_gamma_table::<E, CR, 12, 4096>(reader)
+}
+
+/// Compare the reader of dsi-bitstream with the function consuming long runs
+/// of zeros a word at a time, on huge values.
+macro_rules! bench_huge {
+    ($c:expr, $values:expr, $endianness:ty, $code:literal, $write:ident, $read:ident, $read_burst:ident) => {{
+        let values: &[u64] = $values;
+        let mut group = $c.benchmark_group(format!(
+            "huge_{}/{}",
+            $code,
+            stringify!($endianness).to_lowercase()
+        ));
+        group.throughput(Throughput::Elements(values.len() as u64));
+
+        let mut buffer = Vec::<u64>::new();
+        {
+            let mut writer =
+                <BufferedBitStreamWrite<$endianness, _>>::new(MemWordWriteVec::new(&mut buffer));
+            for &value in values {
+                writer.$write(value).unwrap();
+            }
+            writer.flush().unwrap();
+        }
+        let data: &[u32] = unsafe {
+            core::slice::from_raw_parts(
+                buffer.as_ptr() as *const u32,
+                buffer.len() * (core::mem::size_of::<u64>() / core::mem::size_of::<u32>()),
+            )
+        };
+        group.bench_function("read", |b| {
+            b.iter(|| {
+                let mut reader = <BufferedBitStreamRead<$endianness, u64, _>>::new(
+                    MemWordReadInfinite::new(data),
+                );
+                for _ in 0..values.len() {
+                    black_box(reader.$read().unwrap());
+                }
+            })
+        });
+        group.bench_function("read_burst", |b| {
+            b.iter(|| {
+                let mut reader = <BufferedBitStreamRead<$endianness, u64, _>>::new(
+                    MemWordReadInfinite::new(data),
+                );
+                for _ in 0..values.len() {
+                    black_box($read_burst::<$endianness, _>(&mut reader));
+                }
+            })
+        });
+        group.finish();
+    }};
+}
+
+fn bench_codes(c: &mut Criterion) {
+    let values = values();
+    bench_code!(c, &values, BE, "gamma", write_gamma, read_gamma);
+    bench_code!(c, &values, LE, "gamma", write_gamma, read_gamma);
+    bench_code!(c, &values, BE, "delta", write_delta, read_delta);
+    bench_code!(c, &values, LE, "delta", write_delta, read_delta);
+    bench_code!(c, &values, BE, "zeta3", write_zeta3, read_zeta3);
+    bench_code!(c, &values, LE, "zeta3", write_zeta3, read_zeta3);
+
+    let huge_values = huge_values();
+    bench_huge!(
+        c,
+        &huge_values,
+        BE,
+        "unary",
+        write_unary,
+        read_unary,
+        read_unary_burst
+    );
+    bench_huge!(
+        c,
+        &huge_values,
+        LE,
+        "unary",
+        write_unary,
+        read_unary,
+        read_unary_burst
+    );
+    // the unary parts of the γ codes are longer than the table
+    let huge_values = huge_values
+        .iter()
+        .map(|&value| value << 40)
+        .collect::<Vec<_>>();
+    bench_huge!(
+        c,
+        &huge_values,
+        BE,
+        "gamma",
+        write_gamma,
+        read_gamma,
+        read_gamma_table_12
+    );
+    bench_huge!(
+        c,
+        &huge_values,
+        LE,
+        "gamma",
+        write_gamma,
+        read_gamma,
+        read_gamma_table_12
+    );
+}
+
+criterion_group!(benches, bench_codes);
+criterion_main!(benches);
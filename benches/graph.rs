@@ -0,0 +1,105 @@
+//! Sequential and random access speed on the bundled `cnr-2000` graph.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use rand::prelude::*;
+use webgraph::prelude::*;
+
+const BASENAME: &str = "tests/data/cnr-2000";
+
+/// The number of successor lists read in each iteration of the random-access
+/// benchmark.
+const NUM_QUERIES: usize = 10_000;
+
+fn bench_sequential(c: &mut Criterion) {
+    let graph = webgraph::graph::bvgraph::load_seq(BASENAME).unwrap();
+    let degrees_graph = webgraph::graph::bvgraph::load_seq(BASENAME)
+        .unwrap()
+        .map_codes_reader_builder(DynamicCodesReaderSkipperBuilder::from);
+    let mut group = c.benchmark_group("sequential");
+    // the reported throughput is in arcs per second
+    group.throughput(Throughput::Elements(graph.num_arcs_hint().unwrap() as u64));
+    group.sample_size(10);
+    group.bench_function("scan", |b| {
+        b.iter(|| {
+            for (_, successors) in graph.iter_nodes() {
+                for succ in successors {
+                    black_box(succ);
+                }
+            }
+        })
+    });
+    group.bench_function("degrees", |b| {
+        b.iter(|| {
+            for (_, degree) in degrees_graph.iter_degrees() {
+                black_box(degree);
+            }
+        })
+    });
+    group.finish();
+}
+
+/// Compare sequential scans reading the bitstream in 32-bit and 64-bit
+/// words, which halve the number of refills of the bit buffer.
+fn bench_words(c: &mut Criterion) {
+    macro_rules! bench_scan {
+        ($group:expr, $name:literal, $word:ty) => {
+            let graph = BVGraphLoader::new(BASENAME)
+                .sequential()
+                .word::<$word>()
+                .load()
+                .unwrap();
+            $group.bench_function($name, |b| {
+                b.iter(|| {
+                    for (_, successors) in graph.iter_nodes() {
+                        for succ in successors {
+                            black_box(succ);
+                        }
+                    }
+                })
+            });
+        };
+    }
+
+    let mut group = c.benchmark_group("words");
+    // the reported throughput is in arcs per second
+    let num_arcs = webgraph::graph::bvgraph::load_seq(BASENAME)
+        .unwrap()
+        .num_arcs_hint()
+        .unwrap();
+    group.throughput(Throughput::Elements(num_arcs as u64));
+    group.sample_size(10);
+    bench_scan!(group, "scan_u32", u32);
+    bench_scan!(group, "scan_u64", u64);
+    group.finish();
+}
+
+fn bench_random_access(c: &mut Criterion) {
+    let graph = webgraph::graph::bvgraph::load(BASENAME).unwrap();
+    let mut rng = SmallRng::seed_from_u64(0);
+    let nodes = (0..NUM_QUERIES)
+        .map(|_| rng.gen_range(0..graph.num_nodes()))
+        .collect::<Vec<_>>();
+    let mut group = c.benchmark_group("random_access");
+    // the reported throughput is in successor lists per second
+    group.throughput(Throughput::Elements(NUM_QUERIES as u64));
+    group.bench_function("successors", |b| {
+        b.iter(|| {
+            for &node in &nodes {
+                for succ in graph.successors(node) {
+                    black_box(succ);
+                }
+            }
+        })
+    });
+    group.bench_function("outdegree", |b| {
+        b.iter(|| {
+            for &node in &nodes {
+                black_box(graph.outdegree(node));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_sequential, bench_words, bench_random_access);
+criterion_main!(benches);
@@ -0,0 +1,4 @@
+//! Shared and static libraries exporting the C interface of webgraph-rs,
+//! declared in `include/webgraph.h`, so that the main crate does not need
+//! to be built as a `cdylib`.
+pub use webgraph::ffi::*;
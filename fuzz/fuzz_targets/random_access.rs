@@ -0,0 +1,7 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: webgraph::fuzz::random_access::FuzzCase| {
+    webgraph::fuzz::random_access::harness(data)
+});
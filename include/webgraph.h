@@ -0,0 +1,37 @@
+/*
+ * C interface to webgraph-rs, available when the crate is compiled with the
+ * `ffi` feature. The crate in the `ffi` directory builds it as a shared and
+ * as a static library. See the documentation of the `ffi` module for details.
+ */
+#ifndef WEBGRAPH_H
+#define WEBGRAPH_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct WebGraph WebGraph;
+typedef struct WebGraphIter WebGraphIter;
+
+WebGraph *webgraph_load(const char *basename);
+void webgraph_free(WebGraph *graph);
+
+uint64_t webgraph_num_nodes(const WebGraph *graph);
+uint64_t webgraph_num_arcs(const WebGraph *graph);
+int64_t webgraph_outdegree(const WebGraph *graph, uint64_t node);
+int64_t webgraph_successors_into(const WebGraph *graph, uint64_t node,
+                                 uint64_t *buffer, size_t len);
+
+WebGraphIter *webgraph_iter_new(const WebGraph *graph);
+int64_t webgraph_iter_next(WebGraphIter *iter, uint64_t *node,
+                           uint64_t *buffer, size_t len);
+void webgraph_iter_free(WebGraphIter *iter);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,231 @@
+use crate::traits::RandomAccessGraph;
+use dsi_progress_logger::ProgressLogger;
+use rand::prelude::*;
+use rand_xoshiro::Xoshiro256PlusPlus;
+use rayon::prelude::*;
+
+const UNVISITED: usize = usize::MAX;
+
+/// The state of the visits of Brandes's algorithm, which is reset after each
+/// visit by touching only the visited nodes.
+struct Brandes {
+    /// The distance of each node from the source.
+    distances: Vec<usize>,
+    /// The number of shortest paths from the source to each node.
+    paths: Vec<f64>,
+    /// The dependency of the source on each node.
+    dependencies: Vec<f64>,
+    /// The visited nodes, in order of discovery.
+    order: Vec<usize>,
+}
+
+impl Brandes {
+    fn new(num_nodes: usize) -> Self {
+        Self {
+            distances: vec![UNVISITED; num_nodes],
+            paths: vec![0.0; num_nodes],
+            dependencies: vec![0.0; num_nodes],
+            order: Vec::new(),
+        }
+    }
+
+    /// Add the dependencies of `source` on each other node to `scores`.
+    ///
+    /// The predecessors of a node on shortest paths are not stored: the
+    /// backward pass scans again the successors of each node, and uses those
+    /// one level farther from the source.
+    fn visit<G: RandomAccessGraph>(&mut self, graph: &G, source: usize, scores: &mut [f64]) {
+        self.order.clear();
+        self.order.push(source);
+        self.distances[source] = 0;
+        self.paths[source] = 1.0;
+        let mut head = 0;
+        while head < self.order.len() {
+            let node = self.order[head];
+            head += 1;
+            let distance = self.distances[node] + 1;
+            for succ in graph.successors(node) {
+                if self.distances[succ] == UNVISITED {
+                    self.distances[succ] = distance;
+                    self.order.push(succ);
+                }
+                if self.distances[succ] == distance {
+                    self.paths[succ] += self.paths[node];
+                }
+            }
+        }
+
+        for &node in self.order.iter().rev() {
+            let distance = self.distances[node] + 1;
+            let mut dependency = 0.0;
+            for succ in graph.successors(node) {
+                if self.distances[succ] == distance {
+                    dependency +=
+                        self.paths[node] / self.paths[succ] * (1.0 + self.dependencies[succ]);
+                }
+            }
+            self.dependencies[node] = dependency;
+            if node != source {
+                scores[node] += dependency;
+            }
+        }
+
+        for &node in &self.order {
+            self.distances[node] = UNVISITED;
+            self.paths[node] = 0.0;
+            self.dependencies[node] = 0.0;
+        }
+    }
+}
+
+/// Return the sum of the dependencies of the given sources on each node,
+/// visiting from the sources in parallel.
+fn accumulate<G: RandomAccessGraph + Sync>(graph: &G, sources: &[usize]) -> Vec<f64> {
+    let num_nodes = graph.num_nodes();
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "visit";
+    pl.start("Computing betweenness...");
+    // each task keeps its state and its partial scores, so the memory used
+    // is linear in the number of nodes times the number of tasks
+    let scores = sources
+        .par_iter()
+        .fold(
+            || (Brandes::new(num_nodes), vec![0.0; num_nodes]),
+            |(mut brandes, mut scores), &source| {
+                brandes.visit(graph, source, &mut scores);
+                (brandes, scores)
+            },
+        )
+        .map(|(_, scores)| scores)
+        .reduce(
+            || vec![0.0; num_nodes],
+            |mut a, b| {
+                a.iter_mut().zip(b).for_each(|(a, b)| *a += b);
+                a
+            },
+        );
+    pl.done_with_count(sources.len());
+    scores
+}
+
+/// Return the betweenness centrality of each node of a graph, that is, the
+/// sum over all pairs of nodes `s` ≠ `t` different from the node of the
+/// fraction of shortest paths from `s` to `t` that pass through the node,
+/// using the algorithm of Brandes, “[A faster algorithm for betweenness
+/// centrality](https://doi.org/10.1080/0022250X.2001.9990249)”.
+///
+/// A breadth-first visit is needed from each node, so this is feasible only
+/// on small graphs: see [`approximate_betweenness`]. Visits are run in
+/// parallel. On a symmetric graph, each unordered pair of nodes is counted
+/// twice.
+pub fn betweenness<G: RandomAccessGraph + Sync>(graph: &G) -> Vec<f64> {
+    let sources = (0..graph.num_nodes()).collect::<Vec<_>>();
+    accumulate(graph, &sources)
+}
+
+/// Return the number of sources that [`approximate_betweenness`] must sample
+/// so that, with probability at least 1 − `delta`, the error on the
+/// betweenness of every node is at most `epsilon` · *n* (*n* − 2), where *n*
+/// is the number of nodes; the result is at most `num_nodes`.
+///
+/// Since the dependency of a source on a node is at most *n* − 2, by
+/// Hoeffding's inequality and the union bound on the nodes it is enough to
+/// sample ln(2*n* / `delta`) / (2 `epsilon`²) sources.
+///
+/// # Panics
+/// If `epsilon` is not positive or `delta` is not in (0..1).
+pub fn betweenness_samples(num_nodes: usize, epsilon: f64, delta: f64) -> usize {
+    assert!(epsilon > 0.0, "Invalid epsilon {}", epsilon);
+    assert!(delta > 0.0 && delta < 1.0, "Invalid delta {}", delta);
+    let samples = ((2.0 * num_nodes as f64 / delta).ln() / (2.0 * epsilon * epsilon)).ceil();
+    (samples as usize).min(num_nodes)
+}
+
+/// Approximate the betweenness centrality of the nodes of a graph with the
+/// visits of Brandes's algorithm from `num_samples` sources chosen uniformly
+/// at random without replacement, as in Brandes and Pich, “[Centrality
+/// Estimation in Large Networks](https://doi.org/10.1142/S0218127407018403)”.
+///
+/// The dependencies of the sampled sources are scaled by the number of nodes
+/// divided by `num_samples`, so that the estimates are unbiased; with
+/// `num_samples` equal to the number of nodes the result is exact. Use
+/// [`betweenness_samples`] to choose `num_samples` given a bound on the
+/// error and a confidence. The same `seed` always yields the same result.
+///
+/// # Panics
+/// If `num_samples` is zero or larger than the number of nodes.
+pub fn approximate_betweenness<G: RandomAccessGraph + Sync>(
+    graph: &G,
+    num_samples: usize,
+    seed: u64,
+) -> Vec<f64> {
+    let num_nodes = graph.num_nodes();
+    assert!(
+        num_samples > 0 && num_samples <= num_nodes,
+        "Cannot sample {} sources out of {} nodes",
+        num_samples,
+        num_nodes
+    );
+    let mut rng = Xoshiro256PlusPlus::seed_from_u64(seed);
+    let sources = rand::seq::index::sample(&mut rng, num_nodes, num_samples).into_vec();
+    let mut scores = accumulate(graph, &sources);
+    let scale = num_nodes as f64 / num_samples as f64;
+    scores.iter_mut().for_each(|score| *score *= scale);
+    scores
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_betweenness() {
+    use crate::graph::vec_graph::VecGraph;
+    let num_nodes = 40;
+    let mut rng = SmallRng::seed_from_u64(0);
+    let arcs = (0..150)
+        .map(|_| (rng.gen_range(0..num_nodes), rng.gen_range(0..num_nodes)))
+        .collect::<Vec<_>>();
+    let mut graph = VecGraph::from_arc_list(&arcs);
+    graph.add_node(num_nodes - 1);
+
+    // the distances and the number of shortest paths between all pairs
+    let mut distances = vec![vec![UNVISITED; num_nodes]; num_nodes];
+    let mut paths = vec![vec![0.0; num_nodes]; num_nodes];
+    for (s, (distances, paths)) in distances.iter_mut().zip(&mut paths).enumerate() {
+        distances[s] = 0;
+        paths[s] = 1.0;
+        let mut queue = std::collections::VecDeque::from([s]);
+        while let Some(node) = queue.pop_front() {
+            for succ in graph.successors(node) {
+                if distances[succ] == UNVISITED {
+                    distances[succ] = distances[node] + 1;
+                    queue.push_back(succ);
+                }
+                if distances[succ] == distances[node] + 1 {
+                    paths[succ] += paths[node];
+                }
+            }
+        }
+    }
+    let mut expected = vec![0.0; num_nodes];
+    for (v, expected) in expected.iter_mut().enumerate() {
+        for s in (0..num_nodes).filter(|&s| s != v && distances[s][v] != UNVISITED) {
+            for t in (0..num_nodes).filter(|&t| t != v && t != s) {
+                if distances[v][t] != UNVISITED
+                    && distances[s][v] + distances[v][t] == distances[s][t]
+                {
+                    *expected += paths[s][v] * paths[v][t] / paths[s][t];
+                }
+            }
+        }
+    }
+    assert!(expected.iter().any(|&score| score > 0.0));
+
+    let exact = betweenness(&graph);
+    let sampled = approximate_betweenness(&graph, num_nodes, 1);
+    for ((&expected, &exact), &sampled) in expected.iter().zip(&exact).zip(&sampled) {
+        assert!((expected - exact).abs() < 1E-9);
+        assert!((expected - sampled).abs() < 1E-9);
+    }
+
+    assert_eq!(betweenness_samples(1_000_000, 0.01, 0.1), 84_057);
+    assert_eq!(betweenness_samples(100, 0.01, 0.1), 100);
+}
@@ -4,11 +4,17 @@ use dsi_progress_logger::ProgressLogger;
 use std::collections::VecDeque;
 
 /// Visit the graph in BFS order and return a vector with the order in which the
-/// nodes were visited
+/// nodes were visited.
+///
+/// Nodes are used as starting points of new visits in increasing order, so
+/// all the nodes appear in the result. The result is the inverse of the
+/// permutation mapping each node to its rank, which is the one to use to
+/// renumber the graph.
 pub fn bfs_order<G: RandomAccessGraph>(graph: &G) -> Vec<usize> {
     let num_nodes = graph.num_nodes();
     let mut visited = bitvec![u64, Lsb0; 0; num_nodes];
     let mut queue = VecDeque::new();
+    let mut order = Vec::with_capacity(num_nodes);
 
     let mut pl = ProgressLogger::default().display_memory();
     pl.item_name = "node";
@@ -17,15 +23,15 @@ pub fn bfs_order<G: RandomAccessGraph>(graph: &G) -> Vec<usize> {
     pl.start("Visiting graph in BFS order...");
 
     for start in 0..num_nodes {
-        pl.update();
         if visited[start] {
             continue;
         }
         queue.push_back(start as _);
         visited.set(start, true);
 
-        while !queue.is_empty() {
-            let current_node = queue.pop_front().unwrap();
+        while let Some(current_node) = queue.pop_front() {
+            order.push(current_node);
+            pl.light_update();
             for succ in graph.successors(current_node) {
                 if !visited[succ] {
                     queue.push_back(succ);
@@ -36,5 +42,13 @@ pub fn bfs_order<G: RandomAccessGraph>(graph: &G) -> Vec<usize> {
     }
 
     pl.done();
-    todo!("TODO: return the order in which the nodes were visited");
+    order
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_bfs_order() {
+    use crate::graph::vec_graph::VecGraph;
+    let g = VecGraph::from_arc_list(&[(0, 2), (0, 4), (2, 1), (4, 1), (3, 5), (5, 3)]);
+    assert_eq!(bfs_order(&g), vec![0, 2, 4, 1, 3, 5]);
 }
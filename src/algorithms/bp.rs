@@ -0,0 +1,235 @@
+use crate::algorithms::transpose;
+use crate::traits::SequentialGraph;
+use anyhow::Result;
+use dsi_progress_logger::ProgressLogger;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// The minimum number of nodes of a half for the two halves to be bisected
+/// in parallel: below this size, the cost of spawning a task is comparable to
+/// that of the bisection itself.
+const PAR_BISECTION_MIN_SIZE: usize = 1 << 12;
+
+/// Compute the recursive graph bisection ordering of Dhulipala et al., and
+/// return the permutation mapping each node to its rank.
+///
+/// Each node is seen both as a data vertex, to be ordered, and as a query,
+/// whose successors should receive close ranks. The nodes are split in two
+/// halves, and nodes are swapped between the two halves for at most
+/// `max_iters` iterations to reduce the approximate log-gap cost of the
+/// successor lists; then, the two halves are bisected recursively, in
+/// parallel, until they contain at most `min_size` nodes or `max_depth` is
+/// reached.
+///
+/// The lists of queries containing each node are obtained with an
+/// out-of-core [transposition](transpose) using batches of `batch_size`
+/// pairs, and then kept in memory, so memory usage is proportional to the
+/// number of arcs.
+///
+/// # References
+/// [Compressing Graphs and Indexes with Recursive Graph Bisection](https://arxiv.org/abs/1602.08820)
+pub fn bp<G: SequentialGraph>(
+    graph: &G,
+    max_iters: usize,
+    min_size: usize,
+    max_depth: usize,
+    batch_size: usize,
+) -> Result<Vec<usize>> {
+    let num_nodes = graph.num_nodes();
+
+    // build the lists of queries containing each data vertex, that is, the
+    // predecessors of each node
+    let transposed = transpose(graph, batch_size)?;
+    let mut offsets = Vec::with_capacity(num_nodes + 1);
+    let mut queries = Vec::with_capacity(graph.num_arcs_hint().unwrap_or(0));
+    offsets.push(0);
+    for (_, pred) in transposed.iter_nodes() {
+        queries.extend(pred);
+        offsets.push(queries.len());
+    }
+
+    let mut pl = ProgressLogger::default();
+    pl.item_name = "node";
+    pl.expected_updates = Some(num_nodes);
+    pl.start("Bisecting...");
+    let bisection = Bisection {
+        offsets: &offsets,
+        queries: &queries,
+        max_iters,
+        min_size: min_size.max(1),
+        max_depth,
+        pl: Mutex::new(pl),
+    };
+    let mut order = (0..num_nodes).collect::<Vec<_>>();
+    bisection.bisect(&mut order, 0);
+    bisection.pl.into_inner().unwrap().done();
+
+    // invert the order to get the rank of each node
+    let mut perm = vec![0; num_nodes];
+    for (rank, &node) in order.iter().enumerate() {
+        perm[node] = rank;
+    }
+    Ok(perm)
+}
+
+/// The immutable state of a bisection.
+struct Bisection<'a> {
+    offsets: &'a [usize],
+    queries: &'a [usize],
+    max_iters: usize,
+    min_size: usize,
+    max_depth: usize,
+    pl: Mutex<ProgressLogger<'static>>,
+}
+
+/// The approximate log-gap cost of a query with `d1` data vertices in a
+/// half of `n1` vertices and `d2` in a half of `n2` vertices.
+#[inline(always)]
+fn cost(d1: f64, d2: f64, n1: f64, n2: f64) -> f64 {
+    d1 * (n1 / (d1 + 1.0)).log2() + d2 * (n2 / (d2 + 1.0)).log2()
+}
+
+impl<'a> Bisection<'a> {
+    /// Return the queries containing the data vertex `node`.
+    #[inline(always)]
+    fn queries(&self, node: usize) -> &[usize] {
+        &self.queries[self.offsets[node]..self.offsets[node + 1]]
+    }
+
+    /// Reorder `data` by recursive bisection.
+    fn bisect(&self, data: &mut [usize], depth: usize) {
+        if data.len() <= self.min_size || depth >= self.max_depth {
+            self.pl.lock().unwrap().update_with_count(data.len());
+            return;
+        }
+        let mid = data.len() / 2;
+        let (n1, n2) = (mid as f64, (data.len() - mid) as f64);
+
+        for _ in 0..self.max_iters {
+            // count the data vertices of each query in each half
+            let mut degrees = HashMap::<usize, [u32; 2]>::new();
+            for (i, &node) in data.iter().enumerate() {
+                for &query in self.queries(node) {
+                    degrees.entry(query).or_default()[(i >= mid) as usize] += 1;
+                }
+            }
+            // compute the gain of moving each vertex to the other half
+            let mut gains = data
+                .par_iter()
+                .enumerate()
+                .map(|(i, &node)| {
+                    let gain = self
+                        .queries(node)
+                        .iter()
+                        .map(|query| {
+                            let [d1, d2] = degrees[query];
+                            let (d1, d2) = (d1 as f64, d2 as f64);
+                            if i < mid {
+                                cost(d1, d2, n1, n2) - cost(d1 - 1.0, d2 + 1.0, n1, n2)
+                            } else {
+                                cost(d1, d2, n1, n2) - cost(d1 + 1.0, d2 - 1.0, n1, n2)
+                            }
+                        })
+                        .sum::<f64>();
+                    (gain, node)
+                })
+                .collect::<Vec<_>>();
+            let (left, right) = gains.split_at_mut(mid);
+            // sort by decreasing gain, breaking ties by node for determinism
+            let by_gain =
+                |a: &(f64, usize), b: &(f64, usize)| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1));
+            left.par_sort_unstable_by(by_gain);
+            right.par_sort_unstable_by(by_gain);
+            // swap pairs of vertices while the total gain is positive
+            let mut swapped = 0;
+            for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+                if l.0 + r.0 <= 0.0 {
+                    break;
+                }
+                core::mem::swap(&mut l.1, &mut r.1);
+                swapped += 1;
+            }
+            for (node, &(_, new_node)) in data.iter_mut().zip(gains.iter()) {
+                *node = new_node;
+            }
+            if swapped == 0 {
+                break;
+            }
+        }
+
+        let (left, right) = data.split_at_mut(mid);
+        if left.len() > PAR_BISECTION_MIN_SIZE {
+            rayon::join(
+                || self.bisect(left, depth + 1),
+                || self.bisect(right, depth + 1),
+            );
+        } else {
+            self.bisect(left, depth + 1);
+            self.bisect(right, depth + 1);
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_bp() -> Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    // two interleaved cliques
+    let mut arcs = vec![];
+    for x in 0..16 {
+        for y in 0..16 {
+            if x != y && x % 2 == y % 2 {
+                arcs.push((x, y));
+            }
+        }
+    }
+    let g = VecGraph::from_arc_list(&arcs);
+    let perm = bp(&g, 20, 1, usize::MAX, 100)?;
+    let mut sorted = perm.clone();
+    sorted.sort();
+    assert_eq!(sorted, (0..16).collect::<Vec<_>>());
+    // without bisections the order does not change
+    assert_eq!(bp(&g, 20, 1, 0, 100)?, (0..16).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_bp_cost() -> Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    use crate::traits::RandomAccessGraph;
+    // four cliques of 8 nodes whose ids are scattered by a permutation
+    let num_nodes = 32;
+    let scatter = |x: usize| x * 13 % num_nodes;
+    let mut arcs = vec![];
+    for x in 0..num_nodes {
+        for y in 0..num_nodes {
+            if x != y && x / 8 == y / 8 {
+                arcs.push((scatter(x), scatter(y)));
+            }
+        }
+    }
+    let g = VecGraph::from_arc_list(&arcs);
+    // the sum of the logarithms of the gaps of the successor lists
+    let log_gap_cost = |perm: &[usize]| -> f64 {
+        (0..num_nodes)
+            .map(|x| {
+                let mut succ = g.successors(x).map(|y| perm[y]).collect::<Vec<_>>();
+                succ.sort();
+                let mut prev = perm[x];
+                succ.iter()
+                    .map(|&y| {
+                        let gap = y.abs_diff(prev);
+                        prev = y;
+                        ((gap + 1) as f64).log2()
+                    })
+                    .sum::<f64>()
+            })
+            .sum()
+    };
+    let perm = bp(&g, 20, 1, usize::MAX, 100)?;
+    let identity = (0..num_nodes).collect::<Vec<_>>();
+    assert!(log_gap_cost(&perm) < log_gap_cost(&identity));
+    Ok(())
+}
@@ -0,0 +1,259 @@
+use crate::traits::RandomAccessGraph;
+use anyhow::{Context, Result};
+use dsi_progress_logger::ProgressLogger;
+use epserde::prelude::*;
+use rand::prelude::*;
+use rand_xoshiro::Xoshiro256PlusPlus;
+use rayon::prelude::*;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The number of sources visited at once, one for each bit of a word.
+const BATCH_SIZE: usize = 64;
+
+/// The geometric centralities of the nodes of a graph, as computed by
+/// [`centralities`] or [`approximate_centralities`].
+///
+/// Following Boldi and Vigna, “[Axioms for
+/// Centrality](https://doi.org/10.1080/15427951.2013.865686)”, centralities
+/// are computed on incoming paths: the closeness centrality of `x` is the
+/// reciprocal of the sum of the distances `d(y, x)` over the nodes `y` that
+/// can reach `x`, or zero if there are none, and the harmonic centrality of
+/// `x` is the sum of `1 / d(y, x)` over all nodes `y` different from `x`. To
+/// compute centralities on outgoing paths, pass the transpose of the graph.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Centralities {
+    /// The closeness centrality of each node.
+    pub closeness: Vec<f64>,
+    /// The harmonic centrality of each node.
+    pub harmonic: Vec<f64>,
+}
+
+impl Centralities {
+    /// Serialize the centralities with ε-serde, as `Vec<f64>`s, in the files
+    /// `BASENAME.closeness` and `BASENAME.harmonic`.
+    pub fn store<P: AsRef<Path>>(&self, basename: P) -> Result<()> {
+        let basename = basename.as_ref().to_string_lossy();
+        for (extension, scores) in [("closeness", &self.closeness), ("harmonic", &self.harmonic)] {
+            let path = format!("{}.{}", basename, extension);
+            let mut file = std::io::BufWriter::new(
+                std::fs::File::create(&path).with_context(|| format!("Cannot create {}", path))?,
+            );
+            scores.serialize(&mut file)?;
+            std::io::Write::flush(&mut file)?;
+        }
+        Ok(())
+    }
+}
+
+/// The sums accumulated for each node by the visits.
+#[derive(Clone, Copy, Default)]
+struct Accumulator {
+    /// The sum of the distances from the sources reaching the node.
+    distances: usize,
+    /// The sum of the reciprocals of the distances from the sources.
+    harmonic: f64,
+}
+
+/// Visit the graph from each of the given sources, accumulating the
+/// distances from the sources in `acc`.
+///
+/// The visits are bit-parallel: each node has a word whose bit `i` is set if
+/// the node has been reached by the visit from the source of index `i` of the
+/// current batch, and a level of all the visits of a batch is computed with a
+/// single parallel scan of the frontier.
+fn accumulate<G: RandomAccessGraph + Sync>(
+    graph: &G,
+    sources: &[usize],
+    acc: &mut [Accumulator],
+    pl: &mut ProgressLogger,
+) {
+    let num_nodes = graph.num_nodes();
+    let mut visited = vec![0_u64; num_nodes];
+    let mut frontier = vec![0_u64; num_nodes];
+    let mut next = (0..num_nodes)
+        .map(|_| AtomicU64::new(0))
+        .collect::<Vec<_>>();
+
+    for batch in sources.chunks(BATCH_SIZE) {
+        visited.fill(0);
+        frontier.fill(0);
+        for (i, &source) in batch.iter().enumerate() {
+            visited[source] |= 1 << i;
+            frontier[source] |= 1 << i;
+        }
+
+        let mut distance = 0;
+        loop {
+            distance += 1;
+            frontier
+                .par_iter()
+                .enumerate()
+                .with_min_len(1024)
+                .for_each(|(node, &bits)| {
+                    if bits == 0 {
+                        return;
+                    }
+                    for succ in graph.successors(node) {
+                        let new = bits & !visited[succ];
+                        if new != 0 {
+                            next[succ].fetch_or(new, Ordering::Relaxed);
+                        }
+                    }
+                });
+            let reached: usize = visited
+                .par_iter_mut()
+                .zip(frontier.par_iter_mut())
+                .zip(next.par_iter_mut())
+                .zip(acc.par_iter_mut())
+                .with_min_len(1024)
+                .map(|(((visited, frontier), next), acc)| {
+                    let new = core::mem::take(next.get_mut()) & !*visited;
+                    *visited |= new;
+                    *frontier = new;
+                    let count = new.count_ones() as usize;
+                    acc.distances += count * distance;
+                    acc.harmonic += count as f64 / distance as f64;
+                    count
+                })
+                .sum();
+            if reached == 0 {
+                break;
+            }
+        }
+        pl.update_with_count(batch.len());
+    }
+}
+
+/// Compute the exact geometric centralities of the nodes of a graph with a
+/// visit from each node.
+///
+/// The visits are bit-parallel, in batches of 64 sources, and each level of a
+/// batch is computed in parallel; each batch scans all nodes once for each
+/// level, so this is suitable for graphs of moderate size.
+pub fn centralities<G: RandomAccessGraph + Sync>(graph: &G) -> Centralities {
+    let num_nodes = graph.num_nodes();
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "visit";
+    pl.expected_updates = Some(num_nodes);
+    pl.start("Computing centralities...");
+
+    let sources = (0..num_nodes).collect::<Vec<_>>();
+    let mut acc = vec![Accumulator::default(); num_nodes];
+    accumulate(graph, &sources, &mut acc, &mut pl);
+    pl.done();
+
+    Centralities {
+        closeness: acc
+            .iter()
+            .map(|acc| {
+                if acc.distances == 0 {
+                    0.0
+                } else {
+                    1.0 / acc.distances as f64
+                }
+            })
+            .collect(),
+        harmonic: acc.iter().map(|acc| acc.harmonic).collect(),
+    }
+}
+
+/// Approximate the geometric centralities of the nodes of a graph with visits
+/// from `num_samples` sources chosen uniformly at random without
+/// replacement.
+///
+/// The sums of the distances and of their reciprocals over the sampled
+/// sources are scaled by the number of nodes divided by `num_samples`, as in
+/// Eppstein and Wang, “[Fast Approximation of
+/// Centrality](https://doi.org/10.7155/jgaa.00081)”; with `num_samples`
+/// equal to the number of nodes the result is exact. The same `seed` always
+/// yields the same result.
+///
+/// # Panics
+/// If `num_samples` is zero or larger than the number of nodes.
+pub fn approximate_centralities<G: RandomAccessGraph + Sync>(
+    graph: &G,
+    num_samples: usize,
+    seed: u64,
+) -> Centralities {
+    let num_nodes = graph.num_nodes();
+    assert!(
+        num_samples > 0 && num_samples <= num_nodes,
+        "Cannot sample {} sources out of {} nodes",
+        num_samples,
+        num_nodes
+    );
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "visit";
+    pl.expected_updates = Some(num_samples);
+    pl.start("Approximating centralities...");
+
+    let mut rng = Xoshiro256PlusPlus::seed_from_u64(seed);
+    let mut sources = rand::seq::index::sample(&mut rng, num_nodes, num_samples).into_vec();
+    // visiting in order makes the access pattern of each batch more local
+    sources.sort_unstable();
+    let mut acc = vec![Accumulator::default(); num_nodes];
+    accumulate(graph, &sources, &mut acc, &mut pl);
+    pl.done();
+
+    let scale = num_nodes as f64 / num_samples as f64;
+    Centralities {
+        closeness: acc
+            .iter()
+            .map(|acc| {
+                if acc.distances == 0 {
+                    0.0
+                } else {
+                    1.0 / (acc.distances as f64 * scale)
+                }
+            })
+            .collect(),
+        harmonic: acc.iter().map(|acc| acc.harmonic * scale).collect(),
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_centralities() {
+    use crate::graph::vec_graph::VecGraph;
+    let num_nodes = 150;
+    let mut rng = SmallRng::seed_from_u64(0);
+    let arcs = (0..600)
+        .map(|_| (rng.gen_range(0..num_nodes), rng.gen_range(0..num_nodes)))
+        .collect::<Vec<_>>();
+    let mut graph = VecGraph::from_arc_list(&arcs);
+    graph.add_node(num_nodes - 1);
+
+    // compute the centralities with a plain BFS from each node
+    let mut distances = vec![0_usize; num_nodes];
+    let mut harmonic = vec![0.0; num_nodes];
+    for source in 0..num_nodes {
+        let mut dist = vec![usize::MAX; num_nodes];
+        let mut queue = std::collections::VecDeque::from([source]);
+        dist[source] = 0;
+        while let Some(node) = queue.pop_front() {
+            for succ in graph.successors(node) {
+                if dist[succ] == usize::MAX {
+                    dist[succ] = dist[node] + 1;
+                    distances[succ] += dist[succ];
+                    harmonic[succ] += 1.0 / dist[succ] as f64;
+                    queue.push_back(succ);
+                }
+            }
+        }
+    }
+
+    let exact = centralities(&graph);
+    let full_sample = approximate_centralities(&graph, num_nodes, 0);
+    for node in 0..num_nodes {
+        let closeness = if distances[node] == 0 {
+            0.0
+        } else {
+            1.0 / distances[node] as f64
+        };
+        assert!((exact.closeness[node] - closeness).abs() < 1E-9);
+        assert!((exact.harmonic[node] - harmonic[node]).abs() < 1E-9);
+        assert!((full_sample.closeness[node] - closeness).abs() < 1E-9);
+        assert!((full_sample.harmonic[node] - harmonic[node]).abs() < 1E-9);
+    }
+}
@@ -0,0 +1,181 @@
+use crate::traits::SequentialGraph;
+use dsi_progress_logger::ProgressLogger;
+
+/// A violation of the invariants of a graph found by [`check_graph`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// The nodes are not returned in order by the nodes iterator.
+    NodeOutOfOrder {
+        /// The node that was expected.
+        expected: usize,
+        /// The node that was returned.
+        found: usize,
+    },
+    /// The iterator returned fewer or more nodes than the graph has.
+    NodeCount {
+        /// The number of nodes of the graph.
+        expected: usize,
+        /// The number of nodes returned by the iterator.
+        found: usize,
+    },
+    /// A successor is not larger than the previous one, that is, successors
+    /// are not sorted or they contain duplicates.
+    NotIncreasing {
+        /// The node whose successors are not increasing.
+        node: usize,
+        /// The previous successor.
+        prev: usize,
+        /// The successor not larger than `prev`.
+        succ: usize,
+    },
+    /// A successor is not smaller than the number of nodes.
+    OutOfRange {
+        /// The node with the successor out of range.
+        node: usize,
+        /// The successor out of range.
+        succ: usize,
+    },
+    /// A node is a successor of itself, and self loops are not allowed.
+    SelfLoop {
+        /// The node with a self loop.
+        node: usize,
+    },
+    /// The number of arcs differs from the one declared by the graph (e.g.,
+    /// in the `.properties` file of a BVGraph).
+    ArcCount {
+        /// The number of arcs declared by the graph.
+        expected: usize,
+        /// The number of arcs found.
+        found: usize,
+    },
+}
+
+impl core::fmt::Display for Violation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Violation::NodeOutOfOrder { expected, found } => {
+                write!(f, "Expected node {}, found node {}", expected, found)
+            }
+            Violation::NodeCount { expected, found } => write!(
+                f,
+                "The graph has {} nodes, but {} were returned",
+                expected, found
+            ),
+            Violation::NotIncreasing { node, prev, succ } => write!(
+                f,
+                "The successor {} of node {} follows the successor {}",
+                succ, node, prev
+            ),
+            Violation::OutOfRange { node, succ } => {
+                write!(f, "The successor {} of node {} is out of range", succ, node)
+            }
+            Violation::SelfLoop { node } => write!(f, "Node {} has a self loop", node),
+            Violation::ArcCount { expected, found } => write!(
+                f,
+                "The graph declares {} arcs, but {} were found",
+                expected, found
+            ),
+        }
+    }
+}
+
+/// Scan a graph checking that the nodes are returned in order, that the
+/// successors of each node are strictly increasing and smaller than the
+/// number of nodes, that the number of arcs matches
+/// [`SequentialGraph::num_arcs_hint`], if available, and, unless
+/// `allow_self_loops` is true, that there are no self loops.
+///
+/// All the violations found are returned, in the order in which they are
+/// found; the graph is valid if the result is empty. Graphs with malformed
+/// successor lists usually fail in obscure ways when used, so this is useful
+/// to validate graphs built by external tools.
+pub fn check_graph<G: SequentialGraph>(graph: &G, allow_self_loops: bool) -> Vec<Violation> {
+    let num_nodes = graph.num_nodes();
+    let mut violations = Vec::new();
+    let mut num_arcs = 0;
+    let mut expected_node = 0;
+
+    let mut pl = ProgressLogger::default();
+    pl.item_name = "node";
+    pl.expected_updates = Some(num_nodes);
+    pl.start("Checking the graph...");
+    for (node, successors) in graph.iter_nodes() {
+        if node != expected_node {
+            violations.push(Violation::NodeOutOfOrder {
+                expected: expected_node,
+                found: node,
+            });
+        }
+        expected_node += 1;
+        let mut prev = None;
+        for succ in successors {
+            num_arcs += 1;
+            if let Some(prev) = prev.filter(|&prev| succ <= prev) {
+                violations.push(Violation::NotIncreasing { node, prev, succ });
+            }
+            if succ >= num_nodes {
+                violations.push(Violation::OutOfRange { node, succ });
+            }
+            if succ == node && !allow_self_loops {
+                violations.push(Violation::SelfLoop { node });
+            }
+            prev = Some(succ);
+        }
+        pl.light_update();
+    }
+    pl.done();
+
+    if expected_node != num_nodes {
+        violations.push(Violation::NodeCount {
+            expected: num_nodes,
+            found: expected_node,
+        });
+    }
+    if let Some(expected) = graph.num_arcs_hint() {
+        if expected != num_arcs {
+            violations.push(Violation::ArcCount {
+                expected,
+                found: num_arcs,
+            });
+        }
+    }
+    violations
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_check_graph() -> anyhow::Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    use crate::utils::COOIterToGraph;
+
+    let g = VecGraph::from_arc_list(&[(0, 1), (0, 2), (1, 1), (2, 0)]);
+    assert!(check_graph(&g, true).is_empty());
+    assert_eq!(
+        check_graph(&g, false),
+        vec![Violation::SelfLoop { node: 1 }]
+    );
+
+    // arcs that are not sorted, with a duplicate and a node out of range
+    let arcs = vec![(0, 2), (0, 1), (1, 3), (1, 3), (2, 5)];
+    let coo = COOIterToGraph::new(4, arcs.into_iter());
+    assert_eq!(
+        check_graph(&coo, true),
+        vec![
+            Violation::NotIncreasing {
+                node: 0,
+                prev: 2,
+                succ: 1
+            },
+            Violation::NotIncreasing {
+                node: 1,
+                prev: 3,
+                succ: 3
+            },
+            Violation::OutOfRange { node: 2, succ: 5 },
+        ]
+    );
+
+    let graph = crate::graph::bvgraph::load_seq("tests/data/cnr-2000")?;
+    assert!(check_graph(&graph, true).is_empty());
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use super::triangles::intersect;
+use crate::properties::store_property;
+use crate::traits::SeekableGraph;
+use anyhow::Result;
+use dsi_progress_logger::ProgressLogger;
+use rayon::prelude::*;
+use std::path::Path;
+
+/// Global statistics about the clustering of a symmetric graph, as computed
+/// by [`clustering_coefficients`] or [`store_clustering_coefficients`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ClusteringStats {
+    /// The number of triangles.
+    pub triangles: usize,
+    /// The global clustering coefficient (also known as transitivity), that
+    /// is, three times the number of triangles divided by the number of
+    /// paths of length two, or zero if there are none.
+    pub global: f64,
+    /// The average of the local clustering coefficients over all nodes.
+    pub average: f64,
+}
+
+/// Return the number of triangles containing `node` and its degree, both
+/// ignoring loops.
+///
+/// The triangles are the pairs `v` < `w` of neighbours of `node` that are
+/// adjacent: for each neighbour `v`, the neighbours of `node` are intersected
+/// with the neighbours of `v` larger than `v`.
+fn local_triangles<G: SeekableGraph>(
+    graph: &G,
+    node: usize,
+    successors: &mut Vec<usize>,
+) -> (usize, usize) {
+    successors.clear();
+    successors.extend(graph.successors(node).filter(|&succ| succ != node));
+    let mut triangles = 0;
+    for (i, &v) in successors.iter().enumerate() {
+        intersect(
+            &successors[i + 1..],
+            graph.successors_from(v, v + 1),
+            |_| triangles += 1,
+        );
+    }
+    (triangles, successors.len())
+}
+
+/// Compute the local clustering coefficients of a symmetric graph, passing
+/// them to `sink` in blocks of `block_size` nodes, in order, and return the
+/// global statistics.
+///
+/// The local clustering coefficient of a node of degree `d` that belongs to
+/// `t` triangles is `2t / d(d - 1)`, or zero if `d` < 2; loops are ignored.
+/// The triangles of each node are counted independently, so the coefficients
+/// of a block of nodes are computed in parallel and then discarded after
+/// being passed to `sink`: only one block of coefficients is in memory at any
+/// time, and if the graph is memory-mapped the computation runs out of core.
+///
+/// # Panics
+/// If `block_size` is zero.
+pub fn local_clustering_coefficients<G: SeekableGraph + Sync>(
+    graph: &G,
+    block_size: usize,
+    mut sink: impl FnMut(&[f64]) -> Result<()>,
+) -> Result<ClusteringStats> {
+    assert!(block_size > 0, "The block size must be positive");
+    let num_nodes = graph.num_nodes();
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "node";
+    pl.expected_updates = Some(num_nodes);
+    pl.start("Computing local clustering coefficients...");
+
+    let mut counts = Vec::with_capacity(block_size.min(num_nodes));
+    let mut coefficients = Vec::with_capacity(block_size.min(num_nodes));
+    let mut triangles = 0;
+    let mut paths = 0;
+    let mut sum = 0.0;
+    for start in (0..num_nodes).step_by(block_size) {
+        let end = (start + block_size).min(num_nodes);
+        (start..end)
+            .into_par_iter()
+            .map_init(Vec::new, |successors, node| {
+                local_triangles(graph, node, successors)
+            })
+            .collect_into_vec(&mut counts);
+        coefficients.clear();
+        for &(node_triangles, degree) in &counts {
+            let node_paths = degree * degree.saturating_sub(1) / 2;
+            triangles += node_triangles;
+            paths += node_paths;
+            coefficients.push(if node_paths == 0 {
+                0.0
+            } else {
+                node_triangles as f64 / node_paths as f64
+            });
+        }
+        sum += coefficients.iter().sum::<f64>();
+        sink(&coefficients)?;
+        pl.update_with_count(end - start);
+    }
+    pl.done();
+
+    let stats = ClusteringStats {
+        triangles: triangles / 3,
+        global: if paths == 0 {
+            0.0
+        } else {
+            triangles as f64 / paths as f64
+        },
+        average: if num_nodes == 0 {
+            0.0
+        } else {
+            sum / num_nodes as f64
+        },
+    };
+    log::info!("{:?}", stats);
+    Ok(stats)
+}
+
+/// Return the local clustering coefficients of the nodes of a symmetric
+/// graph, indexed by node, and the global statistics.
+///
+/// See [`local_clustering_coefficients`] for the definitions.
+pub fn clustering_coefficients<G: SeekableGraph + Sync>(graph: &G) -> (Vec<f64>, ClusteringStats) {
+    let mut result = Vec::with_capacity(graph.num_nodes());
+    let stats = local_clustering_coefficients(graph, 1 << 16, |block| {
+        result.extend_from_slice(block);
+        Ok(())
+    })
+    .unwrap();
+    (result, stats)
+}
+
+/// Store the local clustering coefficients of the nodes of a symmetric graph
+/// as the property `clustering` of the graph with the given basename, and
+/// return the global statistics.
+///
+/// The coefficients are serialized with ε-serde as a `Vec<f64>` in the file
+/// `BASENAME.clustering` (see [`store_property`]), so they can be loaded with
+/// [`load_property`](crate::properties::load_property) or memory-mapped with
+/// [`map_property`](crate::properties::map_property). They are computed in
+/// blocks of `block_size` nodes, as in [`local_clustering_coefficients`], and
+/// collected before being stored; use [`local_clustering_coefficients`] to
+/// process them without keeping them in memory.
+pub fn store_clustering_coefficients<G: SeekableGraph + Sync, P: AsRef<Path>>(
+    graph: &G,
+    basename: P,
+    block_size: usize,
+) -> Result<ClusteringStats> {
+    let mut coefficients = Vec::with_capacity(graph.num_nodes());
+    let stats = local_clustering_coefficients(graph, block_size, |block| {
+        coefficients.extend_from_slice(block);
+        Ok(())
+    })?;
+    store_property(basename, "clustering", &coefficients)?;
+    Ok(stats)
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_clustering_coefficients() -> Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    // a triangle 0, 1, 2 with a pendant node 3 attached to 2, and a loop
+    let arcs = [(0, 1), (1, 2), (2, 0), (2, 3), (3, 3)];
+    let mut all = arcs.to_vec();
+    all.extend(arcs.iter().map(|&(src, dst)| (dst, src)));
+    let graph = VecGraph::from_arc_list(&all);
+
+    let (local, stats) = clustering_coefficients(&graph);
+    assert_eq!(local, vec![1.0, 1.0, 1.0 / 3.0, 0.0]);
+    assert_eq!(stats.triangles, 1);
+    // 3 of the 1 + 1 + 3 paths of length two are closed by the triangle
+    assert!((stats.global - 3.0 / 5.0).abs() < 1E-9);
+    assert!((stats.average - (7.0 / 3.0) / 4.0).abs() < 1E-9);
+
+    let dir = tempfile::tempdir()?;
+    let basename = dir.path().join("graph");
+    for block_size in [1, 3, 100] {
+        let streamed = store_clustering_coefficients(&graph, &basename, block_size)?;
+        assert_eq!(streamed.triangles, stats.triangles);
+        assert_eq!(streamed.global, stats.global);
+        assert!((streamed.average - stats.average).abs() < 1E-9);
+        assert_eq!(
+            crate::properties::load_property::<f64, _>(&basename, "clustering")?,
+            local
+        );
+    }
+    Ok(())
+}
@@ -1,3 +1,47 @@
-pub fn compose_orders() {
-    unimplemented!()
+use anyhow::{bail, Result};
+
+/// Return an iterator on the composition of the given permutations, that is,
+/// on the images of the nodes when the permutations are applied in order.
+///
+/// The first permutation is scanned sequentially and the others are accessed
+/// randomly, so the permutations can be [memory-mapped](crate::utils::map_perm)
+/// and the result written with [`store_perm_iter`](crate::utils::store_perm_iter)
+/// without loading anything in memory.
+///
+/// All permutations are scanned once upfront, and an error is returned if
+/// they have different lengths or if some entry is out of range.
+pub fn compose_orders<'a>(perms: &'a [&'a [usize]]) -> Result<impl Iterator<Item = usize> + 'a> {
+    let Some((first, rest)) = perms.split_first() else {
+        bail!("At least one permutation is needed");
+    };
+    if let Some(perm) = rest.iter().find(|perm| perm.len() != first.len()) {
+        bail!(
+            "Cannot compose permutations of different lengths ({} and {})",
+            first.len(),
+            perm.len()
+        );
+    }
+    for (i, perm) in perms.iter().enumerate() {
+        if let Some(&x) = perm.iter().find(|&&x| x >= first.len()) {
+            bail!("Permutation {} contains {}, which is out of range", i, x);
+        }
+    }
+    Ok(first
+        .iter()
+        .map(move |&x| rest.iter().fold(x, |x, perm| perm[x])))
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_compose_orders() -> Result<()> {
+    let a = [3, 0, 2, 1];
+    let b = [1, 2, 3, 0];
+    let c = [2, 3, 0, 1];
+    let perms: [&[usize]; 3] = [&a, &b, &c];
+    let composed = compose_orders(&perms)?.collect::<Vec<_>>();
+    assert_eq!(composed, vec![2, 3, 1, 0]);
+    assert_eq!(compose_orders(&perms[..1])?.collect::<Vec<_>>(), a);
+    assert!(compose_orders(&[&a, &b[1..]]).is_err());
+    assert!(compose_orders(&[&a, &[0, 1, 2, 4]]).is_err());
+    Ok(())
 }
@@ -0,0 +1,120 @@
+use crate::prelude::COOIterToLabelledGraph;
+use crate::traits::SequentialGraph;
+use crate::utils::{
+    sort_arcs, BatchIterator, CountDupsIter, KMergeIters, SortPairs, SortedArcs, SortedArcsGraph,
+};
+use anyhow::Result;
+use dsi_progress_logger::ProgressLogger;
+
+/// Return the arcs of the quotient graph, with the number of labels.
+fn quotient_arcs<'a, G: SequentialGraph>(
+    graph: &'a G,
+    labels: &'a [usize],
+    loops: bool,
+) -> (impl Iterator<Item = (usize, usize)> + 'a, usize) {
+    assert_eq!(
+        labels.len(),
+        graph.num_nodes(),
+        "There must be a label for each node"
+    );
+    let num_labels = labels.iter().max().map_or(0, |&max| max + 1);
+    let arcs = graph.iter_nodes().flat_map(move |(src, succ)| {
+        let src_label = labels[src];
+        succ.into_iter()
+            .map(move |dst| (src_label, labels[dst]))
+            .filter(move |&(src_label, dst_label)| loops || src_label != dst_label)
+    });
+    (arcs, num_labels)
+}
+
+/// Return a sequential graph view of the quotient of a graph by a labelling
+/// of its nodes, that is, the graph having a node for each label and an arc
+/// from `i` to `j` if there is an arc from a node with label `i` to a node
+/// with label `j`.
+///
+/// For example, given the labelling mapping each page of a web graph to its
+/// host, the result is the host graph. The number of nodes of the result is
+/// the largest label plus one. If `loops` is false, arcs between nodes with
+/// the same label are discarded; otherwise, they become loops.
+///
+/// The arcs are merged and deduplicated out of core by [`sort_arcs`] with
+/// batches of `batch_size` pairs, so the graph can be arbitrarily large.
+///
+/// # Panics
+/// If `labels` has not an element for each node.
+pub fn contract<G: SequentialGraph>(
+    graph: &G,
+    labels: &[usize],
+    loops: bool,
+    batch_size: usize,
+) -> Result<SortedArcsGraph> {
+    let (arcs, num_labels) = quotient_arcs(graph, labels, loops);
+    sort_arcs(arcs, Some(num_labels), batch_size)
+}
+
+/// Return a labelled sequential graph view of the quotient of a graph by a
+/// labelling of its nodes, as [`contract`] does, in which the label of each
+/// arc is its multiplicity, that is, the number of arcs of the graph between
+/// nodes with the labels of its endpoints.
+///
+/// The arcs are sorted out of core by a [`SortPairs`] with batches of
+/// `batch_size` pairs in a temporary directory owned by the result.
+///
+/// # Panics
+/// If `labels` has not an element for each node.
+#[allow(clippy::type_complexity)]
+pub fn contract_weighted<G: SequentialGraph>(
+    graph: &G,
+    labels: &[usize],
+    loops: bool,
+    batch_size: usize,
+) -> Result<COOIterToLabelledGraph<SortedArcs<CountDupsIter<(), KMergeIters<(), BatchIterator<()>>>>>>
+{
+    let (arcs, num_labels) = quotient_arcs(graph, labels, loops);
+    let dir = tempfile::tempdir()?;
+    let mut sorted = <SortPairs<()>>::new(batch_size, dir.path())?;
+
+    let mut pl = ProgressLogger::default();
+    pl.item_name = "arc";
+    pl.start("Creating batches...");
+    for (src, dst) in arcs {
+        sorted.push(src, dst, ())?;
+        pl.light_update();
+    }
+    pl.done();
+    // merge the batches, counting duplicate arcs
+    Ok(COOIterToLabelledGraph::new(
+        num_labels,
+        SortedArcs::new(sorted.iter()?.count_dups(), dir),
+    ))
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_contract() -> Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    use crate::traits::{LabelledIterator, RandomAccessGraph};
+    let g = VecGraph::from_arc_list(&[(0, 1), (1, 2), (0, 3), (1, 3), (2, 0), (3, 4), (4, 3)]);
+    let labels = [0, 0, 1, 2, 2];
+
+    let quotient = VecGraph::from_node_iter(contract(&g, &labels, false, 2)?.iter_nodes());
+    assert_eq!(quotient, VecGraph::from_arc_list(&[(0, 1), (0, 2), (1, 0)]));
+    let with_loops = VecGraph::from_node_iter(contract(&g, &labels, true, 2)?.iter_nodes());
+    assert!(with_loops.has_arc(0, 0) && with_loops.has_arc(2, 2));
+    assert_eq!(with_loops.num_arcs(), 5);
+
+    let weighted = contract_weighted(&g, &labels, true, 2)?;
+    let arcs = weighted
+        .iter_nodes()
+        .flat_map(|(src, succ)| {
+            succ.labelled()
+                .map(move |(dst, count)| (src, dst, count))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        arcs,
+        vec![(0, 0, 1), (0, 1, 1), (0, 2, 2), (1, 0, 1), (2, 2, 2)]
+    );
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use crate::algorithms::DegreeKind;
+use crate::traits::SequentialGraph;
+use dsi_progress_logger::ProgressLogger;
+use std::collections::BTreeMap;
+
+/// Return the exact degree distribution of a graph, mapping each degree of
+/// the given kind to the number of nodes having it, computed with a single
+/// sequential pass.
+///
+/// For outdegrees, memory is proportional to the number of distinct degrees;
+/// indegrees (and thus total degrees) require instead an array with an entry
+/// per node.
+pub fn degree_histogram<G: SequentialGraph>(graph: &G, kind: DegreeKind) -> BTreeMap<usize, usize> {
+    let num_nodes = graph.num_nodes();
+    let mut histogram = BTreeMap::new();
+    let mut degrees = if kind == DegreeKind::Out {
+        vec![]
+    } else {
+        vec![0_usize; num_nodes]
+    };
+
+    let mut pl = ProgressLogger::default();
+    pl.item_name = "node";
+    pl.expected_updates = Some(num_nodes);
+    pl.start("Computing the degree distribution...");
+    for (node, succ) in graph.iter_nodes() {
+        match kind {
+            DegreeKind::Out => *histogram.entry(succ.count()).or_insert(0) += 1,
+            _ => {
+                let mut outdegree = 0;
+                for dst in succ {
+                    degrees[dst] += 1;
+                    outdegree += 1;
+                }
+                if kind == DegreeKind::Total {
+                    degrees[node] += outdegree;
+                }
+            }
+        }
+        pl.light_update();
+    }
+    pl.done();
+
+    for degree in degrees {
+        *histogram.entry(degree).or_insert(0) += 1;
+    }
+    histogram
+}
+
+/// Group a degree distribution in logarithmic bins, returning for each
+/// nonempty bin its lower bound (included), its upper bound (excluded), and
+/// its density, that is, the fraction of nodes in the bin divided by the
+/// width of the bin.
+///
+/// The first bin contains degree zero, and the following ones the degrees
+/// from `base`<sup>*i*</sup> (included) to `base`<sup>*i* + 1</sup>
+/// (excluded), rounded up to integers.
+pub fn log_bins(histogram: &BTreeMap<usize, usize>, base: f64) -> Vec<(usize, usize, f64)> {
+    assert!(base > 1.0, "The base must be greater than one");
+    let num_nodes = histogram.values().sum::<usize>() as f64;
+    let mut bins = Vec::new();
+    let (mut lower, mut upper, mut bound) = (0, 1, 1.0_f64);
+    let mut count = 0;
+    for (&degree, &nodes) in histogram {
+        while degree >= upper {
+            if count != 0 {
+                bins.push((
+                    lower,
+                    upper,
+                    count as f64 / num_nodes / (upper - lower) as f64,
+                ));
+                count = 0;
+            }
+            lower = upper;
+            // make sure that bins are at least one degree wide
+            while bound.ceil() as usize <= lower {
+                bound *= base;
+            }
+            upper = bound.ceil() as usize;
+        }
+        count += nodes;
+    }
+    if count != 0 {
+        bins.push((
+            lower,
+            upper,
+            count as f64 / num_nodes / (upper - lower) as f64,
+        ));
+    }
+    bins
+}
+
+/// Return the maximum-likelihood estimate of the exponent α of a discrete
+/// power law fitting the tail of a degree distribution starting at
+/// `min_degree`, which must be positive, or `None` if there are not enough
+/// data.
+///
+/// The estimate uses the usual approximation
+/// α = 1 + *n* / ∑ ln(*d*ᵢ / (`min_degree` − ½)),
+/// where *d*ᵢ ranges over the *n* degrees not smaller than `min_degree`.
+///
+/// # References
+/// [Power-Law Distributions in Empirical Data](https://arxiv.org/abs/0706.1062)
+pub fn power_law_exponent(histogram: &BTreeMap<usize, usize>, min_degree: usize) -> Option<f64> {
+    assert_ne!(min_degree, 0, "The minimum degree must be positive");
+    let x_min = min_degree as f64 - 0.5;
+    let (n, sum) = histogram
+        .range(min_degree..)
+        .fold((0, 0.0), |(n, sum), (&degree, &nodes)| {
+            (n + nodes, sum + nodes as f64 * (degree as f64 / x_min).ln())
+        });
+    if n == 0 || sum <= 0.0 {
+        None
+    } else {
+        Some(1.0 + n as f64 / sum)
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_degree_dist() {
+    use crate::graph::vec_graph::VecGraph;
+    let g = VecGraph::from_arc_list(&[(0, 1), (0, 2), (0, 3), (1, 2), (2, 3), (3, 0)]);
+    let out = degree_histogram(&g, DegreeKind::Out);
+    assert_eq!(out.into_iter().collect::<Vec<_>>(), vec![(1, 3), (3, 1)]);
+    let inn = degree_histogram(&g, DegreeKind::In);
+    assert_eq!(inn.into_iter().collect::<Vec<_>>(), vec![(1, 2), (2, 2)]);
+    let total = degree_histogram(&g, DegreeKind::Total);
+    assert_eq!(
+        total.into_iter().collect::<Vec<_>>(),
+        vec![(2, 1), (3, 2), (4, 1)]
+    );
+
+    let histogram = BTreeMap::from([(0, 1), (1, 4), (2, 2), (3, 1), (10, 2)]);
+    let bins = log_bins(&histogram, 2.0);
+    assert_eq!(
+        bins.iter().map(|&(l, u, _)| (l, u)).collect::<Vec<_>>(),
+        vec![(0, 1), (1, 2), (2, 4), (8, 16)]
+    );
+    assert!((bins[2].2 - 3.0 / 10.0 / 2.0).abs() < 1E-9);
+
+    assert_eq!(power_law_exponent(&histogram, 100), None);
+    let alpha = power_law_exponent(&histogram, 1).unwrap();
+    assert!(alpha > 1.0);
+}
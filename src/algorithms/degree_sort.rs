@@ -0,0 +1,83 @@
+use crate::traits::RandomAccessGraph;
+use dsi_progress_logger::ProgressLogger;
+use rayon::prelude::*;
+
+/// The degree used by [`degree_sort`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DegreeKind {
+    /// The number of successors of a node.
+    Out,
+    /// The number of predecessors of a node.
+    In,
+    /// The sum of the outdegree and of the indegree of a node.
+    Total,
+}
+
+/// Return the permutation mapping each node to its rank when the nodes are
+/// sorted by decreasing degree of the given kind.
+///
+/// Outdegrees are obtained with [`RandomAccessGraph::outdegree`], which on a
+/// [`BVGraph`](crate::graph::bvgraph::BVGraph) decodes just the first code
+/// at the offset of each node, without decoding the successors. Indegrees
+/// require instead a sequential scan of the whole graph.
+///
+/// If `stable` is true, nodes with the same degree keep their relative
+/// order; otherwise, the order among them is unspecified, but the sort is
+/// parallel.
+pub fn degree_sort<G: RandomAccessGraph>(graph: &G, kind: DegreeKind, stable: bool) -> Vec<usize> {
+    let num_nodes = graph.num_nodes();
+    let mut degrees = vec![0_usize; num_nodes];
+
+    let mut pl = ProgressLogger::default();
+    pl.item_name = "node";
+    pl.expected_updates = Some(num_nodes);
+
+    if kind != DegreeKind::In {
+        pl.start("Reading outdegrees...");
+        for (node, degree) in degrees.iter_mut().enumerate() {
+            *degree = graph.outdegree(node);
+            pl.light_update();
+        }
+        pl.done();
+    }
+    if kind != DegreeKind::Out {
+        pl.start("Computing indegrees...");
+        for (_, succ) in graph.iter_nodes() {
+            for dst in succ {
+                degrees[dst] += 1;
+            }
+            pl.light_update();
+        }
+        pl.done();
+    }
+
+    let mut order = (0..num_nodes).collect::<Vec<_>>();
+    let key = |&node: &usize| core::cmp::Reverse(degrees[node]);
+    if stable {
+        order.sort_by_key(key);
+    } else {
+        order.par_sort_unstable_by_key(key);
+    }
+
+    // invert the order to get the rank of each node
+    let mut perm = vec![0; num_nodes];
+    for (rank, &node) in order.iter().enumerate() {
+        perm[node] = rank;
+    }
+    perm
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_degree_sort() {
+    use crate::graph::vec_graph::VecGraph;
+    let g = VecGraph::from_arc_list(&[(0, 3), (1, 0), (1, 2), (1, 3), (2, 3), (3, 0)]);
+    // outdegrees are 1, 3, 1, 1
+    assert_eq!(degree_sort(&g, DegreeKind::Out, true), vec![1, 0, 2, 3]);
+    // indegrees are 2, 0, 1, 3
+    assert_eq!(degree_sort(&g, DegreeKind::In, true), vec![1, 3, 2, 0]);
+    // total degrees are 3, 3, 2, 4
+    assert_eq!(degree_sort(&g, DegreeKind::Total, true), vec![1, 2, 3, 0]);
+    let perm = degree_sort(&g, DegreeKind::Out, false);
+    assert_eq!(perm[1], 0);
+}
@@ -0,0 +1,172 @@
+use crate::traits::{LabelledIterator, LabelledRandomAccessGraph};
+use dsi_progress_logger::ProgressLogger;
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The number of nodes relaxed by each task.
+const CHUNK_SIZE: usize = 1024;
+
+/// Relax in parallel the light (i.e., of weight at most `delta`) or heavy
+/// arcs leaving `nodes`, returning the bucket and the node of each
+/// improvement.
+fn relax<G, W>(
+    graph: &G,
+    nodes: &[usize],
+    distances: &[AtomicU64],
+    delta: u64,
+    light: bool,
+) -> Vec<(u64, usize)>
+where
+    G: LabelledRandomAccessGraph<Label = W> + Sync,
+    for<'a> G::RandomSuccessorIter<'a>: LabelledIterator<Label = W>,
+    W: Into<u64>,
+{
+    nodes
+        .par_chunks(CHUNK_SIZE)
+        .flat_map_iter(|chunk| {
+            let mut requests = Vec::new();
+            for &node in chunk {
+                let distance = distances[node].load(Ordering::Relaxed);
+                for (succ, weight) in graph.successors(node).labelled() {
+                    let weight = weight.into();
+                    if (weight <= delta) != light {
+                        continue;
+                    }
+                    let new_distance = distance + weight;
+                    if new_distance < distances[succ].fetch_min(new_distance, Ordering::Relaxed) {
+                        requests.push((new_distance / delta, succ));
+                    }
+                }
+            }
+            requests
+        })
+        .collect()
+}
+
+/// Compute in parallel the distances from `source` in a graph whose arcs are
+/// labelled by their (integer) weight, using the delta-stepping algorithm of
+/// Meyer and Sanders, “[Δ-stepping: a parallelizable shortest path
+/// algorithm](https://doi.org/10.1016/S0196-6774(03)00076-2)”, and return
+/// them in a vector indexed by node, containing `u64::MAX` for the nodes that
+/// cannot be reached from the source.
+///
+/// Nodes are kept in buckets of width `delta` by their tentative distance,
+/// and buckets are processed in order: the light arcs (of weight at most
+/// `delta`) leaving the nodes of the current bucket are relaxed in parallel
+/// until the bucket is empty, as they can reinsert nodes in it, and then the
+/// heavy arcs leaving the nodes removed from the bucket are relaxed once.
+/// Distances are updated with atomic minimum operations, and a node whose
+/// distance improves is inserted in its new bucket without being removed
+/// from the old one, so stale entries are skipped when a bucket is processed.
+///
+/// A `delta` equal to one gives a parallel Dijkstra's algorithm, with a
+/// bucket for each distance, and a `delta` larger than the maximum weight
+/// gives a parallel Bellman–Ford algorithm. A good starting point is the
+/// maximum weight divided by the average degree; on road networks, a value
+/// close to the average weight usually works well.
+///
+/// # Panics
+/// If `delta` is zero or `source` is not a node of the graph.
+pub fn delta_stepping<G, W>(graph: &G, source: usize, delta: u64) -> Vec<u64>
+where
+    G: LabelledRandomAccessGraph<Label = W> + Sync,
+    for<'a> G::RandomSuccessorIter<'a>: LabelledIterator<Label = W>,
+    W: Into<u64>,
+{
+    assert!(delta > 0, "Delta must be positive");
+    let num_nodes = graph.num_nodes();
+    assert!(source < num_nodes, "Source {} does not exist", source);
+    let distances = (0..num_nodes)
+        .map(|_| AtomicU64::new(u64::MAX))
+        .collect::<Vec<_>>();
+    distances[source].store(0, Ordering::Relaxed);
+    // only nonempty buckets are stored, so their number does not depend on
+    // the ratio between the distances and delta
+    let mut buckets = BTreeMap::new();
+    buckets.insert(0, vec![source]);
+
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "bucket";
+    pl.start("Computing shortest paths...");
+    while let Some((index, mut bucket)) = buckets.pop_first() {
+        let mut settled = Vec::new();
+        loop {
+            // skip the nodes whose distance has improved after the insertion
+            bucket.retain(|&node| distances[node].load(Ordering::Relaxed) / delta == index);
+            bucket.par_sort_unstable();
+            bucket.dedup();
+            if bucket.is_empty() {
+                break;
+            }
+            let requests = relax(graph, &bucket, &distances, delta, true);
+            settled.append(&mut bucket);
+            for (target, node) in requests {
+                if target == index {
+                    bucket.push(node);
+                } else {
+                    buckets.entry(target).or_default().push(node);
+                }
+            }
+        }
+        // a node may have been removed more than once from the bucket
+        settled.par_sort_unstable();
+        settled.dedup();
+        // heavy arcs always lead to a later bucket
+        for (target, node) in relax(graph, &settled, &distances, delta, false) {
+            buckets.entry(target).or_default().push(node);
+        }
+        pl.light_update();
+    }
+    pl.done();
+
+    distances.into_iter().map(AtomicU64::into_inner).collect()
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_delta_stepping() {
+    use super::dijkstra;
+    use crate::graph::vec_graph::VecGraph;
+    use rand::prelude::*;
+    let num_nodes = 200;
+    let mut rng = SmallRng::seed_from_u64(0);
+    let arcs = (0..1000)
+        .map(|_| {
+            (
+                rng.gen_range(0..num_nodes - 1),
+                rng.gen_range(0..num_nodes - 1),
+                rng.gen_range(0..1000_u64),
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut graph = VecGraph::from_arc_and_label_list(&arcs);
+    // an isolated node
+    graph.add_node(num_nodes - 1);
+
+    // a small graph whose distances are computed by hand: the shortest
+    // paths to 1 and 3 are not the ones with fewest arcs
+    let mut small = VecGraph::from_arc_and_label_list(&[
+        (0, 1, 4_u64),
+        (0, 2, 1),
+        (2, 1, 2),
+        (1, 3, 1),
+        (2, 3, 5),
+        (3, 4, 3),
+    ]);
+    small.add_node(5);
+    for delta in [1, 2, 3, 10] {
+        assert_eq!(
+            delta_stepping(&small, 0, delta),
+            vec![0, 3, 1, 4, 7, u64::MAX]
+        );
+    }
+
+    let paths = dijkstra(&graph, 0);
+    let expected = (0..num_nodes)
+        .map(|node| paths.distance(node).unwrap_or(u64::MAX))
+        .collect::<Vec<_>>();
+    for delta in [1, 10, 100, 1000, u64::MAX / 2] {
+        assert_eq!(delta_stepping(&graph, 0, delta), expected);
+    }
+}
@@ -0,0 +1,151 @@
+use super::UNREACHABLE;
+use crate::traits::RandomAccessGraph;
+use dsi_progress_logger::ProgressLogger;
+use rayon::prelude::*;
+
+/// Visit the graph in BFS order from `source`, storing the distances in
+/// `dist`, which must contain only [`UNREACHABLE`], and the visited nodes in
+/// `queue`, and return the eccentricity of `source`.
+fn bfs<G: RandomAccessGraph>(
+    graph: &G,
+    source: usize,
+    dist: &mut [usize],
+    queue: &mut Vec<usize>,
+) -> usize {
+    queue.clear();
+    queue.push(source);
+    dist[source] = 0;
+    let mut pos = 0;
+    while pos < queue.len() {
+        let node = queue[pos];
+        pos += 1;
+        for succ in graph.successors(node) {
+            if dist[succ] == UNREACHABLE {
+                dist[succ] = dist[node] + 1;
+                queue.push(succ);
+            }
+        }
+    }
+    dist[*queue.last().unwrap()]
+}
+
+/// Return the eccentricity of `source`, resetting `dist` after the visit.
+fn eccentricity<G: RandomAccessGraph>(
+    graph: &G,
+    source: usize,
+    dist: &mut [usize],
+    queue: &mut Vec<usize>,
+) -> usize {
+    let ecc = bfs(graph, source, dist, queue);
+    for &node in queue.iter() {
+        dist[node] = UNREACHABLE;
+    }
+    ecc
+}
+
+/// Compute the exact diameter of a symmetric graph (that is, of an
+/// undirected graph) using the iFUB algorithm.
+///
+/// We visit the graph from the node `u` of maximum degree, and we compute
+/// the eccentricities of the nodes at distance `i` from `u`, for decreasing
+/// `i`: the nodes at distance less than `i` from `u` are at distance at most
+/// `2(i - 1)` from each other, so we can stop as soon as the maximum
+/// eccentricity found is at least `2(i - 1)`. See Crescenzi,
+/// Grossi, Habib, Lanzi and Marino, “[On computing the diameter of
+/// real-world undirected graphs](https://doi.org/10.1016/j.tcs.2012.09.018)”.
+/// On real-world graphs, usually a few visits suffice, but in the worst case
+/// all nodes are visited, so this is suitable for moderately sized graphs.
+/// The visits from the nodes at the same distance from `u` are run in
+/// parallel.
+///
+/// If the graph is not connected, the result is the diameter of the
+/// connected component of `u`. If the graph is not symmetric, the result is
+/// meaningless.
+pub fn ifub_diameter<G: RandomAccessGraph + Sync>(graph: &G) -> usize {
+    let num_nodes = graph.num_nodes();
+    if num_nodes == 0 {
+        return 0;
+    }
+    let start = (0..num_nodes)
+        .max_by_key(|&node| graph.outdegree(node))
+        .unwrap();
+
+    let mut dist = vec![UNREACHABLE; num_nodes];
+    let mut queue = Vec::new();
+    let start_ecc = bfs(graph, start, &mut dist, &mut queue);
+    // the fringes, that is, the nodes at each distance from start
+    let mut fringes = vec![Vec::new(); start_ecc + 1];
+    for &node in &queue {
+        fringes[dist[node]].push(node);
+    }
+    drop(dist);
+
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "visit";
+    pl.start("Computing the diameter with iFUB...");
+
+    let mut lower_bound = start_ecc;
+    let mut i = start_ecc;
+    // all nodes not yet visited are at distance at most i from start, so
+    // they are at distance at most 2i from each other
+    while i > 0 && lower_bound < 2 * i {
+        let max_ecc = fringes[i]
+            .par_iter()
+            .map_init(
+                || (vec![UNREACHABLE; num_nodes], Vec::new()),
+                |(dist, queue), &node| eccentricity(graph, node, dist, queue),
+            )
+            .max()
+            .unwrap_or(0);
+        lower_bound = lower_bound.max(max_ecc);
+        pl.update_with_count(fringes[i].len());
+        log::info!(
+            "Fringe {}: lower bound {}, upper bound {}",
+            i,
+            lower_bound,
+            2 * (i - 1)
+        );
+        i -= 1;
+    }
+    pl.done();
+
+    lower_bound
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_ifub_diameter() {
+    use crate::graph::vec_graph::VecGraph;
+    use rand::prelude::*;
+
+    let symmetric = |arcs: &[(usize, usize)]| {
+        let mut all = arcs.to_vec();
+        all.extend(arcs.iter().map(|&(src, dst)| (dst, src)));
+        VecGraph::from_arc_list(&all)
+    };
+    let path = symmetric(&(0..9).map(|node| (node, node + 1)).collect::<Vec<_>>());
+    assert_eq!(ifub_diameter(&path), 9);
+    let cycle = symmetric(
+        &(0..10)
+            .map(|node| (node, (node + 1) % 10))
+            .collect::<Vec<_>>(),
+    );
+    assert_eq!(ifub_diameter(&cycle), 5);
+
+    let mut rng = SmallRng::seed_from_u64(0);
+    for _ in 0..10 {
+        // a random tree, to be connected, plus some random arcs
+        let mut arcs = (1..200)
+            .map(|node| (rng.gen_range(0..node), node))
+            .collect::<Vec<_>>();
+        arcs.extend((0..50).map(|_| (rng.gen_range(0..200), rng.gen_range(0..200))));
+        let graph = symmetric(&arcs);
+        let mut dist = vec![UNREACHABLE; 200];
+        let mut queue = Vec::new();
+        let diameter = (0..200)
+            .map(|node| eccentricity(&graph, node, &mut dist, &mut queue))
+            .max()
+            .unwrap();
+        assert_eq!(ifub_diameter(&graph), diameter);
+    }
+}
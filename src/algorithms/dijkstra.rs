@@ -0,0 +1,300 @@
+use super::UNREACHABLE;
+use crate::traits::{LabelledIterator, LabelledRandomAccessGraph};
+use crate::utils::{KAryHeap, DEFAULT_HEAP_ARITY};
+use dsi_progress_logger::ProgressLogger;
+use std::collections::HashMap;
+use std::ops::Add;
+
+/// The shortest paths from a source node, as computed by [`dijkstra`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShortestPaths<W> {
+    /// The source of the paths.
+    pub source: usize,
+    /// The distance of each node from the source; the distance of the nodes
+    /// that are not reachable is `W::default()`.
+    pub distances: Vec<W>,
+    /// The node preceding each node on a shortest path from the source, or
+    /// [`UNREACHABLE`] for the nodes that are not reachable; the source is
+    /// its own predecessor.
+    pub predecessors: Vec<usize>,
+}
+
+impl<W: Copy> ShortestPaths<W> {
+    /// Return the distance of `node` from the source, or `None` if it is not
+    /// reachable.
+    pub fn distance(&self, node: usize) -> Option<W> {
+        (self.predecessors[node] != UNREACHABLE).then(|| self.distances[node])
+    }
+
+    /// Return the nodes of a shortest path from the source to `node`, both
+    /// included, or `None` if `node` is not reachable.
+    pub fn path(&self, node: usize) -> Option<Vec<usize>> {
+        path_to(node, |node| {
+            Some(self.predecessors[node]).filter(|&pred| pred != UNREACHABLE)
+        })
+    }
+}
+
+/// Follow the predecessors from `node` back to a node that is its own
+/// predecessor, and return the reversed path.
+fn path_to(mut node: usize, predecessor: impl Fn(usize) -> Option<usize>) -> Option<Vec<usize>> {
+    let mut path = vec![node];
+    loop {
+        let pred = predecessor(node)?;
+        if pred == node {
+            break;
+        }
+        path.push(pred);
+        node = pred;
+    }
+    path.reverse();
+    Some(path)
+}
+
+/// Compute the shortest paths from `source` in a graph whose arcs are
+/// labelled by their weight, using Dijkstra's algorithm.
+///
+/// Weights can be of any numeric type: `W::default()` must be zero, and all
+/// weights must be nonnegative (and not NaN). The priority queue is a
+/// [`KAryHeap`] without a decrease-key operation, so a node is pushed each
+/// time its distance improves and stale entries are skipped when popped:
+/// the space used by the queue is proportional to the number of
+/// improvements, which is at most the number of arcs.
+///
+/// The heap has arity [`DEFAULT_HEAP_ARITY`]; use [`dijkstra_with_arity`] to
+/// choose another one.
+pub fn dijkstra<G, W>(graph: &G, source: usize) -> ShortestPaths<W>
+where
+    G: LabelledRandomAccessGraph<Label = W>,
+    for<'a> G::RandomSuccessorIter<'a>: LabelledIterator<Label = W>,
+    W: Copy + PartialOrd + Add<Output = W> + Default,
+{
+    dijkstra_with_arity::<DEFAULT_HEAP_ARITY, G, W>(graph, source)
+}
+
+/// Compute the shortest paths from `source` as [`dijkstra`] does, using as
+/// priority queue a [`KAryHeap`] of arity `ARITY`.
+///
+/// Larger arities make pushes, which happen at each improvement of a
+/// distance, cheaper, and pops more expensive: graphs with many arcs per
+/// node usually benefit from a larger arity.
+pub fn dijkstra_with_arity<const ARITY: usize, G, W>(graph: &G, source: usize) -> ShortestPaths<W>
+where
+    G: LabelledRandomAccessGraph<Label = W>,
+    for<'a> G::RandomSuccessorIter<'a>: LabelledIterator<Label = W>,
+    W: Copy + PartialOrd + Add<Output = W> + Default,
+{
+    let num_nodes = graph.num_nodes();
+    let mut distances = vec![W::default(); num_nodes];
+    let mut predecessors = vec![UNREACHABLE; num_nodes];
+    let mut heap: KAryHeap<(W, usize), ARITY> = KAryHeap::new();
+    predecessors[source] = source;
+    heap.push((W::default(), source));
+
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "node";
+    pl.start("Computing shortest paths...");
+    while !heap.is_empty() {
+        let (distance, node) = *heap.peek();
+        heap.pop();
+        if distance > distances[node] {
+            // a stale entry of a node whose distance has improved
+            continue;
+        }
+        for (succ, weight) in graph.successors(node).labelled() {
+            let new_distance = distance + weight;
+            if predecessors[succ] == UNREACHABLE || new_distance < distances[succ] {
+                distances[succ] = new_distance;
+                predecessors[succ] = node;
+                heap.push((new_distance, succ));
+            }
+        }
+        pl.light_update();
+    }
+    pl.done();
+
+    ShortestPaths {
+        source,
+        distances,
+        predecessors,
+    }
+}
+
+/// Return the length and the nodes of a shortest path from `source` to
+/// `target` in a graph whose arcs are labelled by their weight, or `None` if
+/// `target` is not reachable, using the A* algorithm.
+///
+/// Weights are as in [`dijkstra`]. The `heuristic` must estimate the
+/// distance of each node from `target` without overestimating it, and must
+/// be consistent, that is, for each arc from `u` to `v` with weight `w`,
+/// `heuristic(u)` ≤ `w + heuristic(v)`: nodes are then expanded at most once,
+/// and in general far fewer nodes than with Dijkstra's algorithm are visited.
+/// A heuristic that is constantly zero gives Dijkstra's algorithm stopping
+/// at `target`.
+///
+/// The state is kept in hash maps, so the space used depends only on the
+/// number of nodes visited, and not on the size of the graph. The priority
+/// queue is as in [`dijkstra`]; use [`astar_with_arity`] to choose the arity
+/// of the heap.
+pub fn astar<G, W>(
+    graph: &G,
+    source: usize,
+    target: usize,
+    heuristic: impl Fn(usize) -> W,
+) -> Option<(W, Vec<usize>)>
+where
+    G: LabelledRandomAccessGraph<Label = W>,
+    for<'a> G::RandomSuccessorIter<'a>: LabelledIterator<Label = W>,
+    W: Copy + PartialOrd + Add<Output = W> + Default,
+{
+    astar_with_arity::<DEFAULT_HEAP_ARITY, G, W>(graph, source, target, heuristic)
+}
+
+/// Return a shortest path from `source` to `target` as [`astar`] does, using
+/// as priority queue a [`KAryHeap`] of arity `ARITY`.
+pub fn astar_with_arity<const ARITY: usize, G, W>(
+    graph: &G,
+    source: usize,
+    target: usize,
+    heuristic: impl Fn(usize) -> W,
+) -> Option<(W, Vec<usize>)>
+where
+    G: LabelledRandomAccessGraph<Label = W>,
+    for<'a> G::RandomSuccessorIter<'a>: LabelledIterator<Label = W>,
+    W: Copy + PartialOrd + Add<Output = W> + Default,
+{
+    // the distance from the source and the predecessor of each visited node
+    let mut visited = HashMap::new();
+    let mut heap: KAryHeap<(W, W, usize), ARITY> = KAryHeap::new();
+    visited.insert(source, (W::default(), source));
+    heap.push((heuristic(source), W::default(), source));
+
+    while !heap.is_empty() {
+        let (_, distance, node) = *heap.peek();
+        heap.pop();
+        if distance > visited[&node].0 {
+            continue;
+        }
+        if node == target {
+            let path = path_to(target, |node| visited.get(&node).map(|&(_, pred)| pred))?;
+            return Some((distance, path));
+        }
+        for (succ, weight) in graph.successors(node).labelled() {
+            let new_distance = distance + weight;
+            match visited.get(&succ) {
+                Some(&(old_distance, _)) if old_distance <= new_distance => {}
+                _ => {
+                    visited.insert(succ, (new_distance, node));
+                    heap.push((new_distance + heuristic(succ), new_distance, succ));
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_dijkstra() {
+    use crate::graph::vec_graph::VecGraph;
+    use crate::traits::{RandomAccessGraph, SequentialGraph};
+    use rand::prelude::*;
+    let num_nodes = 60;
+    let mut rng = SmallRng::seed_from_u64(0);
+    let arcs = (0..300)
+        .map(|_| {
+            (
+                rng.gen_range(0..num_nodes - 1),
+                rng.gen_range(0..num_nodes - 1),
+                rng.gen_range(0..100_u64),
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut graph = VecGraph::from_arc_and_label_list(&arcs);
+    // an isolated node
+    graph.add_node(num_nodes - 1);
+
+    // Bellman–Ford
+    let mut expected = vec![None; num_nodes];
+    expected[0] = Some(0);
+    for _ in 0..num_nodes {
+        for (src, succ) in graph.iter_nodes() {
+            for (dst, weight) in succ.labelled() {
+                if let Some(distance) = expected[src] {
+                    if expected[dst].map_or(true, |old| distance + weight < old) {
+                        expected[dst] = Some(distance + weight);
+                    }
+                }
+            }
+        }
+    }
+    assert!(expected.iter().any(|d| d.is_none()));
+
+    let paths = dijkstra(&graph, 0);
+    assert_eq!(
+        dijkstra_with_arity::<2, _, _>(&graph, 0).distances,
+        paths.distances
+    );
+    assert_eq!(
+        dijkstra_with_arity::<16, _, _>(&graph, 0).distances,
+        paths.distances
+    );
+    for (node, &distance) in expected.iter().enumerate() {
+        assert_eq!(paths.distance(node), distance);
+        let path = paths.path(node);
+        assert_eq!(path.is_some(), distance.is_some());
+        if let Some(path) = path {
+            assert_eq!(path[0], 0);
+            assert_eq!(*path.last().unwrap(), node);
+            let length = path
+                .windows(2)
+                .map(|arc| {
+                    graph
+                        .successors(arc[0])
+                        .labelled()
+                        .find(|&(dst, _)| dst == arc[1])
+                        .unwrap()
+                        .1
+                })
+                .sum::<u64>();
+            assert_eq!(Some(length), distance);
+        }
+        assert_eq!(
+            astar(&graph, 0, node, |_| 0).map(|(distance, _)| distance),
+            distance
+        );
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_astar() {
+    use crate::graph::vec_graph::VecGraph;
+    // a 10 × 10 grid with unit weights, and the Manhattan distance heuristic
+    let side = 10;
+    let mut arcs = Vec::new();
+    for x in 0..side {
+        for y in 0..side {
+            let node = x * side + y;
+            if x + 1 < side {
+                arcs.push((node, node + side, 1.0));
+                arcs.push((node + side, node, 1.0));
+            }
+            if y + 1 < side {
+                arcs.push((node, node + 1, 1.0));
+                arcs.push((node + 1, node, 1.0));
+            }
+        }
+    }
+    let graph = VecGraph::from_arc_and_label_list(&arcs);
+    let target = side * side - 1;
+    let manhattan = |node: usize| ((side - 1 - node / side) + (side - 1 - node % side)) as f64;
+    let (distance, path) = astar(&graph, 0, target, manhattan).unwrap();
+    assert_eq!(distance, 18.0);
+    assert_eq!(path.len(), 19);
+    assert_eq!(dijkstra(&graph, 0).distance(target), Some(18.0));
+    assert_eq!(
+        astar_with_arity::<2, _, _>(&graph, 0, target, manhattan).map(|(distance, _)| distance),
+        Some(18.0)
+    );
+}
@@ -0,0 +1,157 @@
+use crate::traits::RandomAccessGraph;
+use anyhow::{bail, Result};
+use dsi_progress_logger::ProgressLogger;
+use rayon::prelude::*;
+
+/// Return the estimate of the number of distinct elements of the
+/// HyperLogLog counter with the given registers.
+fn estimate(registers: &[u8]) -> f64 {
+    let m = registers.len() as f64;
+    let alpha = match registers.len() {
+        16 => 0.673,
+        32 => 0.697,
+        64 => 0.709,
+        _ => 0.7213 / (1.0 + 1.079 / m),
+    };
+    let sum: f64 = registers.iter().map(|&r| (-(r as f64)).exp2()).sum();
+    let estimate = alpha * m * m / sum;
+    let zeros = registers.iter().filter(|&&r| r == 0).count();
+    // linear counting for small cardinalities
+    if estimate <= 2.5 * m && zeros > 0 {
+        m * (m / zeros as f64).ln()
+    } else {
+        estimate
+    }
+}
+
+/// Compute an approximation of the neighbourhood function of a graph using
+/// HyperBall, and return it as a vector whose element of index `t` is the
+/// number of pairs of nodes `(x, y)` such that `y` can be reached from `x`
+/// with at most `t` arcs.
+///
+/// Each node has a HyperLogLog counter with 2<sup>`log2m`</sup> one-byte
+/// registers, which at iteration `t` approximates the ball of radius `t`
+/// around the node: the counter of a node at iteration `t + 1` is the
+/// union of its counter and of the counters of its successors at
+/// iteration `t`. The relative standard deviation of each counter is about
+/// 1.04 / √2<sup>`log2m`</sup>, but errors on the sum over all nodes are
+/// much smaller. The iteration stops when no counter changes, so the length
+/// of the result minus one is a lower bound to the diameter (the maximum
+/// finite distance), and it is usually exact.
+///
+/// `log2m` must be between 4 and 16. The counters are updated in parallel,
+/// and they use 2<sup>`log2m` + 1</sup> bytes per node.
+pub fn neighbourhood_function<G: RandomAccessGraph + Sync>(
+    graph: &G,
+    log2m: usize,
+    seed: u64,
+) -> Result<Vec<f64>> {
+    if !(4..=16).contains(&log2m) {
+        bail!("The number of registers must be between 2^4 and 2^16");
+    }
+    let m = 1 << log2m;
+    let num_nodes = graph.num_nodes();
+
+    // initialize each counter with its node
+    let mut counters = vec![0_u8; num_nodes * m];
+    counters
+        .par_chunks_mut(m)
+        .enumerate()
+        .for_each(|(node, registers)| {
+            let hash = xxhash_rust::xxh3::xxh3_64_with_seed(&(node as u64).to_le_bytes(), seed);
+            let rest = hash >> log2m;
+            let rank = (rest.trailing_zeros() as usize + 1).min(64 - log2m + 1);
+            registers[(hash & (m as u64 - 1)) as usize] = rank as u8;
+        });
+    let mut result = vec![counters.par_chunks(m).map(estimate).sum::<f64>()];
+
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "iteration";
+    pl.start("Computing the neighbourhood function with HyperBall...");
+
+    let mut next = counters.clone();
+    loop {
+        let (modified, sum) = next
+            .par_chunks_mut(m)
+            .enumerate()
+            .map(|(node, registers)| {
+                registers.copy_from_slice(&counters[node * m..(node + 1) * m]);
+                let mut modified = false;
+                for succ in graph.successors(node) {
+                    let other = &counters[succ * m..(succ + 1) * m];
+                    for (r, &o) in registers.iter_mut().zip(other) {
+                        if o > *r {
+                            *r = o;
+                            modified = true;
+                        }
+                    }
+                }
+                (modified as usize, estimate(registers))
+            })
+            .reduce(|| (0, 0.0), |a, b| (a.0 + b.0, a.1 + b.1));
+        if modified == 0 {
+            break;
+        }
+        core::mem::swap(&mut counters, &mut next);
+        // the estimate cannot decrease
+        let last = *result.last().unwrap();
+        result.push(sum.max(last));
+        log::info!(
+            "Iteration {}: {} counters modified, {:.0} pairs",
+            result.len() - 1,
+            modified,
+            sum
+        );
+        pl.light_update();
+    }
+    pl.done();
+
+    Ok(result)
+}
+
+/// Return the effective diameter of a graph with the given neighbourhood
+/// function, that is, the minimum distance within which `alpha` times the
+/// reachable pairs of nodes can be reached, interpolated linearly between
+/// integer distances. The usual value of `alpha` is 0.9.
+///
+/// # Panics
+/// If `neighbourhood_function` is empty, or if `alpha` is not in (0..1].
+pub fn effective_diameter(neighbourhood_function: &[f64], alpha: f64) -> f64 {
+    assert!(!neighbourhood_function.is_empty());
+    assert!(alpha > 0.0 && alpha <= 1.0);
+    let threshold = alpha * neighbourhood_function.last().unwrap();
+    let t = neighbourhood_function
+        .iter()
+        .position(|&n| n >= threshold)
+        .unwrap();
+    if t == 0 {
+        return 0.0;
+    }
+    let (prev, curr) = (neighbourhood_function[t - 1], neighbourhood_function[t]);
+    (t - 1) as f64 + (threshold - prev) / (curr - prev)
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_neighbourhood_function() -> Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    // a symmetric path of 100 nodes
+    let mut arcs = Vec::new();
+    for node in 0..99 {
+        arcs.push((node, node + 1));
+        arcs.push((node + 1, node));
+    }
+    let graph = VecGraph::from_arc_list(&arcs);
+    let nf = neighbourhood_function(&graph, 10, 0)?;
+    assert!(nf.len() <= 100);
+    // the exact value is 100 + 2 * sum_{d=1}^{t} (100 - d)
+    for (t, &n) in nf.iter().enumerate() {
+        let exact = (100 + (1..=t).map(|d| 2 * (100 - d)).sum::<usize>()) as f64;
+        assert!((n - exact).abs() / exact < 0.1, "{} != {}", n, exact);
+    }
+
+    assert_eq!(effective_diameter(&[10.0], 0.9), 0.0);
+    assert!((effective_diameter(&[10.0, 50.0, 100.0], 0.9) - 1.8).abs() < 1E-9);
+    assert!(neighbourhood_function(&graph, 3, 0).is_err());
+    Ok(())
+}
@@ -0,0 +1,196 @@
+use super::{par_bfs, UNREACHABLE};
+use crate::traits::RandomAccessGraph;
+use anyhow::{Context, Result};
+use epserde::prelude::*;
+use rand::prelude::*;
+use rand_xoshiro::Xoshiro256PlusPlus;
+use std::path::Path;
+
+/// How [`Landmarks::build`] chooses the landmarks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LandmarkSelection {
+    /// The nodes with the largest degree (outdegree plus indegree), which
+    /// lie on many shortest paths and usually give the tightest bounds.
+    Degree,
+    /// Nodes chosen uniformly at random using the given seed.
+    Random(u64),
+}
+
+/// A landmark-based distance oracle, as used by the ALT algorithm of
+/// Goldberg and Harrelson, “[Computing the Shortest Path: A* Search Meets
+/// Graph Theory](https://dl.acm.org/doi/10.5555/1070432.1070455)”.
+///
+/// The distances from and to a few landmark nodes are computed once with
+/// breadth-first visits; then, by the triangle inequality, the distance from
+/// `u` to `v` is at least `d(L, v) - d(L, u)` and `d(u, L) - d(v, L)`, and at
+/// most `d(u, L) + d(L, v)`, for every landmark `L`. Answering a query
+/// requires just a scan of the distances of the two nodes, which are stored
+/// contiguously. The oracle can be stored in a sidecar file
+/// `BASENAME.landmarks`, serialized with ε-serde.
+#[derive(Epserde, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Landmarks {
+    /// The landmarks.
+    landmarks: Vec<usize>,
+    /// The distance from the landmark `i` to the node `v` at index
+    /// `v * landmarks.len() + i`, or [`UNREACHABLE`].
+    from: Vec<usize>,
+    /// The distance from the node `v` to the landmark `i` at index
+    /// `v * landmarks.len() + i`, or [`UNREACHABLE`].
+    to: Vec<usize>,
+}
+
+impl Landmarks {
+    /// Choose `num_landmarks` landmarks (or all nodes, if there are fewer)
+    /// and compute their distances from and to all nodes with parallel
+    /// visits of `graph` and of its transpose `transpose`.
+    pub fn build<G: RandomAccessGraph + Sync, T: RandomAccessGraph + Sync>(
+        graph: &G,
+        transpose: &T,
+        num_landmarks: usize,
+        selection: LandmarkSelection,
+    ) -> Self {
+        let num_nodes = graph.num_nodes();
+        assert_eq!(
+            transpose.num_nodes(),
+            num_nodes,
+            "The graph and its transpose have a different number of nodes"
+        );
+        let num_landmarks = num_landmarks.min(num_nodes);
+        let landmarks = match selection {
+            LandmarkSelection::Degree => {
+                let mut nodes = (0..num_nodes).collect::<Vec<_>>();
+                nodes.sort_by_key(|&node| {
+                    std::cmp::Reverse(graph.outdegree(node) + transpose.outdegree(node))
+                });
+                nodes.truncate(num_landmarks);
+                nodes
+            }
+            LandmarkSelection::Random(seed) => {
+                let mut rng = Xoshiro256PlusPlus::seed_from_u64(seed);
+                rand::seq::index::sample(&mut rng, num_nodes, num_landmarks).into_vec()
+            }
+        };
+
+        let mut from = vec![UNREACHABLE; num_nodes * num_landmarks];
+        let mut to = vec![UNREACHABLE; num_nodes * num_landmarks];
+        for (i, &landmark) in landmarks.iter().enumerate() {
+            log::info!("Visiting from landmark {} ({})", i, landmark);
+            for (node, distance) in par_bfs(graph, &[landmark]).into_iter().enumerate() {
+                from[node * num_landmarks + i] = distance;
+            }
+            for (node, distance) in par_bfs(transpose, &[landmark]).into_iter().enumerate() {
+                to[node * num_landmarks + i] = distance;
+            }
+        }
+        Self {
+            landmarks,
+            from,
+            to,
+        }
+    }
+
+    /// Return the landmarks.
+    pub fn landmarks(&self) -> &[usize] {
+        &self.landmarks
+    }
+
+    /// Return a lower and an upper bound on the distance from `u` to `v`.
+    ///
+    /// A lower bound equal to [`UNREACHABLE`] means that `v` is certainly
+    /// not reachable from `u`, and an upper bound equal to [`UNREACHABLE`]
+    /// means that no landmark lies on a path from `u` to `v`, so no upper
+    /// bound is known. The two bounds coincide if a landmark lies on a
+    /// shortest path from `u` to `v`.
+    pub fn distance_bounds(&self, u: usize, v: usize) -> (usize, usize) {
+        if u == v {
+            return (0, 0);
+        }
+        let k = self.landmarks.len();
+        let (from_u, from_v) = (&self.from[u * k..][..k], &self.from[v * k..][..k]);
+        let (to_u, to_v) = (&self.to[u * k..][..k], &self.to[v * k..][..k]);
+        let mut lower = 1;
+        let mut upper = UNREACHABLE;
+        let distances = from_u.iter().zip(from_v).zip(to_u.iter().zip(to_v));
+        for ((&from_u, &from_v), (&to_u, &to_v)) in distances {
+            // d(L, v) ≤ d(L, u) + d(u, v)
+            if from_u != UNREACHABLE {
+                if from_v == UNREACHABLE {
+                    return (UNREACHABLE, UNREACHABLE);
+                }
+                lower = lower.max(from_v.saturating_sub(from_u));
+            }
+            // d(u, L) ≤ d(u, v) + d(v, L)
+            if to_v != UNREACHABLE {
+                if to_u == UNREACHABLE {
+                    return (UNREACHABLE, UNREACHABLE);
+                }
+                lower = lower.max(to_u.saturating_sub(to_v));
+            }
+            // d(u, v) ≤ d(u, L) + d(L, v)
+            if to_u != UNREACHABLE && from_v != UNREACHABLE {
+                upper = upper.min(to_u + from_v);
+            }
+        }
+        (lower, upper)
+    }
+
+    /// Serialize the oracle in the file `BASENAME.landmarks`.
+    pub fn store<P: AsRef<Path>>(&self, basename: P) -> Result<()> {
+        let path = format!("{}.landmarks", basename.as_ref().to_string_lossy());
+        let mut file = std::io::BufWriter::new(
+            std::fs::File::create(&path).with_context(|| format!("Cannot create {}", path))?,
+        );
+        self.serialize(&mut file)?;
+        std::io::Write::flush(&mut file)?;
+        Ok(())
+    }
+
+    /// Deserialize the oracle from the file `BASENAME.landmarks`.
+    pub fn load<P: AsRef<Path>>(basename: P) -> Result<Self> {
+        let path = format!("{}.landmarks", basename.as_ref().to_string_lossy());
+        <Self>::load_full(&path).with_context(|| format!("Cannot deserialize {}", path))
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_landmarks() -> Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    let num_nodes = 100;
+    let mut rng = SmallRng::seed_from_u64(0);
+    let arcs = (0..300)
+        .map(|_| (rng.gen_range(0..num_nodes), rng.gen_range(0..num_nodes)))
+        .collect::<Vec<_>>();
+    let mut graph = VecGraph::from_arc_list(&arcs);
+    graph.add_node(num_nodes - 1);
+    let mut transpose = VecGraph::from_arc_list(
+        &arcs
+            .iter()
+            .map(|&(src, dst)| (dst, src))
+            .collect::<Vec<_>>(),
+    );
+    transpose.add_node(num_nodes - 1);
+
+    let dir = tempfile::tempdir()?;
+    let basename = dir.path().join("graph");
+    for selection in [LandmarkSelection::Degree, LandmarkSelection::Random(0)] {
+        let landmarks = Landmarks::build(&graph, &transpose, 5, selection);
+        assert_eq!(landmarks.landmarks().len(), 5);
+        let mut exact = 0;
+        for u in 0..num_nodes {
+            let distances = par_bfs(&graph, &[u]);
+            for (v, &distance) in distances.iter().enumerate() {
+                let (lower, upper) = landmarks.distance_bounds(u, v);
+                assert!(lower <= distance && distance <= upper);
+                if lower == upper {
+                    exact += 1;
+                }
+            }
+        }
+        assert!(exact > num_nodes);
+
+        landmarks.store(&basename)?;
+        assert_eq!(Landmarks::load(&basename)?, landmarks);
+    }
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use super::triangles::intersect;
+use crate::traits::RandomAccessGraph;
+use dsi_progress_logger::ProgressLogger;
+use rayon::prelude::*;
+
+/// The neighbourhood-based link-prediction scores of a pair of nodes, as
+/// computed by [`link_prediction_scores`].
+///
+/// See Liben-Nowell and Kleinberg, “[The link-prediction problem for social
+/// networks](https://doi.org/10.1002/asi.20591)”, and Zhou, Lü and Zhang,
+/// “[Predicting missing links via local
+/// information](https://doi.org/10.1140/epjb/e2009-00335-8)”.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LinkScores {
+    /// The number of common neighbours.
+    pub common_neighbours: usize,
+    /// The sum of `1 / ln d` over the common neighbours, where `d` is the
+    /// degree of the neighbour; neighbours of degree one, which can only
+    /// occur with loops, are skipped.
+    pub adamic_adar: f64,
+    /// The sum of `1 / d` over the common neighbours, where `d` is the
+    /// degree of the neighbour.
+    pub resource_allocation: f64,
+}
+
+/// Return the common-neighbour, Adamic–Adar and resource-allocation scores of
+/// each of the given pairs of nodes of a symmetric graph, in the same order.
+///
+/// The common neighbours of each pair are found by merging the two sorted
+/// lists of successors, and pairs are processed in parallel. The degrees of
+/// the common neighbours are obtained with [`RandomAccessGraph::outdegree`].
+/// On a graph that is not symmetric, the scores are computed on the common
+/// successors.
+pub fn link_prediction_scores<G: RandomAccessGraph + Sync>(
+    graph: &G,
+    pairs: &[(usize, usize)],
+) -> Vec<LinkScores> {
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "pair";
+    pl.start("Computing link-prediction scores...");
+    let scores = pairs
+        .par_iter()
+        .map_init(Vec::new, |successors, &(u, v)| {
+            successors.clear();
+            successors.extend(graph.successors(u));
+            let mut scores = LinkScores::default();
+            intersect(successors, graph.successors(v), |common| {
+                let degree = graph.outdegree(common);
+                scores.common_neighbours += 1;
+                scores.resource_allocation += 1.0 / degree as f64;
+                if degree > 1 {
+                    scores.adamic_adar += 1.0 / (degree as f64).ln();
+                }
+            });
+            scores
+        })
+        .collect();
+    pl.done_with_count(pairs.len());
+    scores
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_link_prediction_scores() {
+    use crate::graph::vec_graph::VecGraph;
+    // 0 and 1 have the common neighbours 2 (degree 3) and 3 (degree 2)
+    let arcs = [(0, 2), (1, 2), (0, 3), (1, 3), (2, 4), (0, 5)];
+    let mut all = arcs.to_vec();
+    all.extend(arcs.iter().map(|&(src, dst)| (dst, src)));
+    let graph = VecGraph::from_arc_list(&all);
+
+    let scores = link_prediction_scores(&graph, &[(0, 1), (0, 4), (4, 5), (1, 0)]);
+    assert_eq!(scores[0].common_neighbours, 2);
+    assert!((scores[0].adamic_adar - (1.0 / 3_f64.ln() + 1.0 / 2_f64.ln())).abs() < 1E-9);
+    assert!((scores[0].resource_allocation - (1.0 / 3.0 + 1.0 / 2.0)).abs() < 1E-9);
+    assert_eq!(scores[1].common_neighbours, 1);
+    assert_eq!(scores[2], LinkScores::default());
+    assert_eq!(scores[3], scores[0]);
+}
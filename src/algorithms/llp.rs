@@ -1,6 +1,7 @@
 use crate::traits::*;
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use dsi_progress_logger::ProgressLogger;
+use epserde::prelude::*;
 use log::info;
 use rand::rngs::SmallRng;
 use rand::seq::SliceRandom;
@@ -8,12 +9,143 @@ use rand::SeedableRng;
 use rayon::prelude::*;
 use rayon::slice::ParallelSliceMut;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::Ordering;
-use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize};
 use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
-/// Write the permutation computed by the LLP algorithm inside `perm`,
-/// and return the labels of said permutation.
+/// Checkpointing options for [`layered_label_propagation`].
+#[derive(Clone, Debug)]
+pub struct LlpCheckpoint {
+    /// The directory where the state is saved.
+    pub dir: PathBuf,
+    /// The minimum time between two saves of the state, which happen at the
+    /// end of an iteration.
+    pub interval: Duration,
+    /// A directory containing a checkpoint to resume from, which can be the
+    /// same as `dir`.
+    pub resume_from: Option<PathBuf>,
+}
+
+/// When [`layered_label_propagation`] stops iterating for a gamma.
+///
+/// The iterations stop as soon as one of the criteria is met. The default
+/// values stop after 100 iterations or when no label is modified.
+#[derive(Clone, Copy, Debug)]
+pub struct LlpConvergence {
+    /// The maximum number of iterations.
+    pub max_iters: usize,
+    /// Stop when the fraction of nodes whose label was modified during an
+    /// iteration is at most this value.
+    pub modified_threshold: f64,
+    /// Stop when the gain of the objective function during an iteration is
+    /// at most this value.
+    pub delta_threshold: Option<f64>,
+    /// Stop after the first iteration ending after this time from the start
+    /// of the computation for the gamma.
+    pub max_time: Option<Duration>,
+}
+
+impl Default for LlpConvergence {
+    fn default() -> Self {
+        Self {
+            max_iters: 100,
+            modified_threshold: 0.0,
+            delta_threshold: None,
+            max_time: None,
+        }
+    }
+}
+
+impl LlpConvergence {
+    /// Return whether the iterations should stop, given the statistics of
+    /// the last one.
+    fn is_done(&self, stats: &LlpIterationStats, num_nodes: usize) -> bool {
+        stats.iteration + 1 >= self.max_iters
+            || stats.modified as f64 <= self.modified_threshold * num_nodes as f64
+            || self
+                .delta_threshold
+                .map_or(false, |threshold| stats.delta <= threshold)
+            || self
+                .max_time
+                .map_or(false, |max_time| stats.elapsed >= max_time)
+    }
+}
+
+/// The statistics of an iteration of [`layered_label_propagation`].
+#[derive(Clone, Copy, Debug)]
+pub struct LlpIterationStats {
+    /// The gamma of the iteration.
+    pub gamma: f64,
+    /// The index of the gamma in the list of gammas.
+    pub gamma_index: usize,
+    /// The index of the iteration, starting from zero for each gamma.
+    pub iteration: usize,
+    /// The number of nodes whose label was modified.
+    pub modified: usize,
+    /// The gain of the objective function.
+    pub delta: f64,
+    /// The time elapsed since the start of the computation for the gamma.
+    pub elapsed: Duration,
+}
+
+/// The state of a run of [`layered_label_propagation`], serialized with
+/// ε-serde in the file `state` of the checkpoint directory. The labelling
+/// computed for the gamma of index `i` is serialized in the file `labels-i`.
+#[derive(Epserde, Debug, Clone, Default)]
+struct LlpState {
+    /// The gammas of the run.
+    gammas: Vec<f64>,
+    /// The index of the gamma being processed.
+    gamma_index: usize,
+    /// The number of iterations completed for the current gamma.
+    iteration: usize,
+    /// The costs of the labellings of the previous gammas.
+    costs: Vec<f64>,
+    /// The labels for the current gamma, empty if no iteration was completed.
+    labels: Vec<usize>,
+}
+
+/// Serialize `value` in the file `name` of `dir`, atomically replacing the
+/// previous content.
+fn save_atomically<T: Serialize>(dir: &Path, name: &str, value: &T) -> Result<()> {
+    let tmp_path = dir.join(format!("{}.tmp", name));
+    let mut file = std::io::BufWriter::new(
+        std::fs::File::create(&tmp_path)
+            .with_context(|| format!("Cannot create {}", tmp_path.display()))?,
+    );
+    value.serialize(&mut file)?;
+    std::io::Write::flush(&mut file)?;
+    drop(file);
+    std::fs::rename(&tmp_path, dir.join(name))?;
+    Ok(())
+}
+
+/// Run label propagation for each of the given gammas, combine the resulting
+/// labellings, write in `perm` the corresponding permutation and return the
+/// combined labels.
+///
+/// The permutation maps each node to its rank in the new order, that is,
+/// `perm[x]` is the new index of node `x`, which is the format expected by
+/// [`PermutedGraph`](crate::graph::permuted_graph::PermutedGraph) and by the
+/// `perm` binary; it is not the list of the nodes in the new order, which is
+/// its inverse.
+///
+/// The labellings are combined as in the Java implementation: the one whose
+/// induced permutation has the smallest [log-gap cost](log_gap_cost) is used
+/// to sort the nodes, and ties are broken using in turn the other labellings,
+/// by increasing cost. Thus, clusters found with large gammas are refined by
+/// the ones found with smaller gammas.
+///
+/// For each gamma, label propagation is iterated until one of the criteria
+/// in `convergence` is met; after each iteration, `on_iteration`, if given,
+/// is called with the statistics of the iteration.
+///
+/// If `checkpoint` is given, the state of the computation is periodically
+/// saved in a directory, and it can be resumed from there after an
+/// interruption. Resumed runs are not bit-for-bit identical to uninterrupted
+/// ones, as the random permutations of the nodes are different.
 ///
 /// # References
 /// [Layered Label Propagation: A MultiResolution Coordinate-Free Ordering for Compressing Social Networks](https://arxiv.org/pdf/1011.5425.pdf>)
@@ -22,12 +154,14 @@ use std::sync::Mutex;
 pub fn layered_label_propagation<G>(
     graph: &G,
     perm: &mut [usize],
-    gamma: f64,
+    gammas: &[f64],
     num_cpus: Option<usize>,
-    max_iters: usize,
+    convergence: &LlpConvergence,
     chunk_size: usize,
     granularity: usize,
     seed: u64,
+    checkpoint: Option<&LlpCheckpoint>,
+    on_iteration: Option<&mut dyn FnMut(&LlpIterationStats)>,
 ) -> Result<Box<[usize]>>
 where
     G: RandomAccessGraph,
@@ -43,25 +177,200 @@ where
             num_nodes
         );
     }
-    // init the permutation with the indices
-    perm.iter_mut().enumerate().for_each(|(i, x)| *x = i);
-
-    let mut can_change = Vec::with_capacity(num_nodes as _);
-    can_change.extend((0..num_nodes).map(|_| AtomicBool::new(true)));
-    let label_store = LabelStore::new(num_nodes as _);
+    if gammas.is_empty() {
+        bail!("At least one gamma is needed.");
+    }
 
     // build a thread_pool so we avoid having to re-create the threads
     let thread_pool = rayon::ThreadPoolBuilder::new()
         .num_threads(num_cpus)
         .build()?;
 
+    let mut labellings = Vec::with_capacity(gammas.len());
+    let mut costs = Vec::with_capacity(gammas.len());
+    let mut resume = None;
+    if let Some(checkpoint) = checkpoint {
+        std::fs::create_dir_all(&checkpoint.dir)?;
+        if let Some(resume_from) = &checkpoint.resume_from {
+            let state_path = resume_from.join("state");
+            let state = <LlpState>::load_full(&state_path)
+                .with_context(|| format!("Cannot load {}", state_path.display()))?;
+            if state.gammas != gammas {
+                bail!(
+                    "The checkpoint was created with gammas {:?}, not {:?}.",
+                    state.gammas,
+                    gammas
+                );
+            }
+            info!(
+                "Resuming from gamma {}/{}, iteration {}",
+                state.gamma_index + 1,
+                gammas.len(),
+                state.iteration
+            );
+            for gamma_index in 0..state.gamma_index {
+                let name = format!("labels-{}", gamma_index);
+                let labels = <Vec<usize>>::load_full(resume_from.join(&name))?;
+                if resume_from != &checkpoint.dir {
+                    save_atomically(&checkpoint.dir, &name, &labels)?;
+                }
+                labellings.push(labels.into_boxed_slice());
+            }
+            costs = state.costs;
+            if !state.labels.is_empty() {
+                resume = Some((state.iteration, state.labels));
+            }
+        }
+    }
+
+    // use 32-bit labels and volumes when possible, halving the memory used
+    let propagate = if num_nodes <= u32::MAX as usize {
+        label_propagation::<G, CompactLabelStore>
+    } else {
+        label_propagation::<G, LabelStore>
+    };
+
+    let mut noop = |_: &LlpIterationStats| {};
+    let on_iteration: &mut dyn FnMut(&LlpIterationStats) = match on_iteration {
+        Some(on_iteration) => on_iteration,
+        None => &mut noop,
+    };
+
+    for (gamma_index, &gamma) in gammas.iter().enumerate().skip(labellings.len()) {
+        info!(
+            "Running label propagation with gamma {} ({}/{})",
+            gamma,
+            gamma_index + 1,
+            gammas.len()
+        );
+        let labels = propagate(
+            graph,
+            perm,
+            gamma,
+            gamma_index,
+            &thread_pool,
+            num_cpus,
+            convergence,
+            chunk_size,
+            granularity,
+            // use a different sequence of seeds for each gamma
+            seed ^ (gamma_index as u64).wrapping_mul(0x9e3779b97f4a7c15),
+            resume.take(),
+            &mut |iteration, labels| {
+                let Some(checkpoint) = checkpoint else {
+                    return Ok(());
+                };
+                info!("Saving the state after iteration {}", iteration);
+                save_atomically(
+                    &checkpoint.dir,
+                    "state",
+                    &LlpState {
+                        gammas: gammas.to_vec(),
+                        gamma_index,
+                        iteration,
+                        costs: costs.clone(),
+                        labels,
+                    },
+                )
+            },
+            checkpoint.map(|checkpoint| checkpoint.interval),
+            &mut *on_iteration,
+        )?;
+        // compute the cost of the permutation induced by the labels
+        let mut compact_labels = labels.to_vec();
+        let cost = thread_pool.install(|| {
+            combine(&mut compact_labels, &labels, perm);
+            labels_to_perm(&compact_labels, perm);
+            log_gap_cost(graph, perm)
+        });
+        info!("Log-gap cost for gamma {}: {}", gamma, cost);
+        labellings.push(labels);
+        costs.push(cost);
+        if let Some(checkpoint) = checkpoint {
+            save_atomically(
+                &checkpoint.dir,
+                &format!("labels-{}", gamma_index),
+                &labellings[gamma_index].to_vec(),
+            )?;
+            save_atomically(
+                &checkpoint.dir,
+                "state",
+                &LlpState {
+                    gammas: gammas.to_vec(),
+                    gamma_index: gamma_index + 1,
+                    costs: costs.clone(),
+                    ..Default::default()
+                },
+            )?;
+        }
+    }
+
+    // combine the labellings, starting from the best one
+    let mut order = (0..gammas.len()).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| costs[a].total_cmp(&costs[b]));
+    info!(
+        "Best gamma: {} (cost {})",
+        gammas[order[0]], costs[order[0]]
+    );
+    let mut result = labellings[order[0]].to_vec();
+    for &gamma_index in &order[1..] {
+        thread_pool.install(|| combine(&mut result, &labellings[gamma_index], perm));
+    }
+    labels_to_perm(&result, perm);
+
+    Ok(result.into_boxed_slice())
+}
+
+/// Run label propagation with the given gamma and return the labels, using
+/// `perm` as scratch space for the random permutation of the nodes.
+///
+/// If `resume` is given, the computation restarts from the given labels
+/// after the given number of iterations. `save` is called at the end of an
+/// iteration with the number of completed iterations if at least
+/// `save_interval` has elapsed since the start or the previous call, and
+/// `on_iteration` is called with the statistics of each iteration.
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)]
+fn label_propagation<G, L: Labels>(
+    graph: &G,
+    perm: &mut [usize],
+    gamma: f64,
+    gamma_index: usize,
+    thread_pool: &rayon::ThreadPool,
+    num_cpus: usize,
+    convergence: &LlpConvergence,
+    chunk_size: usize,
+    granularity: usize,
+    seed: u64,
+    resume: Option<(usize, Vec<usize>)>,
+    save: &mut dyn FnMut(usize, Vec<usize>) -> Result<()>,
+    save_interval: Option<Duration>,
+    on_iteration: &mut dyn FnMut(&LlpIterationStats),
+) -> Result<Box<[usize]>>
+where
+    G: RandomAccessGraph,
+    for<'a> &'a G: Send + Sync,
+{
+    let num_nodes = graph.num_nodes();
+    // init the permutation with the indices
+    perm.iter_mut().enumerate().for_each(|(i, x)| *x = i);
+
+    let mut can_change = Vec::with_capacity(num_nodes as _);
+    can_change.extend((0..num_nodes).map(|_| AtomicBool::new(true)));
+    let (start_iter, label_store) = match resume {
+        None => (0, L::new(num_nodes as _)),
+        Some((iteration, labels)) => (iteration, L::from_labels(labels)),
+    };
+    let start = Instant::now();
+    let mut last_save = start;
+
     // init the progress logger
     let mut glob_pr = ProgressLogger::default().display_memory();
     glob_pr.item_name = "update";
     glob_pr.start("Starting updates...");
 
     let seed = AtomicU64::new(seed);
-    for _ in 0..max_iters {
+    for iteration in start_iter..convergence.max_iters {
         thread_pool.install(|| {
             // parallel shuffle using the num_cpus
             perm.par_chunks_mut(chunk_size).for_each(|chunk| {
@@ -171,34 +480,131 @@ where
         });
 
         pr.done_with_count(num_nodes as _);
-        info!(
-            "Modified: {} Delta: {}",
-            modified.load(Ordering::Relaxed),
-            delta.lock().unwrap()
-        );
+        let stats = LlpIterationStats {
+            gamma,
+            gamma_index,
+            iteration,
+            modified: modified.load(Ordering::Relaxed),
+            delta: *delta.lock().unwrap(),
+            elapsed: start.elapsed(),
+        };
+        info!("Modified: {} Delta: {}", stats.modified, stats.delta);
         glob_pr.update_and_display();
-        if modified.load(Ordering::Relaxed) == 0 {
+        on_iteration(&stats);
+        if convergence.is_done(&stats, num_nodes) {
             break;
         }
+        if let Some(save_interval) = save_interval {
+            if last_save.elapsed() >= save_interval {
+                save(iteration + 1, label_store.labels())?;
+                last_save = Instant::now();
+            }
+        }
     }
 
     glob_pr.done();
 
-    // create sorted clusters by contiguous labels
-    perm.par_sort_unstable_by(|&a, &b| label_store.label(a as _).cmp(&label_store.label(b as _)));
+    Ok(label_store.into_labels())
+}
+
+/// Sort the nodes lexicographically by `(major[x], minor[x])` and relabel
+/// `major` with the rank of the pair of each node among the distinct pairs,
+/// using `perm` as scratch space. Return the number of distinct pairs.
+///
+/// After the call, `perm` contains the nodes sorted by their new label.
+fn combine(major: &mut [usize], minor: &[usize], perm: &mut [usize]) -> usize {
+    perm.iter_mut().enumerate().for_each(|(i, x)| *x = i);
+    perm.par_sort_unstable_by_key(|&x| (major[x], minor[x], x));
+    let mut num_labels = 0;
+    let mut prev = None;
+    for &x in perm.iter() {
+        let pair = (major[x], minor[x]);
+        if prev != Some(pair) {
+            num_labels += 1;
+            prev = Some(pair);
+        }
+        major[x] = num_labels - 1;
+    }
+    num_labels
+}
+
+/// Write in `perm` the permutation sorting the nodes by label, breaking ties
+/// by node index, so that `perm[x]` is the rank of node `x`; `labels` must
+/// be smaller than the number of nodes, as those computed by label
+/// propagation or by [`combine`].
+fn labels_to_perm(labels: &[usize], perm: &mut [usize]) {
+    // counting sort, to avoid allocating
+    perm.iter_mut().for_each(|x| *x = 0);
+    for &label in labels {
+        perm[label] += 1;
+    }
+    let mut start = 0;
+    for count in perm.iter_mut() {
+        let next = start + *count;
+        *count = start;
+        start = next;
+    }
+    // perm[label] now contains the first rank of the label
+    let mut ranks = vec![0; labels.len()];
+    for (node, &label) in labels.iter().enumerate() {
+        ranks[node] = perm[label];
+        perm[label] += 1;
+    }
+    perm.copy_from_slice(&ranks);
+}
 
-    let labels =
-        unsafe { std::mem::transmute::<Box<[AtomicUsize]>, Box<[usize]>>(label_store.labels) };
+/// Return the log-gap cost of the graph renumbered with `perm`, that is, the
+/// sum over all nodes of the base-2 logarithms of the gaps between the
+/// (sorted) renumbered successors, plus one, where the first gap is taken
+/// with respect to the renumbered node itself.
+///
+/// This is a good proxy of the size of the compressed graph.
+pub fn log_gap_cost<G>(graph: &G, perm: &[usize]) -> f64
+where
+    G: RandomAccessGraph,
+    for<'a> &'a G: Send + Sync,
+{
+    (0..graph.num_nodes())
+        .into_par_iter()
+        .map(|x| {
+            let mut succ = graph.successors(x).map(|s| perm[s]).collect::<Vec<_>>();
+            if succ.is_empty() {
+                return 0.0;
+            }
+            succ.sort_unstable();
+            let mut cost = ((perm[x].abs_diff(succ[0]) + 1) as f64).log2();
+            cost += succ
+                .windows(2)
+                .map(|w| ((w[1] - w[0] + 1) as f64).log2())
+                .sum::<f64>();
+            cost
+        })
+        .sum()
+}
 
-    Ok(labels)
+/// The operations on the labels and on the volumes (the number of nodes with
+/// a given label) needed by label propagation.
+trait Labels: Sync + Sized {
+    /// Create a store in which each node has its own label.
+    fn new(n: usize) -> Self;
+    /// Create a store with the given labels, computing the volumes.
+    fn from_labels(labels: Vec<usize>) -> Self;
+    /// Return a copy of the labels.
+    fn labels(&self) -> Vec<usize>;
+    /// Consume the store and return the labels.
+    fn into_labels(self) -> Box<[usize]>;
+    fn set(&self, node: usize, new_label: usize);
+    fn label(&self, node: usize) -> usize;
+    fn volume(&self, label: usize) -> usize;
 }
 
+/// A label store using a word for each label and for each volume.
 struct LabelStore {
     labels: Box<[AtomicUsize]>,
     volumes: Box<[AtomicUsize]>,
 }
 
-impl LabelStore {
+impl Labels for LabelStore {
     fn new(n: usize) -> Self {
         let mut labels = Vec::with_capacity(n);
         let mut volumes = Vec::with_capacity(n);
@@ -212,6 +618,32 @@ impl LabelStore {
         }
     }
 
+    fn from_labels(labels: Vec<usize>) -> Self {
+        let mut volumes = Vec::with_capacity(labels.len());
+        volumes.extend((0..labels.len()).map(|_| AtomicUsize::new(0)));
+        for &label in &labels {
+            *volumes[label].get_mut() += 1;
+        }
+        Self {
+            // AtomicUsize has the same in-memory representation as usize
+            labels: unsafe {
+                std::mem::transmute::<Box<[usize]>, Box<[AtomicUsize]>>(labels.into_boxed_slice())
+            },
+            volumes: volumes.into_boxed_slice(),
+        }
+    }
+
+    fn labels(&self) -> Vec<usize> {
+        self.labels
+            .iter()
+            .map(|label| label.load(Ordering::Relaxed))
+            .collect()
+    }
+
+    fn into_labels(self) -> Box<[usize]> {
+        unsafe { std::mem::transmute::<Box<[AtomicUsize]>, Box<[usize]>>(self.labels) }
+    }
+
     fn set(&self, node: usize, new_label: usize) {
         let old_label = self.labels[node].swap(new_label, Ordering::Relaxed);
         self.volumes[old_label].fetch_sub(1, Ordering::Relaxed);
@@ -229,3 +661,251 @@ impl LabelStore {
 
 unsafe impl Send for LabelStore {}
 unsafe impl Sync for LabelStore {}
+
+/// A label store using 32-bit labels and volumes, which halves the memory
+/// used by [`LabelStore`]. It can be used only on graphs with less than 2³²
+/// nodes, as labels are node indices and volumes are at most the number of
+/// nodes.
+struct CompactLabelStore {
+    labels: Box<[AtomicU32]>,
+    volumes: Box<[AtomicU32]>,
+}
+
+impl Labels for CompactLabelStore {
+    fn new(n: usize) -> Self {
+        debug_assert!(n <= u32::MAX as usize);
+        let mut labels = Vec::with_capacity(n);
+        let mut volumes = Vec::with_capacity(n);
+        for l in 0..n {
+            labels.push(AtomicU32::new(l as u32));
+            volumes.push(AtomicU32::new(1));
+        }
+        Self {
+            labels: labels.into_boxed_slice(),
+            volumes: volumes.into_boxed_slice(),
+        }
+    }
+
+    fn from_labels(labels: Vec<usize>) -> Self {
+        debug_assert!(labels.len() <= u32::MAX as usize);
+        let mut volumes = Vec::with_capacity(labels.len());
+        volumes.extend((0..labels.len()).map(|_| AtomicU32::new(0)));
+        for &label in &labels {
+            *volumes[label].get_mut() += 1;
+        }
+        Self {
+            labels: labels
+                .into_iter()
+                .map(|label| AtomicU32::new(label as u32))
+                .collect(),
+            volumes: volumes.into_boxed_slice(),
+        }
+    }
+
+    fn labels(&self) -> Vec<usize> {
+        self.labels
+            .iter()
+            .map(|label| label.load(Ordering::Relaxed) as usize)
+            .collect()
+    }
+
+    fn into_labels(self) -> Box<[usize]> {
+        self.labels
+            .into_vec()
+            .into_iter()
+            .map(|label| label.into_inner() as usize)
+            .collect()
+    }
+
+    fn set(&self, node: usize, new_label: usize) {
+        let old_label = self.labels[node].swap(new_label as u32, Ordering::Relaxed);
+        self.volumes[old_label as usize].fetch_sub(1, Ordering::Relaxed);
+        self.volumes[new_label].fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn label(&self, node: usize) -> usize {
+        self.labels[node].load(Ordering::Relaxed) as usize
+    }
+
+    fn volume(&self, label: usize) -> usize {
+        self.volumes[label].load(Ordering::Relaxed) as usize
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_llp() -> Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    // two cliques connected by a single arc
+    let mut arcs = vec![];
+    for (start, end) in [(0, 4), (4, 8)] {
+        for x in start..end {
+            for y in start..end {
+                if x != y {
+                    arcs.push((x, y));
+                }
+            }
+        }
+    }
+    arcs.push((3, 4));
+    let g = VecGraph::from_arc_list(&arcs);
+    let mut perm = vec![0; 8];
+    let labels = layered_label_propagation(
+        &g,
+        &mut perm,
+        &[0.0, 1.0],
+        Some(2),
+        &LlpConvergence::default(),
+        4,
+        2,
+        0,
+        None,
+        None,
+    )?;
+    // perm must be a permutation
+    let mut sorted = perm.clone();
+    sorted.sort();
+    assert_eq!(sorted, (0..8).collect::<Vec<_>>());
+    // and sort the nodes by label
+    for x in 0..8 {
+        for y in 0..8 {
+            if labels[x] < labels[y] {
+                assert!(perm[x] < perm[y]);
+            }
+        }
+    }
+
+    // a threshold of 1 stops after the first iteration of each gamma
+    let mut iterations = vec![];
+    layered_label_propagation(
+        &g,
+        &mut perm,
+        &[0.0, 1.0],
+        Some(2),
+        &LlpConvergence {
+            modified_threshold: 1.0,
+            ..Default::default()
+        },
+        4,
+        2,
+        0,
+        None,
+        Some(&mut |stats: &LlpIterationStats| {
+            iterations.push((stats.gamma_index, stats.iteration))
+        }),
+    )?;
+    assert_eq!(iterations, vec![(0, 0), (1, 0)]);
+
+    // with a single gamma there is nothing to combine, but perm must still
+    // map each node to its rank
+    let labels = layered_label_propagation(
+        &g,
+        &mut perm,
+        &[0.0],
+        Some(2),
+        &LlpConvergence::default(),
+        4,
+        2,
+        0,
+        None,
+        None,
+    )?;
+    let mut sorted = perm.clone();
+    sorted.sort();
+    assert_eq!(sorted, (0..8).collect::<Vec<_>>());
+    for x in 0..8 {
+        for y in 0..8 {
+            if (labels[x], x) < (labels[y], y) {
+                assert!(perm[x] < perm[y]);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_llp_checkpoint() -> Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    let g = VecGraph::from_arc_list(&[(0, 1), (1, 0), (1, 2), (2, 1), (3, 4), (4, 3), (2, 3)]);
+    let dir = tempfile::tempdir()?;
+    let mut checkpoint = LlpCheckpoint {
+        dir: dir.path().to_owned(),
+        interval: Duration::ZERO,
+        resume_from: None,
+    };
+    let gammas = [0.0, 0.5];
+    let convergence = LlpConvergence {
+        max_iters: 10,
+        ..Default::default()
+    };
+    let mut perm = vec![0; 5];
+    let labels = layered_label_propagation(
+        &g,
+        &mut perm,
+        &gammas,
+        Some(2),
+        &convergence,
+        2,
+        1,
+        0,
+        Some(&checkpoint),
+        None,
+    )?;
+    assert!(dir.path().join("state").exists());
+    assert!(dir.path().join("labels-1").exists());
+
+    // resuming a completed run just combines the saved labellings
+    checkpoint.resume_from = Some(dir.path().to_owned());
+    let mut resumed_perm = vec![0; 5];
+    let resumed_labels = layered_label_propagation(
+        &g,
+        &mut resumed_perm,
+        &gammas,
+        Some(2),
+        &convergence,
+        2,
+        1,
+        0,
+        Some(&checkpoint),
+        None,
+    )?;
+    assert_eq!(labels, resumed_labels);
+    assert_eq!(perm, resumed_perm);
+
+    // the gammas must match
+    assert!(layered_label_propagation(
+        &g,
+        &mut perm,
+        &[0.0],
+        Some(2),
+        &convergence,
+        2,
+        1,
+        0,
+        Some(&checkpoint),
+        None,
+    )
+    .is_err());
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_label_stores() {
+    fn check<L: Labels>() {
+        let store = L::new(4);
+        store.set(0, 2);
+        store.set(1, 2);
+        assert_eq!(store.labels(), vec![2, 2, 2, 3]);
+        assert_eq!(store.volume(0), 0);
+        assert_eq!(store.volume(2), 3);
+        let store = L::from_labels(store.labels());
+        assert_eq!(store.volume(2), 3);
+        assert_eq!(store.volume(3), 1);
+        assert_eq!(store.label(1), 2);
+        assert_eq!(&*store.into_labels(), &[2, 2, 2, 3]);
+    }
+    check::<LabelStore>();
+    check::<CompactLabelStore>();
+}
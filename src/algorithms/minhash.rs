@@ -0,0 +1,174 @@
+use crate::traits::SequentialGraph;
+use anyhow::{Context, Result};
+use dsi_progress_logger::ProgressLogger;
+use epserde::prelude::*;
+use rayon::prelude::*;
+use std::path::Path;
+
+/// The MinHash signatures of the successor sets of the nodes of a graph, as
+/// in Broder, “[On the resemblance and containment of
+/// documents](https://doi.org/10.1109/SEQUEN.1997.666900)”.
+///
+/// The signature of a node contains, for each of a number of hash functions,
+/// the minimum hash of its successors; the probability that two signatures
+/// agree on a component is the Jaccard similarity of the two successor
+/// sets. Signatures can be used to estimate similarities with
+/// [`MinHashSignatures::similarity`], and to compute the shingle ordering of
+/// Chierichetti, Kumar, Lattanzi, Mitzenmacher, Panconesi and Raghavan, “[On
+/// Compressing Social Networks](https://doi.org/10.1145/1557019.1557049)”,
+/// with [`MinHashSignatures::shingle_order`]. They can be stored in a sidecar
+/// file `BASENAME.minhash`, serialized with ε-serde.
+#[derive(Epserde, Debug, Clone, Default, PartialEq, Eq)]
+pub struct MinHashSignatures {
+    /// The number of components of a signature.
+    num_hashes: usize,
+    /// The component `i` of the signature of node `v` at index
+    /// `v * num_hashes + i`; nodes without successors have all components
+    /// equal to `u64::MAX`.
+    signatures: Vec<u64>,
+}
+
+impl MinHashSignatures {
+    /// Compute the signatures with `num_hashes` components of all nodes with
+    /// a single sequential pass on the graph.
+    ///
+    /// The hash functions are seeded variants of XXH3 derived from `seed`.
+    ///
+    /// # Panics
+    /// If `num_hashes` is zero.
+    pub fn build<G: SequentialGraph>(graph: &G, num_hashes: usize, seed: u64) -> Self {
+        assert!(
+            num_hashes > 0,
+            "Signatures must have at least one component"
+        );
+        let mut signatures = Vec::with_capacity(graph.num_nodes() * num_hashes);
+
+        let mut pl = ProgressLogger::default().display_memory();
+        pl.item_name = "node";
+        pl.expected_updates = Some(graph.num_nodes());
+        pl.start("Computing MinHash signatures...");
+        for (_, succ) in graph.iter_nodes() {
+            let start = signatures.len();
+            signatures.resize(start + num_hashes, u64::MAX);
+            let signature = &mut signatures[start..];
+            for succ in succ {
+                let bytes = (succ as u64).to_le_bytes();
+                for (i, component) in signature.iter_mut().enumerate() {
+                    let hash =
+                        xxhash_rust::xxh3::xxh3_64_with_seed(&bytes, seed.wrapping_add(i as u64));
+                    *component = (*component).min(hash);
+                }
+            }
+            pl.light_update();
+        }
+        pl.done();
+
+        Self {
+            num_hashes,
+            signatures,
+        }
+    }
+
+    /// Return the number of nodes.
+    pub fn num_nodes(&self) -> usize {
+        self.signatures.len() / self.num_hashes
+    }
+
+    /// Return the signature of a node.
+    pub fn signature(&self, node: usize) -> &[u64] {
+        &self.signatures[node * self.num_hashes..][..self.num_hashes]
+    }
+
+    /// Return an estimate of the Jaccard similarity of the successor sets of
+    /// `u` and `v`, that is, the fraction of the components on which their
+    /// signatures agree.
+    ///
+    /// The standard deviation of the estimate is at most 1 / (2 √*k*), where
+    /// *k* is the number of components. Two nodes without successors have
+    /// similarity one.
+    pub fn similarity(&self, u: usize, v: usize) -> f64 {
+        let agreements = self
+            .signature(u)
+            .iter()
+            .zip(self.signature(v))
+            .filter(|(a, b)| a == b)
+            .count();
+        agreements as f64 / self.num_hashes as f64
+    }
+
+    /// Return the permutation mapping each node to its rank when the nodes
+    /// are sorted by their signatures in lexicographical order.
+    ///
+    /// Nodes with the same smallest hash, the first component of their
+    /// signatures, share a successor; sorting by the following components
+    /// brings together nodes with many common successors, making the
+    /// successor lists of nearby nodes similar, which improves compression
+    /// by referencing. Nodes with the same signature keep their relative
+    /// order.
+    pub fn shingle_order(&self) -> Vec<usize> {
+        let mut order = (0..self.num_nodes()).collect::<Vec<_>>();
+        order.par_sort_by(|&u, &v| self.signature(u).cmp(self.signature(v)));
+        let mut perm = vec![0; order.len()];
+        for (rank, node) in order.into_iter().enumerate() {
+            perm[node] = rank;
+        }
+        perm
+    }
+
+    /// Serialize the signatures in the file `BASENAME.minhash`.
+    pub fn store<P: AsRef<Path>>(&self, basename: P) -> Result<()> {
+        let path = format!("{}.minhash", basename.as_ref().to_string_lossy());
+        let mut file = std::io::BufWriter::new(
+            std::fs::File::create(&path).with_context(|| format!("Cannot create {}", path))?,
+        );
+        self.serialize(&mut file)?;
+        std::io::Write::flush(&mut file)?;
+        Ok(())
+    }
+
+    /// Deserialize the signatures from the file `BASENAME.minhash`.
+    pub fn load<P: AsRef<Path>>(basename: P) -> Result<Self> {
+        let path = format!("{}.minhash", basename.as_ref().to_string_lossy());
+        <Self>::load_full(&path).with_context(|| format!("Cannot deserialize {}", path))
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_minhash() -> Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    let mut arcs = Vec::new();
+    // nodes 0 and 2 have the same successors, node 1 shares half of them,
+    // and node 3 none
+    for succ in 10..110 {
+        arcs.push((0, succ));
+        arcs.push((2, succ));
+    }
+    for succ in 60..160 {
+        arcs.push((1, succ));
+    }
+    for succ in 200..300 {
+        arcs.push((3, succ));
+    }
+    let graph = VecGraph::from_arc_list(&arcs);
+    let signatures = MinHashSignatures::build(&graph, 256, 0);
+    assert_eq!(signatures.num_nodes(), graph.num_nodes());
+
+    assert_eq!(signatures.similarity(0, 2), 1.0);
+    // the Jaccard similarity of 0 and 1 is 50 / 150
+    assert!((signatures.similarity(0, 1) - 1.0 / 3.0).abs() < 0.15);
+    assert!(signatures.similarity(0, 3) < 0.05);
+    assert_eq!(signatures.similarity(10, 11), 1.0);
+
+    let perm = signatures.shingle_order();
+    assert_eq!(perm[0].abs_diff(perm[2]), 1);
+    let mut sorted = perm.clone();
+    sorted.sort();
+    assert_eq!(sorted, (0..graph.num_nodes()).collect::<Vec<_>>());
+
+    let dir = tempfile::tempdir()?;
+    let basename = dir.path().join("graph");
+    signatures.store(&basename)?;
+    assert_eq!(MinHashSignatures::load(&basename)?, signatures);
+    Ok(())
+}
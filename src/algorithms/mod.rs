@@ -1,14 +1,102 @@
 mod llp;
-pub use llp::layered_label_propagation;
+pub use llp::{
+    layered_label_propagation, log_gap_cost, LlpCheckpoint, LlpConvergence, LlpIterationStats,
+};
 
 mod bfs_order;
 pub use bfs_order::bfs_order;
 
+mod par_bfs;
+pub use par_bfs::{par_bfs, par_bfs_direction_optimizing, UNREACHABLE};
+
+mod bidirectional_bfs;
+pub use bidirectional_bfs::bidirectional_bfs;
+
+mod bp;
+pub use bp::bp;
+
+mod scc;
+pub use scc::{condensation, sccs, Sccs};
+
+mod topological_sort;
+pub use topological_sort::{topological_sort, Cycle};
+
+mod dijkstra;
+pub use dijkstra::{astar, astar_with_arity, dijkstra, dijkstra_with_arity, ShortestPaths};
+
+mod delta_stepping;
+pub use delta_stepping::delta_stepping;
+
+mod landmarks;
+pub use landmarks::{LandmarkSelection, Landmarks};
+
+mod hyperball;
+pub use hyperball::{effective_diameter, neighbourhood_function};
+
+mod diameter;
+pub use diameter::ifub_diameter;
+
+mod centrality;
+pub use centrality::{approximate_centralities, centralities, Centralities};
+
+mod betweenness;
+pub use betweenness::{approximate_betweenness, betweenness, betweenness_samples};
+
+mod power_iteration;
+pub use power_iteration::{
+    eigenvector_centrality, hits, katz_centrality, Hits, PowerIterationConvergence,
+};
+
+mod ppr;
+pub use ppr::personalized_pagerank;
+
+mod triangles;
+pub use triangles::{count_triangles, triangles};
+
+mod link_prediction;
+pub use link_prediction::{link_prediction_scores, LinkScores};
+
+mod clustering;
+pub use clustering::{
+    clustering_coefficients, local_clustering_coefficients, store_clustering_coefficients,
+    ClusteringStats,
+};
+
+mod minhash;
+pub use minhash::MinHashSignatures;
+
+mod degree_dist;
+pub use degree_dist::*;
+
+mod degree_sort;
+pub use degree_sort::*;
+
 mod simplify;
 pub use simplify::*;
 
 mod transpose;
 pub use transpose::*;
 
+mod permute;
+pub use permute::{permute, permute_labelled};
+
 mod compose_orders;
 pub use compose_orders::compose_orders;
+
+mod stats;
+pub use stats::*;
+
+mod modularity;
+pub use modularity::modularity;
+
+mod union;
+pub use union::union;
+
+mod contract;
+pub use contract::{contract, contract_weighted};
+
+mod sample;
+pub use sample::*;
+
+mod check;
+pub use check::{check_graph, Violation};
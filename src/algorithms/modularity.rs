@@ -0,0 +1,97 @@
+use crate::traits::SequentialGraph;
+use dsi_progress_logger::ProgressLogger;
+
+/// Compute the modularity of a clustering of the nodes of a graph with a
+/// single sequential pass, given the label of the cluster of each node, as
+/// returned, for example, by
+/// [`layered_label_propagation`](super::layered_label_propagation).
+///
+/// If `directed` is true, we use the directed modularity of Leicht and
+/// Newman, “[Community Structure in Directed
+/// Networks](https://doi.org/10.1103/PhysRevLett.100.118703)”, that is,
+/// the fraction of arcs inside clusters minus the sum over the clusters of
+/// the product of the fractions of arcs leaving and entering the cluster.
+/// Otherwise, each arc is considered an undirected edge, and we use
+/// Newman's modularity, that is, the fraction of edges inside clusters minus
+/// the sum over the clusters of the square of the fraction of edge endpoints
+/// in the cluster. On a symmetric graph the two definitions coincide, and in
+/// that case `directed` should be true, as otherwise each edge is counted
+/// twice.
+///
+/// Labels must be smaller than the number of nodes, as an array with an
+/// entry per label is used to accumulate the volumes of the clusters. The
+/// result is NaN if the graph has no arcs.
+///
+/// # Panics
+/// If `labels` has not an element for each node, or if a label is not
+/// smaller than the number of nodes.
+pub fn modularity<G: SequentialGraph>(graph: &G, labels: &[usize], directed: bool) -> f64 {
+    let num_nodes = graph.num_nodes();
+    assert_eq!(
+        labels.len(),
+        num_nodes,
+        "There must be a label for each node"
+    );
+    // the number of arcs leaving and entering each cluster
+    let mut out_volumes = vec![0_usize; num_nodes];
+    let mut in_volumes = vec![0_usize; num_nodes];
+    let mut num_arcs = 0_usize;
+    let mut intra_arcs = 0_usize;
+
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "node";
+    pl.expected_updates = Some(num_nodes);
+    pl.start("Computing modularity...");
+    for (node, succ) in graph.iter_nodes() {
+        let label = labels[node];
+        for dst in succ {
+            num_arcs += 1;
+            out_volumes[label] += 1;
+            in_volumes[labels[dst]] += 1;
+            if labels[dst] == label {
+                intra_arcs += 1;
+            }
+        }
+        pl.light_update();
+    }
+    pl.done();
+
+    let m = num_arcs as f64;
+    let expected: f64 = if directed {
+        out_volumes
+            .iter()
+            .zip(&in_volumes)
+            .map(|(&out_volume, &in_volume)| (out_volume as f64 / m) * (in_volume as f64 / m))
+            .sum()
+    } else {
+        out_volumes
+            .iter()
+            .zip(&in_volumes)
+            .map(|(&out_volume, &in_volume)| ((out_volume + in_volume) as f64 / (2.0 * m)).powi(2))
+            .sum()
+    };
+    intra_arcs as f64 / m - expected
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_modularity() {
+    use crate::graph::vec_graph::VecGraph;
+    // two symmetric triangles joined by an edge
+    let mut arcs = vec![(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)];
+    arcs.extend(arcs.clone().into_iter().map(|(src, dst)| (dst, src)));
+    let graph = VecGraph::from_arc_list(&arcs);
+    let labels = [0, 0, 0, 1, 1, 1];
+    // 6 of the 7 edges are inside clusters, and each cluster has 7 endpoints
+    let expected = 6.0 / 7.0 - 2.0 * (7.0_f64 / 14.0).powi(2);
+    assert!((modularity(&graph, &labels, true) - expected).abs() < 1E-9);
+    // a single cluster has modularity zero
+    assert!(modularity(&graph, &[0; 6], true).abs() < 1E-9);
+
+    // each arc of a directed graph as an undirected edge
+    let graph = VecGraph::from_arc_list(&[(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)]);
+    assert!((modularity(&graph, &labels, false) - expected).abs() < 1E-9);
+    // arcs leaving cluster 0: 4; entering cluster 0: 3; and vice versa
+    let directed = 6.0 / 7.0 - 2.0 * (4.0 / 7.0) * (3.0 / 7.0);
+    assert!((modularity(&graph, &labels, true) - directed).abs() < 1E-9);
+}
@@ -0,0 +1,287 @@
+use crate::traits::RandomAccessGraph;
+use dsi_progress_logger::ProgressLogger;
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// The level assigned by [`par_bfs`] to the nodes that are not reachable
+/// from the sources.
+pub const UNREACHABLE: usize = usize::MAX;
+
+/// The number of frontier nodes expanded by each task.
+const CHUNK_SIZE: usize = 1024;
+
+/// The direction-optimizing BFS switches to bottom-up when the arcs leaving
+/// the frontier are more than the unexplored arcs divided by this constant.
+const ALPHA: usize = 14;
+
+/// The direction-optimizing BFS switches back to top-down when the frontier
+/// contains less than the nodes divided by this constant.
+const BETA: usize = 24;
+
+/// A bitmap whose bits can be set concurrently.
+struct AtomicBitmap {
+    words: Box<[AtomicU64]>,
+}
+
+impl AtomicBitmap {
+    fn new(len: usize) -> Self {
+        Self {
+            words: (0..len.div_ceil(64)).map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+
+    /// Set the bit of index `index`, returning whether it was unset, that
+    /// is, whether this call is the one that set it.
+    #[inline(always)]
+    fn set(&self, index: usize) -> bool {
+        let word = &self.words[index / 64];
+        let mask = 1 << (index % 64);
+        // a plain load avoids a read-modify-write on nodes already visited,
+        // which are the majority after the first levels
+        if word.load(Ordering::Relaxed) & mask != 0 {
+            return false;
+        }
+        word.fetch_or(mask, Ordering::Relaxed) & mask == 0
+    }
+
+    /// Return whether the bit of index `index` is set.
+    #[inline(always)]
+    fn get(&self, index: usize) -> bool {
+        self.words[index / 64].load(Ordering::Relaxed) & (1 << (index % 64)) != 0
+    }
+}
+
+/// Set the level of the sources, returning the initial frontier.
+fn init_sources(sources: &[usize], visited: &AtomicBitmap, levels: &[AtomicUsize]) -> Vec<usize> {
+    let mut frontier = Vec::with_capacity(sources.len());
+    for &source in sources {
+        assert!(source < levels.len(), "Source {} does not exist", source);
+        if visited.set(source) {
+            levels[source].store(0, Ordering::Relaxed);
+            frontier.push(source);
+        }
+    }
+    frontier
+}
+
+/// Expand the frontier following the arcs of `graph`, returning the next
+/// frontier, whose nodes are at the given level.
+fn top_down_step<G: RandomAccessGraph + Sync>(
+    graph: &G,
+    frontier: &[usize],
+    visited: &AtomicBitmap,
+    levels: &[AtomicUsize],
+    level: usize,
+) -> Vec<usize> {
+    frontier
+        .par_chunks(CHUNK_SIZE)
+        .flat_map_iter(|chunk| {
+            let mut next = Vec::new();
+            for &node in chunk {
+                for succ in graph.successors(node) {
+                    if visited.set(succ) {
+                        levels[succ].store(level, Ordering::Relaxed);
+                        next.push(succ);
+                    }
+                }
+            }
+            next
+        })
+        .collect()
+}
+
+/// Look for a predecessor in the frontier of each unvisited node, following
+/// the arcs of `transpose`, and return the next frontier, whose nodes are at
+/// the given level.
+fn bottom_up_step<T: RandomAccessGraph + Sync>(
+    transpose: &T,
+    frontier: &[usize],
+    visited: &AtomicBitmap,
+    levels: &[AtomicUsize],
+    level: usize,
+) -> Vec<usize> {
+    let in_frontier = AtomicBitmap::new(levels.len());
+    frontier.par_iter().for_each(|&node| {
+        in_frontier.set(node);
+    });
+    // each unvisited node is examined by a single task, and the scan of its
+    // predecessors stops at the first one in the frontier
+    let next = (0..levels.len())
+        .into_par_iter()
+        .with_min_len(CHUNK_SIZE)
+        .filter(|&node| {
+            !visited.get(node) && transpose.successors(node).any(|pred| in_frontier.get(pred))
+        })
+        .collect::<Vec<_>>();
+    next.par_iter().for_each(|&node| {
+        visited.set(node);
+        levels[node].store(level, Ordering::Relaxed);
+    });
+    next
+}
+
+/// Compute in parallel the BFS levels of the nodes of a graph, that is, their
+/// distance from the nearest of the given sources, and return them in a
+/// vector indexed by node, containing [`UNREACHABLE`] for the nodes that
+/// cannot be reached from the sources.
+///
+/// The visit proceeds by levels: the nodes in the frontier are expanded in
+/// parallel, and the first thread setting the bit of a node in a shared
+/// visited bitmap adds it to the next frontier. Differently from
+/// [`bfs_order`](super::bfs_order), the order in which the nodes of a level
+/// are visited is not deterministic, but the levels are.
+///
+/// # Panics
+/// If a source is not a node of the graph.
+pub fn par_bfs<G: RandomAccessGraph + Sync>(graph: &G, sources: &[usize]) -> Vec<usize> {
+    let num_nodes = graph.num_nodes();
+    let visited = AtomicBitmap::new(num_nodes);
+    let levels = (0..num_nodes)
+        .map(|_| AtomicUsize::new(UNREACHABLE))
+        .collect::<Vec<_>>();
+
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "level";
+    pl.start("Visiting graph in parallel BFS...");
+
+    let mut frontier = init_sources(sources, &visited, &levels);
+
+    let mut level = 0;
+    let mut reached = frontier.len();
+    while !frontier.is_empty() {
+        log::info!("Level {}: {} nodes", level, frontier.len());
+        level += 1;
+        frontier = top_down_step(graph, &frontier, &visited, &levels, level);
+        reached += frontier.len();
+        pl.light_update();
+    }
+
+    pl.done();
+    log::info!("Reached {} nodes out of {}", reached, num_nodes);
+    levels.into_iter().map(AtomicUsize::into_inner).collect()
+}
+
+/// Compute in parallel the BFS levels of the nodes of a graph, as
+/// [`par_bfs`] does, using also the transpose of the graph to switch to a
+/// bottom-up visit when the frontier is large.
+///
+/// In a bottom-up step, instead of following the arcs leaving the frontier,
+/// each unvisited node scans its predecessors until it finds one in the
+/// frontier: on graphs with a small diameter, most arcs are explored in a
+/// few levels in which most nodes are in the frontier, and most of them are
+/// never scanned bottom-up. We use the heuristics of Beamer, Asanović and
+/// Patterson, “[Direction-Optimizing Breadth-First
+/// Search](https://doi.org/10.1109/SC.2012.50)”: we switch to bottom-up
+/// when the arcs leaving the frontier are more than 1/14 of the unexplored
+/// arcs, and back to top-down when the frontier contains less than 1/24 of
+/// the nodes.
+///
+/// # Panics
+/// If a source is not a node of the graph, or if `transpose` has not the
+/// same number of nodes of `graph`.
+pub fn par_bfs_direction_optimizing<G: RandomAccessGraph + Sync, T: RandomAccessGraph + Sync>(
+    graph: &G,
+    transpose: &T,
+    sources: &[usize],
+) -> Vec<usize> {
+    let num_nodes = graph.num_nodes();
+    assert_eq!(
+        transpose.num_nodes(),
+        num_nodes,
+        "The transpose has a different number of nodes"
+    );
+    let visited = AtomicBitmap::new(num_nodes);
+    let levels = (0..num_nodes)
+        .map(|_| AtomicUsize::new(UNREACHABLE))
+        .collect::<Vec<_>>();
+
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "level";
+    pl.start("Visiting graph in direction-optimizing parallel BFS...");
+
+    let mut frontier = init_sources(sources, &visited, &levels);
+
+    let mut level = 0;
+    let mut reached = frontier.len();
+    let mut unexplored_arcs = graph.num_arcs();
+    let mut bottom_up = false;
+    while !frontier.is_empty() {
+        let frontier_arcs: usize = frontier.par_iter().map(|&node| graph.outdegree(node)).sum();
+        bottom_up = if bottom_up {
+            frontier.len() >= num_nodes / BETA
+        } else {
+            frontier_arcs > unexplored_arcs / ALPHA
+        };
+        unexplored_arcs = unexplored_arcs.saturating_sub(frontier_arcs);
+        log::info!(
+            "Level {}: {} nodes, {}",
+            level,
+            frontier.len(),
+            if bottom_up { "bottom-up" } else { "top-down" }
+        );
+        level += 1;
+        frontier = if bottom_up {
+            bottom_up_step(transpose, &frontier, &visited, &levels, level)
+        } else {
+            top_down_step(graph, &frontier, &visited, &levels, level)
+        };
+        reached += frontier.len();
+        pl.light_update();
+    }
+
+    pl.done();
+    log::info!("Reached {} nodes out of {}", reached, num_nodes);
+    levels.into_iter().map(AtomicUsize::into_inner).collect()
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_par_bfs() {
+    use crate::graph::vec_graph::VecGraph;
+    let g = VecGraph::from_arc_list(&[(0, 2), (0, 4), (2, 1), (4, 1), (1, 5), (3, 5), (5, 3)]);
+    assert_eq!(par_bfs(&g, &[0]), vec![0, 2, 1, 4, 1, 3]);
+    assert_eq!(par_bfs(&g, &[0, 5]), vec![0, 2, 1, 1, 1, 0]);
+    assert_eq!(
+        par_bfs(&g, &[3]),
+        vec![UNREACHABLE, UNREACHABLE, UNREACHABLE, 0, UNREACHABLE, 1]
+    );
+    assert!(par_bfs(&g, &[]).iter().all(|&level| level == UNREACHABLE));
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_par_bfs_direction_optimizing() {
+    use crate::graph::vec_graph::VecGraph;
+    use rand::prelude::*;
+    let mut rng = SmallRng::seed_from_u64(0);
+    let num_nodes = 1000;
+    // a dense core, to trigger bottom-up steps, and a sparse periphery
+    let arcs = (0..20000)
+        .map(|_| {
+            let src = rng.gen_range(0..num_nodes);
+            let dst = if src < 100 {
+                rng.gen_range(0..100)
+            } else {
+                rng.gen_range(0..num_nodes)
+            };
+            (src, dst)
+        })
+        .collect::<Vec<_>>();
+    let mut graph = VecGraph::from_arc_list(&arcs);
+    let mut transpose = VecGraph::from_arc_list(
+        &arcs
+            .iter()
+            .map(|&(src, dst)| (dst, src))
+            .collect::<Vec<_>>(),
+    );
+    // make sure both graphs have all the nodes
+    graph.add_node(num_nodes - 1);
+    transpose.add_node(num_nodes - 1);
+
+    for sources in [&[0][..], &[500, 999], &[]] {
+        assert_eq!(
+            par_bfs_direction_optimizing(&graph, &transpose, sources),
+            par_bfs(&graph, sources)
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use crate::prelude::{COOIterToGraph, COOIterToLabelledGraph, SortPairsPayload};
+use crate::traits::{LabelledIterator, LabelledSequentialGraph, SequentialGraph};
+use crate::utils::{BatchIterator, KMergeIters, SortPairs, SortedArcs};
+use anyhow::{bail, Result};
+use dsi_progress_logger::ProgressLogger;
+
+/// Apply the permutation `perm` to the nodes of the graph and return a
+/// sequential graph view of the result, in which node `perm[x]` has the
+/// successors `perm[y]` for each successor `y` of `x`.
+///
+/// The arcs are mapped and re-sorted out of core with a [`SortPairs`] with
+/// batches of `batch_size` pairs, so the graph can be arbitrarily large. The
+/// batches are stored in a temporary directory, which is deleted when the
+/// result and all its iterators are dropped.
+#[allow(clippy::type_complexity)]
+pub fn permute<G: SequentialGraph>(
+    graph: &G,
+    perm: &[usize],
+    batch_size: usize,
+) -> Result<
+    COOIterToGraph<
+        SortedArcs<
+            std::iter::Map<
+                KMergeIters<(), BatchIterator<()>>,
+                fn((usize, usize, ())) -> (usize, usize),
+            >,
+        >,
+    >,
+> {
+    if perm.len() != graph.num_nodes() {
+        bail!(
+            "The permutation has {} elements but the graph has {} nodes",
+            perm.len(),
+            graph.num_nodes()
+        );
+    }
+    let dir = tempfile::tempdir()?;
+    let mut sorted = <SortPairs<()>>::new(batch_size, dir.path())?;
+
+    let mut pl = ProgressLogger::default();
+    pl.item_name = "node";
+    pl.expected_updates = Some(graph.num_nodes());
+    pl.start("Creating batches...");
+    // create batches of sorted edges
+    for (src, succ) in graph.iter_nodes() {
+        for dst in succ {
+            sorted.push(perm[src], perm[dst], ())?;
+        }
+        pl.light_update();
+    }
+    // merge the batches
+    let map: fn((usize, usize, ())) -> (usize, usize) = |(src, dst, _)| (src, dst);
+    let sorted = COOIterToGraph::new(
+        graph.num_nodes(),
+        SortedArcs::new(sorted.iter()?.map(map), dir),
+    );
+    pl.done();
+
+    Ok(sorted)
+}
+
+/// Apply the permutation `perm` to the nodes of a labelled graph and return
+/// a sequential labelled graph view of the result, in which the arc from
+/// `perm[x]` to `perm[y]` has the label of the arc from `x` to `y`.
+///
+/// The labels are written to disk together with the arcs, so they must
+/// implement [`SortPairsPayload`]. As in [`permute`], the temporary directory
+/// of the batches is deleted with the last iterator on the result.
+#[allow(clippy::type_complexity)]
+pub fn permute_labelled<G: LabelledSequentialGraph>(
+    graph: &G,
+    perm: &[usize],
+    batch_size: usize,
+) -> Result<COOIterToLabelledGraph<SortedArcs<KMergeIters<G::Label, BatchIterator<G::Label>>>>>
+where
+    G::Label: SortPairsPayload + 'static,
+    for<'a> G::SequentialSuccessorIter<'a>: LabelledIterator<Label = G::Label>,
+{
+    if perm.len() != graph.num_nodes() {
+        bail!(
+            "The permutation has {} elements but the graph has {} nodes",
+            perm.len(),
+            graph.num_nodes()
+        );
+    }
+    let dir = tempfile::tempdir()?;
+    let mut sorted = <SortPairs<G::Label>>::new(batch_size, dir.path())?;
+
+    let mut pl = ProgressLogger::default();
+    pl.item_name = "node";
+    pl.expected_updates = Some(graph.num_nodes());
+    pl.start("Creating batches...");
+    // create batches of sorted edges
+    for (src, succ) in graph.iter_nodes() {
+        for (dst, label) in succ.labelled() {
+            sorted.push(perm[src], perm[dst], label)?;
+        }
+        pl.light_update();
+    }
+    // merge the batches
+    let sorted =
+        COOIterToLabelledGraph::new(graph.num_nodes(), SortedArcs::new(sorted.iter()?, dir));
+    pl.done();
+
+    Ok(sorted)
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_permute() -> anyhow::Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    use crate::prelude::PermutedGraph;
+    let g = VecGraph::from_arc_list(&[(0, 1), (1, 2), (2, 0), (2, 1), (3, 0)]);
+    let perm = [2, 0, 3, 1];
+    let permuted = permute(&g, &perm, 2)?;
+    let g2 = VecGraph::from_node_iter(permuted.iter_nodes());
+    let expected = VecGraph::from_node_iter(
+        PermutedGraph {
+            graph: &g,
+            perm: &perm,
+        }
+        .iter_nodes(),
+    );
+    assert_eq!(g2, expected);
+    assert!(permute(&g, &perm[1..], 2).is_err());
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_permute_labelled() -> anyhow::Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    let g = VecGraph::from_arc_and_label_list(&[
+        (0, 1, 10_u32),
+        (1, 2, 20),
+        (2, 0, 30),
+        (2, 1, 40),
+        (3, 0, 50),
+    ]);
+    let perm = [2, 0, 3, 1];
+    let permuted = permute_labelled(&g, &perm, 2)?;
+    let g2 = VecGraph::from_labelled_node_iter(permuted.iter_nodes());
+    let mut expected = g
+        .iter_nodes()
+        .flat_map(|(src, succ)| {
+            succ.labelled()
+                .map(move |(dst, l)| (perm[src], perm[dst], l))
+        })
+        .collect::<Vec<_>>();
+    expected.sort();
+    let arcs = g2
+        .iter_nodes()
+        .flat_map(|(src, succ)| succ.labelled().map(move |(dst, l)| (src, dst, l)))
+        .collect::<Vec<_>>();
+    assert_eq!(arcs, expected);
+    assert!(permute_labelled(&g, &perm[1..], 2).is_err());
+    Ok(())
+}
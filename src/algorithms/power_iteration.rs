@@ -0,0 +1,277 @@
+use crate::traits::SequentialGraph;
+use dsi_progress_logger::ProgressLogger;
+
+/// When a power iteration stops.
+///
+/// The iterations stop as soon as one of the criteria is met. The default
+/// values stop after 100 iterations or when the ℓ₁ norm of the difference
+/// between two successive vectors is at most 10⁻⁹.
+#[derive(Clone, Copy, Debug)]
+pub struct PowerIterationConvergence {
+    /// The maximum number of iterations.
+    pub max_iters: usize,
+    /// Stop when the ℓ₁ norm of the difference between the vectors computed
+    /// by two successive iterations is at most this value.
+    pub tolerance: f64,
+}
+
+impl Default for PowerIterationConvergence {
+    fn default() -> Self {
+        Self {
+            max_iters: 100,
+            tolerance: 1E-9,
+        }
+    }
+}
+
+/// Scan the graph sequentially and set `next[y]` to the sum of
+/// `contribution(x, prev[x])` over the arcs from `x` to `y`.
+fn push<G: SequentialGraph>(
+    graph: &G,
+    prev: &[f64],
+    next: &mut [f64],
+    contribution: impl Fn(usize, f64) -> f64,
+) {
+    next.fill(0.0);
+    for (node, successors) in graph.iter_nodes() {
+        let value = contribution(node, prev[node]);
+        if value != 0.0 {
+            for succ in successors {
+                next[succ] += value;
+            }
+        }
+    }
+}
+
+/// Divide a vector by its ℓ₂ norm, unless it is zero.
+fn normalize(x: &mut [f64]) {
+    let norm = x.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm != 0.0 {
+        x.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+/// Return the ℓ₁ norm of the difference of two vectors.
+fn l1_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| (a - b).abs()).sum()
+}
+
+/// Run a power iteration starting from `init`, and return the last vector.
+///
+/// Each iteration scans the graph sequentially and adds, for each arc from
+/// `x` to `y`, `contribution(x, prev[x])` to `next[y]`, which is then
+/// passed to `finish` together with `prev`. In this way, the centralities of
+/// a node depend on its predecessors, and the graph is never transposed.
+pub(crate) fn power_iteration<G: SequentialGraph>(
+    graph: &G,
+    init: Vec<f64>,
+    convergence: &PowerIterationConvergence,
+    contribution: impl Fn(usize, f64) -> f64,
+    finish: impl Fn(&[f64], &mut [f64]),
+) -> Vec<f64> {
+    let num_nodes = graph.num_nodes();
+    assert_eq!(init.len(), num_nodes);
+    let mut prev = init;
+    let mut next = vec![0.0; num_nodes];
+
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "iteration";
+    pl.expected_updates = Some(convergence.max_iters);
+    pl.start("Iterating...");
+
+    for iteration in 0..convergence.max_iters {
+        push(graph, &prev, &mut next, &contribution);
+        finish(&prev, &mut next);
+        let delta = l1_distance(&prev, &next);
+        core::mem::swap(&mut prev, &mut next);
+        pl.update();
+        log::info!("Iteration {}: delta {}", iteration, delta);
+        if delta <= convergence.tolerance {
+            break;
+        }
+    }
+    pl.done();
+
+    prev
+}
+
+/// Compute the Katz centrality of the nodes of a graph with attenuation
+/// factor `alpha`, that is, the solution of `x = 1 + alpha Aᵀx`, where `A`
+/// is the adjacency matrix of the graph: the centrality of a node is the sum
+/// over all paths ending at the node of `alpha` raised to the length of the
+/// path.
+///
+/// The iteration converges if `alpha` is smaller than the reciprocal of the
+/// spectral radius of the graph, which is at most the maximum degree.
+///
+/// # Panics
+/// If `alpha` is not positive.
+pub fn katz_centrality<G: SequentialGraph>(
+    graph: &G,
+    alpha: f64,
+    convergence: &PowerIterationConvergence,
+) -> Vec<f64> {
+    assert!(alpha > 0.0, "The attenuation factor must be positive");
+    power_iteration(
+        graph,
+        vec![1.0; graph.num_nodes()],
+        convergence,
+        |_, value| alpha * value,
+        |_, next| next.iter_mut().for_each(|x| *x += 1.0),
+    )
+}
+
+/// Compute the eigenvector centrality of the nodes of a graph, that is, the
+/// dominant left eigenvector of its adjacency matrix, normalized in ℓ₂ norm.
+///
+/// The iteration starts from the uniform vector. If the graph is strongly
+/// connected and aperiodic, the iteration converges, but possibly slowly if
+/// the two largest eigenvalues are close; on periodic graphs, such as
+/// bipartite ones, it might not converge at all. If all paths in the graph
+/// are shorter than the number of iterations, for example on a DAG, the
+/// result is zero.
+pub fn eigenvector_centrality<G: SequentialGraph>(
+    graph: &G,
+    convergence: &PowerIterationConvergence,
+) -> Vec<f64> {
+    let num_nodes = graph.num_nodes();
+    power_iteration(
+        graph,
+        vec![1.0 / (num_nodes as f64).sqrt(); num_nodes],
+        convergence,
+        |_, value| value,
+        |_, next| normalize(next),
+    )
+}
+
+/// The hub and authority scores computed by [`hits`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Hits {
+    /// The hub score of each node.
+    pub hubs: Vec<f64>,
+    /// The authority score of each node.
+    pub authorities: Vec<f64>,
+}
+
+/// Compute the hub and authority scores of the nodes of a graph with
+/// Kleinberg's HITS algorithm.
+///
+/// The authority score of a node is proportional to the sum of the hub scores
+/// of its predecessors, and the hub score of a node is proportional to the
+/// sum of the authority scores of its successors; both vectors are normalized
+/// in ℓ₂ norm. Each iteration scans sequentially the graph, to update the
+/// authority scores, and its transpose, to update the hub scores, starting
+/// from uniform hub scores. The distance between two successive iterations is
+/// the sum of the ℓ₁ distances of the two vectors.
+///
+/// # Panics
+/// If `transpose` has not the same number of nodes of `graph`.
+pub fn hits<G: SequentialGraph, T: SequentialGraph>(
+    graph: &G,
+    transpose: &T,
+    convergence: &PowerIterationConvergence,
+) -> Hits {
+    let num_nodes = graph.num_nodes();
+    assert_eq!(
+        transpose.num_nodes(),
+        num_nodes,
+        "The transpose has a different number of nodes"
+    );
+    let mut hubs = vec![1.0 / (num_nodes as f64).sqrt(); num_nodes];
+    let mut authorities = vec![0.0; num_nodes];
+    let mut next_hubs = vec![0.0; num_nodes];
+    let mut next_authorities = vec![0.0; num_nodes];
+
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "iteration";
+    pl.expected_updates = Some(convergence.max_iters);
+    pl.start("Computing HITS...");
+
+    for iteration in 0..convergence.max_iters {
+        push(graph, &hubs, &mut next_authorities, |_, value| value);
+        normalize(&mut next_authorities);
+        push(transpose, &next_authorities, &mut next_hubs, |_, value| {
+            value
+        });
+        normalize(&mut next_hubs);
+        let delta = l1_distance(&hubs, &next_hubs) + l1_distance(&authorities, &next_authorities);
+        core::mem::swap(&mut hubs, &mut next_hubs);
+        core::mem::swap(&mut authorities, &mut next_authorities);
+        pl.update();
+        log::info!("Iteration {}: delta {}", iteration, delta);
+        if delta <= convergence.tolerance {
+            break;
+        }
+    }
+    pl.done();
+
+    Hits { hubs, authorities }
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_katz_centrality() {
+    use crate::graph::vec_graph::VecGraph;
+    let g = VecGraph::from_arc_list(&[(0, 1), (1, 2)]);
+    let alpha = 0.5;
+    let katz = katz_centrality(&g, alpha, &PowerIterationConvergence::default());
+    let expected = [1.0, 1.0 + alpha, 1.0 + alpha * (1.0 + alpha)];
+    for (x, y) in katz.iter().zip(expected) {
+        assert!((x - y).abs() < 1E-9);
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_eigenvector_centrality() {
+    use crate::graph::vec_graph::VecGraph;
+    // strongly connected, with cycles of length 2 and 3
+    let g = VecGraph::from_arc_list(&[(0, 1), (1, 0), (1, 2), (2, 0), (2, 3), (3, 2)]);
+    let convergence = PowerIterationConvergence {
+        max_iters: 1000,
+        tolerance: 1E-12,
+    };
+    let x = eigenvector_centrality(&g, &convergence);
+    assert!((x.iter().map(|x| x * x).sum::<f64>() - 1.0).abs() < 1E-9);
+    // x is an eigenvector of the transpose of the adjacency matrix
+    let mut y = vec![0.0; 4];
+    for (node, successors) in g.iter_nodes() {
+        for succ in successors {
+            y[succ] += x[node];
+        }
+    }
+    let lambda = y[0] / x[0];
+    for (y, x) in y.iter().zip(&x) {
+        assert!((y - lambda * x).abs() < 1E-6);
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_hits() {
+    use crate::graph::vec_graph::VecGraph;
+    let arcs = [(0, 2), (0, 3), (1, 2), (1, 4), (4, 2)];
+    let g = VecGraph::from_arc_list(&arcs);
+    let t = VecGraph::from_arc_list(&arcs.map(|(src, dst)| (dst, src)));
+    let convergence = PowerIterationConvergence {
+        max_iters: 1000,
+        tolerance: 1E-12,
+    };
+    let Hits { hubs, authorities } = hits(&g, &t, &convergence);
+    // the authorities are proportional to the hub scores of the predecessors,
+    // and the hubs to the authority scores of the successors
+    let mut a = vec![0.0; 5];
+    let mut h = vec![0.0; 5];
+    for &(src, dst) in &arcs {
+        a[dst] += hubs[src];
+        h[src] += authorities[dst];
+    }
+    normalize(&mut a);
+    normalize(&mut h);
+    for (x, y) in a.iter().zip(&authorities).chain(h.iter().zip(&hubs)) {
+        assert!((x - y).abs() < 1E-6);
+    }
+    // node 2 has the most predecessors, and nodes 0 and 1 point to it
+    assert!(authorities[2] > authorities[3]);
+    assert!(hubs[2] == 0.0 && hubs[0] > hubs[4]);
+}
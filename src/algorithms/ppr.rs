@@ -0,0 +1,124 @@
+use crate::traits::RandomAccessGraph;
+use std::collections::{HashMap, VecDeque};
+
+/// Approximate the personalized PageRank vector of a set of seed nodes with
+/// the forward-push algorithm of Andersen, Chung and Lang, “[Local Graph
+/// Partitioning using PageRank
+/// Vectors](https://doi.org/10.1109/FOCS.2006.44)”, returning the nonzero
+/// scores in a map indexed by node.
+///
+/// The personalized PageRank vector is the stationary distribution of a
+/// random walk that at each step jumps to a seed chosen uniformly at random
+/// with probability `alpha`, and otherwise follows a random arc; dangling
+/// nodes are considered to have a loop. Each node has a residual probability,
+/// initially uniform on the seeds: pushing a node moves `alpha` times its
+/// residual to its score, and spreads the rest evenly on the residuals of its
+/// successors. Nodes are pushed until the residual of each node is less than
+/// `epsilon` times its outdegree: the scores are then underestimates, and the
+/// ℓ₁ error is the sum of the residuals. On symmetric graphs, the error on
+/// the score of each node is at most `epsilon` times its degree.
+///
+/// Only the nodes with a nonzero residual and their successors are accessed,
+/// and there are at most 1 / (`alpha` · `epsilon`) pushes, independently of
+/// the size of the graph, so this is suitable for local queries, such as
+/// local community detection or similarity, on large graphs.
+///
+/// # Panics
+/// If `seeds` is empty or contains a node not in the graph, if `alpha` is
+/// not in (0..1], or if `epsilon` is not positive.
+pub fn personalized_pagerank<G: RandomAccessGraph>(
+    graph: &G,
+    seeds: &[usize],
+    alpha: f64,
+    epsilon: f64,
+) -> HashMap<usize, f64> {
+    assert!(!seeds.is_empty(), "No seed nodes");
+    assert!(alpha > 0.0 && alpha <= 1.0, "Invalid alpha {}", alpha);
+    assert!(epsilon > 0.0, "Invalid epsilon {}", epsilon);
+    let threshold = |node: usize| epsilon * graph.outdegree(node).max(1) as f64;
+
+    let mut scores = HashMap::new();
+    let mut residuals = HashMap::new();
+    for &seed in seeds {
+        assert!(seed < graph.num_nodes(), "Seed {} does not exist", seed);
+        *residuals.entry(seed).or_insert(0.0) += 1.0 / seeds.len() as f64;
+    }
+    let mut queue = residuals
+        .iter()
+        .filter(|(&node, &residual)| residual >= threshold(node))
+        .map(|(&node, _)| node)
+        .collect::<VecDeque<_>>();
+
+    let mut pushes = 0_usize;
+    while let Some(node) = queue.pop_front() {
+        let residual = residuals.insert(node, 0.0).unwrap();
+        let outdegree = graph.outdegree(node);
+        if outdegree == 0 {
+            // the walk stays on the node until it jumps back to a seed
+            *scores.entry(node).or_insert(0.0) += residual;
+            continue;
+        }
+        *scores.entry(node).or_insert(0.0) += alpha * residual;
+        let share = (1.0 - alpha) * residual / outdegree as f64;
+        for succ in graph.successors(node) {
+            let r = residuals.entry(succ).or_insert(0.0);
+            let was_queued = *r >= threshold(succ);
+            *r += share;
+            // a node is in the queue iff its residual is above the threshold
+            if !was_queued && *r >= threshold(succ) {
+                queue.push_back(succ);
+            }
+        }
+        pushes += 1;
+    }
+    log::debug!("{} pushes, {} nodes touched", pushes, residuals.len());
+
+    scores
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_personalized_pagerank() {
+    use crate::graph::vec_graph::VecGraph;
+    use rand::prelude::*;
+    let num_nodes = 100;
+    let mut rng = SmallRng::seed_from_u64(0);
+    // a symmetric graph, for which the error bound is per node
+    let mut arcs = (0..400)
+        .map(|_| (rng.gen_range(0..num_nodes), rng.gen_range(0..num_nodes)))
+        .collect::<Vec<_>>();
+    arcs.extend(arcs.clone().into_iter().map(|(src, dst)| (dst, src)));
+    let mut graph = VecGraph::from_arc_list(&arcs);
+    graph.add_node(num_nodes - 1);
+    let seeds = [3, 42];
+    let alpha = 0.15;
+
+    // compute the exact vector by power iteration
+    let mut exact = vec![0.0; num_nodes];
+    for _ in 0..1000 {
+        let mut next = vec![0.0; num_nodes];
+        for &seed in &seeds {
+            next[seed] += alpha / seeds.len() as f64;
+        }
+        for (node, &score) in exact.iter().enumerate() {
+            let outdegree = graph.outdegree(node);
+            if outdegree == 0 {
+                next[node] += (1.0 - alpha) * score;
+            }
+            for succ in graph.successors(node) {
+                next[succ] += (1.0 - alpha) * score / outdegree as f64;
+            }
+        }
+        exact = next;
+    }
+
+    for epsilon in [1E-3, 1E-5, 1E-7] {
+        let ppr = personalized_pagerank(&graph, &seeds, alpha, epsilon);
+        assert!(ppr.values().sum::<f64>() <= 1.0 + 1E-9);
+        for (node, &exact) in exact.iter().enumerate() {
+            let score = ppr.get(&node).copied().unwrap_or(0.0);
+            assert!(score <= exact + 1E-9);
+            assert!(exact - score <= epsilon * graph.outdegree(node).max(1) as f64 + 1E-9);
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use crate::graph::vec_graph::VecGraph;
+use crate::traits::*;
+use rand::prelude::*;
+use rand_xoshiro::Xoshiro256PlusPlus;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// The strategy used by [`sample`] to choose the nodes of the sample.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SampleMethod {
+    /// Nodes are chosen uniformly at random.
+    UniformNodes,
+    /// Nodes are collected by a random walk on the successors that, at each
+    /// step, jumps back to its starting node with the given probability.
+    /// Walks that get stuck are restarted from a new random node.
+    RandomWalk { restart: f64 },
+    /// Nodes are collected by the forest-fire process of Leskovec, Kleinberg
+    /// and Faloutsos: each burning node sets fire to a geometrically
+    /// distributed number of its unburnt successors, with mean
+    /// `forward / (1 - forward)`. When the fire dies out, it is rekindled at
+    /// a new random node.
+    ForestFire { forward: f64 },
+}
+
+/// Sample `num_nodes` nodes of `graph` with the given method, returning the
+/// subgraph they induce and the mapping from the ids of the sample to the ids
+/// of `graph`.
+///
+/// Sampled nodes keep their relative order, so the mapping is increasing. If
+/// `num_nodes` is larger than the number of nodes of `graph`, all nodes are
+/// sampled. The random choices are driven by a Xoshiro256++ generator, so
+/// the same `seed` always yields the same sample, on every platform.
+///
+/// # Panics
+/// If the probability of [`SampleMethod::RandomWalk`] is not in `[0, 1]`, or
+/// that of [`SampleMethod::ForestFire`] is not in `[0, 1)`.
+pub fn sample<G: RandomAccessGraph>(
+    graph: &G,
+    method: SampleMethod,
+    num_nodes: usize,
+    seed: u64,
+) -> (VecGraph<()>, Vec<usize>) {
+    let num_nodes = num_nodes.min(graph.num_nodes());
+    let mut rng = Xoshiro256PlusPlus::seed_from_u64(seed);
+
+    let mut nodes = match method {
+        SampleMethod::UniformNodes => {
+            rand::seq::index::sample(&mut rng, graph.num_nodes(), num_nodes).into_vec()
+        }
+        SampleMethod::RandomWalk { restart } => {
+            assert!(
+                (0.0..=1.0).contains(&restart),
+                "Invalid restart probability {}",
+                restart
+            );
+            random_walk(graph, num_nodes, restart, &mut rng)
+        }
+        SampleMethod::ForestFire { forward } => {
+            assert!(
+                (0.0..1.0).contains(&forward),
+                "Invalid forward probability {}",
+                forward
+            );
+            forest_fire(graph, num_nodes, forward, &mut rng)
+        }
+    };
+    nodes.sort_unstable();
+
+    let ids = nodes
+        .iter()
+        .enumerate()
+        .map(|(id, &node)| (node, id))
+        .collect::<HashMap<_, _>>();
+    let mut sample = VecGraph::empty(nodes.len());
+    for (id, &node) in nodes.iter().enumerate() {
+        for succ in graph.successors(node) {
+            if let Some(&succ_id) = ids.get(&succ) {
+                sample.add_arc(id, succ_id);
+            }
+        }
+    }
+    (sample, nodes)
+}
+
+/// Collect `num_nodes` distinct nodes by random walks with restart.
+fn random_walk<G: RandomAccessGraph>(
+    graph: &G,
+    num_nodes: usize,
+    restart: f64,
+    rng: &mut Xoshiro256PlusPlus,
+) -> Vec<usize> {
+    let mut seen = HashSet::new();
+    let mut nodes = Vec::with_capacity(num_nodes);
+    // a walk trapped in a small region is abandoned after this many steps
+    // without finding new nodes
+    let patience = 100 * num_nodes.max(1);
+    while nodes.len() < num_nodes {
+        let start = rng.gen_range(0..graph.num_nodes());
+        let mut current = start;
+        let mut idle = 0;
+        while nodes.len() < num_nodes && idle < patience {
+            if seen.insert(current) {
+                nodes.push(current);
+                idle = 0;
+            } else {
+                idle += 1;
+            }
+            let outdegree = graph.outdegree(current);
+            if outdegree == 0 {
+                break;
+            }
+            current = if rng.gen_bool(restart) {
+                start
+            } else {
+                graph
+                    .successors(current)
+                    .nth(rng.gen_range(0..outdegree))
+                    .unwrap()
+            };
+        }
+    }
+    nodes
+}
+
+/// Collect `num_nodes` distinct nodes by forest-fire sampling.
+fn forest_fire<G: RandomAccessGraph>(
+    graph: &G,
+    num_nodes: usize,
+    forward: f64,
+    rng: &mut Xoshiro256PlusPlus,
+) -> Vec<usize> {
+    let mut burnt = HashSet::new();
+    let mut nodes = Vec::with_capacity(num_nodes);
+    let mut queue = VecDeque::new();
+    while nodes.len() < num_nodes {
+        let seed = rng.gen_range(0..graph.num_nodes());
+        if !burnt.insert(seed) {
+            continue;
+        }
+        nodes.push(seed);
+        queue.push_back(seed);
+        while let Some(node) = queue.pop_front() {
+            if nodes.len() == num_nodes {
+                break;
+            }
+            // the number of successors to burn is geometric: keep going
+            // while a coin with probability `forward` lands heads
+            let mut to_burn = 0;
+            while rng.gen_bool(forward) {
+                to_burn += 1;
+            }
+            let mut unburnt = graph
+                .successors(node)
+                .filter(|succ| !burnt.contains(succ))
+                .collect::<Vec<_>>();
+            unburnt.shuffle(rng);
+            for succ in unburnt.into_iter().take(to_burn) {
+                if nodes.len() == num_nodes {
+                    break;
+                }
+                burnt.insert(succ);
+                nodes.push(succ);
+                queue.push_back(succ);
+            }
+        }
+        queue.clear();
+    }
+    nodes
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_sample() {
+    let arcs = (0..100)
+        .flat_map(|x| [(x, (x + 1) % 100), (x, (x * 7) % 100)])
+        .collect::<Vec<_>>();
+    let graph = VecGraph::from_arc_list(&arcs);
+    for method in [
+        SampleMethod::UniformNodes,
+        SampleMethod::RandomWalk { restart: 0.15 },
+        SampleMethod::ForestFire { forward: 0.7 },
+    ] {
+        let (sample_graph, nodes) = sample(&graph, method, 20, 0);
+        assert_eq!(sample_graph.num_nodes(), 20);
+        assert_eq!(nodes.len(), 20);
+        assert!(nodes.windows(2).all(|w| w[0] < w[1]));
+        // the sample is the induced subgraph
+        for (x, &node_x) in nodes.iter().enumerate() {
+            for (y, &node_y) in nodes.iter().enumerate() {
+                assert_eq!(sample_graph.has_arc(x, y), graph.has_arc(node_x, node_y));
+            }
+        }
+        // the same seed yields the same sample
+        assert_eq!(sample(&graph, method, 20, 0).1, nodes);
+    }
+    // asking for too many nodes returns the whole graph
+    let (sample_graph, nodes) = sample(&graph, SampleMethod::UniformNodes, 1000, 0);
+    assert_eq!(nodes, (0..100).collect::<Vec<_>>());
+    assert_eq!(sample_graph.num_arcs(), graph.num_arcs());
+}
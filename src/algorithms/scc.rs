@@ -0,0 +1,185 @@
+use crate::traits::{RandomAccessGraph, SequentialGraph};
+use crate::utils::{sort_arcs, SortedArcsGraph};
+use anyhow::{Context, Result};
+use dsi_progress_logger::ProgressLogger;
+use epserde::prelude::*;
+use std::path::Path;
+
+/// The strongly connected components of a graph, as computed by [`sccs`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sccs {
+    /// The number of components.
+    pub num_components: usize,
+    /// The component of each node. Components are numbered in topological
+    /// order: if there is an arc from a node of component `i` to a node of
+    /// component `j` ≠ `i`, then `i` < `j`.
+    pub components: Vec<usize>,
+}
+
+impl Sccs {
+    /// Return the number of nodes of each component.
+    pub fn sizes(&self) -> Vec<usize> {
+        let mut sizes = vec![0; self.num_components];
+        for &component in &self.components {
+            sizes[component] += 1;
+        }
+        sizes
+    }
+
+    /// Serialize the component of each node with ε-serde, as a
+    /// `Vec<usize>`, in the file `BASENAME.sccs`.
+    pub fn store<P: AsRef<Path>>(&self, basename: P) -> Result<()> {
+        let path = format!("{}.sccs", basename.as_ref().to_string_lossy());
+        let mut file = std::io::BufWriter::new(
+            std::fs::File::create(&path).with_context(|| format!("Cannot create {}", path))?,
+        );
+        self.components.serialize(&mut file)?;
+        std::io::Write::flush(&mut file)?;
+        Ok(())
+    }
+}
+
+/// Compute the strongly connected components of a graph with Tarjan's
+/// algorithm.
+///
+/// The visit is iterative, so there is no limit on the depth of the visit,
+/// and it uses three words per node besides the stack of the visit.
+pub fn sccs<G: RandomAccessGraph>(graph: &G) -> Sccs {
+    const UNVISITED: usize = usize::MAX;
+    let num_nodes = graph.num_nodes();
+    // the order of discovery of each node
+    let mut index = vec![UNVISITED; num_nodes];
+    // the smallest index reachable through the visit tree and one back arc
+    let mut low = vec![0; num_nodes];
+    let mut components = vec![UNVISITED; num_nodes];
+    // the nodes visited whose component is not known yet
+    let mut stack = Vec::new();
+    let mut visit = Vec::new();
+    let mut next_index = 0;
+    let mut num_components = 0;
+
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "node";
+    pl.expected_updates = Some(num_nodes);
+    pl.start("Computing strongly connected components...");
+
+    for root in 0..num_nodes {
+        if index[root] != UNVISITED {
+            continue;
+        }
+        index[root] = next_index;
+        low[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        visit.push((root, graph.successors(root)));
+
+        while let Some((node, successors)) = visit.last_mut() {
+            let node = *node;
+            match successors.next() {
+                Some(succ) if index[succ] == UNVISITED => {
+                    index[succ] = next_index;
+                    low[succ] = next_index;
+                    next_index += 1;
+                    stack.push(succ);
+                    visit.push((succ, graph.successors(succ)));
+                }
+                Some(succ) => {
+                    // nodes with a component are not on the stack
+                    if components[succ] == UNVISITED {
+                        low[node] = low[node].min(index[succ]);
+                    }
+                }
+                None => {
+                    visit.pop();
+                    if let Some(&(parent, _)) = visit.last() {
+                        low[parent] = low[parent].min(low[node]);
+                    }
+                    if low[node] == index[node] {
+                        loop {
+                            let member = stack.pop().unwrap();
+                            components[member] = num_components;
+                            pl.light_update();
+                            if member == node {
+                                break;
+                            }
+                        }
+                        num_components += 1;
+                    }
+                }
+            }
+        }
+    }
+    pl.done();
+
+    // Tarjan's algorithm completes a component after all the components
+    // reachable from it, so reversing the numbering yields a topological order
+    for component in &mut components {
+        *component = num_components - 1 - *component;
+    }
+    log::info!("{} strongly connected components", num_components);
+    Sccs {
+        num_components,
+        components,
+    }
+}
+
+/// Return a sequential graph view of the condensation of a graph, that is,
+/// the directed acyclic graph having the strongly connected components of the
+/// graph as nodes, and an arc from `i` to `j` if there is an arc from a node
+/// of component `i` to a node of component `j` ≠ `i`.
+///
+/// Since the components of `sccs` are numbered in topological order, all arcs
+/// go from a component to a component with a larger index. The arcs are
+/// merged and deduplicated out of core by [`sort_arcs`] with batches of
+/// `batch_size` pairs, and the result can be compressed like any other
+/// sequential graph, for example with
+/// [`parallel_compress_sequential_iter`](crate::graph::bvgraph::parallel_compress_sequential_iter).
+pub fn condensation<G: SequentialGraph>(
+    graph: &G,
+    sccs: &Sccs,
+    batch_size: usize,
+) -> Result<SortedArcsGraph> {
+    let arcs = graph.iter_nodes().flat_map(|(src, succ)| {
+        let src_component = sccs.components[src];
+        succ.into_iter().filter_map(move |dst| {
+            let dst_component = sccs.components[dst];
+            (src_component != dst_component).then_some((src_component, dst_component))
+        })
+    });
+    sort_arcs(arcs, Some(sccs.num_components), batch_size)
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_sccs() -> Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    // {0, 1, 2} -> {3, 4} -> {5}, {0, 1, 2} -> {5}, and {6} isolated
+    let g = VecGraph::from_arc_list(&[
+        (0, 1),
+        (1, 2),
+        (2, 0),
+        (1, 3),
+        (2, 4),
+        (3, 4),
+        (4, 3),
+        (4, 5),
+        (0, 5),
+        (5, 5),
+        (6, 6),
+    ]);
+    let sccs = sccs(&g);
+    assert_eq!(sccs.num_components, 4);
+    let c = &sccs.components;
+    assert!(c[0] == c[1] && c[1] == c[2]);
+    assert!(c[3] == c[4]);
+    assert!(c[0] < c[3] && c[3] < c[5]);
+    let mut sizes = sccs.sizes();
+    sizes.sort();
+    assert_eq!(sizes, vec![1, 1, 2, 3]);
+
+    let dag = VecGraph::from_node_iter(condensation(&g, &sccs, 2)?.iter_nodes());
+    let mut expected = VecGraph::from_arc_list(&[(c[0], c[3]), (c[0], c[5]), (c[3], c[5])]);
+    expected.add_node(sccs.num_components - 1);
+    assert_eq!(dag, expected);
+    Ok(())
+}
@@ -0,0 +1,418 @@
+use crate::traits::SequentialGraph;
+use dsi_progress_logger::ProgressLogger;
+
+/// Global statistics of a graph, as computed by [`graph_stats`].
+///
+/// The statistics mirror those of the `Stats` class of the Java version.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GraphStats {
+    /// The number of nodes.
+    pub num_nodes: usize,
+    /// The number of arcs.
+    pub num_arcs: usize,
+    /// The number of arcs from a node to itself.
+    pub self_loops: usize,
+    /// The number of nodes without successors.
+    pub dangling: usize,
+    /// The minimum outdegree.
+    pub min_outdegree: usize,
+    /// The maximum outdegree.
+    pub max_outdegree: usize,
+    /// A node with maximum outdegree.
+    pub max_outdegree_node: usize,
+    /// The minimum indegree.
+    pub min_indegree: usize,
+    /// The maximum indegree.
+    pub max_indegree: usize,
+    /// A node with maximum indegree.
+    pub max_indegree_node: usize,
+    /// The average degree, which is the same for indegrees and outdegrees.
+    pub avg_degree: f64,
+    /// The variance of the outdegree.
+    pub outdegree_variance: f64,
+    /// The number of bits per arc of the encoding of the graph, if known.
+    pub bits_per_arc: Option<f64>,
+    /// The dyad census of the graph, if computed with [`dyad_census`].
+    pub dyads: Option<DyadCensus>,
+    /// The outdegree assortativity of the graph, if computed with
+    /// [`degree_assortativity`].
+    pub assortativity: Option<f64>,
+}
+
+/// The dyad census of a graph, as computed by [`dyad_census`]: the number of
+/// unordered pairs of distinct nodes connected by arcs in both directions, in
+/// one direction, or not connected at all.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DyadCensus {
+    /// The number of pairs connected by arcs in both directions.
+    pub mutual: usize,
+    /// The number of pairs connected by an arc in one direction only.
+    pub asymmetric: usize,
+    /// The number of pairs not connected, which can exceed a `usize`.
+    pub null: u128,
+}
+
+impl DyadCensus {
+    /// Return the reciprocity of the graph, that is, the fraction of the arcs
+    /// that are not loops whose reverse arc is in the graph, or NaN if there
+    /// are no such arcs.
+    pub fn reciprocity(&self) -> f64 {
+        let mutual_arcs = 2 * self.mutual;
+        mutual_arcs as f64 / (mutual_arcs + self.asymmetric) as f64
+    }
+}
+
+impl GraphStats {
+    /// Return the statistics as a JSON object.
+    pub fn to_json(&self) -> String {
+        /// Format a float as JSON, which has no representation for NaN.
+        fn float(x: f64) -> String {
+            if x.is_finite() {
+                x.to_string()
+            } else {
+                "null".to_string()
+            }
+        }
+        format!(
+            concat!(
+                "{{\"nodes\":{},\"arcs\":{},\"selfloops\":{},\"dangling\":{},",
+                "\"minoutdegree\":{},\"maxoutdegree\":{},\"maxoutdegreenode\":{},",
+                "\"minindegree\":{},\"maxindegree\":{},\"maxindegreenode\":{},",
+                "\"avgdegree\":{},\"outdegreevariance\":{},\"bitsperarc\":{},",
+                "\"reciprocity\":{},\"mutualdyads\":{},\"asymmetricdyads\":{},",
+                "\"nulldyads\":{},\"assortativity\":{}}}"
+            ),
+            self.num_nodes,
+            self.num_arcs,
+            self.self_loops,
+            self.dangling,
+            self.min_outdegree,
+            self.max_outdegree,
+            self.max_outdegree_node,
+            self.min_indegree,
+            self.max_indegree,
+            self.max_indegree_node,
+            float(self.avg_degree),
+            float(self.outdegree_variance),
+            self.bits_per_arc.map_or("null".to_string(), float),
+            self.dyads
+                .map_or("null".to_string(), |dyads| float(dyads.reciprocity())),
+            self.dyads
+                .map_or("null".to_string(), |dyads| dyads.mutual.to_string()),
+            self.dyads
+                .map_or("null".to_string(), |dyads| dyads.asymmetric.to_string()),
+            self.dyads
+                .map_or("null".to_string(), |dyads| dyads.null.to_string()),
+            self.assortativity.map_or("null".to_string(), float),
+        )
+    }
+}
+
+impl core::fmt::Display for GraphStats {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(f, "{:<20}{:>20}", "nodes", self.num_nodes)?;
+        writeln!(f, "{:<20}{:>20}", "arcs", self.num_arcs)?;
+        writeln!(f, "{:<20}{:>20}", "self-loops", self.self_loops)?;
+        writeln!(f, "{:<20}{:>20}", "dangling nodes", self.dangling)?;
+        writeln!(f, "{:<20}{:>20}", "min outdegree", self.min_outdegree)?;
+        writeln!(
+            f,
+            "{:<20}{:>20} (node {})",
+            "max outdegree", self.max_outdegree, self.max_outdegree_node
+        )?;
+        writeln!(f, "{:<20}{:>20}", "min indegree", self.min_indegree)?;
+        writeln!(
+            f,
+            "{:<20}{:>20} (node {})",
+            "max indegree", self.max_indegree, self.max_indegree_node
+        )?;
+        writeln!(f, "{:<20}{:>20.3}", "avg degree", self.avg_degree)?;
+        writeln!(
+            f,
+            "{:<20}{:>20.3}",
+            "outdegree variance", self.outdegree_variance
+        )?;
+        if let Some(bits_per_arc) = self.bits_per_arc {
+            writeln!(f, "{:<20}{:>20.3}", "bits/arc", bits_per_arc)?;
+        }
+        if let Some(dyads) = self.dyads {
+            writeln!(f, "{:<20}{:>20.3}", "reciprocity", dyads.reciprocity())?;
+            writeln!(f, "{:<20}{:>20}", "mutual dyads", dyads.mutual)?;
+            writeln!(f, "{:<20}{:>20}", "asymmetric dyads", dyads.asymmetric)?;
+            writeln!(f, "{:<20}{:>20}", "null dyads", dyads.null)?;
+        }
+        if let Some(assortativity) = self.assortativity {
+            writeln!(f, "{:<20}{:>20.3}", "assortativity", assortativity)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compute the global statistics of a graph with a single sequential pass.
+///
+/// Indegrees are accumulated in an array with an entry per node. The number
+/// of bits per arc is left unknown, as it depends on the file containing the
+/// graph.
+pub fn graph_stats<G: SequentialGraph>(graph: &G) -> GraphStats {
+    stats_pass(graph, None)
+}
+
+/// Compute the global statistics of a graph as [`graph_stats`], returning
+/// also the outdegrees of the nodes collected during the same pass, which
+/// can be used to compute the [`degree_assortativity`].
+pub fn graph_stats_with_outdegrees<G: SequentialGraph>(graph: &G) -> (GraphStats, Vec<usize>) {
+    let mut outdegrees = Vec::with_capacity(graph.num_nodes());
+    let stats = stats_pass(graph, Some(&mut outdegrees));
+    (stats, outdegrees)
+}
+
+fn stats_pass<G: SequentialGraph>(
+    graph: &G,
+    mut outdegrees: Option<&mut Vec<usize>>,
+) -> GraphStats {
+    let num_nodes = graph.num_nodes();
+    let mut indegrees = vec![0_usize; num_nodes];
+    let mut stats = GraphStats {
+        num_nodes,
+        min_outdegree: usize::MAX,
+        ..Default::default()
+    };
+    let mut sum_of_squares = 0_u128;
+
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "node";
+    pl.expected_updates = Some(num_nodes);
+    pl.start("Computing statistics...");
+    for (node, succ) in graph.iter_nodes() {
+        let mut outdegree = 0;
+        for dst in succ {
+            outdegree += 1;
+            indegrees[dst] += 1;
+            if dst == node {
+                stats.self_loops += 1;
+            }
+        }
+        stats.num_arcs += outdegree;
+        if let Some(outdegrees) = outdegrees.as_mut() {
+            outdegrees.push(outdegree);
+        }
+        sum_of_squares += (outdegree as u128) * (outdegree as u128);
+        if outdegree == 0 {
+            stats.dangling += 1;
+        }
+        stats.min_outdegree = stats.min_outdegree.min(outdegree);
+        if outdegree > stats.max_outdegree {
+            stats.max_outdegree = outdegree;
+            stats.max_outdegree_node = node;
+        }
+        pl.light_update();
+    }
+    pl.done();
+
+    stats.min_outdegree = stats.min_outdegree.min(stats.max_outdegree);
+    stats.min_indegree = indegrees.iter().copied().min().unwrap_or(0);
+    if let Some((node, &indegree)) = indegrees
+        .iter()
+        .enumerate()
+        .max_by_key(|&(node, indegree)| (indegree, core::cmp::Reverse(node)))
+    {
+        stats.max_indegree = indegree;
+        stats.max_indegree_node = node;
+    }
+    if num_nodes > 0 {
+        let avg = stats.num_arcs as f64 / num_nodes as f64;
+        stats.avg_degree = avg;
+        stats.outdegree_variance = sum_of_squares as f64 / num_nodes as f64 - avg * avg;
+    }
+    stats
+}
+
+/// Compute the dyad census of a graph with a single sequential pass on the
+/// graph and on its transpose, merging the successors and the predecessors of
+/// each node. Loops are ignored.
+///
+/// # Panics
+/// If `transpose` has not the same number of nodes of `graph`.
+pub fn dyad_census<G: SequentialGraph, T: SequentialGraph>(graph: &G, transpose: &T) -> DyadCensus {
+    let num_nodes = graph.num_nodes();
+    assert_eq!(
+        transpose.num_nodes(),
+        num_nodes,
+        "The transpose has a different number of nodes"
+    );
+    let mut census = DyadCensus::default();
+
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "node";
+    pl.expected_updates = Some(num_nodes);
+    pl.start("Computing the dyad census...");
+    for ((node, succ), (_, pred)) in graph.iter_nodes().zip(transpose.iter_nodes()) {
+        // each pair is counted from its smaller node
+        let mut succ = succ.filter(|&x| x > node).peekable();
+        let mut pred = pred.filter(|&x| x > node).peekable();
+        loop {
+            match (succ.peek(), pred.peek()) {
+                (None, None) => break,
+                (Some(&s), Some(&p)) if s == p => {
+                    census.mutual += 1;
+                    succ.next();
+                    pred.next();
+                }
+                (Some(&s), Some(&p)) if s < p => {
+                    census.asymmetric += 1;
+                    succ.next();
+                }
+                (Some(_), None) => {
+                    census.asymmetric += 1;
+                    succ.next();
+                }
+                _ => {
+                    census.asymmetric += 1;
+                    pred.next();
+                }
+            }
+        }
+        pl.light_update();
+    }
+    pl.done();
+
+    let pairs = num_nodes as u128 * (num_nodes as u128).saturating_sub(1) / 2;
+    census.null = pairs - census.mutual as u128 - census.asymmetric as u128;
+    census
+}
+
+/// Compute the degree assortativity of a graph with a single sequential pass,
+/// that is, the Pearson correlation coefficient between the outdegrees of the
+/// source and of the target of the arcs, as defined by Newman, “[Mixing
+/// patterns in networks](https://doi.org/10.1103/PhysRevE.67.026126)”. On
+/// symmetric graphs, this is the usual degree assortativity.
+///
+/// `outdegree` must return the outdegree of a node, for example by looking it
+/// up in the array returned by [`graph_stats_with_outdegrees`] or in
+/// [`Outdegrees`](crate::graph::bvgraph::Outdegrees), rather than decoding
+/// it for each arc. The sums are exact, so the result does not depend on the
+/// order of the arcs; their products are exact, too, unless they do not fit
+/// in a `u128`, in which case they are computed in floating point. The
+/// result is NaN if there are no arcs, or if all targets or all sources have
+/// the same outdegree.
+pub fn degree_assortativity<G: SequentialGraph>(
+    graph: &G,
+    outdegree: impl Fn(usize) -> usize,
+) -> f64 {
+    let (mut m, mut sum_src, mut sum_dst) = (0_u128, 0_u128, 0_u128);
+    let (mut sum_src_sq, mut sum_dst_sq, mut sum_prod) = (0_u128, 0_u128, 0_u128);
+
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "node";
+    pl.expected_updates = Some(graph.num_nodes());
+    pl.start("Computing the degree assortativity...");
+    for (node, succ) in graph.iter_nodes() {
+        let src_degree = outdegree(node) as u128;
+        for dst in succ {
+            let dst_degree = outdegree(dst) as u128;
+            m += 1;
+            sum_src += src_degree;
+            sum_dst += dst_degree;
+            sum_src_sq += src_degree * src_degree;
+            sum_dst_sq += dst_degree * dst_degree;
+            sum_prod += src_degree * dst_degree;
+        }
+        pl.light_update();
+    }
+    pl.done();
+
+    // m² times the covariance and the variances, to keep the sums exact
+    let covariance = diff_of_products(m, sum_prod, sum_src, sum_dst);
+    let src_variance = diff_of_products(m, sum_src_sq, sum_src, sum_src);
+    let dst_variance = diff_of_products(m, sum_dst_sq, sum_dst, sum_dst);
+    covariance / (src_variance * dst_variance).sqrt()
+}
+
+/// Return `a * b - c * d`, which is exact if the products fit in a `u128`.
+fn diff_of_products(a: u128, b: u128, c: u128, d: u128) -> f64 {
+    match (a.checked_mul(b), c.checked_mul(d)) {
+        (Some(x), Some(y)) if x >= y => (x - y) as f64,
+        (Some(x), Some(y)) => -((y - x) as f64),
+        _ => a as f64 * b as f64 - c as f64 * d as f64,
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_graph_stats() {
+    use crate::graph::vec_graph::VecGraph;
+    let g = VecGraph::from_arc_list(&[(0, 1), (0, 2), (1, 1), (2, 1)]);
+    let stats = graph_stats(&g);
+    assert_eq!(stats.num_nodes, 3);
+    assert_eq!(stats.num_arcs, 4);
+    assert_eq!(stats.self_loops, 1);
+    assert_eq!(stats.dangling, 0);
+    assert_eq!((stats.min_outdegree, stats.max_outdegree), (1, 2));
+    assert_eq!(stats.max_outdegree_node, 0);
+    assert_eq!((stats.min_indegree, stats.max_indegree), (0, 3));
+    assert_eq!(stats.max_indegree_node, 1);
+    assert!((stats.avg_degree - 4.0 / 3.0).abs() < 1E-9);
+    assert!((stats.outdegree_variance - (6.0 / 3.0 - 16.0 / 9.0)).abs() < 1E-9);
+    assert!(stats.to_json().starts_with("{\"nodes\":3,\"arcs\":4,"));
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_dyad_census() {
+    use crate::graph::vec_graph::VecGraph;
+    let arcs = [(0, 1), (1, 0), (1, 2), (3, 1), (2, 2), (0, 3), (3, 0)];
+    let g = VecGraph::from_arc_list(&arcs);
+    let t = VecGraph::from_arc_list(&arcs.map(|(src, dst)| (dst, src)));
+    let census = dyad_census(&g, &t);
+    assert_eq!(
+        census,
+        DyadCensus {
+            mutual: 2,
+            asymmetric: 2,
+            null: 2,
+        }
+    );
+    assert!((census.reciprocity() - 4.0 / 6.0).abs() < 1E-9);
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_degree_assortativity() {
+    use crate::graph::vec_graph::VecGraph;
+    use crate::traits::RandomAccessGraph;
+    // a symmetric star is perfectly disassortative
+    let star = VecGraph::from_arc_list(&[(0, 1), (1, 0), (0, 2), (2, 0), (0, 3), (3, 0)]);
+    let r = degree_assortativity(&star, |node| star.outdegree(node));
+    assert!((r + 1.0).abs() < 1E-9);
+    // two disjoint symmetric cliques of different sizes are perfectly
+    // assortative
+    let mut arcs = Vec::new();
+    for (start, size) in [(0, 2), (2, 4)] {
+        for x in start..start + size {
+            for y in start..start + size {
+                if x != y {
+                    arcs.push((x, y));
+                }
+            }
+        }
+    }
+    let cliques = VecGraph::from_arc_list(&arcs);
+    let r = degree_assortativity(&cliques, |node| cliques.outdegree(node));
+    assert!((r - 1.0).abs() < 1E-9);
+    let (stats, outdegrees) = graph_stats_with_outdegrees(&cliques);
+    assert_eq!(stats, graph_stats(&cliques));
+    assert_eq!(outdegrees, [1, 1, 3, 3, 3, 3]);
+    let r = degree_assortativity(&cliques, |node| outdegrees[node]);
+    assert!((r - 1.0).abs() < 1E-9);
+
+    assert_eq!(diff_of_products(3, 4, 5, 2), 2.0);
+    assert_eq!(diff_of_products(2, 5, 3, 4), -2.0);
+    // products beyond the range of an i128 keep their sign
+    assert_eq!(diff_of_products(u128::MAX, 1, 0, 0), u128::MAX as f64);
+    assert_eq!(diff_of_products(0, 0, u128::MAX, 1), -(u128::MAX as f64));
+    let big = 1_u128 << 100;
+    assert_eq!(
+        diff_of_products(big, big, 1, 1),
+        (big as f64) * (big as f64)
+    );
+}
@@ -0,0 +1,127 @@
+use crate::traits::RandomAccessGraph;
+use dsi_progress_logger::ProgressLogger;
+use std::collections::VecDeque;
+
+/// A cycle of a graph, proving that it has no topological order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// The nodes of the cycle: there is an arc from each node to the next
+    /// one, and from the last node to the first one.
+    pub nodes: Vec<usize>,
+}
+
+impl core::fmt::Display for Cycle {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "The graph contains the cycle {:?}", self.nodes)
+    }
+}
+
+impl std::error::Error for Cycle {}
+
+/// Return a topological order of the nodes of a graph, that is, an order in
+/// which each node precedes its successors, or a [`Cycle`] if there is none.
+///
+/// We use Kahn's algorithm: indegrees are computed with a sequential scan of
+/// the graph, and the nodes without predecessors are output and removed from
+/// the graph, in increasing order, so the result is deterministic. If some
+/// nodes cannot be removed, a cycle among them is found with a depth-first
+/// visit.
+pub fn topological_sort<G: RandomAccessGraph>(graph: &G) -> Result<Vec<usize>, Cycle> {
+    let num_nodes = graph.num_nodes();
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "node";
+    pl.expected_updates = Some(num_nodes);
+
+    pl.start("Computing indegrees...");
+    let mut indegrees = vec![0_usize; num_nodes];
+    for (_, successors) in graph.iter_nodes() {
+        for succ in successors {
+            indegrees[succ] += 1;
+        }
+        pl.light_update();
+    }
+    pl.done();
+
+    pl.start("Sorting topologically...");
+    let mut queue = (0..num_nodes)
+        .filter(|&node| indegrees[node] == 0)
+        .collect::<VecDeque<_>>();
+    let mut order = Vec::with_capacity(num_nodes);
+    while let Some(node) = queue.pop_front() {
+        order.push(node);
+        for succ in graph.successors(node) {
+            indegrees[succ] -= 1;
+            if indegrees[succ] == 0 {
+                queue.push_back(succ);
+            }
+        }
+        pl.light_update();
+    }
+    pl.done();
+
+    if order.len() == num_nodes {
+        Ok(order)
+    } else {
+        // the remaining nodes have a positive indegree in the subgraph they
+        // induce, so it contains a cycle
+        Err(find_cycle(graph, |node| indegrees[node] != 0))
+    }
+}
+
+/// Find a cycle in the subgraph induced by the nodes satisfying `remaining`,
+/// which must contain one.
+fn find_cycle<G: RandomAccessGraph>(graph: &G, remaining: impl Fn(usize) -> bool) -> Cycle {
+    const UNVISITED: u8 = 0;
+    const ON_STACK: u8 = 1;
+    const DONE: u8 = 2;
+    let mut state = vec![UNVISITED; graph.num_nodes()];
+    let mut stack = Vec::new();
+    for start in 0..graph.num_nodes() {
+        if !remaining(start) || state[start] != UNVISITED {
+            continue;
+        }
+        state[start] = ON_STACK;
+        stack.push((start, graph.successors(start)));
+        while let Some((node, successors)) = stack.last_mut() {
+            let node = *node;
+            match successors.find(|&succ| remaining(succ) && state[succ] != DONE) {
+                Some(succ) if state[succ] == ON_STACK => {
+                    // the cycle goes from succ to the top of the stack
+                    let pos = stack.iter().position(|(n, _)| *n == succ).unwrap();
+                    return Cycle {
+                        nodes: stack[pos..].iter().map(|(n, _)| *n).collect(),
+                    };
+                }
+                Some(succ) => {
+                    state[succ] = ON_STACK;
+                    stack.push((succ, graph.successors(succ)));
+                }
+                None => {
+                    state[node] = DONE;
+                    stack.pop();
+                }
+            }
+        }
+    }
+    unreachable!("The subgraph does not contain a cycle")
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_topological_sort() {
+    use crate::graph::vec_graph::VecGraph;
+    let g = VecGraph::from_arc_list(&[(0, 2), (0, 4), (2, 1), (4, 1), (3, 5), (1, 5)]);
+    assert_eq!(topological_sort(&g), Ok(vec![0, 3, 2, 4, 1, 5]));
+
+    // 6 -> 7 -> 8 -> 6 is a cycle, reachable from the DAG
+    let g = VecGraph::from_arc_list(&[(0, 1), (1, 6), (6, 7), (7, 8), (8, 6), (8, 9), (2, 2)]);
+    let cycle = topological_sort(&g).unwrap_err();
+    let nodes = &cycle.nodes;
+    assert!(!nodes.is_empty());
+    for i in 0..nodes.len() {
+        assert!(g.has_arc(nodes[i], nodes[(i + 1) % nodes.len()]));
+    }
+    // a self-loop is a cycle
+    let g = VecGraph::from_arc_list(&[(0, 1), (1, 1)]);
+    assert_eq!(topological_sort(&g), Err(Cycle { nodes: vec![1] }));
+}
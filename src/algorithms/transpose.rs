@@ -1,6 +1,8 @@
 use crate::prelude::{COOIterToGraph, COOIterToLabelledGraph, SortPairsPayload};
-use crate::traits::{LabelledIterator, LabelledSequentialGraph, SequentialGraph};
-use crate::utils::{BatchIterator, KMergeIters, SortPairs};
+use crate::traits::{
+    LabelledIterator, LabelledSequentialGraph, RandomAccessGraph, SequentialGraph,
+};
+use crate::utils::{BatchIterator, KMergeIters, SortPairs, SortedArcs, SortedArcsGraph};
 use anyhow::Result;
 use dsi_progress_logger::ProgressLogger;
 
@@ -39,6 +41,73 @@ pub fn transpose<G: SequentialGraph>(
     Ok(sorted)
 }
 
+/// Transpose the graph in parallel and return a sequential graph view of it.
+///
+/// The node range is split in `num_threads` contiguous chunks, and each
+/// thread scans its chunk through random access, pushing the reversed arcs
+/// into its own [`SortPairs`] with batches of `batch_size` pairs, so up to
+/// `num_threads` times as many pairs are kept in memory as with
+/// [`transpose`]. The batches of all the threads are then merged into a
+/// single sorted iterator, which owns the temporary directory of the
+/// batches.
+pub fn par_transpose<G: RandomAccessGraph + Sync>(
+    graph: &G,
+    batch_size: usize,
+    num_threads: usize,
+) -> Result<SortedArcsGraph> {
+    assert_ne!(num_threads, 0);
+    let dir = tempfile::tempdir()?;
+    let num_nodes = graph.num_nodes();
+    let nodes_per_thread = (num_nodes + num_threads - 1) / num_threads;
+
+    let mut pl = ProgressLogger::default();
+    pl.item_name = "node";
+    pl.expected_updates = Some(num_nodes);
+    pl.start("Creating batches in parallel...");
+    let pl = std::sync::Mutex::new(pl);
+
+    let batches = std::thread::scope(|s| {
+        let handles = (0..num_threads)
+            .map(|thread_id| {
+                let thread_dir = dir.path().join(format!("{:04x}", thread_id));
+                let pl = &pl;
+                s.spawn(move || -> Result<Vec<BatchIterator<()>>> {
+                    std::fs::create_dir(&thread_dir)?;
+                    let mut sorted = <SortPairs<()>>::new(batch_size, thread_dir)?;
+                    let start = (thread_id * nodes_per_thread).min(num_nodes);
+                    let end = (start + nodes_per_thread).min(num_nodes);
+                    for src in start..end {
+                        for dst in graph.successors(src) {
+                            sorted.push(dst, src, ())?;
+                        }
+                        if (src - start) % 1024 == 1023 {
+                            pl.lock().unwrap().update_with_count(1024);
+                        }
+                    }
+                    sorted.batches()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut batches = Vec::new();
+        for handle in handles {
+            match handle.join() {
+                Ok(thread_batches) => batches.extend(thread_batches?),
+                Err(_) => anyhow::bail!("A transposition thread panicked"),
+            }
+        }
+        Ok(batches)
+    })?;
+    pl.into_inner().unwrap().done();
+
+    // merge the batches of all the threads; there are no duplicates, as
+    // the arcs of the graph are distinct
+    Ok(COOIterToGraph::new(
+        num_nodes,
+        SortedArcs::dedup(batches, dir),
+    ))
+}
+
 /// Create transpose the graph and return a sequential graph view of it
 #[allow(clippy::type_complexity)]
 pub fn transpose_labelled<G: LabelledSequentialGraph>(
@@ -87,6 +156,30 @@ fn test_transposition() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_par_transposition() -> anyhow::Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    let arcs = vec![
+        (0, 1),
+        (0, 2),
+        (1, 2),
+        (1, 3),
+        (2, 4),
+        (3, 4),
+        (4, 0),
+        (4, 1),
+    ];
+    let g = VecGraph::from_arc_list(&arcs);
+
+    let expected = VecGraph::from_node_iter(transpose(&g, 3)?.iter_nodes());
+    for num_threads in 1..=7 {
+        let trans = par_transpose(&g, 2, num_threads)?;
+        assert_eq!(VecGraph::from_node_iter(trans.iter_nodes()), expected);
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 #[cfg_attr(test, test)]
 fn test_transposition_labelled() -> anyhow::Result<()> {
@@ -108,7 +201,7 @@ fn test_transposition_labelled() -> anyhow::Result<()> {
             &self,
             bitstream: &mut B,
         ) -> Result<usize> {
-            let value = self.0 as u64;
+            let value = self.0.to_bits();
             let mantissa = value & ((1 << 53) - 1);
             let exponent = value >> 53;
             let mut written_bits = 0;
@@ -139,13 +232,30 @@ fn test_transposition_labelled() -> anyhow::Result<()> {
 
     assert_eq!(g3, g4);
 
-    //// test transposition with labels
-    //let trans = transpose_labelled(&g, 3)?;
-    //let g5 = VecGraph::from_labelled_node_iter(trans.iter_nodes());
-    //
-    //let trans = transpose_labelled(&g5, 3)?;
-    //let g6 = VecGraph::from_labelled_node_iter(trans.iter_nodes());
-    //
-    //assert_eq!(g, g6);
+    // test transposition with labels; labels are compared bitwise, as
+    // VecGraph ignores them when comparing graphs and NaN is not equal to
+    // itself
+    let labelled_arcs = |g: &VecGraph<Payload>| {
+        g.iter_nodes()
+            .flat_map(|(src, succ)| {
+                succ.labelled()
+                    .map(move |(dst, l)| (src, dst, l.0.to_bits()))
+            })
+            .collect::<Vec<_>>()
+    };
+    let trans = transpose_labelled(&g, 3)?;
+    let g5 = VecGraph::from_labelled_node_iter(trans.iter_nodes());
+    let mut expected = labelled_arcs(&g)
+        .into_iter()
+        .map(|(src, dst, l)| (dst, src, l))
+        .collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(labelled_arcs(&g5), expected);
+
+    let trans = transpose_labelled(&g5, 3)?;
+    let g6 = VecGraph::from_labelled_node_iter(trans.iter_nodes());
+
+    assert_eq!(g, g6);
+    assert_eq!(labelled_arcs(&g), labelled_arcs(&g6));
     Ok(())
 }
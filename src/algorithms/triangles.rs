@@ -0,0 +1,181 @@
+use crate::traits::RandomAccessGraph;
+use dsi_progress_logger::ProgressLogger;
+use rayon::prelude::*;
+
+/// Call `f` on each element of `successors`, a sorted iterator, that is also
+/// in the sorted slice `candidates`.
+#[inline(always)]
+pub(crate) fn intersect(
+    candidates: &[usize],
+    successors: impl Iterator<Item = usize>,
+    mut f: impl FnMut(usize),
+) {
+    let mut candidates = candidates.iter().copied().peekable();
+    for succ in successors {
+        while candidates.next_if(|&candidate| candidate < succ).is_some() {}
+        match candidates.peek() {
+            None => return,
+            Some(&candidate) if candidate == succ => f(succ),
+            _ => {}
+        }
+    }
+}
+
+/// The symmetric graph oriented from each node to the adjacent nodes with a
+/// larger `(degree, id)` pair, with the successors sorted by id.
+struct Oriented {
+    /// The successors of `u` are at positions `offsets[u]..offsets[u + 1]`.
+    offsets: Vec<usize>,
+    successors: Vec<usize>,
+}
+
+impl Oriented {
+    fn new<G: RandomAccessGraph + Sync>(graph: &G) -> Self {
+        let num_nodes = graph.num_nodes();
+        let degrees = (0..num_nodes)
+            .into_par_iter()
+            .map(|x| graph.outdegree(x))
+            .collect::<Vec<_>>();
+        let mut offsets = Vec::with_capacity(num_nodes + 1);
+        let mut successors = Vec::new();
+        offsets.push(0);
+        for (u, succ) in graph.iter_nodes() {
+            successors.extend(succ.filter(|&v| (degrees[u], u) < (degrees[v], v)));
+            offsets.push(successors.len());
+        }
+        // the successors of a random-access graph are sorted, but those of
+        // its sequential iterator need not be
+        for u in 0..num_nodes {
+            successors[offsets[u]..offsets[u + 1]].sort_unstable();
+        }
+        Self {
+            offsets,
+            successors,
+        }
+    }
+
+    #[inline(always)]
+    fn successors(&self, u: usize) -> &[usize] {
+        &self.successors[self.offsets[u]..self.offsets[u + 1]]
+    }
+
+    /// Call `f` on each triangle `(u, v, w)` such that `u` is the given node,
+    /// and `v` and `w` are successors of `u` in the oriented graph, with `w`
+    /// a successor of `v`.
+    #[inline(always)]
+    fn node_triangles(&self, u: usize, mut f: impl FnMut(usize, usize, usize)) {
+        let successors = self.successors(u);
+        for &v in successors {
+            intersect(successors, self.successors(v).iter().copied(), |w| {
+                f(u, v, w)
+            });
+        }
+    }
+}
+
+/// Return the number of triangles of a symmetric graph, ignoring loops.
+///
+/// The graph is first oriented from each node to the adjacent nodes with a
+/// larger `(degree, id)` pair, and the oriented graph is kept in memory, so
+/// each triangle is found once from its node with the smallest pair, by
+/// intersecting, for each successor `v` of `u` in the oriented graph, the
+/// oriented successors of `u` and of `v`. Since oriented lists have
+/// *O*(√*m*) elements, the running time is *O*(*m* √*m*) independently of
+/// the numbering of the nodes. Nodes are processed in parallel.
+///
+/// If the graph is not symmetric, only the arcs from a node to a node with a
+/// larger `(degree, id)` pair are considered.
+pub fn count_triangles<G: RandomAccessGraph + Sync>(graph: &G) -> usize {
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "node";
+    pl.start("Orienting the graph...");
+    let oriented = Oriented::new(graph);
+    pl.done_with_count(graph.num_nodes());
+    pl.start("Counting triangles...");
+    let count = (0..graph.num_nodes())
+        .into_par_iter()
+        .map(|u| {
+            let mut count = 0;
+            oriented.node_triangles(u, |_, _, _| count += 1);
+            count
+        })
+        .sum();
+    pl.done_with_count(graph.num_nodes());
+    log::info!("{} triangles", count);
+    count
+}
+
+/// Return a sequential iterator over the triangles of a symmetric graph,
+/// ignoring loops, as triples of nodes `(u, v, w)` with `u` < `v` < `w`.
+///
+/// The triangles are found as in [`count_triangles`], from the node with the
+/// smallest `(degree, id)` pair, in increasing order of that node; thus, the
+/// triangles are not in lexicographical order.
+pub fn triangles<G: RandomAccessGraph + Sync>(
+    graph: &G,
+) -> impl Iterator<Item = (usize, usize, usize)> {
+    let oriented = Oriented::new(graph);
+    (0..graph.num_nodes()).flat_map(move |u| {
+        let mut triangles = Vec::new();
+        oriented.node_triangles(u, |u, v, w| {
+            let mut triangle = [u, v, w];
+            triangle.sort_unstable();
+            triangles.push((triangle[0], triangle[1], triangle[2]))
+        });
+        triangles
+    })
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_triangles() {
+    use crate::graph::vec_graph::VecGraph;
+    use rand::prelude::*;
+    let num_nodes = 50;
+    let mut rng = SmallRng::seed_from_u64(0);
+    let mut arcs = (0..400)
+        .map(|_| (rng.gen_range(0..num_nodes), rng.gen_range(0..num_nodes)))
+        .collect::<Vec<_>>();
+    arcs.extend(arcs.clone().into_iter().map(|(src, dst)| (dst, src)));
+    let graph = VecGraph::from_arc_list(&arcs);
+
+    let mut expected = Vec::new();
+    for u in 0..graph.num_nodes() {
+        for v in u + 1..graph.num_nodes() {
+            for w in v + 1..graph.num_nodes() {
+                if graph.has_arc(u, v) && graph.has_arc(u, w) && graph.has_arc(v, w) {
+                    expected.push((u, v, w));
+                }
+            }
+        }
+    }
+    assert!(!expected.is_empty());
+    let mut found = triangles(&graph).collect::<Vec<_>>();
+    found.sort_unstable();
+    assert_eq!(found, expected);
+    assert_eq!(count_triangles(&graph), expected.len());
+
+    // a triangle with loops
+    let graph = VecGraph::from_arc_list(&[
+        (0, 0),
+        (0, 1),
+        (1, 0),
+        (1, 2),
+        (2, 1),
+        (0, 2),
+        (2, 0),
+        (2, 2),
+    ]);
+    assert_eq!(count_triangles(&graph), 1);
+
+    // a symmetric wheel, whose hub has the largest degree and the smallest
+    // id, has as many triangles as spokes
+    let mut arcs = Vec::new();
+    for x in 1..=10 {
+        let y = x % 10 + 1;
+        arcs.extend([(0, x), (x, 0), (x, y), (y, x)]);
+    }
+    let graph = VecGraph::from_arc_list(&arcs);
+    assert_eq!(count_triangles(&graph), 10);
+    assert!(triangles(&graph).all(|(u, v, w)| u == 0 && v < w));
+}
@@ -0,0 +1,64 @@
+use crate::traits::SequentialGraph;
+use crate::utils::{sort_arcs, SortedArcsGraph};
+use anyhow::Result;
+
+/// Return a sequential graph view of the union of the given graphs.
+///
+/// If `disjoint` is false, the graphs share the same node id space, and the
+/// result has as many nodes as the largest graph; arcs appearing in more
+/// than one graph appear just once in the result. If `disjoint` is true, the
+/// id spaces are instead concatenated, so node `x` of the `i`-th graph
+/// becomes node `x` plus the number of nodes of the previous graphs, and the
+/// result is the disjoint union of the graphs.
+///
+/// The arcs are merged out of core by [`sort_arcs`] with batches of
+/// `batch_size` pairs, so the graphs can be arbitrarily large.
+pub fn union<G: SequentialGraph>(
+    graphs: &[G],
+    disjoint: bool,
+    batch_size: usize,
+) -> Result<SortedArcsGraph> {
+    let num_nodes = if disjoint {
+        graphs.iter().map(|graph| graph.num_nodes()).sum()
+    } else {
+        graphs
+            .iter()
+            .map(|graph| graph.num_nodes())
+            .max()
+            .unwrap_or(0)
+    };
+    let mut offset = 0;
+    let arcs = graphs.iter().flat_map(|graph| {
+        let graph_offset = offset;
+        if disjoint {
+            offset += graph.num_nodes();
+        }
+        graph.iter_nodes().flat_map(move |(src, succ)| {
+            succ.into_iter()
+                .map(move |dst| (src + graph_offset, dst + graph_offset))
+        })
+    });
+    sort_arcs(arcs, Some(num_nodes), batch_size)
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_union() -> anyhow::Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    let graphs = [
+        VecGraph::from_arc_list(&[(0, 1), (1, 2), (2, 0)]),
+        VecGraph::from_arc_list(&[(0, 1), (1, 0), (3, 1)]),
+    ];
+    let shared = VecGraph::from_node_iter(union(&graphs, false, 2)?.iter_nodes());
+    assert_eq!(
+        shared,
+        VecGraph::from_arc_list(&[(0, 1), (1, 0), (1, 2), (2, 0), (3, 1)])
+    );
+    let disjoint = union(&graphs, true, 2)?;
+    assert_eq!(disjoint.num_nodes(), 7);
+    assert_eq!(
+        VecGraph::from_node_iter(disjoint.iter_nodes()),
+        VecGraph::from_arc_list(&[(0, 1), (1, 2), (2, 0), (3, 4), (4, 3), (6, 4)])
+    );
+    Ok(())
+}
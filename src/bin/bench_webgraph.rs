@@ -59,7 +59,7 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
             let seq = seq_iter.next_successors()?;
             let random = random_reader.successors(node_id).collect::<Vec<_>>();
 
-            assert_eq!(deg_reader.next_degree()?, seq.len(), "{}", node_id);
+            assert_eq!(deg_reader.next(), Some((node_id, seq.len())), "{}", node_id);
             assert_eq!(seq, random, "{}", node_id);
         }
     } else if args.sequential {
@@ -85,12 +85,10 @@ pub fn main() -> Result<(), Box<dyn std::error::Error>> {
             let seq_graph = webgraph::graph::bvgraph::load_seq(&args.basename)?;
             let seq_graph =
                 seq_graph.map_codes_reader_builder(DynamicCodesReaderSkipperBuilder::from);
-            let mut deg_reader = seq_graph.iter_degrees();
-
             let mut c: usize = 0;
             let start = std::time::Instant::now();
-            for _ in 0..seq_graph.num_nodes() {
-                c += deg_reader.next_degree()?;
+            for (_, degree) in seq_graph.iter_degrees() {
+                c += degree;
             }
             println!(
                 "Degrees Only:{:>20} ns/arc",
@@ -0,0 +1,66 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use epserde::prelude::*;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Computes or approximates the betweenness centrality of the nodes of a graph, storing it in BASENAME.betweenness", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: String,
+    /// The basename of the file BASENAME.betweenness where the scores,
+    /// serialized with ε-serde as a Vec<f64>, will be stored; defaults to the
+    /// basename of the graph.
+    #[clap(long)]
+    dest: Option<String>,
+    /// Sample enough sources so that the error on each score is at most
+    /// epsilon · n (n - 2) with probability 1 - delta, instead of computing
+    /// the scores exactly.
+    #[clap(short, long)]
+    epsilon: Option<f64>,
+    /// The probability that the error bound does not hold.
+    #[clap(short, long, default_value_t = 0.1)]
+    delta: f64,
+    /// Sample this number of sources, instead of computing the scores
+    /// exactly.
+    #[clap(long, conflicts_with = "epsilon")]
+    samples: Option<usize>,
+    /// The seed of the random choice of the sources.
+    #[clap(long, default_value_t = 0)]
+    seed: u64,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let graph = webgraph::graph::bvgraph::load(&args.basename)?;
+    let samples = args.samples.or_else(|| {
+        args.epsilon
+            .map(|epsilon| betweenness_samples(graph.num_nodes(), epsilon, args.delta))
+    });
+    let scores = match samples {
+        Some(samples) => {
+            log::info!("Sampling {} sources", samples);
+            approximate_betweenness(&graph, samples, args.seed)
+        }
+        None => betweenness(&graph),
+    };
+
+    let path = format!(
+        "{}.betweenness",
+        args.dest.as_ref().unwrap_or(&args.basename)
+    );
+    let mut file = std::io::BufWriter::new(
+        std::fs::File::create(&path).with_context(|| format!("Cannot create {}", path))?,
+    );
+    scores.serialize(&mut file)?;
+    std::io::Write::flush(&mut file)?;
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use anyhow::Result;
+use clap::Parser;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Computes the permutation induced by a BFS visit of a graph", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: String,
+    /// The file where the permutation (node to rank) will be stored.
+    perm: String,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let graph = webgraph::graph::bvgraph::load(&args.basename)?;
+    let order = bfs_order(&graph);
+
+    // the visit order maps ranks to nodes, we need the inverse
+    let mut perm = vec![0; order.len()];
+    for (rank, &node) in order.iter().enumerate() {
+        perm[node] = rank;
+    }
+    store_perm(&args.perm, &perm)?;
+
+    Ok(())
+}
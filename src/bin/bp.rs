@@ -0,0 +1,46 @@
+use anyhow::Result;
+use clap::Parser;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Computes the recursive graph bisection ordering of a graph", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: String,
+    /// The file where the permutation (node to rank) will be stored.
+    perm: String,
+    /// The maximum number of swapping iterations at each level.
+    #[clap(short = 'i', long, default_value_t = 20)]
+    max_iters: usize,
+    /// The size under which a set of nodes is not bisected anymore.
+    #[clap(short = 'm', long, default_value_t = 32)]
+    min_size: usize,
+    /// The maximum depth of the recursion.
+    #[clap(short = 'd', long, default_value_t = usize::MAX)]
+    max_depth: usize,
+    /// The size of a batch used by the transposition.
+    #[clap(short = 's', long, default_value_t = 1_000_000)]
+    batch_size: usize,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let graph = webgraph::graph::bvgraph::load_seq(&args.basename)?;
+    let perm = bp(
+        &graph,
+        args.max_iters,
+        args.min_size,
+        args.max_depth,
+        args.batch_size,
+    )?;
+    store_perm(&args.perm, &perm)?;
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use anyhow::Result;
+use clap::Parser;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Builds the map between the identifiers of the nodes of a graph and their ids", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: String,
+    /// The file containing the identifiers of the nodes, one per line,
+    /// sorted in increasing order.
+    ids: String,
+    /// The number of identifiers in a block of the front-coded list.
+    #[clap(short = 'r', long, default_value_t = 32)]
+    ratio: usize,
+    /// Look up the given identifiers after building the map, printing their
+    /// node ids.
+    #[clap(long)]
+    lookup: Vec<String>,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let map = NodeIdMapBuilder::new()
+        .ratio(args.ratio)
+        .build_from_file(&args.ids)?;
+    map.store(&args.basename)?;
+    for name in &args.lookup {
+        match map.id_of(name) {
+            Some(node) => println!("{}\t{}", name, node),
+            None => println!("{}\t-", name),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use anyhow::Result;
+use clap::Parser;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Create the '.outdegrees' file for a graph, caching its outdegrees for random access", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: String,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let seq_graph = webgraph::graph::bvgraph::load_seq(&args.basename)?;
+    let seq_graph = seq_graph.map_codes_reader_builder(DynamicCodesReaderSkipperBuilder::from);
+    let outdegrees = Outdegrees::build(&seq_graph);
+    log::info!(
+        "Using {} bits per node for {} nodes",
+        outdegrees.bit_width(),
+        outdegrees.len()
+    );
+    outdegrees.store(&args.basename)
+}
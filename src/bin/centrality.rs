@@ -0,0 +1,41 @@
+use anyhow::Result;
+use clap::Parser;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Computes the closeness and harmonic centralities of the nodes of a graph", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: String,
+    /// The basename of the files BASENAME.closeness and BASENAME.harmonic
+    /// where the centralities will be stored; defaults to the basename of
+    /// the graph.
+    #[clap(long)]
+    dest: Option<String>,
+    /// Approximate the centralities with visits from this number of random
+    /// sources instead of computing them exactly.
+    #[clap(long)]
+    samples: Option<usize>,
+    /// The seed of the random choice of the sources.
+    #[clap(long, default_value_t = 0)]
+    seed: u64,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let graph = webgraph::graph::bvgraph::load(&args.basename)?;
+    let scores = match args.samples {
+        Some(samples) => approximate_centralities(&graph, samples, args.seed),
+        None => centralities(&graph),
+    };
+    scores.store(args.dest.as_ref().unwrap_or(&args.basename))?;
+
+    Ok(())
+}
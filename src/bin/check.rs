@@ -0,0 +1,34 @@
+use anyhow::Result;
+use clap::Parser;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Checks that the successors of a graph are strictly increasing and in range, and that the number of arcs matches the .properties file", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: String,
+    /// Report self loops as violations.
+    #[clap(long)]
+    no_self_loops: bool,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let graph = load_seq_dyn(&args.basename)?;
+    let violations = check_graph(&graph, !args.no_self_loops);
+    for violation in &violations {
+        println!("{}", violation);
+    }
+    if !violations.is_empty() {
+        eprintln!("Found {} violations", violations.len());
+        std::process::exit(1);
+    }
+    Ok(())
+}
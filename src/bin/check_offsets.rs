@@ -0,0 +1,125 @@
+use anyhow::{ensure, Context, Result};
+use clap::Parser;
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::ProgressLogger;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use sux::prelude::*;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Check that the '.ef' and '.offsets' files of a graph match its bitstream", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: String,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let seq_graph = webgraph::graph::bvgraph::load_seq(&args.basename)?;
+    let seq_graph = seq_graph.map_codes_reader_builder(DynamicCodesReaderSkipperBuilder::from);
+    let num_nodes = seq_graph.num_nodes();
+
+    let ef_path = format!("{}.ef", args.basename);
+    let ef = if Path::new(&ef_path).exists() {
+        let ef = sux::prelude::map::<_, webgraph::EF<&[u64]>>(
+            &ef_path,
+            &sux::prelude::Flags::TRANSPARENT_HUGE_PAGES,
+        )
+        .with_context(|| format!("Cannot open the elias-fano file {}", ef_path))?;
+        // one offset per node, plus the end of the bitstream
+        if ef.len() != num_nodes + 1 {
+            eprintln!(
+                "The elias-fano file contains {} offsets, but {} are needed for {} nodes",
+                ef.len(),
+                num_nodes + 1,
+                num_nodes
+            );
+            std::process::exit(1);
+        }
+        Some(ef)
+    } else {
+        None
+    };
+
+    let offsets_path = format!("{}.offsets", args.basename);
+    let mut offsets = if Path::new(&offsets_path).exists() {
+        let file = BufReader::with_capacity(1 << 20, File::open(&offsets_path)?);
+        let reader = BufferedBitStreamRead::<BE, u64, _>::new(<FileBackend<u32, _>>::new(file));
+        Some(reader)
+    } else {
+        None
+    };
+
+    ensure!(
+        ef.is_some() || offsets.is_some(),
+        "Neither {} nor {} exist, there is nothing to check",
+        ef_path,
+        offsets_path
+    );
+
+    // compare the offset of the given node in the bitstream with the ones
+    // in the offset files, returning a description of the first mismatch
+    let mut offset = 0;
+    let mut check = |node: usize, pos: u64| -> Option<String> {
+        if let Some(ef) = &ef {
+            if ef.get(node) != pos {
+                return Some(format!(
+                    "Node {} starts at bit {} of the bitstream, but the elias-fano file says {}",
+                    node,
+                    pos,
+                    ef.get(node)
+                ));
+            }
+        }
+        if let Some(reader) = &mut offsets {
+            match reader.read_gamma() {
+                Ok(delta) => offset += delta,
+                Err(e) => {
+                    return Some(format!(
+                        "Cannot read the offset of node {} from the offsets file: {}",
+                        node, e
+                    ))
+                }
+            }
+            if offset != pos {
+                return Some(format!(
+                    "Node {} starts at bit {} of the bitstream, but the offsets file says {}",
+                    node, pos, offset
+                ));
+            }
+        }
+        None
+    };
+
+    let mut pr = ProgressLogger::default().display_memory();
+    pr.item_name = "offset";
+    pr.expected_updates = Some(num_nodes + 1);
+    pr.start("Checking offsets...");
+    let mut degs_iter = seq_graph.iter_degrees_with_offsets();
+    for (pos, node_id, _degree) in &mut degs_iter {
+        if let Some(error) = check(node_id, pos) {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+        pr.light_update();
+    }
+    // the offset past the last node, that is, the end of the bitstream
+    if let Some(error) = check(num_nodes, degs_iter.get_pos()) {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
+    pr.light_update();
+    pr.done();
+
+    log::info!("The offsets are consistent with the bitstream");
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use anyhow::Result;
+use clap::Parser;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Computes the local clustering coefficients of a symmetric graph, storing them in BASENAME.clustering", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: String,
+    /// The basename of the file BASENAME.clustering where the coefficients,
+    /// serialized with ε-serde as a Vec<f64>, will be stored; defaults to the
+    /// basename of the graph.
+    #[clap(long)]
+    dest: Option<String>,
+    /// The number of nodes whose coefficients are computed at once.
+    #[clap(long, default_value_t = 1 << 20)]
+    block_size: usize,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let graph = webgraph::graph::bvgraph::load(&args.basename)?;
+    let dest = args.dest.as_ref().unwrap_or(&args.basename);
+    let stats = store_clustering_coefficients(&graph, dest, args.block_size)?;
+    println!("triangles\t{}", stats.triangles);
+    println!("global\t{}", stats.global);
+    println!("average\t{}", stats.average);
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use anyhow::Result;
+use clap::Parser;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Compose a sequence of permutations, applying them in order", long_about = None)]
+struct Args {
+    /// The file where the composed permutation will be stored.
+    dest: String,
+    /// The permutations to compose; native permutations are memory-mapped,
+    /// while the other formats detected by `load_perm` are loaded in memory.
+    #[arg(required = true)]
+    perms: Vec<String>,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let perms = args
+        .perms
+        .iter()
+        .map(|path| -> Result<Box<dyn AsRef<[usize]>>> {
+            Ok(match PermFormat::detect(path)? {
+                PermFormat::Raw => Box::new(map_perm(path)?),
+                _ => Box::new(load_perm(path)?),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let perms = perms
+        .iter()
+        .map(|perm| (**perm).as_ref())
+        .collect::<Vec<_>>();
+
+    log::info!("Composing {} permutations", perms.len());
+    store_perm_iter(&args.dest, compose_orders(&perms)?)?;
+
+    Ok(())
+}
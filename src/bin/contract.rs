@@ -0,0 +1,47 @@
+use anyhow::Result;
+use clap::Parser;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Compresses the quotient of a graph by a labelling of its nodes (e.g., the host graph of a page graph)", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    source: String,
+    /// The file containing the label of each node, in any of the formats of
+    /// permutation files.
+    labels: String,
+    /// The basename of the quotient graph.
+    dest: String,
+    /// Keep the arcs between nodes with the same label as loops.
+    #[clap(long)]
+    loops: bool,
+    /// The size of a batch.
+    #[clap(short = 's', long, default_value_t = 1_000_000)]
+    batch_size: usize,
+    /// The number of cores to use to compress the quotient graph.
+    #[arg(short = 'j', long)]
+    num_cpus: Option<usize>,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let graph = webgraph::graph::bvgraph::load_seq(&args.source)?;
+    let labels = load_perm(&args.labels)?;
+    let quotient = contract(&graph, &labels, args.loops, args.batch_size)?;
+    parallel_compress_sequential_iter(
+        args.dest,
+        quotient.iter_nodes(),
+        quotient.num_nodes(),
+        CompFlags::default(),
+        args.num_cpus.unwrap_or(rayon::current_num_threads()),
+    )?;
+
+    Ok(())
+}
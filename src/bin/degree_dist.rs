@@ -0,0 +1,83 @@
+use anyhow::Result;
+use clap::Parser;
+use clap::ValueEnum;
+use std::io::{BufWriter, Write};
+use webgraph::prelude::*;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum PrivDegree {
+    Out,
+    In,
+    Total,
+}
+
+impl From<PrivDegree> for DegreeKind {
+    fn from(value: PrivDegree) -> Self {
+        match value {
+            PrivDegree::Out => DegreeKind::Out,
+            PrivDegree::In => DegreeKind::In,
+            PrivDegree::Total => DegreeKind::Total,
+        }
+    }
+}
+
+#[derive(Parser, Debug)]
+#[command(about = "Writes the degree distribution of a graph as CSV and fits a power law to it", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: String,
+    /// The CSV file where the distribution will be stored.
+    csv: String,
+
+    #[arg(value_enum)]
+    #[clap(short, long, default_value = "out")]
+    /// The degree to use
+    degree: PrivDegree,
+
+    #[arg(short, long)]
+    /// Group the degrees in logarithmic bins with the given base, writing
+    /// the density of each bin instead of the number of nodes of each degree
+    log_bins: Option<f64>,
+
+    #[arg(short, long, default_value_t = 1)]
+    /// The minimum degree of the tail used to fit the power law
+    min_degree: usize,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let graph = webgraph::graph::bvgraph::load_seq(&args.basename)?;
+    let histogram = degree_histogram(&graph, args.degree.into());
+
+    let mut csv = BufWriter::new(std::fs::File::create(&args.csv)?);
+    if let Some(base) = args.log_bins {
+        writeln!(csv, "lower,upper,density")?;
+        for (lower, upper, density) in log_bins(&histogram, base) {
+            writeln!(csv, "{},{},{}", lower, upper, density)?;
+        }
+    } else {
+        writeln!(csv, "degree,count")?;
+        for (degree, count) in &histogram {
+            writeln!(csv, "{},{}", degree, count)?;
+        }
+    }
+    csv.flush()?;
+
+    match power_law_exponent(&histogram, args.min_degree) {
+        Some(alpha) => log::info!(
+            "Power-law exponent for degrees at least {}: {:.4}",
+            args.min_degree,
+            alpha
+        ),
+        None => log::info!("Not enough degrees to fit a power law"),
+    }
+
+    Ok(())
+}
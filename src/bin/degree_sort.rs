@@ -0,0 +1,55 @@
+use anyhow::Result;
+use clap::Parser;
+use clap::ValueEnum;
+use webgraph::prelude::*;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum PrivDegree {
+    Out,
+    In,
+    Total,
+}
+
+impl From<PrivDegree> for DegreeKind {
+    fn from(value: PrivDegree) -> Self {
+        match value {
+            PrivDegree::Out => DegreeKind::Out,
+            PrivDegree::In => DegreeKind::In,
+            PrivDegree::Total => DegreeKind::Total,
+        }
+    }
+}
+
+#[derive(Parser, Debug)]
+#[command(about = "Computes the permutation sorting the nodes of a graph by decreasing degree", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: String,
+    /// The file where the permutation (node to rank) will be stored.
+    perm: String,
+
+    #[arg(value_enum)]
+    #[clap(short, long, default_value = "out")]
+    /// The degree to sort by
+    degree: PrivDegree,
+
+    #[arg(short, long)]
+    /// Keep nodes with the same degree in their original order
+    stable: bool,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let graph = webgraph::graph::bvgraph::load(&args.basename)?;
+    let perm = degree_sort(&graph, args.degree.into(), args.stable);
+    store_perm(&args.perm, &perm)?;
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use anyhow::Result;
+use clap::Parser;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Estimates the effective diameter of a graph, or computes its exact diameter", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: String,
+    /// Compute the exact diameter with iFUB; the graph must be symmetric.
+    #[clap(long)]
+    exact: bool,
+    /// The base-2 logarithm of the number of registers of the HyperBall counters.
+    #[clap(long, default_value_t = 6)]
+    log2m: usize,
+    /// The seed of the hash function of the HyperBall counters.
+    #[clap(long, default_value_t = 0)]
+    seed: u64,
+    /// The fraction of reachable pairs defining the effective diameter.
+    #[clap(long, default_value_t = 0.9)]
+    alpha: f64,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let graph = webgraph::graph::bvgraph::load(&args.basename)?;
+
+    if args.exact {
+        println!("diameter\t{}", ifub_diameter(&graph));
+    } else {
+        let nf = neighbourhood_function(&graph, args.log2m, args.seed)?;
+        println!(
+            "effective_diameter\t{}",
+            effective_diameter(&nf, args.alpha)
+        );
+        println!("diameter_lower_bound\t{}", nf.len() - 1);
+    }
+
+    Ok(())
+}
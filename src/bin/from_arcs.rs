@@ -0,0 +1,98 @@
+use anyhow::Result;
+use clap::Parser;
+use dsi_progress_logger::ProgressLogger;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Compresses a graph from a file containing its arcs, sorted by source and destination unless --sort is given", long_about = None)]
+struct Args {
+    /// The file containing the arcs, one per line, as pairs of nodes
+    /// separated by whitespace, or `-` to read them from standard input;
+    /// arcs read from standard input are always sorted first, as with
+    /// --sort.
+    arcs: String,
+    /// The basename of the compressed graph.
+    dest: String,
+    /// The arcs are pairs of 64-bit little-endian integers.
+    #[clap(long)]
+    binary: bool,
+    /// The number of nodes; if missing, the file is scanned once more to
+    /// compute one plus the largest node.
+    #[clap(short = 'n', long)]
+    num_nodes: Option<usize>,
+    /// The arcs are not sorted, or contain duplicates: sort them first in
+    /// batches of the given number of arcs, which are kept in memory
+    /// (default for standard input: 16777216).
+    #[clap(long)]
+    sort: Option<usize>,
+    /// The number of cores to use to compress the graph.
+    #[arg(short = 'j', long)]
+    num_cpus: Option<usize>,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let format = if args.binary {
+        ArcListFormat::Binary
+    } else {
+        ArcListFormat::Text
+    };
+    let num_threads = args.num_cpus.unwrap_or(rayon::current_num_threads());
+    if args.arcs == "-" {
+        // standard input cannot be scanned twice, so the arcs are sorted
+        compress_arcs_with(
+            args.dest,
+            ArcListReader::new(std::io::stdin().lock(), format, "standard input"),
+            args.num_nodes,
+            CompFlags::default(),
+            args.sort.unwrap_or(DEFAULT_BATCH_SIZE),
+            num_threads,
+        )?;
+        return Ok(());
+    }
+    if let Some(batch_size) = args.sort {
+        compress_arcs_with(
+            args.dest,
+            ArcListIter::new(&args.arcs, format)?,
+            args.num_nodes,
+            CompFlags::default(),
+            batch_size,
+            num_threads,
+        )?;
+        return Ok(());
+    }
+
+    let num_nodes = match args.num_nodes {
+        Some(num_nodes) => num_nodes,
+        None => {
+            let mut pl = ProgressLogger::default();
+            pl.item_name = "arc";
+            pl.start("Computing the number of nodes...");
+            let mut num_nodes = 0;
+            for (src, dst) in ArcListIter::new(&args.arcs, format)? {
+                num_nodes = num_nodes.max(src.max(dst) + 1);
+                pl.light_update();
+            }
+            pl.done();
+            num_nodes
+        }
+    };
+
+    let graph = ArcListGraph::from_file(num_nodes, &args.arcs, format)?;
+    parallel_compress_sequential_iter(
+        args.dest,
+        graph.iter_nodes(),
+        graph.num_nodes(),
+        CompFlags::default(),
+        num_threads,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use anyhow::Result;
+use clap::Parser;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Compresses a graph in the ASCIIGraph format of the Java version of WebGraph", long_about = None)]
+struct Args {
+    /// The basename of the BASENAME.graph-txt file to read.
+    source: String,
+    /// The basename of the compressed graph.
+    dest: String,
+    /// The number of cores to use to compress the graph.
+    #[arg(short = 'j', long)]
+    num_cpus: Option<usize>,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let graph = ASCIIGraph::load(&args.source)?;
+    parallel_compress_sequential_iter(
+        args.dest,
+        graph.iter_nodes(),
+        graph.num_nodes(),
+        CompFlags::default(),
+        args.num_cpus.unwrap_or(rayon::current_num_threads()),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use anyhow::Result;
+use clap::Parser;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Generate a random R-MAT graph and compress it as a BVGraph", long_about = None)]
+struct Args {
+    /// The basename of the generated graph.
+    dest: String,
+    /// The base-2 logarithm of the number of nodes.
+    scale: u32,
+
+    #[arg(short, long, default_value_t = GRAPH500_EDGE_FACTOR)]
+    /// The number of arcs to generate per node, before removing duplicates
+    edge_factor: usize,
+
+    #[arg(short, default_value_t = 0.57)]
+    /// The probability of the top-left quadrant
+    a: f64,
+    #[arg(short, default_value_t = 0.19)]
+    /// The probability of the top-right quadrant
+    b: f64,
+    #[arg(short, default_value_t = 0.19)]
+    /// The probability of the bottom-left quadrant
+    c: f64,
+
+    #[arg(long, default_value_t = 0)]
+    /// The seed of the pseudorandom number generator
+    seed: u64,
+
+    #[clap(short = 's', long, default_value_t = 1_000_000)]
+    /// The size of a batch.
+    batch_size: usize,
+
+    #[arg(short = 'j', long)]
+    /// The number of cores to use
+    num_cpus: Option<usize>,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let rmat = Rmat::new(args.scale, args.a, args.b, args.c, args.seed);
+    let graph = rmat.to_graph(args.edge_factor * rmat.num_nodes(), args.batch_size)?;
+    parallel_compress_sequential_iter(
+        args.dest,
+        graph.iter_nodes(),
+        graph.num_nodes(),
+        CompFlags::default(),
+        args.num_cpus.unwrap_or(rayon::current_num_threads()),
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use anyhow::{ensure, Context, Result};
+use clap::Parser;
+use std::io::{BufRead, BufWriter, Write};
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Builds a landmark distance oracle for a graph, storing it in BASENAME.landmarks, or answers distance queries with it", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: String,
+    /// The basename of the transpose of the graph, needed to build the
+    /// oracle.
+    #[clap(short, long)]
+    transpose: Option<String>,
+    /// The number of landmarks.
+    #[clap(short = 'k', long, default_value_t = 16)]
+    num_landmarks: usize,
+    /// Choose the landmarks at random with this seed, rather than by degree.
+    #[clap(long)]
+    random: Option<u64>,
+    /// Read pairs of whitespace-separated node ids from standard input and
+    /// print a lower and an upper bound on their distance, using the stored
+    /// oracle.
+    #[clap(short, long)]
+    query: bool,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    if !args.query {
+        let transpose = args
+            .transpose
+            .context("The transpose is needed to build the oracle")?;
+        let graph = webgraph::graph::bvgraph::load(&args.basename)?;
+        let transpose = webgraph::graph::bvgraph::load(&transpose)?;
+        let selection = args
+            .random
+            .map_or(LandmarkSelection::Degree, LandmarkSelection::Random);
+        Landmarks::build(&graph, &transpose, args.num_landmarks, selection)
+            .store(&args.basename)?;
+        return Ok(());
+    }
+
+    let landmarks = Landmarks::load(&args.basename)?;
+    let format = |distance: usize| {
+        if distance == UNREACHABLE {
+            "inf".to_string()
+        } else {
+            distance.to_string()
+        }
+    };
+    let mut out = BufWriter::new(std::io::stdout().lock());
+    for line in std::io::stdin().lock().lines() {
+        let line = line?;
+        let nodes = line
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<Vec<usize>, _>>()?;
+        if nodes.is_empty() {
+            continue;
+        }
+        ensure!(nodes.len() == 2, "Invalid query: {}", line);
+        let (lower, upper) = landmarks.distance_bounds(nodes[0], nodes[1]);
+        writeln!(
+            out,
+            "{}\t{}\t{}\t{}",
+            nodes[0],
+            nodes[1],
+            format(lower),
+            format(upper)
+        )?;
+    }
+    out.flush()?;
+
+    Ok(())
+}
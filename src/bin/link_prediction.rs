@@ -0,0 +1,57 @@
+use anyhow::{ensure, Result};
+use clap::Parser;
+use std::io::{BufRead, BufWriter, Write};
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Reads pairs of whitespace-separated node ids from standard input and prints their common-neighbour, Adamic–Adar and resource-allocation scores in a symmetric graph", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: String,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let graph = webgraph::graph::bvgraph::load(&args.basename)?;
+    let mut pairs = Vec::new();
+    for line in std::io::stdin().lock().lines() {
+        let line = line?;
+        let nodes = line
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<Vec<usize>, _>>()?;
+        if nodes.is_empty() {
+            continue;
+        }
+        ensure!(nodes.len() == 2, "Invalid pair: {}", line);
+        for &node in &nodes {
+            ensure!(
+                node < graph.num_nodes(),
+                "Node {} does not exist: the graph has {} nodes",
+                node,
+                graph.num_nodes()
+            );
+        }
+        pairs.push((nodes[0], nodes[1]));
+    }
+
+    let scores = link_prediction_scores(&graph, &pairs);
+    let mut out = BufWriter::new(std::io::stdout().lock());
+    for ((u, v), scores) in pairs.iter().zip(scores) {
+        writeln!(
+            out,
+            "{}\t{}\t{}\t{}\t{}",
+            u, v, scores.common_neighbours, scores.adamic_adar, scores.resource_allocation
+        )?;
+    }
+    out.flush()?;
+
+    Ok(())
+}
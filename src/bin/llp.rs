@@ -13,6 +13,18 @@ struct Args {
     /// The maximum number of LLP iterations
     max_iters: usize,
 
+    #[arg(long, default_value_t = 0.0)]
+    /// Stop when at most this fraction of nodes changes label in an iteration
+    modified_threshold: f64,
+
+    #[arg(long)]
+    /// Stop when the gain of the objective function in an iteration is at most this value
+    delta_threshold: Option<f64>,
+
+    #[arg(long)]
+    /// The maximum number of seconds spent on each gamma
+    max_time: Option<u64>,
+
     #[arg(short, long, default_value_t = 1000)]
     /// The size of the chunks each thread processes for the LLP
     granularity: usize,
@@ -22,9 +34,9 @@ struct Args {
     /// at the start of each iteration
     chunk_size: usize,
 
-    #[arg(short, long, default_value_t = 1.0)]
-    /// The gamma to use in LLP
-    gamma: f64,
+    #[arg(long, value_delimiter = ',', default_values_t = vec![1.0])]
+    /// The gammas to use in LLP, separated by commas
+    gammas: Vec<f64>,
 
     #[arg(short = 'j', long)]
     /// The number of cores to use
@@ -33,14 +45,22 @@ struct Args {
     #[arg(short, long, default_value_t = 0x6135062444a930d0)]
     /// The seed to use for the prng
     seed: u64,
-}
 
-fn ceil_log2(x: usize) -> usize {
-    if x <= 2 {
-        x - 1
-    } else {
-        64 - (x - 1).leading_zeros() as usize
-    }
+    #[arg(short, long)]
+    /// Where to store the permutation, which can be applied with `perm`
+    perm: Option<String>,
+
+    #[arg(long)]
+    /// A directory where the state is periodically saved
+    checkpoint_dir: Option<String>,
+
+    #[arg(long, default_value_t = 600)]
+    /// The minimum number of seconds between two saves of the state
+    checkpoint_interval: u64,
+
+    #[arg(long)]
+    /// Resume from the state saved in the checkpoint directory
+    resume: bool,
 }
 
 pub fn main() -> Result<()> {
@@ -56,17 +76,30 @@ pub fn main() -> Result<()> {
     // load the graph
     let graph = webgraph::graph::bvgraph::load(&args.basename)?;
 
+    let checkpoint = args.checkpoint_dir.as_ref().map(|dir| LlpCheckpoint {
+        dir: dir.into(),
+        interval: std::time::Duration::from_secs(args.checkpoint_interval),
+        resume_from: args.resume.then(|| dir.into()),
+    });
+
     let mut perm = vec![0; graph.num_nodes()];
     // compute the LLP
     let labels = layered_label_propagation(
         &graph,
         &mut perm,
-        args.gamma,
+        &args.gammas,
         args.num_cpus,
-        args.max_iters,
+        &LlpConvergence {
+            max_iters: args.max_iters,
+            modified_threshold: args.modified_threshold,
+            delta_threshold: args.delta_threshold,
+            max_time: args.max_time.map(std::time::Duration::from_secs),
+        },
         args.chunk_size,
         args.granularity,
-        0,
+        args.seed,
+        checkpoint.as_ref(),
+        None,
     )?;
 
     log::info!("Elapsed: {}", start.elapsed().as_secs_f64());
@@ -75,25 +108,10 @@ pub fn main() -> Result<()> {
     std::fs::File::create(format!("{}-{}.labels", args.basename, 0))?.write_all(&labels)?;
 
     // dump the permutation
-    let cost = PermutedGraph {
-        graph: &graph,
-        perm: &perm,
+    if let Some(perm_path) = &args.perm {
+        store_perm(perm_path, &perm)?;
     }
-    .iter_nodes()
-    .map(|(x, succ)| {
-        let mut cost = 0;
-        if !succ.len() != 0 {
-            let mut sorted: Vec<_> = succ.collect();
-            sorted.sort();
-            cost += ceil_log2((x as isize - sorted[0] as isize).unsigned_abs());
-            cost += sorted
-                .windows(2)
-                .map(|w| ceil_log2(w[1] - w[0]))
-                .sum::<usize>();
-        }
-        cost
-    })
-    .sum::<usize>();
+    let cost = log_gap_cost(&graph, &perm);
     log::info!("The final cost is: {}", cost);
 
     Ok(())
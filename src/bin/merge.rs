@@ -0,0 +1,55 @@
+use anyhow::Result;
+use clap::Parser;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Compute the union of several BVGraphs and compress it", long_about = None)]
+struct Args {
+    /// The basename of the resulting graph.
+    dest: String,
+    /// The basenames of the graphs to merge.
+    #[arg(required = true)]
+    basenames: Vec<String>,
+
+    #[arg(short, long)]
+    /// Concatenate the id spaces of the graphs, computing their disjoint
+    /// union, instead of using a shared id space
+    disjoint: bool,
+
+    #[clap(short = 's', long, default_value_t = 1_000_000)]
+    /// The size of a batch.
+    batch_size: usize,
+
+    #[arg(short = 'j', long)]
+    /// The number of cores to use
+    num_cpus: Option<usize>,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let graphs = args
+        .basenames
+        .iter()
+        .map(webgraph::graph::bvgraph::load_seq)
+        .collect::<Result<Vec<_>>>()?;
+
+    // merge the graphs
+    let merged = union(&graphs, args.disjoint, args.batch_size)?;
+    // compress the merged graph
+    parallel_compress_sequential_iter(
+        args.dest,
+        merged.iter_nodes(),
+        merged.num_nodes(),
+        CompFlags::default(),
+        args.num_cpus.unwrap_or(rayon::current_num_threads()),
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use anyhow::Result;
+use clap::Parser;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Computes the MinHash signatures of the successor sets of the nodes of a graph, storing them in BASENAME.minhash, and optionally the shingle ordering", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: String,
+    /// The number of components of each signature.
+    #[clap(short = 'k', long, default_value_t = 16)]
+    num_hashes: usize,
+    /// The seed of the hash functions.
+    #[clap(long, default_value_t = 0)]
+    seed: u64,
+    /// Store the permutation (node to rank) sorting the nodes by signature
+    /// in this file.
+    #[clap(short, long)]
+    perm: Option<String>,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let graph = webgraph::graph::bvgraph::load_seq(&args.basename)?;
+    let signatures = MinHashSignatures::build(&graph, args.num_hashes, args.seed);
+    signatures.store(&args.basename)?;
+    if let Some(perm) = args.perm {
+        store_perm(perm, &signatures.shingle_order())?;
+    }
+
+    Ok(())
+}
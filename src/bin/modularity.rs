@@ -0,0 +1,33 @@
+use anyhow::Result;
+use clap::Parser;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Computes the modularity of a clustering of the nodes of a graph", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: String,
+    /// The file containing the label of the cluster of each node, in any of
+    /// the formats of permutation files (e.g., as written by llp).
+    labels: String,
+    /// Consider each arc as an undirected edge; do not use on symmetric
+    /// graphs, for which the directed modularity is the undirected one.
+    #[clap(long)]
+    undirected: bool,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let graph = webgraph::graph::bvgraph::load_seq(&args.basename)?;
+    let labels = load_perm(&args.labels)?;
+    println!("{}", modularity(&graph, &labels, !args.undirected));
+
+    Ok(())
+}
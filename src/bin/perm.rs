@@ -1,25 +1,41 @@
 use anyhow::Result;
 use clap::Parser;
-//use dsi_bitstream::prelude::*;
-//use dsi_progress_logger::ProgressLogger;
-//use std::io::prelude::*;
-//use std::io::BufWriter;
-//use webgraph::prelude::*;
+use webgraph::prelude::*;
 
 #[derive(Parser, Debug)]
-#[command(about = "Performs an LLP round", long_about = None)]
+#[command(about = "Permute the nodes of a BVGraph and recompress it", long_about = None)]
 struct Args {
-    /// The basename of the sourge graph.
+    /// The basename of the source graph.
     source: String,
     /// The basename of the destination graph.
     dest: String,
-    /// The permutation.
+    /// The permutation, in any of the formats detected by `load_perm`.
     perm: String,
+
+    #[clap(short = 's', long, default_value_t = 1_000_000)]
+    /// The size of a batch.
+    batch_size: usize,
+
+    #[arg(short = 'j', long)]
+    /// The number of cores to use
+    num_cpus: Option<usize>,
+    /// Choose the codes minimizing the size of the graph with a preliminary
+    /// pass, instead of those of the source graph
+    #[clap(long)]
+    auto_codes: bool,
+    /// The compression window, instead of that of the source graph
+    #[clap(short = 'w', long)]
+    compression_window: Option<usize>,
+    /// The minimum interval length, instead of that of the source graph
+    #[clap(short = 'l', long)]
+    min_interval_length: Option<usize>,
+    /// The maximum recursion depth for references, instead of that of the
+    /// source graph
+    #[clap(short = 'c', long)]
+    max_ref_count: Option<usize>,
 }
 
 pub fn main() -> Result<()> {
-    todo!();
-    /*
     let args = Args::parse();
 
     stderrlog::new()
@@ -27,45 +43,34 @@ pub fn main() -> Result<()> {
         .timestamp(stderrlog::Timestamp::Second)
         .init()
         .unwrap();
-    let graph = webgraph::bvgraph::load(&args.source)?;
-    let num_nodes = graph.num_nodes();
-    let mut glob_pr = ProgressLogger::default().display_memory();
-    glob_pr.item_name = "node";
-
-    let mut perm = (0..num_nodes).collect::<Vec<_>>();
-    std::fs::File::open(args.perm)?
-        .read_exact(unsafe { std::mem::transmute::<&mut [usize], &mut [u8]>(&mut perm) })?;
-
-    let bit_write = <BufferedBitStreamWrite<LE, _>>::new(<FileBackend<u64, _>>::new(
-        BufWriter::new(std::fs::File::create(args.dest)?),
-    ));
 
-    let codes_writer = DynamicCodesWriter::new(
-        bit_write,
-        &CompFlags {
-            ..Default::default()
-        },
-    );
+    let seq_graph = webgraph::graph::bvgraph::load_seq(&args.source)?;
+    let perm = load_perm(&args.perm)?;
 
-    let mut sort_pairs = Sorted::new(num_nodes, 1_000_000_000).unwrap();
-    PermutedGraph {
-        graph: &graph,
-        perm: &perm,
+    // permute the graph
+    let permuted = permute(&seq_graph, &perm, args.batch_size)?;
+    // compress with the flags of the source graph, unless overridden
+    let mut compression_flags = BVGraphLoader::new(&args.source).comp_flags()?;
+    if let Some(compression_window) = args.compression_window {
+        compression_flags.compression_window = compression_window;
+    }
+    if let Some(min_interval_length) = args.min_interval_length {
+        compression_flags.min_interval_length = min_interval_length;
+    }
+    if let Some(max_ref_count) = args.max_ref_count {
+        compression_flags.max_ref_count = max_ref_count;
+    }
+    if args.auto_codes {
+        compression_flags = best_comp_flags(permuted.iter_nodes(), &compression_flags)?;
     }
-    .iter_nodes()
-    .for_each(|(x, succ)| {
-        succ.for_each(|s| {
-            sort_pairs.push(x, s).unwrap();
-        })
-    });
+    // compress the permuted graph
+    parallel_compress_sequential_iter(
+        args.dest,
+        permuted.iter_nodes(),
+        seq_graph.num_nodes(),
+        compression_flags,
+        args.num_cpus.unwrap_or(rayon::current_num_threads()),
+    )?;
 
-    let mut bvcomp = BVComp::new(codes_writer, 1, 4, 3, 0);
-    glob_pr.expected_updates = Some(num_nodes);
-    glob_pr.item_name = "node";
-    glob_pr.start("Writing...");
-    bvcomp.extend(sort_pairs.build()?.iter_nodes())?;
-    bvcomp.flush()?;
-    glob_pr.done();
     Ok(())
-    */
 }
@@ -0,0 +1,33 @@
+use anyhow::Result;
+use clap::Parser;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Writes a uniformly random permutation", long_about = None)]
+struct Args {
+    /// The number of elements of the permutation.
+    num_nodes: usize,
+    /// The file where the permutation will be stored.
+    perm: String,
+    /// The seed of the pseudorandom number generator.
+    #[clap(short, long, default_value_t = 0)]
+    seed: u64,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    log::info!(
+        "Generating a random permutation of {} elements",
+        args.num_nodes
+    );
+    store_perm(&args.perm, &random_perm(args.num_nodes, args.seed))?;
+
+    Ok(())
+}
@@ -23,6 +23,36 @@ impl From<PrivCode> for Code {
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum PrivEndianness {
+    Big,
+    Little,
+}
+
+impl From<PrivEndianness> for GraphEndianness {
+    fn from(value: PrivEndianness) -> Self {
+        match value {
+            PrivEndianness::Big => GraphEndianness::Big,
+            PrivEndianness::Little => GraphEndianness::Little,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum PrivChecksum {
+    Xxh3,
+    Sha256,
+}
+
+impl From<PrivChecksum> for Checksum {
+    fn from(value: PrivChecksum) -> Self {
+        match value {
+            PrivChecksum::Xxh3 => Checksum::Xxh3,
+            PrivChecksum::Sha256 => Checksum::Sha256,
+        }
+    }
+}
+
 #[derive(Parser, Debug)]
 #[command(about = "Recompress a BVGraph", long_about = None)]
 struct Args {
@@ -34,6 +64,18 @@ struct Args {
     #[arg(short = 'j', long)]
     /// The number of cores to use
     num_cpus: Option<usize>,
+    /// Choose the codes minimizing the size of the graph with a preliminary
+    /// pass, ignoring the codes specified
+    #[clap(long)]
+    auto_codes: bool,
+    /// Split the graph among the threads in chunks with approximately the
+    /// same number of arcs, rather than of nodes
+    #[clap(long)]
+    balance_arcs: bool,
+    /// Keep the compressed chunks in NEW_BASENAME.chunks so that, if the
+    /// compression fails, running again the same command resumes it
+    #[clap(long)]
+    resumable: bool,
     /// The compression windows
     #[clap(short = 'w', long, default_value_t = 7)]
     compression_window: usize,
@@ -68,6 +110,18 @@ struct Args {
     #[clap(short = 'e', long, default_value = "zeta3")]
     /// The code to use for the residuals
     residuals_code: PrivCode,
+
+    #[arg(value_enum)]
+    #[clap(short = 'E', long, default_value = "big")]
+    /// The endianness of the new graph; only big-endian graphs can be read
+    /// by the Java version
+    endianness: PrivEndianness,
+
+    #[arg(value_enum)]
+    #[clap(long)]
+    /// Record in the `.properties` file a digest of the new `.graph` file,
+    /// so that it can be verified when loading
+    checksum: Option<PrivChecksum>,
 }
 
 pub fn main() -> Result<()> {
@@ -90,15 +144,68 @@ pub fn main() -> Result<()> {
         max_ref_count: args.max_ref_count,
     };
 
-    let seq_graph = webgraph::graph::bvgraph::load_seq(&args.basename)?;
+    // the source graph is read with its own endianness, which might differ
+    // from the one of the new graph
+    match BVGraphLoader::new(&args.basename).graph_endianness()? {
+        GraphEndianness::Big => recompress(
+            &args,
+            compression_flags,
+            &webgraph::graph::bvgraph::load_seq(&args.basename)?,
+        )?,
+        GraphEndianness::Little => recompress(
+            &args,
+            compression_flags,
+            &webgraph::graph::bvgraph::load_seq_le(&args.basename)?,
+        )?,
+    }
+    if let Some(checksum) = args.checksum {
+        add_checksums(&args.new_basename, checksum.into())?;
+    }
+
+    Ok(())
+}
 
-    webgraph::graph::bvgraph::parallel_compress_sequential_iter(
-        args.new_basename,
-        seq_graph.iter_nodes(),
-        seq_graph.num_nodes(),
-        compression_flags,
-        args.num_cpus.unwrap_or(rayon::max_num_threads()),
-    )?;
+/// Compress `seq_graph` with the given flags, or with the best ones if
+/// `--auto-codes` was given, into the new basename.
+fn recompress<G: SequentialGraph>(
+    args: &Args,
+    mut compression_flags: CompFlags,
+    seq_graph: &G,
+) -> Result<()>
+where
+    for<'a> G::NodesIter<'a>: Clone + Send,
+{
+    if args.auto_codes {
+        compression_flags = best_comp_flags(seq_graph.iter_nodes(), &compression_flags)?;
+    }
 
+    let num_threads = args.num_cpus.unwrap_or(rayon::max_num_threads());
+    let bounds = if args.balance_arcs {
+        log::info!("Scanning the outdegrees to balance the chunks");
+        let degrees = seq_graph
+            .iter_nodes()
+            .map(|(_, successors)| successors.count())
+            .collect::<Vec<_>>();
+        arc_balanced_bounds(degrees.into_iter(), num_threads)
+    } else {
+        node_balanced_bounds(seq_graph.num_nodes(), num_threads)
+    };
+    if args.resumable {
+        parallel_compress_sequential_iter_resumable(
+            &args.new_basename,
+            seq_graph.iter_nodes(),
+            &bounds,
+            compression_flags,
+            args.endianness.into(),
+        )?;
+    } else {
+        parallel_compress_sequential_iter_with_bounds(
+            &args.new_basename,
+            seq_graph.iter_nodes(),
+            &bounds,
+            compression_flags,
+            args.endianness.into(),
+        )?;
+    }
     Ok(())
 }
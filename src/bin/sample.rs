@@ -0,0 +1,82 @@
+use anyhow::Result;
+use clap::Parser;
+use clap::ValueEnum;
+use webgraph::prelude::*;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum PrivMethod {
+    Uniform,
+    RandomWalk,
+    ForestFire,
+}
+
+#[derive(Parser, Debug)]
+#[command(about = "Samples the nodes of a BVGraph and compresses the subgraph they induce", long_about = None)]
+struct Args {
+    /// The basename of the source graph.
+    source: String,
+    /// The basename of the destination graph.
+    dest: String,
+    /// The number of nodes to sample.
+    num_nodes: usize,
+    /// The file where the ids in the source graph of the sampled nodes will be
+    /// stored, in the same format of permutations.
+    map: String,
+
+    #[arg(value_enum)]
+    #[clap(short, long, default_value = "forest-fire")]
+    /// The sampling method
+    method: PrivMethod,
+
+    #[arg(short, long)]
+    /// The restart probability of random walks (default 0.15) or the forward
+    /// burning probability of forest fires (default 0.7)
+    probability: Option<f64>,
+
+    #[arg(short, long, default_value_t = 0)]
+    /// The seed of the pseudorandom number generator
+    seed: u64,
+
+    #[arg(short = 'j', long)]
+    /// The number of cores to use
+    num_cpus: Option<usize>,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let method = match args.method {
+        PrivMethod::Uniform => SampleMethod::UniformNodes,
+        PrivMethod::RandomWalk => SampleMethod::RandomWalk {
+            restart: args.probability.unwrap_or(0.15),
+        },
+        PrivMethod::ForestFire => SampleMethod::ForestFire {
+            forward: args.probability.unwrap_or(0.7),
+        },
+    };
+
+    let graph = webgraph::graph::bvgraph::load(&args.source)?;
+    let (sample_graph, nodes) = sample(&graph, method, args.num_nodes, args.seed);
+    log::info!(
+        "Sampled {} nodes and {} arcs",
+        sample_graph.num_nodes(),
+        sample_graph.num_arcs()
+    );
+
+    store_perm(&args.map, &nodes)?;
+    parallel_compress_sequential_iter(
+        args.dest,
+        sample_graph.iter_nodes(),
+        sample_graph.num_nodes(),
+        CompFlags::default(),
+        args.num_cpus.unwrap_or(rayon::current_num_threads()),
+    )?;
+
+    Ok(())
+}
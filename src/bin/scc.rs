@@ -0,0 +1,59 @@
+use anyhow::Result;
+use clap::Parser;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Computes the strongly connected components of a graph, and optionally its condensation", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: String,
+    /// Store the component of each node, serialized with ε-serde, in
+    /// BASENAME.sccs.
+    #[clap(long)]
+    store: bool,
+    /// Compress the condensation of the graph with this basename.
+    #[clap(long)]
+    condensation: Option<String>,
+    /// The size of a batch when building the condensation.
+    #[clap(short = 's', long, default_value_t = 1_000_000)]
+    batch_size: usize,
+    /// The number of cores to use to compress the condensation.
+    #[arg(short = 'j', long)]
+    num_cpus: Option<usize>,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let graph = webgraph::graph::bvgraph::load(&args.basename)?;
+    let sccs = sccs(&graph);
+    let sizes = sccs.sizes();
+    println!("components\t{}", sccs.num_components);
+    println!("largest\t{}", sizes.iter().copied().max().unwrap_or(0));
+    println!(
+        "singletons\t{}",
+        sizes.iter().filter(|&&size| size == 1).count()
+    );
+
+    if args.store {
+        sccs.store(&args.basename)?;
+    }
+    if let Some(dest) = args.condensation {
+        let dag = condensation(&graph, &sccs, args.batch_size)?;
+        parallel_compress_sequential_iter(
+            dest,
+            dag.iter_nodes(),
+            sccs.num_components,
+            CompFlags::default(),
+            args.num_cpus.unwrap_or(rayon::current_num_threads()),
+        )?;
+    }
+
+    Ok(())
+}
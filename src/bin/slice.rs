@@ -0,0 +1,58 @@
+use anyhow::{ensure, Result};
+use clap::Parser;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Extract the subgraph induced by a range of nodes of a BVGraph and compress it", long_about = None)]
+struct Args {
+    /// The basename of the source graph.
+    source: String,
+    /// The basename of the destination graph.
+    dest: String,
+    /// The first node of the range (included).
+    start: usize,
+    /// The last node of the range (excluded).
+    end: usize,
+
+    #[arg(short = 'j', long)]
+    /// The number of cores to use
+    num_cpus: Option<usize>,
+    /// Choose the codes minimizing the size of the graph with a preliminary
+    /// pass, instead of the default ones
+    #[clap(long)]
+    auto_codes: bool,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let graph = webgraph::graph::bvgraph::load(&args.source)?;
+    ensure!(
+        args.start <= args.end && args.end <= graph.num_nodes(),
+        "Invalid range [{}..{}) for a graph with {} nodes",
+        args.start,
+        args.end,
+        graph.num_nodes()
+    );
+
+    let slice = SliceGraph::new(&graph, args.start, args.end);
+    let mut compression_flags = CompFlags::default();
+    if args.auto_codes {
+        compression_flags = best_comp_flags(slice.iter_nodes(), &compression_flags)?;
+    }
+    parallel_compress_sequential_iter(
+        args.dest,
+        slice.iter_nodes(),
+        slice.num_nodes(),
+        compression_flags,
+        args.num_cpus.unwrap_or(rayon::current_num_threads()),
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use anyhow::Result;
+use clap::Parser;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Computes global statistics of a graph", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: String,
+    /// Print the statistics as JSON instead of as a table.
+    #[clap(long)]
+    json: bool,
+    /// Compute also the reciprocity and the dyad census, transposing the
+    /// graph unless --transpose is given.
+    #[clap(long)]
+    reciprocity: bool,
+    /// The basename of the transpose of the graph.
+    #[clap(long)]
+    transpose: Option<String>,
+    /// Compute also the outdegree assortativity.
+    #[clap(long)]
+    assortativity: bool,
+    /// The size of a batch when transposing the graph.
+    #[clap(short = 's', long, default_value_t = 1_000_000)]
+    batch_size: usize,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let graph = webgraph::graph::bvgraph::load_seq(&args.basename)?;
+    // the outdegrees needed by the assortativity are read from the
+    // .outdegrees file if available, or collected while computing the stats
+    let outdegrees_path = format!("{}.outdegrees", args.basename);
+    let (mut stats, outdegrees) =
+        if args.assortativity && !std::path::Path::new(&outdegrees_path).exists() {
+            let (stats, outdegrees) = graph_stats_with_outdegrees(&graph);
+            (stats, Some(outdegrees))
+        } else {
+            (graph_stats(&graph), None)
+        };
+    let graph_len = std::fs::metadata(format!("{}.graph", args.basename))?.len();
+    if stats.num_arcs != 0 {
+        stats.bits_per_arc = Some(graph_len as f64 * 8.0 / stats.num_arcs as f64);
+    }
+    if args.reciprocity || args.transpose.is_some() {
+        stats.dyads = Some(match &args.transpose {
+            Some(basename) => dyad_census(&graph, &webgraph::graph::bvgraph::load_seq(basename)?),
+            None => dyad_census(&graph, &transpose(&graph, args.batch_size)?),
+        });
+    }
+
+    if args.assortativity {
+        stats.assortativity = Some(match outdegrees {
+            Some(outdegrees) => degree_assortativity(&graph, |node| outdegrees[node]),
+            None => {
+                let outdegrees = Outdegrees::load(&args.basename)?;
+                degree_assortativity(&graph, |node| outdegrees.get(node))
+            }
+        });
+    }
+
+    if args.json {
+        println!("{}", stats.to_json());
+    } else {
+        print!("{}", stats);
+    }
+
+    Ok(())
+}
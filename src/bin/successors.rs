@@ -0,0 +1,80 @@
+use anyhow::{ensure, Result};
+use clap::Parser;
+use std::io::{BufRead, BufWriter, Write};
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Prints the outdegree and the successors of some nodes of a BVGraph", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: String,
+    /// The nodes to query; if none is given, whitespace-separated node ids
+    /// are read from standard input.
+    nodes: Vec<usize>,
+
+    #[arg(short, long)]
+    /// A permutation, in any of the formats detected by `load_perm`, that was
+    /// applied to the graph: node ids are given and printed before the
+    /// permutation
+    perm: Option<String>,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let graph = webgraph::graph::bvgraph::load(&args.basename)?;
+    let perm = args.perm.as_ref().map(load_perm).transpose()?;
+    let inv_perm = perm.as_ref().map(|perm| {
+        let mut inv_perm = vec![0; perm.len()];
+        for (node, &image) in perm.iter().enumerate() {
+            inv_perm[image] = node;
+        }
+        inv_perm
+    });
+
+    let mut out = BufWriter::new(std::io::stdout().lock());
+    let mut query = |node: usize| -> Result<()> {
+        ensure!(
+            node < graph.num_nodes(),
+            "Node {} does not exist: the graph has {} nodes",
+            node,
+            graph.num_nodes()
+        );
+        let graph_node = perm.as_ref().map_or(node, |perm| perm[node]);
+        let mut successors = graph
+            .successors(graph_node)
+            .map(|succ| inv_perm.as_ref().map_or(succ, |inv_perm| inv_perm[succ]))
+            .collect::<Vec<_>>();
+        successors.sort_unstable();
+        write!(out, "{}\t{}\t", node, successors.len())?;
+        for (i, succ) in successors.iter().enumerate() {
+            if i != 0 {
+                write!(out, " ")?;
+            }
+            write!(out, "{}", succ)?;
+        }
+        writeln!(out)?;
+        Ok(())
+    };
+
+    if args.nodes.is_empty() {
+        for line in std::io::stdin().lock().lines() {
+            for token in line?.split_whitespace() {
+                query(token.parse()?)?;
+            }
+        }
+    } else {
+        for &node in &args.nodes {
+            query(node)?;
+        }
+    }
+    out.flush()?;
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use anyhow::Result;
+use clap::Parser;
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Writes a graph in the ASCIIGraph format of the Java version of WebGraph", long_about = None)]
+struct Args {
+    /// The basename of the graph.
+    basename: String,
+    /// The basename of the BASENAME.graph-txt file to write.
+    dest: String,
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let seq_graph = load_seq_dyn(&args.basename)?;
+    ASCIIGraph::store(&seq_graph, &args.dest)
+}
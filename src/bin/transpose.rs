@@ -40,6 +40,10 @@ struct Args {
     #[arg(short = 'j', long)]
     /// The number of cores to use
     num_cpus: Option<usize>,
+    /// Choose the codes minimizing the size of the graph with a preliminary
+    /// pass, ignoring the codes specified
+    #[clap(long)]
+    auto_codes: bool,
     /// The compression windows
     #[clap(short = 'w', long, default_value_t = 7)]
     compression_window: usize,
@@ -85,7 +89,7 @@ pub fn main() -> Result<()> {
         .init()
         .unwrap();
 
-    let compression_flags = CompFlags {
+    let mut compression_flags = CompFlags {
         outdegrees: args.outdegrees_code.into(),
         references: args.references_code.into(),
         blocks: args.blocks_code.into(),
@@ -100,6 +104,9 @@ pub fn main() -> Result<()> {
 
     // transpose the graph
     let sorted = webgraph::algorithms::transpose(&seq_graph, args.batch_size).unwrap();
+    if args.auto_codes {
+        compression_flags = best_comp_flags(sorted.iter_nodes(), &compression_flags)?;
+    }
     // compress the transposed graph
     parallel_compress_sequential_iter(
         args.basename,
@@ -0,0 +1,118 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use dsi_progress_logger::ProgressLogger;
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use webgraph::prelude::*;
+
+#[derive(Parser, Debug)]
+#[command(about = "Checks that two graphs have the same arcs, possibly with different codes or endianness", long_about = None)]
+struct Args {
+    /// The basename of the first graph.
+    first: String,
+    /// The basename of the second graph.
+    second: String,
+}
+
+/// The number of nodes sent at once by a decoding thread.
+const BATCH_SIZE: usize = 1024;
+
+type Batch = Vec<(usize, Vec<usize>)>;
+
+/// Send to `tx` the nodes of `graph` with their successors, in batches.
+fn send_batches<G: SequentialGraph>(graph: &G, tx: SyncSender<Batch>) {
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+    for (node, succ) in graph.iter_nodes() {
+        batch.push((node, succ.collect()));
+        if batch.len() == BATCH_SIZE {
+            if tx.send(batch).is_err() {
+                // the receiver found a difference and stopped
+                return;
+            }
+            batch = Vec::with_capacity(BATCH_SIZE);
+        }
+    }
+    if !batch.is_empty() {
+        let _ = tx.send(batch);
+    }
+}
+
+/// Load sequentially the graph with the given basename, using the
+/// endianness recorded in its properties, and decode it sending the nodes to
+/// `tx`.
+fn decode(basename: &str, tx: SyncSender<Batch>) -> Result<()> {
+    let properties_path = format!("{}.properties", basename);
+    let f = File::open(&properties_path)
+        .with_context(|| format!("Cannot open property file {}", properties_path))?;
+    let map = java_properties::read(BufReader::new(f))
+        .with_context(|| "cannot parse the .properties file as a java properties file")?;
+    match GraphEndianness::from_properties(&map)? {
+        GraphEndianness::Big => send_batches(&webgraph::graph::bvgraph::load_seq(basename)?, tx),
+        GraphEndianness::Little => {
+            send_batches(&webgraph::graph::bvgraph::load_seq_le(basename)?, tx)
+        }
+    }
+    Ok(())
+}
+
+/// Compare the nodes received from the two decoding threads, returning a
+/// description of the first difference, if any.
+fn compare(first: Receiver<Batch>, second: Receiver<Batch>) -> Option<String> {
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "node";
+    pl.start("Comparing the graphs...");
+
+    let mut first = first.into_iter().flatten();
+    let mut second = second.into_iter().flatten();
+    loop {
+        match (first.next(), second.next()) {
+            (None, None) => break,
+            (Some((node, _)), None) => {
+                return Some(format!("The second graph has only {} nodes", node))
+            }
+            (None, Some((node, _))) => {
+                return Some(format!("The first graph has only {} nodes", node))
+            }
+            (Some((node, succ)), Some((_, other_succ))) => {
+                if succ != other_succ {
+                    return Some(format!(
+                        "Node {} differs: {:?} != {:?}",
+                        node, succ, other_succ
+                    ));
+                }
+            }
+        }
+        pl.light_update();
+    }
+    pl.done();
+    None
+}
+
+pub fn main() -> Result<()> {
+    let args = Args::parse();
+
+    stderrlog::new()
+        .verbosity(2)
+        .timestamp(stderrlog::Timestamp::Second)
+        .init()
+        .unwrap();
+
+    let difference = std::thread::scope(|s| -> Result<Option<String>> {
+        let (first_tx, first_rx) = sync_channel(16);
+        let (second_tx, second_rx) = sync_channel(16);
+        let first = s.spawn(|| decode(&args.first, first_tx));
+        let second = s.spawn(|| decode(&args.second, second_tx));
+        let difference = compare(first_rx, second_rx);
+        first.join().unwrap()?;
+        second.join().unwrap()?;
+        Ok(difference)
+    })?;
+
+    if let Some(difference) = difference {
+        eprintln!("{}", difference);
+        std::process::exit(1);
+    }
+    log::info!("The graphs are equal");
+    Ok(())
+}
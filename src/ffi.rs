@@ -0,0 +1,276 @@
+//! C FFI to load BVGraphs and access them from C/C++.
+//!
+//! All the functions work on opaque handles that are created by
+//! [`webgraph_load`] and [`webgraph_iter_new`] and must be released with
+//! [`webgraph_free`] and [`webgraph_iter_free`] respectively. Errors are
+//! logged using the [`log`] crate and signalled with a null pointer or a
+//! negative return value; panics are caught, logged and signalled in the
+//! same way, as they must not unwind across the FFI boundary.
+//!
+//! A matching C header can be found in `include/webgraph.h`.
+use crate::prelude::*;
+use dsi_bitstream::prelude::*;
+use std::ffi::{c_char, CStr};
+
+type FfiCodesReaderBuilder = DynamicCodesReaderBuilder<BE, LoadBackend<u32>>;
+type FfiGraph = BVGraph<FfiCodesReaderBuilder, LoadedOffsets>;
+type FfiSeqIter =
+    WebgraphSequentialIter<<FfiCodesReaderBuilder as BVGraphCodesReaderBuilder>::Reader<'static>>;
+
+/// Run `f`, returning `error` if it panics.
+fn catch_panic<T>(name: &str, error: T, f: impl FnOnce() -> T) -> T {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).unwrap_or_else(|_| {
+        log::error!("{}: panic", name);
+        error
+    })
+}
+
+/// Opaque handle to a BVGraph loaded for random access.
+pub struct WebGraph {
+    graph: FfiGraph,
+}
+
+/// Opaque handle to a sequential iterator on a [`WebGraph`].
+pub struct WebGraphIter {
+    iter: FfiSeqIter,
+    next_node: usize,
+}
+
+/// Load the BVGraph with the given basename.
+///
+/// Return a null pointer if the graph could not be loaded.
+///
+/// # Safety
+/// `basename` must be a valid nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn webgraph_load(basename: *const c_char) -> *mut WebGraph {
+    if basename.is_null() {
+        log::error!("webgraph_load: null basename");
+        return core::ptr::null_mut();
+    }
+    let basename = match CStr::from_ptr(basename).to_str() {
+        Ok(basename) => basename,
+        Err(e) => {
+            log::error!("webgraph_load: basename is not valid UTF-8: {}", e);
+            return core::ptr::null_mut();
+        }
+    };
+    catch_panic("webgraph_load", core::ptr::null_mut(), || {
+        match load(basename) {
+            Ok(graph) => Box::into_raw(Box::new(WebGraph { graph })),
+            Err(e) => {
+                log::error!("webgraph_load: cannot load {}: {:?}", basename, e);
+                core::ptr::null_mut()
+            }
+        }
+    })
+}
+
+/// Release a graph created by [`webgraph_load`]. Passing a null pointer is a
+/// no-op.
+///
+/// # Safety
+/// `graph` must have been returned by [`webgraph_load`], must not have been
+/// already freed, and no iterator created from it must be alive.
+#[no_mangle]
+pub unsafe extern "C" fn webgraph_free(graph: *mut WebGraph) {
+    if !graph.is_null() {
+        catch_panic("webgraph_free", (), || drop(Box::from_raw(graph)));
+    }
+}
+
+/// Return the number of nodes of the graph.
+///
+/// # Safety
+/// `graph` must be a valid handle returned by [`webgraph_load`].
+#[no_mangle]
+pub unsafe extern "C" fn webgraph_num_nodes(graph: *const WebGraph) -> u64 {
+    (*graph).graph.num_nodes() as u64
+}
+
+/// Return the number of arcs of the graph.
+///
+/// # Safety
+/// `graph` must be a valid handle returned by [`webgraph_load`].
+#[no_mangle]
+pub unsafe extern "C" fn webgraph_num_arcs(graph: *const WebGraph) -> u64 {
+    (*graph).graph.num_arcs() as u64
+}
+
+/// Return the outdegree of `node`, or -1 if `node` is out of range.
+///
+/// # Safety
+/// `graph` must be a valid handle returned by [`webgraph_load`].
+#[no_mangle]
+pub unsafe extern "C" fn webgraph_outdegree(graph: *const WebGraph, node: u64) -> i64 {
+    let graph = &(*graph).graph;
+    if node >= graph.num_nodes() as u64 {
+        return -1;
+    }
+    catch_panic("webgraph_outdegree", -1, || {
+        graph.outdegree(node as usize) as i64
+    })
+}
+
+/// Write the successors of `node` in `buffer`, which has space for `len`
+/// values, and return the outdegree of `node`.
+///
+/// If the outdegree is larger than `len` only the first `len` successors are
+/// written, so the caller can grow the buffer and retry. Return -1 if `node`
+/// is out of range.
+///
+/// # Safety
+/// `graph` must be a valid handle returned by [`webgraph_load`] and `buffer`
+/// must be valid for `len` writes.
+#[no_mangle]
+pub unsafe extern "C" fn webgraph_successors_into(
+    graph: *const WebGraph,
+    node: u64,
+    buffer: *mut u64,
+    len: usize,
+) -> i64 {
+    let graph = &(*graph).graph;
+    if node >= graph.num_nodes() as u64 {
+        return -1;
+    }
+    catch_panic("webgraph_successors_into", -1, || {
+        let succ = graph.successors(node as usize);
+        let degree = succ.len();
+        if len != 0 {
+            let buffer = core::slice::from_raw_parts_mut(buffer, len);
+            for (dst, succ) in buffer.iter_mut().zip(succ) {
+                *dst = succ as u64;
+            }
+        }
+        degree as i64
+    })
+}
+
+/// Create a sequential iterator on the graph, which is much faster than
+/// calling [`webgraph_successors_into`] on every node.
+///
+/// # Safety
+/// `graph` must be a valid handle returned by [`webgraph_load`] and must
+/// outlive the iterator.
+#[no_mangle]
+pub unsafe extern "C" fn webgraph_iter_new(graph: *const WebGraph) -> *mut WebGraphIter {
+    // the caller guarantees that the graph outlives the iterator
+    let graph: &'static FfiGraph = &(*graph).graph;
+    catch_panic("webgraph_iter_new", core::ptr::null_mut(), || {
+        Box::into_raw(Box::new(WebGraphIter {
+            iter: graph.iter_nodes(),
+            next_node: 0,
+        }))
+    })
+}
+
+/// Advance the iterator, storing the next node in `node` and its successors
+/// in `buffer`, which has space for `len` values.
+///
+/// Return the outdegree of the node, or -1 if the iterator is exhausted. If
+/// the outdegree is larger than `len` only the first `len` successors are
+/// written; the remaining ones are lost, so `len` should be at least the
+/// maximum outdegree of the graph.
+///
+/// # Safety
+/// `iter` must be a valid handle returned by [`webgraph_iter_new`], `node`
+/// must be valid for writes and `buffer` must be valid for `len` writes.
+#[no_mangle]
+pub unsafe extern "C" fn webgraph_iter_next(
+    iter: *mut WebGraphIter,
+    node: *mut u64,
+    buffer: *mut u64,
+    len: usize,
+) -> i64 {
+    let iter = &mut *iter;
+    if iter.iter.len() == 0 {
+        return -1;
+    }
+    catch_panic("webgraph_iter_next", -1, || {
+        let succ = match iter.iter.next_successors() {
+            Ok(succ) => succ,
+            Err(e) => {
+                log::error!(
+                    "webgraph_iter_next: cannot decode node {}: {:?}",
+                    iter.next_node,
+                    e
+                );
+                return -1;
+            }
+        };
+        *node = iter.next_node as u64;
+        if len != 0 {
+            let buffer = core::slice::from_raw_parts_mut(buffer, len);
+            for (dst, succ) in buffer.iter_mut().zip(succ) {
+                *dst = *succ as u64;
+            }
+        }
+        iter.next_node += 1;
+        succ.len() as i64
+    })
+}
+
+/// Release an iterator created by [`webgraph_iter_new`]. Passing a null
+/// pointer is a no-op.
+///
+/// # Safety
+/// `iter` must have been returned by [`webgraph_iter_new`] and must not have
+/// been already freed.
+#[no_mangle]
+pub unsafe extern "C" fn webgraph_iter_free(iter: *mut WebGraphIter) {
+    if !iter.is_null() {
+        catch_panic("webgraph_iter_free", (), || drop(Box::from_raw(iter)));
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_ffi() -> anyhow::Result<()> {
+    let basename = std::ffi::CString::new("tests/data/cnr-2000")?;
+    let expected = load("tests/data/cnr-2000")?;
+    unsafe {
+        let graph = webgraph_load(basename.as_ptr());
+        assert!(!graph.is_null());
+        assert_eq!(webgraph_num_nodes(graph), expected.num_nodes() as u64);
+        assert_eq!(webgraph_num_arcs(graph), expected.num_arcs() as u64);
+        assert_eq!(webgraph_outdegree(graph, expected.num_nodes() as u64), -1);
+
+        let max_degree = (0..expected.num_nodes())
+            .map(|node| expected.outdegree(node))
+            .max()
+            .unwrap_or(0);
+        let mut buffer = vec![0; max_degree];
+        for node in (0..expected.num_nodes()).step_by(97) {
+            let degree =
+                webgraph_successors_into(graph, node as u64, buffer.as_mut_ptr(), buffer.len());
+            assert_eq!(degree, expected.outdegree(node) as i64);
+            assert_eq!(webgraph_outdegree(graph, node as u64), degree);
+            assert!(buffer[..degree as usize]
+                .iter()
+                .map(|&succ| succ as usize)
+                .eq(expected.successors(node)));
+        }
+
+        let iter = webgraph_iter_new(graph);
+        assert!(!iter.is_null());
+        let mut node = 0;
+        for (expected_node, succ) in expected.iter_nodes() {
+            let degree = webgraph_iter_next(iter, &mut node, buffer.as_mut_ptr(), buffer.len());
+            assert_eq!(node, expected_node as u64);
+            assert!(buffer[..degree as usize]
+                .iter()
+                .map(|&succ| succ as usize)
+                .eq(succ));
+        }
+        assert_eq!(
+            webgraph_iter_next(iter, &mut node, buffer.as_mut_ptr(), buffer.len()),
+            -1
+        );
+        webgraph_iter_free(iter);
+        webgraph_free(graph);
+
+        let missing = std::ffi::CString::new("tests/data/missing")?;
+        assert!(webgraph_load(missing.as_ptr()).is_null());
+    }
+    Ok(())
+}
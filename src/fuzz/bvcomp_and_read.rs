@@ -158,7 +158,7 @@ pub fn harness(data: FuzzCase) {
         assert_eq!(succ_be, succ_le);
         assert_eq!(succ, succ_be);
         offsets.push(seq_iter_be.get_pos());
-        efb.push(seq_iter_be.get_pos() as u64).unwrap();
+        efb.push(seq_iter_be.get_pos()).unwrap();
     }
     // build elias-fano
     let ef = efb.build();
@@ -166,7 +166,7 @@ pub fn harness(data: FuzzCase) {
     // verify that elias-fano has the right values
     assert_eq!(IndexedDict::len(&ef), offsets.len());
     for (i, offset) in offsets.iter().enumerate() {
-        assert_eq!(ef.get(i as usize) as usize, *offset);
+        assert_eq!(ef.get(i), *offset);
     }
 
     // create code reader builders
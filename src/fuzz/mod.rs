@@ -1 +1,2 @@
 pub mod bvcomp_and_read;
+pub mod random_access;
@@ -0,0 +1,101 @@
+use super::bvcomp_and_read::CompFlagsFuzz;
+use crate::prelude::*;
+use arbitrary::Arbitrary;
+use dsi_bitstream::prelude::*;
+use sux::prelude::*;
+
+#[derive(Arbitrary, Debug)]
+pub struct FuzzCase {
+    pub compression_flags: CompFlagsFuzz,
+    pub graph: VecGraph<()>,
+    /// Pairs of nodes (reduced modulo the number of nodes) on which
+    /// `successors` and `outdegree` are called for the first node, and
+    /// `has_arc` for the pair; the second node also limits how many
+    /// successors are read, so that partially consumed iterators are
+    /// exercised too.
+    pub queries: Vec<(u8, u8)>,
+}
+
+/// Compress `graph` with the given endianness, build its offsets by a
+/// sequential scan, and check the random accesses in `queries` against it.
+macro_rules! check_random_access {
+    ($endianness:ty, $graph:expr, $comp_flags:expr, $queries:expr) => {{
+        let graph = $graph;
+        let comp_flags = $comp_flags;
+        let mut codes_data = Vec::new();
+        {
+            let bit_writer = <BufferedBitStreamWrite<$endianness, _>>::new(MemWordWriteVec::new(
+                &mut codes_data,
+            ));
+            let codes_writer = <DynamicCodesWriter<$endianness, _>>::new(bit_writer, &comp_flags);
+            let mut bvcomp = BVComp::new(
+                codes_writer,
+                comp_flags.compression_window,
+                comp_flags.min_interval_length,
+                comp_flags.max_ref_count,
+            );
+            bvcomp.extend(graph.iter_nodes()).unwrap();
+            bvcomp.flush().unwrap();
+        }
+        // convert to u32 for faster reader
+        let data: &[u32] = unsafe {
+            core::slice::from_raw_parts(
+                codes_data.as_ptr() as *const u32,
+                codes_data.len() * (core::mem::size_of::<u64>() / core::mem::size_of::<u32>()),
+            )
+        };
+
+        // build the offsets with a sequential scan
+        let seq_graph = BVGraphSequential::new(
+            <DynamicCodesReaderBuilder<$endianness, _>>::new(data, comp_flags).unwrap(),
+            comp_flags.compression_window,
+            comp_flags.min_interval_length,
+            graph.num_nodes(),
+            Some(graph.num_arcs()),
+        );
+        let mut efb = EliasFanoBuilder::new(
+            (data.len() * 8 * core::mem::size_of::<u32>()) as u64,
+            (graph.num_nodes() + 1) as u64,
+        );
+        efb.push(0).unwrap();
+        let mut seq_iter = seq_graph.iter_nodes();
+        while seq_iter.next().is_some() {
+            efb.push(seq_iter.get_pos()).unwrap();
+        }
+
+        let bvgraph: BVGraph<_, _> = BVGraph::new(
+            <DynamicCodesReaderBuilder<$endianness, _>>::new(data, comp_flags).unwrap(),
+            encase_mem(efb.build()),
+            comp_flags.min_interval_length,
+            comp_flags.compression_window,
+            graph.num_nodes(),
+            graph.num_arcs(),
+        );
+
+        for &(x, y) in $queries {
+            let node = x as usize % graph.num_nodes();
+            let other = y as usize % graph.num_nodes();
+            assert_eq!(bvgraph.outdegree(node), graph.outdegree(node));
+            assert_eq!(
+                bvgraph.has_arc(node, other),
+                graph.has_arc(node, other),
+                "has_arc({}, {})",
+                node,
+                other
+            );
+            let successors = bvgraph.successors(node);
+            assert_eq!(successors.len(), graph.outdegree(node));
+            // read only a prefix of the successors
+            assert!(successors
+                .take(y as usize)
+                .eq(graph.successors(node).take(y as usize)));
+            assert!(bvgraph.successors(node).eq(graph.successors(node)));
+        }
+    }};
+}
+
+pub fn harness(data: FuzzCase) {
+    let comp_flags: CompFlags = data.compression_flags.into();
+    check_random_access!(BE, &data.graph, comp_flags, &data.queries);
+    check_random_access!(LE, &data.graph, comp_flags, &data.queries);
+}
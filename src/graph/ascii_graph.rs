@@ -0,0 +1,213 @@
+use crate::traits::*;
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// A sequential graph in the `ASCIIGraph` format of the Java version of
+/// WebGraph, read lazily from the file `BASENAME.graph-txt`.
+///
+/// The first line of the file contains the number of nodes; then, the
+/// `i`-th of the following lines contains the successors of node `i`, in
+/// decimal notation, separated by whitespace. The format is meant for small
+/// graphs and test fixtures exchanged with the Java implementation, which
+/// can be written with [`ASCIIGraph::store`].
+#[derive(Debug, Clone)]
+pub struct ASCIIGraph {
+    path: PathBuf,
+    num_nodes: usize,
+}
+
+impl ASCIIGraph {
+    /// Open the graph with the given basename, reading the number of nodes
+    /// from the first line of `BASENAME.graph-txt`.
+    pub fn load<P: AsRef<Path>>(basename: P) -> Result<Self> {
+        let path = ascii_path(basename);
+        let mut first_line = String::new();
+        open(&path)?
+            .read_line(&mut first_line)
+            .with_context(|| format!("Cannot read {}", path.display()))?;
+        let num_nodes = first_line
+            .trim()
+            .parse::<usize>()
+            .with_context(|| format!("Cannot parse the number of nodes in {}", path.display()))?;
+        Ok(Self { path, num_nodes })
+    }
+
+    /// Write a graph in the file `BASENAME.graph-txt`.
+    pub fn store<G: SequentialGraph, P: AsRef<Path>>(graph: &G, basename: P) -> Result<()> {
+        let path = ascii_path(basename);
+        let mut writer = BufWriter::new(
+            File::create(&path).with_context(|| format!("Cannot create {}", path.display()))?,
+        );
+        writeln!(writer, "{}", graph.num_nodes())?;
+        for (_, successors) in graph.iter_nodes() {
+            let mut separator = "";
+            for succ in successors {
+                write!(writer, "{}{}", separator, succ)?;
+                separator = " ";
+            }
+            writeln!(writer)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Return the path of the file of the graph with the given basename.
+fn ascii_path<P: AsRef<Path>>(basename: P) -> PathBuf {
+    PathBuf::from(format!("{}.graph-txt", basename.as_ref().to_string_lossy()))
+}
+
+/// Open a file for buffered reading.
+fn open(path: &Path) -> Result<BufReader<File>> {
+    let file = File::open(path).with_context(|| format!("Cannot open {}", path.display()))?;
+    Ok(BufReader::new(file))
+}
+
+impl SequentialGraph for ASCIIGraph {
+    type NodesIter<'a> = ASCIIGraphNodesIter where Self: 'a;
+    type SequentialSuccessorIter<'a> = std::vec::IntoIter<usize> where Self: 'a;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.num_nodes
+    }
+
+    fn iter_nodes(&self) -> Self::NodesIter<'_> {
+        let mut iter = ASCIIGraphNodesIter {
+            path: self.path.clone(),
+            reader: open(&self.path).unwrap(),
+            offset: 0,
+            next_node: 0,
+            num_nodes: self.num_nodes,
+            line: String::new(),
+        };
+        // skip the number of nodes
+        iter.read_line();
+        iter
+    }
+}
+
+/// The nodes iterator of an [`ASCIIGraph`].
+///
+/// # Panics
+/// The iterator panics if the file cannot be read, has fewer lines than
+/// nodes, or contains a malformed successor.
+#[derive(Debug)]
+pub struct ASCIIGraphNodesIter {
+    path: PathBuf,
+    reader: BufReader<File>,
+    /// The position in the file of the next line.
+    offset: u64,
+    next_node: usize,
+    num_nodes: usize,
+    /// The buffer for the lines of the file.
+    line: String,
+}
+
+impl ASCIIGraphNodesIter {
+    /// Read the next line in the buffer, returning whether the file ended.
+    fn read_line(&mut self) -> bool {
+        self.line.clear();
+        let len = self
+            .reader
+            .read_line(&mut self.line)
+            .unwrap_or_else(|err| panic!("Cannot read {}: {}", self.path.display(), err));
+        self.offset += len as u64;
+        len == 0
+    }
+}
+
+/// Cloning the iterator reopens the file and seeks to the current position.
+impl Clone for ASCIIGraphNodesIter {
+    fn clone(&self) -> Self {
+        let mut reader = open(&self.path).unwrap();
+        reader.seek(SeekFrom::Start(self.offset)).unwrap();
+        Self {
+            path: self.path.clone(),
+            reader,
+            offset: self.offset,
+            next_node: self.next_node,
+            num_nodes: self.num_nodes,
+            line: String::new(),
+        }
+    }
+}
+
+impl Iterator for ASCIIGraphNodesIter {
+    type Item = (usize, std::vec::IntoIter<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next_node == self.num_nodes {
+            return None;
+        }
+        let node = self.next_node;
+        self.next_node += 1;
+        if self.read_line() {
+            panic!(
+                "The file {} ends before the successors of node {}",
+                self.path.display(),
+                node
+            );
+        }
+        let successors = self
+            .line
+            .split_ascii_whitespace()
+            .map(|succ| {
+                succ.parse::<usize>().unwrap_or_else(|_| {
+                    panic!(
+                        "Malformed successor of node {} in {}: {}",
+                        node,
+                        self.path.display(),
+                        succ
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        Some((node, successors.into_iter()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.num_nodes - self.next_node;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for ASCIIGraphNodesIter {}
+
+/// Nodes are returned in increasing order
+unsafe impl SortedIterator for ASCIIGraphNodesIter {}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_ascii_graph() -> Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    let arcs = [(0, 1), (0, 2), (1, 2), (1, 3), (2, 4), (3, 4), (4, 0)];
+    let mut g = VecGraph::from_arc_list(&arcs);
+    // a node without successors at the end
+    g.add_node(5);
+    let dir = tempfile::tempdir()?;
+    let basename = dir.path().join("graph");
+    ASCIIGraph::store(&g, &basename)?;
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("graph.graph-txt"))?,
+        "6\n1 2\n2 3\n4\n4\n0\n\n"
+    );
+    let ascii = ASCIIGraph::load(&basename)?;
+    assert_eq!(ascii.num_nodes(), 6);
+    assert_eq!(VecGraph::from_node_iter(ascii.iter_nodes()), g);
+    let mut iter = ascii.iter_nodes();
+    iter.next();
+    let (node, successors) = iter.clone().next().unwrap();
+    assert_eq!((node, successors.collect::<Vec<_>>()), (1, vec![2, 3]));
+
+    // files written by the Java version may use tabs and trailing spaces
+    std::fs::write(dir.path().join("java.graph-txt"), "3\n1\t2 \n\n 0\n")?;
+    let ascii = ASCIIGraph::load(dir.path().join("java"))?;
+    assert_eq!(
+        VecGraph::from_node_iter(ascii.iter_nodes()),
+        VecGraph::from_arc_list(&[(0, 1), (0, 2), (2, 0)])
+    );
+    Ok(())
+}
@@ -0,0 +1,143 @@
+//! Batched decoding of runs of γ and ζ₃ codes using BMI2 instructions.
+//!
+//! Instead of reading codes one at a time, we peek at a window of bits, and
+//! decode from the window all the codes that fit in it using leading/trailing
+//! zero counts and `bzhi` to extract fields, skipping the consumed bits at the
+//! end. Codes that do not fit in an empty window are read with the scalar
+//! decoder of the underlying reader.
+
+use core::arch::x86_64::_bzhi_u64;
+use dsi_bitstream::prelude::*;
+
+/// The number of bits we peek at each refill.
+const WINDOW_BITS: u32 = 32;
+
+/// A window of bits peeked from a stream of endianness `E`.
+///
+/// For big-endian streams the bits are aligned to the most significant bit,
+/// for little-endian streams to the least significant bit, so in both cases
+/// consuming bits is a shift and the unused part of the word is zero.
+struct Window<E: Endianness> {
+    bits: u64,
+    avail: u32,
+    _marker: core::marker::PhantomData<E>,
+}
+
+impl<E: Endianness> Window<E> {
+    #[inline(always)]
+    fn new(peeked: u64) -> Self {
+        Self {
+            bits: if E::IS_BIG {
+                peeked << (64 - WINDOW_BITS)
+            } else {
+                peeked
+            },
+            avail: WINDOW_BITS,
+            _marker: core::marker::PhantomData,
+        }
+    }
+
+    /// Return the length of the unary code at the start of the window, that
+    /// is, the number of zeros before the first one, which might exceed
+    /// the number of available bits if there is no one.
+    #[inline(always)]
+    fn unary_len(&self) -> u32 {
+        if E::IS_BIG {
+            self.bits.leading_zeros()
+        } else {
+            self.bits.trailing_zeros()
+        }
+    }
+
+    /// Consume `n` bits, which must be available, returning them with the
+    /// same semantics as `read_bits`.
+    #[inline(always)]
+    fn take(&mut self, n: u32) -> u64 {
+        debug_assert!(n <= self.avail);
+        self.avail -= n;
+        if n == 0 {
+            return 0;
+        }
+        if E::IS_BIG {
+            let value = self.bits >> (64 - n);
+            self.bits <<= n;
+            value
+        } else {
+            // SAFETY: the module is compiled only if BMI2 is available
+            let value = unsafe { _bzhi_u64(self.bits, n) };
+            self.bits >>= n;
+            value
+        }
+    }
+
+    /// Decode a γ code, if it fits in the window.
+    #[inline(always)]
+    fn gamma(&mut self) -> Option<u64> {
+        let len = self.unary_len();
+        if 2 * len + 1 > self.avail {
+            return None;
+        }
+        self.take(len + 1);
+        Some(self.take(len) + (1 << len) - 1)
+    }
+
+    /// Decode a ζ₃ code, if it fits in the window.
+    #[inline(always)]
+    fn zeta3(&mut self) -> Option<u64> {
+        let h = self.unary_len();
+        // the minimal binary code of the interval [2^3h, 2^(3h+3)) has
+        // 3h + 2 or 3h + 3 bits, the threshold being 2^3h
+        let short_len = 3 * h + 2;
+        if h + 1 + short_len + 1 > self.avail {
+            return None;
+        }
+        self.take(h + 1);
+        let l = 1 << (3 * h);
+        let mut value = self.take(short_len);
+        if value >= l {
+            value = ((value << 1) | self.take(1)) - l;
+        }
+        Some(l + value - 1)
+    }
+}
+
+/// Fill `gaps` with γ codes (if `ZETA3` is false) or ζ₃ codes (if `ZETA3` is
+/// true) read from `reader`.
+#[inline]
+pub(crate) fn read_codes<E: Endianness, CR: ReadCodes<E>, const ZETA3: bool>(
+    reader: &mut CR,
+    gaps: &mut [u64],
+) {
+    let mut filled = 0;
+    while filled < gaps.len() {
+        let peeked: u64 = reader.peek_bits(WINDOW_BITS as usize).unwrap().upcast();
+        let mut window = <Window<E>>::new(peeked);
+        let start = filled;
+        while filled < gaps.len() {
+            let code = if ZETA3 {
+                window.zeta3()
+            } else {
+                window.gamma()
+            };
+            match code {
+                Some(value) => {
+                    gaps[filled] = value;
+                    filled += 1;
+                }
+                None => break,
+            }
+        }
+        reader
+            .skip_bits((WINDOW_BITS - window.avail) as usize)
+            .unwrap();
+        // the next code is longer than a window
+        if filled == start {
+            gaps[filled] = if ZETA3 {
+                reader.read_zeta3().unwrap()
+            } else {
+                reader.read_gamma().unwrap()
+            };
+            filled += 1;
+        }
+    }
+}
@@ -20,6 +20,8 @@ pub struct BVGraph<CRB: BVGraphCodesReaderBuilder, OFF: IndexedDict<Value = u64>
     number_of_nodes: usize,
     /// The number of arcs in the graph.
     number_of_arcs: usize,
+    /// The outdegrees of the nodes, if cached.
+    outdegrees: Option<Outdegrees>,
 }
 
 impl<CRB, OFF> BVGraph<CRB, OFF>
@@ -57,6 +59,7 @@ where
             compression_window,
             number_of_nodes,
             number_of_arcs,
+            outdegrees: None,
         }
     }
 
@@ -74,6 +77,7 @@ where
             number_of_arcs: self.number_of_arcs,
             compression_window: self.compression_window,
             min_interval_length: self.min_interval_length,
+            outdegrees: self.outdegrees,
         }
     }
 
@@ -91,14 +95,55 @@ where
             number_of_arcs: self.number_of_arcs,
             compression_window: self.compression_window,
             min_interval_length: self.min_interval_length,
+            outdegrees: self.outdegrees,
         }
     }
 
+    /// Use `outdegrees` to compute the outdegrees of the nodes instead of
+    /// decoding them, which is useful for algorithms calling
+    /// [`outdegree`](RandomAccessGraph::outdegree) very often.
+    ///
+    /// # Errors
+    /// If the number of outdegrees is not the number of nodes of the graph.
+    pub fn with_outdegrees(mut self, outdegrees: Outdegrees) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            outdegrees.len() == self.number_of_nodes,
+            "There are {} outdegrees, but the graph has {} nodes",
+            outdegrees.len(),
+            self.number_of_nodes
+        );
+        self.outdegrees = Some(outdegrees);
+        Ok(self)
+    }
+
+    #[inline(always)]
+    /// Return a reference to the codes reader builder
+    pub fn get_codes_reader_builder(&self) -> &CRB {
+        &self.codes_reader_builder
+    }
+
     #[inline(always)]
     /// Consume self and return the codes reader builder and the offsets
     pub fn unwrap(self) -> (CRB, MemCase<OFF>) {
         (self.codes_reader_builder, self.offsets)
     }
+
+    /// Return a random access iterator over the successors of a node that
+    /// are greater than or equal to `lower_bound`.
+    ///
+    /// Intervals are skipped arithmetically, so only the residuals and
+    /// the copied successors smaller than `lower_bound` are decoded. This
+    /// is useful to intersect sorted lists of successors, for example when
+    /// computing clustering coefficients.
+    pub fn successors_from(
+        &self,
+        node_id: usize,
+        lower_bound: usize,
+    ) -> RandomSuccessorIter<CRB::Reader<'_>> {
+        let mut result = self.successors(node_id);
+        result.skip_to(lower_bound);
+        result
+    }
 }
 
 impl<CRB, OFF> SequentialGraph for BVGraph<CRB, OFF>
@@ -133,6 +178,36 @@ where
             self.number_of_nodes,
         )
     }
+
+    /// Return an iterator over the nodes starting at `start_node`, whose
+    /// reader is positioned using the offsets; the successors of the nodes
+    /// in the compression window preceding `start_node`, which might be
+    /// referenced, are decoded by random access.
+    fn iter_nodes_from(&self, start_node: usize) -> WebgraphSequentialIter<CRB::Reader<'_>> {
+        let start_node = start_node.min(self.number_of_nodes);
+        let offset = if start_node == self.number_of_nodes {
+            0
+        } else {
+            self.offsets.get(start_node)
+        };
+        let window_start = start_node.saturating_sub(self.compression_window);
+        WebgraphSequentialIter::new_from(
+            self.codes_reader_builder.get_reader(offset).unwrap(),
+            self.compression_window,
+            self.min_interval_length,
+            self.number_of_nodes,
+            start_node,
+            (window_start..start_node).map(|node| (node, self.successors(node).collect())),
+        )
+    }
+}
+
+/// The iteration can start at any node using the offsets.
+impl<CRB, OFF> SplitSequentialGraph for BVGraph<CRB, OFF>
+where
+    CRB: BVGraphCodesReaderBuilder,
+    OFF: IndexedDict<Value = u64>,
+{
 }
 
 impl<CRB, OFF> RandomAccessGraph for BVGraph<CRB, OFF>
@@ -151,9 +226,12 @@ where
 
     /// Return the outdegree of a node.
     fn outdegree(&self, node_id: usize) -> usize {
+        if let Some(outdegrees) = &self.outdegrees {
+            return outdegrees.get(node_id);
+        }
         let mut codes_reader = self
             .codes_reader_builder
-            .get_reader(self.offsets.get(node_id) as _)
+            .get_reader(self.offsets.get(node_id))
             .expect("Cannot create reader");
         codes_reader.read_outdegree() as usize
     }
@@ -163,7 +241,7 @@ where
     fn successors(&self, node_id: usize) -> RandomSuccessorIter<CRB::Reader<'_>> {
         let codes_reader = self
             .codes_reader_builder
-            .get_reader(self.offsets.get(node_id) as _)
+            .get_reader(self.offsets.get(node_id))
             .expect("Cannot create reader");
 
         let mut result = RandomSuccessorIter::new(codes_reader);
@@ -267,6 +345,128 @@ where
 
         result
     }
+
+    /// Return whether the arc `src_node_id -> dst_node_id` exists.
+    ///
+    /// Instead of merging the successors, this method checks the intervals
+    /// directly, decodes the residuals only up to `dst_node_id`, and looks up
+    /// the position of `dst_node_id` in the list of the reference only if
+    /// needed, to check whether it falls in a copied block.
+    fn has_arc(&self, src_node_id: usize, dst_node_id: usize) -> bool {
+        let mut reader = self
+            .codes_reader_builder
+            .get_reader(self.offsets.get(src_node_id))
+            .expect("Cannot create reader");
+        let degree = reader.read_outdegree() as usize;
+        if degree == 0 {
+            return false;
+        }
+        let mut nodes_left_to_decode = degree;
+
+        // read the reference offset
+        let ref_delta = if self.compression_window != 0 {
+            reader.read_reference_offset() as usize
+        } else {
+            0
+        };
+        // the reference and the lengths of its blocks
+        let mut reference = None;
+        if ref_delta != 0 {
+            let reference_node_id = src_node_id - ref_delta;
+            let number_of_blocks = reader.read_block_count() as usize;
+            let mut blocks = Vec::with_capacity(number_of_blocks);
+            if number_of_blocks != 0 {
+                // the first block could be zero
+                blocks.push(reader.read_blocks() as usize);
+                // while the other can't
+                for _ in 1..number_of_blocks {
+                    blocks.push(reader.read_blocks() as usize + 1);
+                }
+            }
+            // blocks of even index are copied, and so is the rest of the list
+            // if the number of blocks is even
+            let mut copied = blocks.iter().step_by(2).sum::<usize>();
+            if number_of_blocks % 2 == 0 {
+                copied += self.outdegree(reference_node_id) - blocks.iter().sum::<usize>();
+            }
+            nodes_left_to_decode -= copied;
+            reference = Some((reference_node_id, blocks));
+        }
+
+        // check the intervals
+        if nodes_left_to_decode != 0 && self.min_interval_length != 0 {
+            let number_of_intervals = reader.read_interval_count() as usize;
+            if number_of_intervals != 0 {
+                let node_id_offset = nat2int(reader.read_interval_start());
+                let mut start = (src_node_id as i64 + node_id_offset) as usize;
+                for i in 0..number_of_intervals {
+                    if i != 0 {
+                        start += 1 + reader.read_interval_start() as usize;
+                    }
+                    let len = reader.read_interval_len() as usize + self.min_interval_length;
+                    if (start..start + len).contains(&dst_node_id) {
+                        return true;
+                    }
+                    start += len;
+                    nodes_left_to_decode -= len;
+                }
+            }
+        }
+
+        // decode the residuals up to the destination
+        if nodes_left_to_decode != 0 {
+            let node_id_offset = nat2int(reader.read_first_residual());
+            let mut residual = (src_node_id as i64 + node_id_offset) as usize;
+            for i in 0..nodes_left_to_decode {
+                if i != 0 {
+                    residual += 1 + reader.read_residual() as usize;
+                }
+                if residual >= dst_node_id {
+                    if residual == dst_node_id {
+                        return true;
+                    }
+                    break;
+                }
+            }
+        }
+
+        // find the position of the destination in the reference list, and
+        // check whether it falls in a copied block
+        if let Some((reference_node_id, blocks)) = reference {
+            let position = self
+                .successors(reference_node_id)
+                .take_while(|&succ| succ <= dst_node_id)
+                .enumerate()
+                .find(|&(_, succ)| succ == dst_node_id)
+                .map(|(position, _)| position);
+            if let Some(position) = position {
+                let mut end = 0;
+                for (i, block) in blocks.iter().enumerate() {
+                    end += block;
+                    if position < end {
+                        return i % 2 == 0;
+                    }
+                }
+                return blocks.len() % 2 == 0;
+            }
+        }
+        false
+    }
+}
+
+impl<CRB, OFF> SeekableGraph for BVGraph<CRB, OFF>
+where
+    CRB: BVGraphCodesReaderBuilder,
+    OFF: IndexedDict<Value = u64>,
+{
+    #[inline(always)]
+    fn successors_from(
+        &self,
+        node_id: usize,
+        lower_bound: usize,
+    ) -> RandomSuccessorIter<CRB::Reader<'_>> {
+        BVGraph::successors_from(self, node_id, lower_bound)
+    }
 }
 
 /// The iterator returend from [`BVGraph`] that returns the successors of a
@@ -319,6 +519,53 @@ impl<CR: BVGraphCodesReader> RandomSuccessorIter<CR> {
     }
 }
 
+impl<CR: BVGraphCodesReader> RandomSuccessorIter<CR> {
+    /// Skip all successors smaller than `lower_bound`.
+    fn skip_to(&mut self, lower_bound: usize) {
+        // usize::MAX - 1 is the fake final interval
+        let lower_bound = lower_bound.min(usize::MAX - 1);
+
+        // skip the intervals, a whole run at a time
+        while self.next_interval_node < lower_bound {
+            // drop the cached node
+            self.size -= 1;
+            let (start, len) = &mut self.intervals[self.intervals_idx];
+            let skip = lower_bound.saturating_sub(*start).min(*len);
+            self.size -= skip;
+            *start += skip;
+            *len -= skip;
+            self.intervals_idx += (*len == 0) as usize;
+            // cache the next node
+            let (start, len) = &mut self.intervals[self.intervals_idx];
+            self.next_interval_node = *start;
+            *start += 1;
+            *len -= 1;
+            self.intervals_idx += (*len == 0) as usize;
+        }
+
+        // residuals must be decoded one at a time
+        while self.next_residual_node < lower_bound {
+            self.size -= 1;
+            if self.residuals_to_go == 0 {
+                self.next_residual_node = usize::MAX;
+            } else {
+                self.residuals_to_go -= 1;
+                self.next_residual_node += 1 + self.reader.read_residual() as usize;
+            }
+        }
+
+        // and so must be the copied nodes
+        while self.next_copied_node < lower_bound {
+            self.size -= 1;
+            self.next_copied_node = self
+                .copied_nodes_iter
+                .as_mut()
+                .and_then(|iter| iter.next())
+                .unwrap_or(usize::MAX);
+        }
+    }
+}
+
 impl<CR: BVGraphCodesReader> Iterator for RandomSuccessorIter<CR> {
     type Item = usize;
 
@@ -386,3 +633,70 @@ where
         self.iter_nodes()
     }
 }
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_has_arc() -> anyhow::Result<()> {
+    let graph = crate::graph::bvgraph::load("tests/data/cnr-2000")?;
+    for node in (0..graph.num_nodes()).step_by(97) {
+        let successors = graph.successors(node).collect::<Vec<_>>();
+        for &succ in &successors {
+            assert!(graph.has_arc(node, succ), "{} -> {}", node, succ);
+            for other in [succ.saturating_sub(1), succ + 1] {
+                assert_eq!(
+                    graph.has_arc(node, other),
+                    successors.contains(&other),
+                    "{} -> {}",
+                    node,
+                    other
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_successors_from() -> anyhow::Result<()> {
+    let graph = crate::graph::bvgraph::load("tests/data/cnr-2000")?;
+    for node in (0..graph.num_nodes()).step_by(97) {
+        let successors = graph.successors(node).collect::<Vec<_>>();
+        let mut lower_bounds = vec![0, node, usize::MAX];
+        lower_bounds.extend(successors.iter().flat_map(|&succ| [succ, succ + 1]));
+        for lower_bound in lower_bounds {
+            let expected = successors
+                .iter()
+                .copied()
+                .filter(|&succ| succ >= lower_bound)
+                .collect::<Vec<_>>();
+            let iter = graph.successors_from(node, lower_bound);
+            assert_eq!(iter.len(), expected.len());
+            assert_eq!(iter.collect::<Vec<_>>(), expected);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_split_iter() -> anyhow::Result<()> {
+    let graph = crate::graph::bvgraph::load("tests/data/cnr-2000")?;
+    let seq_graph = crate::graph::bvgraph::load_seq("tests/data/cnr-2000")?;
+    for start in [0, 1, 1000, graph.num_nodes() - 1, graph.num_nodes()] {
+        let expected = seq_graph.iter_nodes_from(start).take(100);
+        for ((node, succ), (expected_node, expected_succ)) in
+            graph.iter_nodes_from(start).take(100).zip(expected)
+        {
+            assert_eq!(node, expected_node);
+            assert!(succ.eq(expected_succ));
+        }
+    }
+    for how_many in [1, 7, 64] {
+        let iters = graph.split_iter(how_many);
+        assert_eq!(iters.len(), how_many);
+        let nodes = iters.into_iter().flatten().map(|(node, _)| node);
+        assert!(nodes.eq(0..graph.num_nodes()));
+    }
+    Ok(())
+}
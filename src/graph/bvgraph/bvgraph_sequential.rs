@@ -81,6 +81,12 @@ impl<CRB: BVGraphCodesReaderBuilder> BVGraphSequential<CRB> {
         }
     }
 
+    #[inline(always)]
+    /// Return a reference to the codes reader builder
+    pub fn get_codes_reader_builder(&self) -> &CRB {
+        &self.codes_reader_builder
+    }
+
     #[inline(always)]
     /// Consume self and return the codes reader builder
     pub fn unwrap_codes_reader_builder(self) -> CRB {
@@ -93,10 +99,11 @@ where
     for<'a> CRB::Reader<'a>: BVGraphCodesSkipper,
 {
     #[inline(always)]
-    /// Create an iterator specialized in the degrees of the nodes.
+    /// Create an iterator specialized in the degrees of the nodes, returning
+    /// also the bit offset of each node, as needed to build the offsets.
     /// This is slightly faster because it can avoid decoding some of the nodes
     /// and completely skip the merging step.
-    pub fn iter_degrees(&self) -> WebgraphDegreesIter<CRB::Reader<'_>> {
+    pub fn iter_degrees_with_offsets(&self) -> WebgraphDegreesIter<CRB::Reader<'_>> {
         WebgraphDegreesIter::new(
             self.codes_reader_builder.get_reader(0).unwrap(),
             self.min_interval_length,
@@ -104,6 +111,16 @@ where
             self.number_of_nodes,
         )
     }
+
+    #[inline(always)]
+    /// Create an iterator over pairs `(node, outdegree)` which skips the
+    /// successor lists instead of decoding them, and is thus much faster
+    /// than iterating over the nodes.
+    pub fn iter_degrees(&self) -> WebgraphOutdegreesIter<CRB::Reader<'_>> {
+        WebgraphOutdegreesIter {
+            degrees: self.iter_degrees_with_offsets(),
+        }
+    }
 }
 
 /// A fast sequential iterator over the nodes of the graph and their successors.
@@ -118,11 +135,11 @@ pub struct WebgraphSequentialIter<CR: BVGraphCodesReader> {
     current_node: usize,
 }
 
-impl<CR: BVGraphCodesReader + BitSeek> WebgraphSequentialIter<CR> {
+impl<CR: BVGraphCodesReader + BVGraphCodesSeek> WebgraphSequentialIter<CR> {
     #[inline(always)]
     /// Forward the call of `get_pos` to the inner `codes_reader`.
     /// This returns the current bits offset in the bitstream.
-    pub fn get_pos(&self) -> usize {
+    pub fn get_pos(&self) -> u64 {
         self.codes_reader.get_pos()
     }
 }
@@ -145,6 +162,31 @@ impl<CR: BVGraphCodesReader> WebgraphSequentialIter<CR> {
         }
     }
 
+    /// Create a new iterator starting at `start_node` from a codes reader
+    /// positioned at the successors of `start_node`, given the successors of
+    /// the nodes in the compression window preceding `start_node`, which
+    /// might be referenced.
+    pub(crate) fn new_from(
+        codes_reader: CR,
+        compression_window: usize,
+        min_interval_length: usize,
+        number_of_nodes: usize,
+        start_node: usize,
+        window: impl IntoIterator<Item = (usize, Vec<usize>)>,
+    ) -> Self {
+        let mut res = Self::new(
+            codes_reader,
+            compression_window,
+            min_interval_length,
+            number_of_nodes,
+        );
+        for (node, successors) in window {
+            res.backrefs.push(node, successors);
+        }
+        res.current_node = start_node;
+        res
+    }
+
     /// Get the successors of the next node in the stream
     pub fn next_successors(&mut self) -> Result<&[usize]> {
         let mut res = self.backrefs.take(self.current_node);
@@ -239,10 +281,18 @@ impl<CR: BVGraphCodesReader> WebgraphSequentialIter<CR> {
             let node_id_offset = nat2int(self.codes_reader.read_first_residual());
             let mut extra = (node_id as i64 + node_id_offset) as usize;
             results.push(extra);
-            // decode the successive extra nodes
-            for _ in 1..nodes_left_to_decode {
-                extra += 1 + self.codes_reader.read_residual() as usize;
-                results.push(extra);
+            // decode the successive extra nodes in batches, so that readers
+            // can decode runs of residuals at once
+            let mut gaps = [0; 32];
+            let mut to_decode = nodes_left_to_decode - 1;
+            while to_decode != 0 {
+                let batch = &mut gaps[..to_decode.min(32)];
+                self.codes_reader.read_residuals(batch);
+                for &gap in batch.iter() {
+                    extra += 1 + gap as usize;
+                    results.push(extra);
+                }
+                to_decode -= batch.len();
             }
         }
 
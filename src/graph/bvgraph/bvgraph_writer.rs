@@ -6,6 +6,15 @@ use crate::utils::{CircularBuffer, CircularBufferVec};
 use anyhow::Result;
 
 /// A BVGraph compressor, this is used to compress a graph into a BVGraph
+///
+/// The choice of the reference is exhaustive, as in the Java implementation:
+/// the successors of each node are compressed against every previous node in
+/// the compression window using the mock writer, and the reference yielding
+/// the shortest encoding, including the reference offset and the blocks, is
+/// used, preferring the nearest one in case of ties. The only greedy aspect
+/// is `max_ref_count`: a candidate whose reference chain is already too long
+/// is discarded, so the choices made for a node restrict the candidates of
+/// the following ones.
 pub struct BVComp<WGCW: BVGraphCodesWriter> {
     /// The ring-buffer that stores the neighbours of the last
     /// `compression_window` neighbours
@@ -80,26 +89,28 @@ impl Compressor {
     /// buffers which are initialized by calling `compress` so this has to be
     /// called only after `compress`.
     ///
-    /// This returns the number of bits written.
+    /// This returns the number of bits written, as a `u64` like all bit
+    /// counts and offsets, so that it cannot overflow on 32-bit targets.
     fn write<WGCW: BVGraphCodesWriter>(
         &self,
         writer: &mut WGCW,
         curr_node: usize,
         reference_offset: Option<usize>,
         min_interval_length: usize,
-    ) -> Result<usize> {
+    ) -> Result<u64> {
         let mut written_bits = 0;
         // write the outdegree
-        written_bits += writer.write_outdegree(self.outdegree as u64)?;
+        written_bits += writer.write_outdegree(self.outdegree as u64)? as u64;
         // write the references
         if self.outdegree != 0 {
             if let Some(reference_offset) = reference_offset {
-                written_bits += writer.write_reference_offset(reference_offset as u64)?;
+                written_bits += writer.write_reference_offset(reference_offset as u64)? as u64;
                 if reference_offset != 0 {
-                    written_bits += writer.write_block_count(self.blocks.len() as _)?;
+                    written_bits += writer.write_block_count(self.blocks.len() as _)? as u64;
                     if !self.blocks.is_empty() {
                         for i in 0..self.blocks.len() {
-                            written_bits += writer.write_blocks((self.blocks[i] - 1) as u64)?;
+                            written_bits +=
+                                writer.write_blocks((self.blocks[i] - 1) as u64)? as u64;
                         }
                     }
                 }
@@ -107,21 +118,24 @@ impl Compressor {
         }
         // write the intervals
         if !self.extra_nodes.is_empty() && min_interval_length != Self::NO_INTERVALS {
-            written_bits += writer.write_interval_count(self.left_interval.len() as _)?;
+            written_bits += writer.write_interval_count(self.left_interval.len() as _)? as u64;
 
             if !self.left_interval.is_empty() {
                 written_bits += writer.write_interval_start(int2nat(
                     self.left_interval[0] as i64 - curr_node as i64,
-                ))?;
+                ))? as u64;
                 written_bits += writer
-                    .write_interval_len((self.len_interval[0] - min_interval_length) as u64)?;
+                    .write_interval_len((self.len_interval[0] - min_interval_length) as u64)?
+                    as u64;
                 let mut prev = self.left_interval[0] + self.len_interval[0];
 
                 for i in 1..self.left_interval.len() {
-                    written_bits +=
-                        writer.write_interval_start((self.left_interval[i] - prev - 1) as u64)?;
                     written_bits += writer
-                        .write_interval_len((self.len_interval[i] - min_interval_length) as u64)?;
+                        .write_interval_start((self.left_interval[i] - prev - 1) as u64)?
+                        as u64;
+                    written_bits += writer
+                        .write_interval_len((self.len_interval[i] - min_interval_length) as u64)?
+                        as u64;
                     prev = self.left_interval[i] + self.len_interval[i];
                 }
             }
@@ -129,11 +143,13 @@ impl Compressor {
         // write the residuals
         if !self.residuals.is_empty() {
             written_bits += writer
-                .write_first_residual(int2nat(self.residuals[0] as i64 - curr_node as i64))?;
+                .write_first_residual(int2nat(self.residuals[0] as i64 - curr_node as i64))?
+                as u64;
 
             for i in 1..self.residuals.len() {
                 written_bits += writer
-                    .write_residual((self.residuals[i] - self.residuals[i - 1] - 1) as u64)?;
+                    .write_residual((self.residuals[i] - self.residuals[i - 1] - 1) as u64)?
+                    as u64;
             }
         }
 
@@ -326,7 +342,7 @@ impl<WGCW: BVGraphCodesWriter> BVComp<WGCW> {
     /// The iterator must yield the successors of the node and the nodes HAVE
     /// TO BE CONTIGUOUS (i.e. if a node has no neighbours you have to pass an
     /// empty iterator)
-    pub fn push<I: Iterator<Item = usize>>(&mut self, succ_iter: I) -> Result<usize> {
+    pub fn push<I: Iterator<Item = usize>>(&mut self, succ_iter: I) -> Result<u64> {
         // collect the iterator inside the backrefs, to reuse the capacity already
         // allocated
         {
@@ -425,7 +441,7 @@ impl<WGCW: BVGraphCodesWriter> BVComp<WGCW> {
     pub fn extend<I: Iterator<Item = (usize, J)>, J: Iterator<Item = usize>>(
         &mut self,
         iter_nodes: I,
-    ) -> Result<usize> {
+    ) -> Result<u64> {
         iter_nodes.map(|(_, succ)| self.push(succ)).sum()
     }
 
@@ -602,6 +618,38 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_exhaustive_references() -> Result<()> {
+        // without limits on the reference chains, every candidate of a window
+        // is a candidate of a larger window, so no node can get longer (a
+        // window of zero does not write reference offsets, so we start from one)
+        let seq_graph = crate::graph::bvgraph::load_seq("tests/data/cnr-2000")?;
+        let mut buffers: Vec<Vec<u64>> = vec![Vec::new(); 3];
+        let mut bvcomps = buffers
+            .iter_mut()
+            .zip(1..)
+            .map(|(buffer, compression_window)| {
+                let bit_write = <BufferedBitStreamWrite<LE, _>>::new(MemWordWriteVec::new(buffer));
+                let codes_writer = <ConstCodesWriter<LE, _>>::new(bit_write);
+                BVComp::new(codes_writer, compression_window, 4, usize::MAX, 0)
+            })
+            .collect::<Vec<_>>();
+
+        for (node, succ) in (&seq_graph).into_iter() {
+            let succ = succ.collect::<Vec<_>>();
+            let bits = bvcomps
+                .iter_mut()
+                .map(|bvcomp| bvcomp.push(succ.iter().copied()))
+                .collect::<Result<Vec<_>>>()?;
+            assert!(bits[1] <= bits[0], "node_id: {}", node);
+            assert!(bits[2] <= bits[1], "node_id: {}", node);
+        }
+        for bvcomp in bvcomps {
+            bvcomp.flush()?;
+        }
+        Ok(())
+    }
+
     fn test_compression(compression_window: usize, min_interval_length: usize) -> Result<()> {
         let seq_graph = crate::graph::bvgraph::load_seq("tests/data/cnr-2000")?;
 
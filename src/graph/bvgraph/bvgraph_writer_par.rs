@@ -1,38 +1,390 @@
 use super::*;
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use dsi_bitstream::prelude::*;
 use std::fs::File;
-use std::io::{BufReader, BufWriter};
-use std::path::Path;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::thread::ScopedJoinHandle;
 use tempfile::tempdir;
 
+/// Return the boundaries of `num_chunks` chunks of `num_nodes` nodes, all
+/// containing the same number of nodes but the last one, which contains
+/// also the remaining nodes.
+///
+/// The result has `num_chunks + 1` elements: chunk `i` contains the nodes
+/// in `[bounds[i]..bounds[i + 1])`.
+pub fn node_balanced_bounds(num_nodes: usize, num_chunks: usize) -> Vec<usize> {
+    assert_ne!(num_chunks, 0);
+    let nodes_per_chunk = num_nodes / num_chunks;
+    let mut bounds = (0..num_chunks)
+        .map(|i| i * nodes_per_chunk)
+        .collect::<Vec<_>>();
+    bounds.push(num_nodes);
+    bounds
+}
+
+/// Return the boundaries of `num_chunks` chunks containing approximately
+/// the same number of arcs, given an iterator on the outdegrees of the nodes.
+///
+/// Each node is weighted by its outdegree plus one, so that long runs of
+/// nodes without successors, which have a cost, too, are split as well. The
+/// iterator is cloned and scanned twice.
+///
+/// The result has the same format as [`node_balanced_bounds`].
+pub fn arc_balanced_bounds<I: Iterator<Item = usize> + Clone>(
+    degrees: I,
+    num_chunks: usize,
+) -> Vec<usize> {
+    assert_ne!(num_chunks, 0);
+    let (num_nodes, total_weight) = degrees
+        .clone()
+        .fold((0, 0), |(nodes, weight), d| (nodes + 1, weight + d + 1));
+    let mut bounds = Vec::with_capacity(num_chunks + 1);
+    bounds.push(0);
+    let mut weight = 0;
+    for (node, d) in degrees.enumerate() {
+        // close all the chunks whose share of the total weight was reached
+        while bounds.len() < num_chunks
+            && weight as u128 * num_chunks as u128 >= bounds.len() as u128 * total_weight as u128
+        {
+            bounds.push(node);
+        }
+        weight += d + 1;
+    }
+    bounds.resize(num_chunks + 1, num_nodes);
+    bounds
+}
+
 /// Compress an iterator of nodes and successors in parllel and return the
 /// lenght in bits of the produced file
+///
+/// The nodes are split among the threads in chunks containing the same
+/// number of nodes; on skewed graphs, [`parallel_compress_sequential_iter_arc_balanced`]
+/// usually keeps the threads busier.
 pub fn parallel_compress_sequential_iter<
     P: AsRef<Path> + Send + Sync,
     I: Iterator<Item = (usize, J)> + Clone + Send,
     J: Iterator<Item = usize>,
 >(
     basename: P,
-    mut iter: I,
+    iter: I,
+    num_nodes: usize,
+    compression_flags: CompFlags,
+    num_threads: usize,
+) -> Result<u64> {
+    parallel_compress_sequential_iter_with_bounds(
+        basename,
+        iter,
+        &node_balanced_bounds(num_nodes, num_threads),
+        compression_flags,
+        GraphEndianness::Big,
+    )
+}
+
+/// Compress an iterator of nodes and successors in parallel, splitting the
+/// nodes among the threads in chunks containing approximately the same
+/// number of arcs, and return the length in bits of the produced file.
+///
+/// The boundaries of the chunks are computed by [`arc_balanced_bounds`]
+/// with a preliminary scan of the iterator, so this is convenient when
+/// scanning is much cheaper than compressing, as it happens for a BVGraph.
+pub fn parallel_compress_sequential_iter_arc_balanced<
+    P: AsRef<Path> + Send + Sync,
+    I: Iterator<Item = (usize, J)> + Clone + Send,
+    J: Iterator<Item = usize>,
+>(
+    basename: P,
+    iter: I,
     num_nodes: usize,
     compression_flags: CompFlags,
     num_threads: usize,
-) -> Result<usize> {
+) -> Result<u64> {
+    log::info!("Scanning the outdegrees to balance the chunks");
+    let degrees = iter
+        .clone()
+        .take(num_nodes)
+        .map(|(_, successors)| successors.count())
+        .collect::<Vec<_>>();
+    let bounds = arc_balanced_bounds(degrees.into_iter(), num_threads);
+    parallel_compress_sequential_iter_with_bounds(
+        basename,
+        iter,
+        &bounds,
+        compression_flags,
+        GraphEndianness::Big,
+    )
+}
+
+/// Compress an iterator of nodes and successors in parallel and return the
+/// length in bits of the produced file.
+///
+/// `bounds` contains the boundaries of the chunks compressed by each thread,
+/// as returned by [`node_balanced_bounds`] or [`arc_balanced_bounds`]: a
+/// thread is spawned for each chunk, and the last boundary is the number of
+/// nodes.
+///
+/// The bitstream is written with the given endianness, which is recorded in
+/// the `.properties` file.
+pub fn parallel_compress_sequential_iter_with_bounds<
+    P: AsRef<Path> + Send + Sync,
+    I: Iterator<Item = (usize, J)> + Clone + Send,
+    J: Iterator<Item = usize>,
+>(
+    basename: P,
+    iter: I,
+    bounds: &[usize],
+    compression_flags: CompFlags,
+    endianness: GraphEndianness,
+) -> Result<u64> {
+    let dir = tempdir()?.into_path();
+    let manifest = Manifest::new(&dir, bounds, &compression_flags, endianness, false)?;
+    let result_len = compress_chunks(
+        basename.as_ref(),
+        iter,
+        bounds,
+        compression_flags,
+        endianness,
+        &dir,
+        &manifest,
+        None,
+    )?;
+    // cleanup the temp files
+    std::fs::remove_dir_all(dir)?;
+    Ok(result_len)
+}
+
+/// Compress an iterator of nodes and successors in parallel, as
+/// [`parallel_compress_sequential_iter_with_bounds`] does, writing the
+/// bitstream into segments of `segment_size` bytes (see [`Segments`]) instead
+/// of a single `.graph` file, and return the length in bits of the bitstream.
+///
+/// `segment_size` must be a positive multiple of [`SEGMENT_ALIGN`].
+pub fn parallel_compress_sequential_iter_segmented<
+    P: AsRef<Path> + Send + Sync,
+    I: Iterator<Item = (usize, J)> + Clone + Send,
+    J: Iterator<Item = usize>,
+>(
+    basename: P,
+    iter: I,
+    bounds: &[usize],
+    compression_flags: CompFlags,
+    endianness: GraphEndianness,
+    segment_size: usize,
+) -> Result<u64> {
+    let dir = tempdir()?.into_path();
+    let manifest = Manifest::new(&dir, bounds, &compression_flags, endianness, false)?;
+    let result_len = compress_chunks(
+        basename.as_ref(),
+        iter,
+        bounds,
+        compression_flags,
+        endianness,
+        &dir,
+        &manifest,
+        Some(segment_size),
+    )?;
+    std::fs::remove_dir_all(dir)?;
+    Ok(result_len)
+}
+
+/// Compress an iterator of nodes and successors in parallel, as
+/// [`parallel_compress_sequential_iter_with_bounds`] does, in a way that can
+/// be resumed after a crash, and return the length in bits of the produced
+/// file.
+///
+/// The bitstreams of the chunks are written in the directory
+/// `{basename}.chunks`, together with a manifest recording the chunks that
+/// have been completely written. If the manifest already exists, the chunks
+/// it lists are not compressed again (they are just skipped on the
+/// iterator), so in case of failure calling again this function with the
+/// same arguments will compress only the missing chunks before the
+/// concatenation. The directory is deleted when the graph is complete.
+pub fn parallel_compress_sequential_iter_resumable<
+    P: AsRef<Path> + Send + Sync,
+    I: Iterator<Item = (usize, J)> + Clone + Send,
+    J: Iterator<Item = usize>,
+>(
+    basename: P,
+    iter: I,
+    bounds: &[usize],
+    compression_flags: CompFlags,
+    endianness: GraphEndianness,
+) -> Result<u64> {
     let basename = basename.as_ref();
+    let dir = PathBuf::from(format!("{}.chunks", basename.to_string_lossy()));
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Cannot create the directory {}", dir.display()))?;
+    let manifest = Manifest::new(&dir, bounds, &compression_flags, endianness, true)?;
+    let result_len = compress_chunks(
+        basename,
+        iter,
+        bounds,
+        compression_flags,
+        endianness,
+        &dir,
+        &manifest,
+        None,
+    )?;
+    std::fs::remove_dir_all(dir)?;
+    Ok(result_len)
+}
+
+/// The manifest of the chunks of a parallel compression.
+///
+/// The file `manifest` starts with the boundaries of the chunks, the
+/// compression flags and the endianness, so that a compression is not resumed with different
+/// parameters, followed by a line `chunk <id> <bits> <arcs>` for each chunk
+/// whose bitstream has been completely written.
+struct Manifest {
+    file: Mutex<File>,
+    /// The number of bits and arcs of the completed chunks.
+    done: Vec<Option<(u64, usize)>>,
+}
+
+impl Manifest {
+    /// Create a manifest in `dir` or, if `resume` is true and there is
+    /// already one, load the completed chunks from it.
+    fn new(
+        dir: &Path,
+        bounds: &[usize],
+        compression_flags: &CompFlags,
+        endianness: GraphEndianness,
+        resume: bool,
+    ) -> Result<Self> {
+        let path = dir.join("manifest");
+        let header = format!(
+            "bounds {}\nflags {:?}\nendianness {}\n",
+            bounds
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+                .join(" "),
+            compression_flags,
+            endianness.as_str()
+        );
+        let mut done = vec![None; bounds.len() - 1];
+        let mut contents = header.clone();
+        if resume && path.exists() {
+            let old = std::fs::read_to_string(&path)
+                .with_context(|| format!("Cannot read the manifest {}", path.display()))?;
+            let Some(body) = old.strip_prefix(&header) else {
+                bail!(
+                    "The manifest {} was written with different chunks, compression flags or endianness",
+                    path.display()
+                );
+            };
+            // a line without a newline was being written during a crash
+            let body = &body[..body.rfind('\n').map_or(0, |i| i + 1)];
+            for line in body.lines() {
+                let fields = line.split(' ').collect::<Vec<_>>();
+                let (id, bits, arcs) = match fields[..] {
+                    ["chunk", id, bits, arcs] => {
+                        (id.parse::<usize>()?, bits.parse()?, arcs.parse()?)
+                    }
+                    _ => bail!(
+                        "Unexpected line in the manifest {}: {}",
+                        path.display(),
+                        line
+                    ),
+                };
+                if id >= done.len() {
+                    bail!(
+                        "Chunk {} out of range in the manifest {}",
+                        id,
+                        path.display()
+                    );
+                }
+                done[id] = Some((bits, arcs));
+            }
+            contents.push_str(body);
+            log::info!(
+                "Resuming the compression: {} out of {} chunks are already compressed",
+                done.iter().filter(|d| d.is_some()).count(),
+                done.len()
+            );
+        }
+        // rewrite the manifest, dropping a possibly truncated last line
+        std::fs::write(&path, contents)
+            .with_context(|| format!("Cannot write the manifest {}", path.display()))?;
+        let file = std::fs::OpenOptions::new().append(true).open(&path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+            done,
+        })
+    }
+
+    /// Record that the bitstream of chunk `id` has been completely written.
+    fn record(&self, id: usize, bits: u64, arcs: usize) -> Result<()> {
+        let mut file = self.file.lock().unwrap();
+        writeln!(file, "chunk {} {} {}", id, bits, arcs)?;
+        file.sync_data()?;
+        Ok(())
+    }
+}
+
+/// Compress the nodes returned by `iter` on `file_path` with the given
+/// endianness, starting from `start_node`, and return the number of bits
+/// written and the number of arcs.
+fn compress_chunk<I: Iterator<Item = (usize, J)>, J: Iterator<Item = usize>>(
+    file_path: &Path,
+    iter: I,
+    compression_flags: &CompFlags,
+    endianness: GraphEndianness,
+    start_node: usize,
+) -> Result<(u64, usize)> {
+    macro_rules! compress {
+        ($endianness:ident) => {{
+            let writer = <BufferedBitStreamWrite<$endianness, _>>::new(FileBackend::new(
+                BufWriter::new(File::create(file_path)?),
+            ));
+            let codes_writer = <DynamicCodesWriter<$endianness, _>>::new(writer, compression_flags);
+            let mut bvcomp = BVComp::new(
+                codes_writer,
+                compression_flags.compression_window,
+                compression_flags.min_interval_length,
+                compression_flags.max_ref_count,
+                start_node,
+            );
+            let written_bits = bvcomp.extend(iter)?;
+            let arcs = bvcomp.arcs;
+            bvcomp.flush()?;
+            Ok((written_bits, arcs))
+        }};
+    }
+    match endianness {
+        GraphEndianness::Big => compress!(BE),
+        GraphEndianness::Little => compress!(LE),
+    }
+}
+
+/// Compress the chunks that are not listed as completed in the manifest,
+/// writing their bitstreams in `dir`, and concatenate all chunks, in
+/// segments of the given size if `segment_size` is not `None`.
+#[allow(clippy::too_many_arguments)]
+fn compress_chunks<I: Iterator<Item = (usize, J)> + Clone + Send, J: Iterator<Item = usize>>(
+    basename: &Path,
+    mut iter: I,
+    bounds: &[usize],
+    compression_flags: CompFlags,
+    endianness: GraphEndianness,
+    dir: &Path,
+    manifest: &Manifest,
+    segment_size: Option<usize>,
+) -> Result<u64> {
     let graph_path = format!("{}.graph", basename.to_string_lossy());
-    assert_ne!(num_threads, 0);
-    let nodes_per_thread = num_nodes / num_threads;
-    let dir = tempdir()?.into_path();
-    let tmp_dir = dir.clone();
+    assert!(bounds.len() >= 2);
+    assert_eq!(bounds[0], 0);
+    assert!(bounds.windows(2).all(|w| w[0] <= w[1]));
+    let num_threads = bounds.len() - 1;
+    let num_nodes = bounds[num_threads];
+    let tmp_dir = dir.to_owned();
 
     std::thread::scope(|s| {
         // collect the handles in vec, otherwise the handles will be dropped
         // in-place calling a join and making the algorithm sequential.
         #[allow(clippy::type_complexity)]
-        let mut handles: Vec<Mutex<Option<ScopedJoinHandle<(usize, usize)>>>> = vec![];
+        let mut handles: Vec<Mutex<Option<ScopedJoinHandle<(u64, usize)>>>> = vec![];
         handles.resize_with(num_threads, || Mutex::new(None));
         let handles = Arc::new(handles);
 
@@ -48,7 +400,7 @@ pub fn parallel_compress_sequential_iter<
             "Spawning the main compression thread {} writing on {} writing from node_id {} to {}",
             last_thread_id,
             last_file_path.to_string_lossy(),
-            last_thread_id * nodes_per_thread,
+            bounds[last_thread_id],
             num_nodes,
         );
         let sub_handles = handles.clone();
@@ -56,92 +408,100 @@ pub fn parallel_compress_sequential_iter<
             // for the first N - 1 threads, clone the iter and skip to the next
             // splitting point, then start a new compression thread
             for thread_id in 0..num_threads.saturating_sub(1) {
-                // the first thread can directly write to the result bitstream
-                let file_path = tmp_dir
-                    .clone()
-                    .join(format!("{:016x}.bitstream", thread_id));
-
-                // spawn the thread
-                log::info!(
-                    "Spawning compression thread {} writing on {} form node id {} to {}",
-                    thread_id,
-                    file_path.to_string_lossy(),
-                    nodes_per_thread * thread_id,
-                    nodes_per_thread * (thread_id + 1),
-                );
-                // Spawn the thread
-                let thread_iter = iter.clone().take(nodes_per_thread);
-                let handle = s.spawn(move || {
-                    log::info!("Thread {} started", thread_id,);
-                    let writer = <BufferedBitStreamWrite<BE, _>>::new(FileBackend::new(
-                        BufWriter::new(File::create(&file_path).unwrap()),
-                    ));
-                    let codes_writer = <DynamicCodesWriter<BE, _>>::new(writer, cp_flags);
-                    let mut bvcomp = BVComp::new(
-                        codes_writer,
-                        cp_flags.compression_window,
-                        cp_flags.min_interval_length,
-                        cp_flags.max_ref_count,
-                        nodes_per_thread * thread_id,
-                    );
-                    let written_bits = bvcomp.extend(thread_iter).unwrap();
+                let chunk_len = bounds[thread_id + 1] - bounds[thread_id];
+                let handle = if let Some(done) = manifest.done[thread_id] {
+                    log::info!("Chunk {} is already compressed", thread_id);
+                    s.spawn(move || done)
+                } else {
+                    // the first thread can directly write to the result bitstream
+                    let file_path = tmp_dir
+                        .clone()
+                        .join(format!("{:016x}.bitstream", thread_id));
 
+                    // spawn the thread
                     log::info!(
-                        "Finished Compression thread {} and wrote {} bits bits [{}, {})",
+                        "Spawning compression thread {} writing on {} form node id {} to {}",
                         thread_id,
-                        written_bits,
-                        nodes_per_thread * thread_id,
-                        nodes_per_thread * (thread_id + 1),
+                        file_path.to_string_lossy(),
+                        bounds[thread_id],
+                        bounds[thread_id + 1],
                     );
+                    // Spawn the thread
+                    let thread_iter = iter.clone().take(chunk_len);
+                    s.spawn(move || {
+                        log::info!("Thread {} started", thread_id,);
+                        let (written_bits, arcs) = compress_chunk(
+                            &file_path,
+                            thread_iter,
+                            cp_flags,
+                            endianness,
+                            bounds[thread_id],
+                        )
+                        .unwrap();
+                        manifest.record(thread_id, written_bits, arcs).unwrap();
 
-                    (written_bits, bvcomp.arcs)
-                });
+                        log::info!(
+                            "Finished Compression thread {} and wrote {} bits bits [{}, {})",
+                            thread_id,
+                            written_bits,
+                            bounds[thread_id],
+                            bounds[thread_id + 1],
+                        );
+
+                        (written_bits, arcs)
+                    })
+                };
                 {
                     *(sub_handles[thread_id]).lock().unwrap() = Some(handle);
                 }
-                log::info!("Skipping {} nodes from the iterator", nodes_per_thread);
+                log::info!("Skipping {} nodes from the iterator", chunk_len);
 
-                // skip the next nodes_per_thread nodes
-                for _ in 0..nodes_per_thread {
+                // skip the nodes of the chunk
+                for _ in 0..chunk_len {
                     iter.next();
                 }
             }
 
+            if let Some(done) = manifest.done[last_thread_id] {
+                log::info!("Chunk {} is already compressed", last_thread_id);
+                return done;
+            }
             // handle the case when this is the only available thread
             let last_file_path = tmp_dir.join(format!("{:016x}.bitstream", last_thread_id));
             // complete the last chunk
-            let writer = <BufferedBitStreamWrite<BE, _>>::new(FileBackend::new(BufWriter::new(
-                File::create(last_file_path).unwrap(),
-            )));
-            let codes_writer = <DynamicCodesWriter<BE, _>>::new(writer, &compression_flags);
-            let mut bvcomp = BVComp::new(
-                codes_writer,
-                compression_flags.compression_window,
-                compression_flags.min_interval_length,
-                compression_flags.max_ref_count,
-                last_thread_id * nodes_per_thread,
-            );
-            let written_bits = bvcomp.extend(iter).unwrap();
+            let (written_bits, arcs) = compress_chunk(
+                &last_file_path,
+                iter,
+                &compression_flags,
+                endianness,
+                bounds[last_thread_id],
+            )
+            .unwrap();
+            manifest.record(last_thread_id, written_bits, arcs).unwrap();
 
             log::info!(
                 "Finished Compression thread {} and wrote {} bits [{}, {})",
                 last_thread_id,
                 written_bits,
-                last_thread_id * nodes_per_thread,
+                bounds[last_thread_id],
                 num_nodes,
             );
-            (written_bits, bvcomp.arcs)
+            (written_bits, arcs)
         });
         {
             *(handles[last_thread_id]).lock().unwrap() = Some(handle);
         }
         // setup the final bitstream from the end, because the first thread
         // already wrote the first chunk
-        let file = File::create(graph_path)?;
+        let writer = match segment_size {
+            None => MergedWriter::File(BufWriter::new(File::create(graph_path)?)),
+            Some(segment_size) => {
+                MergedWriter::Segmented(SegmentedWriter::new(basename, segment_size)?)
+            }
+        };
 
-        // create hte buffered writer
-        let mut result_writer =
-            <BufferedBitStreamWrite<BE, _>>::new(FileBackend::new(BufWriter::new(file)));
+        // create the concatenator of the chunks
+        let mut result_writer = BitConcat::new(writer, endianness);
 
         let mut result_len = 0;
         let mut total_arcs = 0;
@@ -150,7 +510,7 @@ pub fn parallel_compress_sequential_iter<
         for thread_id in 0..num_threads {
             log::info!("Waiting for thread {}", thread_id);
             // wait for the thread to finish
-            let (mut bits_to_copy, n_arcs) = loop {
+            let (bits_to_copy, n_arcs) = loop {
                 {
                     let mut maybe_handle = handles[thread_id].lock().unwrap();
                     if maybe_handle.is_some() {
@@ -162,7 +522,7 @@ pub fn parallel_compress_sequential_iter<
             };
             total_arcs += n_arcs;
             // compute the path of the bitstream created by this thread
-            let file_path = dir.clone().join(format!("{:016x}.bitstream", thread_id));
+            let file_path = dir.join(format!("{:016x}.bitstream", thread_id));
             log::info!(
                 "Copying {} [{}, {}) bits from {} to {}",
                 bits_to_copy,
@@ -173,23 +533,19 @@ pub fn parallel_compress_sequential_iter<
             );
             result_len += bits_to_copy;
 
-            let mut reader = <BufferedBitStreamRead<BE, u64, _>>::new(<FileBackend<u32, _>>::new(
-                BufReader::new(File::open(&file_path).unwrap()),
-            ));
             // copy all the data
-            while bits_to_copy > 0 {
-                let bits = bits_to_copy.min(64);
-                let word = reader.read_bits(bits)?;
-                result_writer.write_bits(word, bits)?;
-                bits_to_copy -= bits;
-            }
+            result_writer.append(BufReader::new(File::open(&file_path)?), bits_to_copy)?;
         }
 
         log::info!("Flushing the merged Compression bitstream");
-        result_writer.flush().unwrap();
+        let segments = result_writer.finish()?.finish()?;
 
         log::info!("Writing the .properties file");
-        let properties = compression_flags.to_properties(num_nodes, total_arcs);
+        let mut properties = compression_flags.to_properties(num_nodes, total_arcs);
+        properties.push_str(&endianness.to_properties());
+        if let Some(segments) = segments {
+            properties.push_str(&segments.to_properties());
+        }
         std::fs::write(
             format!("{}.properties", basename.to_string_lossy()),
             properties,
@@ -202,8 +558,283 @@ pub fn parallel_compress_sequential_iter<
             result_len as f64 / total_arcs as f64
         );
 
-        // cleanup the temp files
-        std::fs::remove_dir_all(dir)?;
         Ok(result_len)
     })
 }
+
+/// Concatenates bitstreams a word at a time.
+///
+/// The bitstreams of the chunks are contiguous in the final file, as both
+/// sequential decoding and the offsets require, so in general a chunk does
+/// not start on a word boundary: each 64-bit word of a chunk is split among
+/// two words of the output with a couple of shifts, instead of being decoded
+/// and re-encoded by bit streams.
+///
+/// Big-endian bitstreams are sequences of bytes read from the most
+/// significant bit, little-endian ones from the least significant bit.
+struct BitConcat<W: Write> {
+    writer: W,
+    endianness: GraphEndianness,
+    /// The pending bits, left-aligned if big endian, right-aligned otherwise.
+    buffer: u64,
+    /// The number of pending bits, always less than 64.
+    bits_in_buffer: usize,
+}
+
+impl<W: Write> BitConcat<W> {
+    fn new(writer: W, endianness: GraphEndianness) -> Self {
+        Self {
+            writer,
+            endianness,
+            buffer: 0,
+            bits_in_buffer: 0,
+        }
+    }
+
+    /// Append the first `bits` bits of the bitstream read from `reader`.
+    fn append<R: Read>(&mut self, mut reader: R, mut bits: u64) -> Result<()> {
+        while bits > 0 {
+            let len = bits.min(64) as usize;
+            let mut bytes = [0_u8; 8];
+            reader.read_exact(&mut bytes[..(len + 7) / 8])?;
+            let word = match self.endianness {
+                GraphEndianness::Big => u64::from_be_bytes(bytes),
+                GraphEndianness::Little => u64::from_le_bytes(bytes),
+            };
+            self.push(word, len)?;
+            bits -= len as u64;
+        }
+        Ok(())
+    }
+
+    /// Append the first `len` bits of `word`, in stream order.
+    #[inline(always)]
+    fn push(&mut self, word: u64, len: usize) -> Result<()> {
+        let b = self.bits_in_buffer;
+        let total = b + len;
+        match self.endianness {
+            GraphEndianness::Big => {
+                // clear the bits after the end of the bitstream
+                let word = if len < 64 {
+                    word & !(u64::MAX >> len)
+                } else {
+                    word
+                };
+                self.buffer |= word >> b;
+                if total >= 64 {
+                    self.writer.write_all(&self.buffer.to_be_bytes())?;
+                    self.buffer = if b == 0 { 0 } else { word << (64 - b) };
+                }
+            }
+            GraphEndianness::Little => {
+                let word = if len < 64 {
+                    word & ((1 << len) - 1)
+                } else {
+                    word
+                };
+                self.buffer |= word << b;
+                if total >= 64 {
+                    self.writer.write_all(&self.buffer.to_le_bytes())?;
+                    self.buffer = if b == 0 { 0 } else { word >> (64 - b) };
+                }
+            }
+        }
+        self.bits_in_buffer = total % 64;
+        Ok(())
+    }
+
+    /// Write the pending bits, padding them with zeros to a word, and return
+    /// the underlying writer.
+    fn finish(mut self) -> Result<W> {
+        if self.bits_in_buffer > 0 {
+            let bytes = match self.endianness {
+                GraphEndianness::Big => self.buffer.to_be_bytes(),
+                GraphEndianness::Little => self.buffer.to_le_bytes(),
+            };
+            self.writer.write_all(&bytes)?;
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// The writer of the merged bitstream: a single file, or a sequence of
+/// segments (see [`SegmentedWriter`]).
+enum MergedWriter {
+    File(BufWriter<File>),
+    Segmented(SegmentedWriter),
+}
+
+impl MergedWriter {
+    /// Flush the bitstream, returning the layout of the segments, if any.
+    fn finish(self) -> Result<Option<Segments>> {
+        match self {
+            Self::File(mut writer) => {
+                writer.flush()?;
+                Ok(None)
+            }
+            Self::Segmented(writer) => writer.finish().map(Some),
+        }
+    }
+}
+
+impl Write for MergedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::File(writer) => writer.write(buf),
+            Self::Segmented(writer) => writer.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::File(writer) => writer.flush(),
+            Self::Segmented(writer) => writer.flush(),
+        }
+    }
+}
+
+#[cfg_attr(test, test)]
+#[cfg(test)]
+fn test_chunk_bounds() {
+    assert_eq!(node_balanced_bounds(10, 3), vec![0, 3, 6, 10]);
+    assert_eq!(node_balanced_bounds(2, 3), vec![0, 0, 0, 2]);
+    // a single node with many arcs gets a chunk of its own
+    let degrees = [100, 1, 1, 1, 1, 1, 1, 1];
+    assert_eq!(
+        arc_balanced_bounds(degrees.iter().copied(), 2),
+        vec![0, 1, 8]
+    );
+    assert_eq!(
+        arc_balanced_bounds([1; 8].iter().copied(), 4),
+        vec![0, 2, 4, 6, 8]
+    );
+    assert_eq!(arc_balanced_bounds(core::iter::empty(), 2), vec![0, 0, 0]);
+}
+
+#[cfg_attr(test, test)]
+#[cfg(test)]
+fn test_bit_concat() -> Result<()> {
+    use rand::prelude::*;
+    let mut rng = SmallRng::seed_from_u64(0);
+    for endianness in [GraphEndianness::Big, GraphEndianness::Little] {
+        // the mask of the i-th bit of a byte in stream order
+        let mask = |i: usize| match endianness {
+            GraphEndianness::Big => 0x80 >> (i % 8),
+            GraphEndianness::Little => 1 << (i % 8),
+        };
+        let mut result = BitConcat::new(Vec::new(), endianness);
+        let mut expected = Vec::<bool>::new();
+        for _ in 0..20 {
+            let bits = rng.gen_range(0..300);
+            let mut bytes = (0..(bits + 63) / 64 * 8)
+                .map(|_| rng.gen::<u8>())
+                .collect::<Vec<_>>();
+            // garbage after the end of the bitstream must be ignored
+            bytes.push(0xff);
+            for i in 0..bits {
+                expected.push(bytes[i / 8] & mask(i) != 0);
+            }
+            result.append(bytes.as_slice(), bits as u64)?;
+        }
+        let result = result.finish()?;
+        assert_eq!(result.len(), (expected.len() + 63) / 64 * 8);
+        for (i, &bit) in expected.iter().enumerate() {
+            assert_eq!(result[i / 8] & mask(i) != 0, bit);
+        }
+    }
+    Ok(())
+}
+
+#[cfg_attr(test, test)]
+#[cfg(test)]
+fn test_resumable() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let graph = crate::graph::bvgraph::load_seq("tests/data/cnr-2000")?;
+    let bounds = node_balanced_bounds(graph.num_nodes(), 3);
+    let compression_flags = CompFlags::default();
+
+    let reference = dir.path().join("reference");
+    parallel_compress_sequential_iter_with_bounds(
+        &reference,
+        graph.iter_nodes(),
+        &bounds,
+        compression_flags,
+        GraphEndianness::Big,
+    )?;
+
+    // simulate a crash after the second chunk has been compressed, while
+    // the first one was being recorded
+    let resumed = dir.path().join("resumed");
+    let chunks = PathBuf::from(format!("{}.chunks", resumed.to_string_lossy()));
+    std::fs::create_dir(&chunks)?;
+    {
+        let manifest = Manifest::new(
+            &chunks,
+            &bounds,
+            &compression_flags,
+            GraphEndianness::Big,
+            true,
+        )?;
+        let (bits, arcs) = compress_chunk(
+            &chunks.join(format!("{:016x}.bitstream", 1)),
+            graph
+                .iter_nodes()
+                .skip(bounds[1])
+                .take(bounds[2] - bounds[1]),
+            &compression_flags,
+            GraphEndianness::Big,
+            bounds[1],
+        )?;
+        manifest.record(1, bits, arcs)?;
+        write!(manifest.file.lock().unwrap(), "chunk 0 12")?;
+    }
+    // different chunks cannot be resumed
+    assert!(parallel_compress_sequential_iter_resumable(
+        &resumed,
+        graph.iter_nodes(),
+        &node_balanced_bounds(graph.num_nodes(), 2),
+        compression_flags,
+        GraphEndianness::Big,
+    )
+    .is_err());
+
+    parallel_compress_sequential_iter_resumable(
+        &resumed,
+        graph.iter_nodes(),
+        &bounds,
+        compression_flags,
+        GraphEndianness::Big,
+    )?;
+    assert!(!chunks.exists());
+    assert_eq!(
+        std::fs::read(dir.path().join("reference.graph"))?,
+        std::fs::read(dir.path().join("resumed.graph"))?
+    );
+    Ok(())
+}
+
+#[cfg_attr(test, test)]
+#[cfg(test)]
+fn test_little_endian() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let graph = crate::graph::bvgraph::load_seq("tests/data/cnr-2000")?;
+    let basename = dir.path().join("cnr-2000-le");
+    parallel_compress_sequential_iter_with_bounds(
+        &basename,
+        graph.iter_nodes(),
+        &node_balanced_bounds(graph.num_nodes(), 3),
+        CompFlags::default(),
+        GraphEndianness::Little,
+    )?;
+    assert!(crate::graph::bvgraph::load_seq(&basename).is_err());
+    let le_graph = crate::graph::bvgraph::load_seq_le(&basename)?;
+    let mut le_iter = le_graph.iter_nodes();
+    for (node, succ) in graph.iter_nodes() {
+        let (le_node, le_succ) = le_iter.next().unwrap();
+        assert_eq!(node, le_node);
+        assert_eq!(succ.collect::<Vec<_>>(), le_succ.collect::<Vec<_>>());
+    }
+    assert!(le_iter.next().is_none());
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use anyhow::{bail, Context, Result};
+use sha2::Digest;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+/// The extensions of the files of a graph whose digests can be stored in
+/// its `.properties` file.
+const CHECKSUMMED_FILES: [&str; 2] = ["graph", "ef"];
+
+/// A digest algorithm used to checksum the files of a graph.
+///
+/// The digest of the file `{basename}.{ext}` is stored in the `.properties`
+/// file under the key `{ext}.{algorithm}`, as a lowercase hexadecimal string;
+/// the Java version ignores these keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Checksum {
+    /// The 128-bit XXH3 hash, which is not cryptographic but is fast enough
+    /// to be computed at memory speed.
+    Xxh3,
+    /// SHA-256, to compare against digests published by a third party.
+    Sha256,
+}
+
+impl Checksum {
+    /// All the supported algorithms.
+    pub const ALL: [Checksum; 2] = [Checksum::Xxh3, Checksum::Sha256];
+
+    /// Return the name of the algorithm, as used in the keys of the
+    /// `.properties` file.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Checksum::Xxh3 => "xxh3",
+            Checksum::Sha256 => "sha256",
+        }
+    }
+
+    /// Compute the digest of the file at `path` as a hexadecimal string.
+    pub fn digest_file<P: AsRef<Path>>(&self, path: P) -> Result<String> {
+        let path = path.as_ref();
+        let mut file = BufReader::with_capacity(
+            1 << 20,
+            File::open(path)
+                .with_context(|| format!("Cannot open {} to checksum it", path.display()))?,
+        );
+        let mut buffer = vec![0; 1 << 20];
+        match self {
+            Checksum::Xxh3 => {
+                let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+                loop {
+                    let len = file.read(&mut buffer)?;
+                    if len == 0 {
+                        break;
+                    }
+                    hasher.update(&buffer[..len]);
+                }
+                Ok(format!("{:032x}", hasher.digest128()))
+            }
+            Checksum::Sha256 => {
+                let mut hasher = sha2::Sha256::new();
+                loop {
+                    let len = file.read(&mut buffer)?;
+                    if len == 0 {
+                        break;
+                    }
+                    hasher.update(&buffer[..len]);
+                }
+                Ok(hasher
+                    .finalize()
+                    .iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect())
+            }
+        }
+    }
+
+    /// Return the lines of the `.properties` file recording the digests of
+    /// the `.graph` and, if it exists, `.ef` files of the graph with the given
+    /// basename.
+    pub fn to_properties<P: AsRef<Path>>(&self, basename: P) -> Result<String> {
+        let basename = basename.as_ref().to_string_lossy();
+        let mut s = String::new();
+        for ext in CHECKSUMMED_FILES {
+            let path = format!("{}.{}", basename, ext);
+            if Path::new(&path).exists() {
+                s.push_str(&format!(
+                    "{}.{}={}\n",
+                    ext,
+                    self.as_str(),
+                    self.digest_file(&path)?
+                ));
+            }
+        }
+        Ok(s)
+    }
+}
+
+/// Add to the `.properties` file of the graph with the given basename the
+/// digests of its files computed with the given algorithm, replacing the ones
+/// already present.
+///
+/// This is usually invoked after compressing a graph and building its
+/// `.ef` file.
+pub fn add_checksums<P: AsRef<Path>>(basename: P, checksum: Checksum) -> Result<()> {
+    let basename = basename.as_ref();
+    let properties_path = format!("{}.properties", basename.to_string_lossy());
+    let properties = std::fs::read_to_string(&properties_path)
+        .with_context(|| format!("Cannot read property file {}", properties_path))?;
+    let stale = CHECKSUMMED_FILES.map(|ext| format!("{}.{}=", ext, checksum.as_str()));
+    let mut result = properties
+        .lines()
+        .filter(|line| !stale.iter().any(|prefix| line.starts_with(prefix.as_str())))
+        .map(|line| format!("{}\n", line))
+        .collect::<String>();
+    result.push_str(&checksum.to_properties(basename)?);
+    std::fs::write(&properties_path, result)?;
+    Ok(())
+}
+
+/// Recompute the digests of the files of the graph with the given basename
+/// that are recorded in its decoded `.properties` file, failing at the first
+/// mismatch.
+///
+/// Graphs without digests are accepted.
+pub fn verify_checksums<P: AsRef<Path>>(basename: P, map: &HashMap<String, String>) -> Result<()> {
+    let basename = basename.as_ref().to_string_lossy();
+    for ext in CHECKSUMMED_FILES {
+        for checksum in Checksum::ALL {
+            if let Some(expected) = map.get(&format!("{}.{}", ext, checksum.as_str())) {
+                let path = format!("{}.{}", basename, ext);
+                let digest = checksum.digest_file(&path)?;
+                if !digest.eq_ignore_ascii_case(expected) {
+                    bail!(
+                        "The {} digest of {} is {}, but {} was expected: the file is corrupted or truncated",
+                        checksum.as_str(),
+                        path,
+                        digest,
+                        expected
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_checksums() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let basename = dir.path().join("test");
+    let basename_str = basename.to_string_lossy();
+    std::fs::write(format!("{}.graph", basename_str), b"abc")?;
+    std::fs::write(format!("{}.properties", basename_str), "nodes=0\narcs=0\n")?;
+
+    // the SHA-256 of "abc" is a well-known test vector
+    assert_eq!(
+        Checksum::Sha256.digest_file(format!("{}.graph", basename_str))?,
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+
+    add_checksums(&basename, Checksum::Xxh3)?;
+    add_checksums(&basename, Checksum::Sha256)?;
+    // adding twice replaces the previous digests
+    add_checksums(&basename, Checksum::Xxh3)?;
+    let properties = std::fs::read_to_string(format!("{}.properties", basename_str))?;
+    assert_eq!(properties.matches("graph.xxh3=").count(), 1);
+    assert_eq!(properties.matches("graph.sha256=").count(), 1);
+    assert!(!properties.contains("ef."));
+
+    let map = java_properties::read(properties.as_bytes())?;
+    verify_checksums(&basename, &map)?;
+
+    let properties = super::CompFlags::default().to_properties_with_checksums(
+        0,
+        0,
+        &basename,
+        &[Checksum::Sha256],
+    )?;
+    assert!(properties.starts_with(&super::CompFlags::default().to_properties(0, 0)));
+    assert!(properties.ends_with(
+        "graph.sha256=ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad\n"
+    ));
+
+    // a truncated file is detected
+    std::fs::write(format!("{}.graph", basename_str), b"ab")?;
+    assert!(verify_checksums(&basename, &map).is_err());
+    Ok(())
+}
@@ -1,49 +1,89 @@
 use super::*;
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use dsi_bitstream::prelude::*;
 
-type BitReader<'a, E> = BufferedBitStreamRead<E, u64, MemWordReadInfinite<u32, &'a [u32]>>;
+/// A word type in which the bitstream of a graph can be read from memory.
+///
+/// Reading 64-bit words halves the number of refills of the bit buffer with
+/// respect to 32-bit words, which is usually faster on 64-bit CPUs, but
+/// it requires a 128-bit buffer.
+pub trait BitStreamWord: Word {
+    /// The type of the bit buffer, which must hold two words.
+    type Buffer: Word;
+}
+
+impl BitStreamWord for u32 {
+    type Buffer = u64;
+}
+
+impl BitStreamWord for u64 {
+    type Buffer = u128;
+}
+
+pub(crate) type BitReader<'a, E, W = u32> =
+    BufferedBitStreamRead<E, <W as BitStreamWord>::Buffer, MemWordReadInfinite<W, &'a [W]>>;
 
 /// A builder for the [`DynamicCodesReader`] that stores the data and gives
 /// references to the [`DynamicCodesReader`]. This does single-static-dispatching
 /// to optimize the reader building time.
-pub struct DynamicCodesReaderBuilder<E: Endianness, B: AsRef<[u32]>> {
+pub struct DynamicCodesReaderBuilder<E: Endianness, B: AsRef<[W]>, W: BitStreamWord = u32> {
     /// The owned data we will read as a bitstream.
     data: B,
     /// The compression flags.
     compression_flags: CompFlags,
     // The cached functions to read the codes.
-    read_outdegree: for<'a> fn(&mut BitReader<'a, E>) -> u64,
-    read_reference_offset: for<'a> fn(&mut BitReader<'a, E>) -> u64,
-    read_block_count: for<'a> fn(&mut BitReader<'a, E>) -> u64,
-    read_blocks: for<'a> fn(&mut BitReader<'a, E>) -> u64,
-    read_interval_count: for<'a> fn(&mut BitReader<'a, E>) -> u64,
-    read_interval_start: for<'a> fn(&mut BitReader<'a, E>) -> u64,
-    read_interval_len: for<'a> fn(&mut BitReader<'a, E>) -> u64,
-    read_first_residual: for<'a> fn(&mut BitReader<'a, E>) -> u64,
-    read_residual: for<'a> fn(&mut BitReader<'a, E>) -> u64,
+    read_outdegree: for<'a> fn(&mut BitReader<'a, E, W>) -> u64,
+    read_reference_offset: for<'a> fn(&mut BitReader<'a, E, W>) -> u64,
+    read_block_count: for<'a> fn(&mut BitReader<'a, E, W>) -> u64,
+    read_blocks: for<'a> fn(&mut BitReader<'a, E, W>) -> u64,
+    read_interval_count: for<'a> fn(&mut BitReader<'a, E, W>) -> u64,
+    read_interval_start: for<'a> fn(&mut BitReader<'a, E, W>) -> u64,
+    read_interval_len: for<'a> fn(&mut BitReader<'a, E, W>) -> u64,
+    read_first_residual: for<'a> fn(&mut BitReader<'a, E, W>) -> u64,
+    read_residual: for<'a> fn(&mut BitReader<'a, E, W>) -> u64,
     /// Tell the compiler that's Ok that we don't store `E` but we need it
     /// for typing.
-    _marker: core::marker::PhantomData<E>,
+    _marker: core::marker::PhantomData<(E, W)>,
 }
 
-impl<E: Endianness, B: AsRef<[u32]>> DynamicCodesReaderBuilder<E, B>
+impl<E: Endianness, B: AsRef<[W]>, W: BitStreamWord> DynamicCodesReaderBuilder<E, B, W>
 where
-    for<'a> BitReader<'a, E>: ReadCodes<E> + BitSeek,
+    for<'a> BitReader<'a, E, W>: ReadCodes<E> + BitSeek,
 {
     // Const cached functions we use to decode the data. These could be general
     // functions, but this way we have better visibility and we ensure that
     // they are compiled once!
-    const READ_UNARY: for<'a> fn(&mut BitReader<'a, E>) -> u64 = |cr| cr.read_unary().unwrap();
-    const READ_GAMMA: for<'a> fn(&mut BitReader<'a, E>) -> u64 = |cr| cr.read_gamma().unwrap();
-    const READ_DELTA: for<'a> fn(&mut BitReader<'a, E>) -> u64 = |cr| cr.read_delta().unwrap();
-    const READ_ZETA2: for<'a> fn(&mut BitReader<'a, E>) -> u64 = |cr| cr.read_zeta(2).unwrap();
-    const READ_ZETA3: for<'a> fn(&mut BitReader<'a, E>) -> u64 = |cr| cr.read_zeta3().unwrap();
-    const READ_ZETA4: for<'a> fn(&mut BitReader<'a, E>) -> u64 = |cr| cr.read_zeta(4).unwrap();
-    const READ_ZETA5: for<'a> fn(&mut BitReader<'a, E>) -> u64 = |cr| cr.read_zeta(5).unwrap();
-    const READ_ZETA6: for<'a> fn(&mut BitReader<'a, E>) -> u64 = |cr| cr.read_zeta(6).unwrap();
-    const READ_ZETA7: for<'a> fn(&mut BitReader<'a, E>) -> u64 = |cr| cr.read_zeta(7).unwrap();
-    const READ_ZETA1: for<'a> fn(&mut BitReader<'a, E>) -> u64 = Self::READ_GAMMA;
+    const READ_UNARY: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 =
+        |cr| read_unary_burst::<E, _>(cr);
+    const READ_GAMMA: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 = |cr| cr.read_gamma().unwrap();
+    const READ_DELTA: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 = |cr| cr.read_delta().unwrap();
+    const READ_ZETA2: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 = |cr| cr.read_zeta(2).unwrap();
+    const READ_ZETA3: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 = |cr| cr.read_zeta3().unwrap();
+    const READ_ZETA4: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 = |cr| cr.read_zeta(4).unwrap();
+    const READ_ZETA5: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 = |cr| cr.read_zeta(5).unwrap();
+    const READ_ZETA6: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 = |cr| cr.read_zeta(6).unwrap();
+    const READ_ZETA7: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 = |cr| cr.read_zeta(7).unwrap();
+    const READ_ZETA1: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 = Self::READ_GAMMA;
+
+    // The same, using decoding tables of the widths in TABLE_BITS.
+    const READ_GAMMA_T8: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 =
+        |cr| read_gamma_table::<E, _, 8, 256>(cr);
+    const READ_GAMMA_T12: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 =
+        |cr| read_gamma_table::<E, _, 12, 4096>(cr);
+    const READ_GAMMA_T16: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 =
+        |cr| read_gamma_table::<E, _, 16, 65536>(cr);
+    const READ_DELTA_T8: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 =
+        |cr| read_delta_table::<E, _, 8, 256>(cr);
+    const READ_DELTA_T12: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 =
+        |cr| read_delta_table::<E, _, 12, 4096>(cr);
+    const READ_DELTA_T16: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 =
+        |cr| read_delta_table::<E, _, 16, 65536>(cr);
+    const READ_ZETA3_T8: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 =
+        |cr| read_zeta3_table::<E, _, 8, 256>(cr);
+    const READ_ZETA3_T12: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 =
+        |cr| read_zeta3_table::<E, _, 12, 4096>(cr);
+    const READ_ZETA3_T16: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 =
+        |cr| read_zeta3_table::<E, _, 16, 65536>(cr);
 
     #[inline(always)]
     /// Return a clone of the compression flags.
@@ -51,22 +91,52 @@ where
         self.compression_flags
     }
 
+    #[inline(always)]
+    /// Return a reference to the data.
+    pub fn get_data(&self) -> &B {
+        &self.data
+    }
+
     /// Create a new builder from the data and the compression flags.
     pub fn new(data: B, cf: CompFlags) -> Result<Self> {
+        Self::with_table_bits(data, cf, None)
+    }
+
+    /// Create a new builder from the data and the compression flags, whose
+    /// readers decode γ, δ and ζ₃ codes using decoding tables with
+    /// `table_bits` bits, which must be one of [`TABLE_BITS`], or no tables
+    /// if `table_bits` is `None`.
+    ///
+    /// [`select_table_bits`] can be used to choose the width from a sample
+    /// of the values of the graph, as
+    /// [`with_sampled_table_bits`](Self::with_sampled_table_bits) does.
+    pub fn with_table_bits(data: B, cf: CompFlags, table_bits: Option<usize>) -> Result<Self> {
         macro_rules! select_code {
             ($code:expr) => {
-                match $code {
-                    Code::Unary => Self::READ_UNARY,
-                    Code::Gamma => Self::READ_GAMMA,
-                    Code::Delta => Self::READ_DELTA,
-                    Code::Zeta { k: 1 } => Self::READ_ZETA1,
-                    Code::Zeta { k: 2 } => Self::READ_ZETA2,
-                    Code::Zeta { k: 3 } => Self::READ_ZETA3,
-                    Code::Zeta { k: 4 } => Self::READ_ZETA4,
-                    Code::Zeta { k: 5 } => Self::READ_ZETA5,
-                    Code::Zeta { k: 6 } => Self::READ_ZETA6,
-                    Code::Zeta { k: 7 } => Self::READ_ZETA7,
-                    code => bail!(
+                match ($code, table_bits) {
+                    (Code::Gamma | Code::Zeta { k: 1 }, Some(8)) => Self::READ_GAMMA_T8,
+                    (Code::Gamma | Code::Zeta { k: 1 }, Some(12)) => Self::READ_GAMMA_T12,
+                    (Code::Gamma | Code::Zeta { k: 1 }, Some(16)) => Self::READ_GAMMA_T16,
+                    (Code::Delta, Some(8)) => Self::READ_DELTA_T8,
+                    (Code::Delta, Some(12)) => Self::READ_DELTA_T12,
+                    (Code::Delta, Some(16)) => Self::READ_DELTA_T16,
+                    (Code::Zeta { k: 3 }, Some(8)) => Self::READ_ZETA3_T8,
+                    (Code::Zeta { k: 3 }, Some(12)) => Self::READ_ZETA3_T12,
+                    (Code::Zeta { k: 3 }, Some(16)) => Self::READ_ZETA3_T16,
+                    (_, Some(bits)) if !TABLE_BITS.contains(&bits) => {
+                        bail!("Unsupported decoding table width {}", bits)
+                    }
+                    (Code::Unary, _) => Self::READ_UNARY,
+                    (Code::Gamma, _) => Self::READ_GAMMA,
+                    (Code::Delta, _) => Self::READ_DELTA,
+                    (Code::Zeta { k: 1 }, _) => Self::READ_ZETA1,
+                    (Code::Zeta { k: 2 }, _) => Self::READ_ZETA2,
+                    (Code::Zeta { k: 3 }, _) => Self::READ_ZETA3,
+                    (Code::Zeta { k: 4 }, _) => Self::READ_ZETA4,
+                    (Code::Zeta { k: 5 }, _) => Self::READ_ZETA5,
+                    (Code::Zeta { k: 6 }, _) => Self::READ_ZETA6,
+                    (Code::Zeta { k: 7 }, _) => Self::READ_ZETA7,
+                    (code, _) => bail!(
                         "Only unary, ɣ, δ, and ζ₁-ζ₇ codes are allowed, {:?} is not supported",
                         code
                     ),
@@ -89,21 +159,135 @@ where
             _marker: core::marker::PhantomData,
         })
     }
+
+    /// Create a new builder from the data and the compression flags of a
+    /// graph with `num_nodes` nodes, whose readers use decoding tables of the
+    /// width chosen by [`select_table_bits_mixed`] on the values of its first
+    /// [`TABLE_SAMPLE_NODES`] nodes, or no tables if no width reaches
+    /// `min_hit_rate`.
+    pub fn with_sampled_table_bits(
+        data: B,
+        cf: CompFlags,
+        num_nodes: usize,
+        min_hit_rate: f64,
+    ) -> Result<Self> {
+        let builder = Self::new(data, cf)?;
+        let mut samples = Vec::new();
+        let mut iter = WebgraphSequentialIter::new(
+            SamplingCodesReader {
+                codes_reader: builder.get_reader(0)?,
+                compression_flags: cf,
+                samples: &mut samples,
+            },
+            cf.compression_window,
+            cf.min_interval_length,
+            num_nodes,
+        );
+        for _ in 0..num_nodes.min(TABLE_SAMPLE_NODES) {
+            iter.next_successors()?;
+        }
+        drop(iter);
+
+        let (mut gamma, mut delta, mut zeta3) = (vec![], vec![], vec![]);
+        for (code, value) in samples {
+            match code {
+                Code::Gamma | Code::Zeta { k: 1 } => gamma.push(value),
+                Code::Delta => delta.push(value),
+                Code::Zeta { k: 3 } => zeta3.push(value),
+                // codes without decoding tables
+                _ => {}
+            }
+        }
+        let table_bits = select_table_bits_mixed(
+            &[
+                (Code::Gamma, &gamma),
+                (Code::Delta, &delta),
+                (Code::Zeta { k: 3 }, &zeta3),
+            ],
+            min_hit_rate,
+        )?;
+        log::info!("Using decoding tables of {:?} bits", table_bits);
+        Self::with_table_bits(builder.data, cf, table_bits)
+    }
+}
+
+/// The number of nodes whose codes are sampled by
+/// [`DynamicCodesReaderBuilder::with_sampled_table_bits`].
+pub const TABLE_SAMPLE_NODES: usize = 10_000;
+
+/// A [`BVGraphCodesReader`] recording the values it reads, together with the
+/// codes they are written with.
+struct SamplingCodesReader<'a, CR: BVGraphCodesReader> {
+    codes_reader: CR,
+    compression_flags: CompFlags,
+    samples: &'a mut Vec<(Code, u64)>,
+}
+
+impl<'a, CR: BVGraphCodesReader> SamplingCodesReader<'a, CR> {
+    #[inline(always)]
+    fn sample(&mut self, code: Code, value: u64) -> u64 {
+        self.samples.push((code, value));
+        value
+    }
 }
 
-impl<E: Endianness, B: AsRef<[u32]>> BVGraphCodesReaderBuilder for DynamicCodesReaderBuilder<E, B>
+impl<'a, CR: BVGraphCodesReader> BVGraphCodesReader for SamplingCodesReader<'a, CR> {
+    fn read_outdegree(&mut self) -> u64 {
+        let value = self.codes_reader.read_outdegree();
+        self.sample(self.compression_flags.outdegrees, value)
+    }
+
+    fn read_reference_offset(&mut self) -> u64 {
+        let value = self.codes_reader.read_reference_offset();
+        self.sample(self.compression_flags.references, value)
+    }
+
+    fn read_block_count(&mut self) -> u64 {
+        let value = self.codes_reader.read_block_count();
+        self.sample(self.compression_flags.blocks, value)
+    }
+    fn read_blocks(&mut self) -> u64 {
+        let value = self.codes_reader.read_blocks();
+        self.sample(self.compression_flags.blocks, value)
+    }
+
+    fn read_interval_count(&mut self) -> u64 {
+        let value = self.codes_reader.read_interval_count();
+        self.sample(self.compression_flags.intervals, value)
+    }
+    fn read_interval_start(&mut self) -> u64 {
+        let value = self.codes_reader.read_interval_start();
+        self.sample(self.compression_flags.intervals, value)
+    }
+    fn read_interval_len(&mut self) -> u64 {
+        let value = self.codes_reader.read_interval_len();
+        self.sample(self.compression_flags.intervals, value)
+    }
+
+    fn read_first_residual(&mut self) -> u64 {
+        let value = self.codes_reader.read_first_residual();
+        self.sample(self.compression_flags.residuals, value)
+    }
+    fn read_residual(&mut self) -> u64 {
+        let value = self.codes_reader.read_residual();
+        self.sample(self.compression_flags.residuals, value)
+    }
+}
+
+impl<E: Endianness, B: AsRef<[W]>, W: BitStreamWord> BVGraphCodesReaderBuilder
+    for DynamicCodesReaderBuilder<E, B, W>
 where
-    for<'a> BitReader<'a, E>: ReadCodes<E> + BitSeek,
+    for<'a> BitReader<'a, E, W>: ReadCodes<E> + BitSeek,
 {
     type Reader<'a> =
-        DynamicCodesReader<E, BitReader<'a, E>>
+        DynamicCodesReader<E, BitReader<'a, E, W>>
     where
         Self: 'a;
 
-    fn get_reader(&self, offset: usize) -> Result<Self::Reader<'_>> {
-        let mut code_reader: BitReader<'_, E> =
+    fn get_reader(&self, offset: u64) -> Result<Self::Reader<'_>> {
+        let mut code_reader: BitReader<'_, E, W> =
             BufferedBitStreamRead::new(MemWordReadInfinite::new(self.data.as_ref()));
-        code_reader.set_pos(offset)?;
+        code_reader.set_pos(bit_pos(offset)?)?;
 
         Ok(DynamicCodesReader {
             code_reader,
@@ -128,70 +312,71 @@ where
 /// which basically double the size of the readers. So during random access
 /// we won't need them, so we can slightly speedup the random accesses at the
 /// cost of more code.
-pub struct DynamicCodesReaderSkipperBuilder<E: Endianness, B: AsRef<[u32]>> {
+pub struct DynamicCodesReaderSkipperBuilder<E: Endianness, B: AsRef<[W]>, W: BitStreamWord = u32> {
     /// The owned data we will read as a bitstream.
     data: B,
     /// The compression flags.
     compression_flags: CompFlags,
 
     // The cached functions to read the codes.
-    read_outdegree: for<'a> fn(&mut BitReader<'a, E>) -> u64,
-    read_reference_offset: for<'a> fn(&mut BitReader<'a, E>) -> u64,
-    read_block_count: for<'a> fn(&mut BitReader<'a, E>) -> u64,
-    read_blocks: for<'a> fn(&mut BitReader<'a, E>) -> u64,
-    read_interval_count: for<'a> fn(&mut BitReader<'a, E>) -> u64,
-    read_interval_start: for<'a> fn(&mut BitReader<'a, E>) -> u64,
-    read_interval_len: for<'a> fn(&mut BitReader<'a, E>) -> u64,
-    read_first_residual: for<'a> fn(&mut BitReader<'a, E>) -> u64,
-    read_residual: for<'a> fn(&mut BitReader<'a, E>) -> u64,
+    read_outdegree: for<'a> fn(&mut BitReader<'a, E, W>) -> u64,
+    read_reference_offset: for<'a> fn(&mut BitReader<'a, E, W>) -> u64,
+    read_block_count: for<'a> fn(&mut BitReader<'a, E, W>) -> u64,
+    read_blocks: for<'a> fn(&mut BitReader<'a, E, W>) -> u64,
+    read_interval_count: for<'a> fn(&mut BitReader<'a, E, W>) -> u64,
+    read_interval_start: for<'a> fn(&mut BitReader<'a, E, W>) -> u64,
+    read_interval_len: for<'a> fn(&mut BitReader<'a, E, W>) -> u64,
+    read_first_residual: for<'a> fn(&mut BitReader<'a, E, W>) -> u64,
+    read_residual: for<'a> fn(&mut BitReader<'a, E, W>) -> u64,
 
     // The cached functions to skip the codes.
-    skip_outdegrees: for<'a> fn(&mut BitReader<'a, E>),
-    skip_reference_offsets: for<'a> fn(&mut BitReader<'a, E>),
-    skip_block_counts: for<'a> fn(&mut BitReader<'a, E>),
-    skip_blocks: for<'a> fn(&mut BitReader<'a, E>),
-    skip_interval_counts: for<'a> fn(&mut BitReader<'a, E>),
-    skip_interval_starts: for<'a> fn(&mut BitReader<'a, E>),
-    skip_interval_lens: for<'a> fn(&mut BitReader<'a, E>),
-    skip_first_residuals: for<'a> fn(&mut BitReader<'a, E>),
-    skip_residuals: for<'a> fn(&mut BitReader<'a, E>),
+    skip_outdegrees: for<'a> fn(&mut BitReader<'a, E, W>),
+    skip_reference_offsets: for<'a> fn(&mut BitReader<'a, E, W>),
+    skip_block_counts: for<'a> fn(&mut BitReader<'a, E, W>),
+    skip_blocks: for<'a> fn(&mut BitReader<'a, E, W>),
+    skip_interval_counts: for<'a> fn(&mut BitReader<'a, E, W>),
+    skip_interval_starts: for<'a> fn(&mut BitReader<'a, E, W>),
+    skip_interval_lens: for<'a> fn(&mut BitReader<'a, E, W>),
+    skip_first_residuals: for<'a> fn(&mut BitReader<'a, E, W>),
+    skip_residuals: for<'a> fn(&mut BitReader<'a, E, W>),
 
     /// Tell the compiler that's Ok that we don't store `E` but we need it
     /// for typing.
-    _marker: core::marker::PhantomData<E>,
+    _marker: core::marker::PhantomData<(E, W)>,
 }
 
-impl<E: Endianness, B: AsRef<[u32]>> DynamicCodesReaderSkipperBuilder<E, B>
+impl<E: Endianness, B: AsRef<[W]>, W: BitStreamWord> DynamicCodesReaderSkipperBuilder<E, B, W>
 where
-    for<'a> BitReader<'a, E>: ReadCodes<E> + BitSeek,
+    for<'a> BitReader<'a, E, W>: ReadCodes<E> + BitSeek,
 {
     // Const cached functions we use to decode the data. These could be general
     // functions, but this way we have better visibility and we ensure that
     // they are compiled once!
-    const READ_UNARY: for<'a> fn(&mut BitReader<'a, E>) -> u64 = |cr| cr.read_unary().unwrap();
-    const READ_GAMMA: for<'a> fn(&mut BitReader<'a, E>) -> u64 = |cr| cr.read_gamma().unwrap();
-    const READ_DELTA: for<'a> fn(&mut BitReader<'a, E>) -> u64 = |cr| cr.read_delta().unwrap();
-    const READ_ZETA2: for<'a> fn(&mut BitReader<'a, E>) -> u64 = |cr| cr.read_zeta(2).unwrap();
-    const READ_ZETA3: for<'a> fn(&mut BitReader<'a, E>) -> u64 = |cr| cr.read_zeta3().unwrap();
-    const READ_ZETA4: for<'a> fn(&mut BitReader<'a, E>) -> u64 = |cr| cr.read_zeta(4).unwrap();
-    const READ_ZETA5: for<'a> fn(&mut BitReader<'a, E>) -> u64 = |cr| cr.read_zeta(5).unwrap();
-    const READ_ZETA6: for<'a> fn(&mut BitReader<'a, E>) -> u64 = |cr| cr.read_zeta(6).unwrap();
-    const READ_ZETA7: for<'a> fn(&mut BitReader<'a, E>) -> u64 = |cr| cr.read_zeta(7).unwrap();
-    const READ_ZETA1: for<'a> fn(&mut BitReader<'a, E>) -> u64 = Self::READ_GAMMA;
+    const READ_UNARY: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 =
+        |cr| read_unary_burst::<E, _>(cr);
+    const READ_GAMMA: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 = |cr| cr.read_gamma().unwrap();
+    const READ_DELTA: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 = |cr| cr.read_delta().unwrap();
+    const READ_ZETA2: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 = |cr| cr.read_zeta(2).unwrap();
+    const READ_ZETA3: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 = |cr| cr.read_zeta3().unwrap();
+    const READ_ZETA4: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 = |cr| cr.read_zeta(4).unwrap();
+    const READ_ZETA5: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 = |cr| cr.read_zeta(5).unwrap();
+    const READ_ZETA6: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 = |cr| cr.read_zeta(6).unwrap();
+    const READ_ZETA7: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 = |cr| cr.read_zeta(7).unwrap();
+    const READ_ZETA1: for<'a> fn(&mut BitReader<'a, E, W>) -> u64 = Self::READ_GAMMA;
 
     // Const cached functions we use to skip the data. These could be general
     // functions, but this way we have better visibility and we ensure that
     // they are compiled once!
-    const SKIP_UNARY: for<'a> fn(&mut BitReader<'a, E>) = |cr| cr.skip_unary().unwrap();
-    const SKIP_GAMMA: for<'a> fn(&mut BitReader<'a, E>) = |cr| cr.skip_gamma().unwrap();
-    const SKIP_DELTA: for<'a> fn(&mut BitReader<'a, E>) = |cr| cr.skip_delta().unwrap();
-    const SKIP_ZETA2: for<'a> fn(&mut BitReader<'a, E>) = |cr| cr.skip_zeta(2).unwrap();
-    const SKIP_ZETA3: for<'a> fn(&mut BitReader<'a, E>) = |cr| cr.skip_zeta3().unwrap();
-    const SKIP_ZETA4: for<'a> fn(&mut BitReader<'a, E>) = |cr| cr.skip_zeta(4).unwrap();
-    const SKIP_ZETA5: for<'a> fn(&mut BitReader<'a, E>) = |cr| cr.skip_zeta(5).unwrap();
-    const SKIP_ZETA6: for<'a> fn(&mut BitReader<'a, E>) = |cr| cr.skip_zeta(6).unwrap();
-    const SKIP_ZETA7: for<'a> fn(&mut BitReader<'a, E>) = |cr| cr.skip_zeta(7).unwrap();
-    const SKIP_ZETA1: for<'a> fn(&mut BitReader<'a, E>) = Self::SKIP_GAMMA;
+    const SKIP_UNARY: for<'a> fn(&mut BitReader<'a, E, W>) = |cr| skip_unary_burst::<E, _>(cr);
+    const SKIP_GAMMA: for<'a> fn(&mut BitReader<'a, E, W>) = |cr| cr.skip_gamma().unwrap();
+    const SKIP_DELTA: for<'a> fn(&mut BitReader<'a, E, W>) = |cr| cr.skip_delta().unwrap();
+    const SKIP_ZETA2: for<'a> fn(&mut BitReader<'a, E, W>) = |cr| cr.skip_zeta(2).unwrap();
+    const SKIP_ZETA3: for<'a> fn(&mut BitReader<'a, E, W>) = |cr| cr.skip_zeta3().unwrap();
+    const SKIP_ZETA4: for<'a> fn(&mut BitReader<'a, E, W>) = |cr| cr.skip_zeta(4).unwrap();
+    const SKIP_ZETA5: for<'a> fn(&mut BitReader<'a, E, W>) = |cr| cr.skip_zeta(5).unwrap();
+    const SKIP_ZETA6: for<'a> fn(&mut BitReader<'a, E, W>) = |cr| cr.skip_zeta(6).unwrap();
+    const SKIP_ZETA7: for<'a> fn(&mut BitReader<'a, E, W>) = |cr| cr.skip_zeta(7).unwrap();
+    const SKIP_ZETA1: for<'a> fn(&mut BitReader<'a, E, W>) = Self::SKIP_GAMMA;
 
     #[inline(always)]
     /// Return a copy of the compression flags used to build this reader.
@@ -275,21 +460,21 @@ where
     }
 }
 
-impl<E: Endianness, B: AsRef<[u32]>> BVGraphCodesReaderBuilder
-    for DynamicCodesReaderSkipperBuilder<E, B>
+impl<E: Endianness, B: AsRef<[W]>, W: BitStreamWord> BVGraphCodesReaderBuilder
+    for DynamicCodesReaderSkipperBuilder<E, B, W>
 where
-    for<'a> BitReader<'a, E>: ReadCodes<E> + BitSeek,
+    for<'a> BitReader<'a, E, W>: ReadCodes<E> + BitSeek,
 {
     type Reader<'a> =
-        DynamicCodesReaderSkipper<E, BitReader<'a, E>>
+        DynamicCodesReaderSkipper<E, BitReader<'a, E, W>>
     where
         Self: 'a;
 
     #[inline(always)]
-    fn get_reader(&self, offset: usize) -> Result<Self::Reader<'_>> {
-        let mut code_reader: BitReader<'_, E> =
+    fn get_reader(&self, offset: u64) -> Result<Self::Reader<'_>> {
+        let mut code_reader: BitReader<'_, E, W> =
             BufferedBitStreamRead::new(MemWordReadInfinite::new(self.data.as_ref()));
-        code_reader.set_pos(offset)?;
+        code_reader.set_pos(bit_pos(offset)?)?;
         Ok(DynamicCodesReaderSkipper {
             code_reader,
             read_outdegree: self.read_outdegree,
@@ -315,24 +500,24 @@ where
     }
 }
 
-impl<E: Endianness, B: AsRef<[u32]>> From<DynamicCodesReaderBuilder<E, B>>
-    for DynamicCodesReaderSkipperBuilder<E, B>
+impl<E: Endianness, B: AsRef<[W]>, W: BitStreamWord> From<DynamicCodesReaderBuilder<E, B, W>>
+    for DynamicCodesReaderSkipperBuilder<E, B, W>
 where
-    for<'a> BitReader<'a, E>: ReadCodes<E> + BitSeek,
+    for<'a> BitReader<'a, E, W>: ReadCodes<E> + BitSeek,
 {
     #[inline(always)]
-    fn from(value: DynamicCodesReaderBuilder<E, B>) -> Self {
+    fn from(value: DynamicCodesReaderBuilder<E, B, W>) -> Self {
         Self::new(value.data, value.compression_flags).unwrap()
     }
 }
 
-impl<E: Endianness, B: AsRef<[u32]>> From<DynamicCodesReaderSkipperBuilder<E, B>>
-    for DynamicCodesReaderBuilder<E, B>
+impl<E: Endianness, B: AsRef<[W]>, W: BitStreamWord> From<DynamicCodesReaderSkipperBuilder<E, B, W>>
+    for DynamicCodesReaderBuilder<E, B, W>
 where
-    for<'a> BitReader<'a, E>: ReadCodes<E> + BitSeek,
+    for<'a> BitReader<'a, E, W>: ReadCodes<E> + BitSeek,
 {
     #[inline(always)]
-    fn from(value: DynamicCodesReaderSkipperBuilder<E, B>) -> Self {
+    fn from(value: DynamicCodesReaderSkipperBuilder<E, B, W>) -> Self {
         Self::new(value.data, value.compression_flags).unwrap()
     }
 }
@@ -342,31 +527,33 @@ where
 /// the indirection layer which can results in more / better inlining.
 pub struct ConstCodesReaderBuilder<
     E: Endianness,
-    B: AsRef<[u32]>,
+    B: AsRef<[W]>,
     const OUTDEGREES: usize = { const_codes::GAMMA },
     const REFERENCES: usize = { const_codes::UNARY },
     const BLOCKS: usize = { const_codes::GAMMA },
     const INTERVALS: usize = { const_codes::GAMMA },
     const RESIDUALS: usize = { const_codes::ZETA },
     const K: u64 = 3,
+    W: BitStreamWord = u32,
 > {
     /// The owned data
     data: B,
     /// Tell the compiler that's Ok that we don't store `E` but we need it
     /// for typing.
-    _marker: core::marker::PhantomData<E>,
+    _marker: core::marker::PhantomData<(E, W)>,
 }
 
 impl<
         E: Endianness,
-        B: AsRef<[u32]>,
+        B: AsRef<[W]>,
         const OUTDEGREES: usize,
         const REFERENCES: usize,
         const BLOCKS: usize,
         const INTERVALS: usize,
         const RESIDUALS: usize,
         const K: u64,
-    > ConstCodesReaderBuilder<E, B, OUTDEGREES, REFERENCES, BLOCKS, INTERVALS, RESIDUALS, K>
+        W: BitStreamWord,
+    > ConstCodesReaderBuilder<E, B, OUTDEGREES, REFERENCES, BLOCKS, INTERVALS, RESIDUALS, K, W>
 {
     /// Create a new builder from the given data and compression flags.
     pub fn new(data: B, comp_flags: CompFlags) -> Result<Self> {
@@ -390,31 +577,38 @@ impl<
             _marker: core::marker::PhantomData,
         })
     }
+
+    #[inline(always)]
+    /// Return a reference to the data.
+    pub fn get_data(&self) -> &B {
+        &self.data
+    }
 }
 
 impl<
         E: Endianness,
-        B: AsRef<[u32]>,
+        B: AsRef<[W]>,
         const OUTDEGREES: usize,
         const REFERENCES: usize,
         const BLOCKS: usize,
         const INTERVALS: usize,
         const RESIDUALS: usize,
         const K: u64,
+        W: BitStreamWord,
     > BVGraphCodesReaderBuilder
-    for ConstCodesReaderBuilder<E, B, OUTDEGREES, REFERENCES, BLOCKS, INTERVALS, RESIDUALS, K>
+    for ConstCodesReaderBuilder<E, B, OUTDEGREES, REFERENCES, BLOCKS, INTERVALS, RESIDUALS, K, W>
 where
-    for<'a> BitReader<'a, E>: ReadCodes<E> + BitSeek,
+    for<'a> BitReader<'a, E, W>: ReadCodes<E> + BitSeek,
 {
     type Reader<'a> =
-        ConstCodesReader<E, BitReader<'a, E>>
+        ConstCodesReader<E, BitReader<'a, E, W>>
     where
         Self: 'a;
 
-    fn get_reader(&self, offset: usize) -> Result<Self::Reader<'_>> {
-        let mut code_reader: BitReader<'_, E> =
+    fn get_reader(&self, offset: u64) -> Result<Self::Reader<'_>> {
+        let mut code_reader: BitReader<'_, E, W> =
             BufferedBitStreamRead::new(MemWordReadInfinite::new(self.data.as_ref()));
-        code_reader.set_pos(offset)?;
+        code_reader.set_pos(bit_pos(offset)?)?;
 
         Ok(ConstCodesReader {
             code_reader,
@@ -422,3 +616,227 @@ where
         })
     }
 }
+
+type StreamBitReader<E, R> = BufferedBitStreamRead<E, u64, FileBackend<u32, ZeroPadded<R>>>;
+
+/// A [`std::io::Read`] adapter returning zeros after the end of the inner
+/// stream, so that, like with [`MemWordReadInfinite`], the last codes of the
+/// bitstream can be decoded even if the stream length is not a multiple of
+/// the word size.
+pub struct ZeroPadded<R: std::io::Read> {
+    inner: R,
+    eof: bool,
+}
+
+impl<R: std::io::Read> ZeroPadded<R> {
+    /// Wrap the given stream.
+    pub fn new(inner: R) -> Self {
+        Self { inner, eof: false }
+    }
+}
+
+impl<R: std::io::Read> std::io::Read for ZeroPadded<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if !self.eof {
+            let read = self.inner.read(buf)?;
+            if read != 0 || buf.is_empty() {
+                return Ok(read);
+            }
+            self.eof = true;
+        }
+        buf.fill(0);
+        Ok(buf.len())
+    }
+}
+
+/// A builder for [`DynamicCodesReader`] reading the bitstream from a
+/// [`std::io::Read`], such as a pipe or a decompressor.
+///
+/// Since the stream cannot be rewound, this builder can create a single
+/// reader at offset zero: a [`BVGraphSequential`] built on top of it can be
+/// iterated only once.
+pub struct DynamicCodesReaderStreamBuilder<E: Endianness, R: std::io::Read> {
+    /// The stream, until a reader is created.
+    stream: core::cell::RefCell<Option<R>>,
+    /// The compression flags.
+    compression_flags: CompFlags,
+    /// Tell the compiler that's Ok that we don't store `E` but we need it
+    /// for typing.
+    _marker: core::marker::PhantomData<E>,
+}
+
+impl<E: Endianness, R: std::io::Read> DynamicCodesReaderStreamBuilder<E, R>
+where
+    StreamBitReader<E, R>: ReadCodes<E>,
+{
+    /// Create a new builder from a stream and the compression flags.
+    ///
+    /// Unsupported codes are reported when the reader is created.
+    pub fn new(stream: R, compression_flags: CompFlags) -> Self {
+        Self {
+            stream: core::cell::RefCell::new(Some(stream)),
+            compression_flags,
+            _marker: core::marker::PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    /// Return a clone of the compression flags.
+    pub fn get_compression_flags(&self) -> CompFlags {
+        self.compression_flags
+    }
+
+    /// Create a reader on `stream`, skipping its first `skip_bits` bits.
+    fn reader_on(
+        stream: R,
+        skip_bits: usize,
+        compression_flags: &CompFlags,
+    ) -> Result<DynamicCodesReader<E, StreamBitReader<E, R>>> {
+        let mut code_reader: StreamBitReader<E, R> =
+            BufferedBitStreamRead::new(<FileBackend<u32, _>>::new(ZeroPadded::new(stream)));
+        code_reader.skip_bits(skip_bits)?;
+        DynamicCodesReader::new(code_reader, compression_flags)
+    }
+}
+
+impl<E: Endianness, R: std::io::Read> BVGraphCodesReaderBuilder
+    for DynamicCodesReaderStreamBuilder<E, R>
+where
+    StreamBitReader<E, R>: ReadCodes<E>,
+{
+    type Reader<'a> =
+        DynamicCodesReader<E, StreamBitReader<E, R>>
+    where
+        Self: 'a;
+
+    fn get_reader(&self, offset: u64) -> Result<Self::Reader<'_>> {
+        if offset != 0 {
+            bail!(
+                "A stream can only be read from the start, not from offset {}",
+                offset
+            );
+        }
+        let stream = self
+            .stream
+            .borrow_mut()
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("The stream has already been consumed"))?;
+        Self::reader_on(stream, 0, &self.compression_flags)
+    }
+}
+
+type FileStreamBuilder<E> = DynamicCodesReaderStreamBuilder<E, std::io::BufReader<std::fs::File>>;
+type FileBitReader<E> = StreamBitReader<E, std::io::BufReader<std::fs::File>>;
+
+/// A builder for [`DynamicCodesReader`] reading the bitstream from a file
+/// through buffered I/O, for environments in which memory-mapping is not
+/// available or slower, such as some containers and network filesystems.
+///
+/// Each reader is a [`DynamicCodesReaderStreamBuilder`] reader on the file
+/// opened again and positioned at the requested offset, so, differently from
+/// the latter, a [`BVGraphSequential`] built on top of this builder can be
+/// iterated many times.
+pub struct DynamicCodesReaderFileBuilder<E: Endianness> {
+    /// The path of the file containing the bitstream.
+    path: std::path::PathBuf,
+    /// The compression flags.
+    compression_flags: CompFlags,
+    /// Tell the compiler that's Ok that we don't store `E` but we need it
+    /// for typing.
+    _marker: core::marker::PhantomData<E>,
+}
+
+impl<E: Endianness> DynamicCodesReaderFileBuilder<E>
+where
+    FileBitReader<E>: ReadCodes<E>,
+{
+    /// Create a new builder from the path of the bitstream and the
+    /// compression flags, checking that the file can be opened.
+    pub fn new<P: AsRef<std::path::Path>>(path: P, compression_flags: CompFlags) -> Result<Self> {
+        let path = path.as_ref().to_owned();
+        std::fs::File::open(&path)
+            .with_context(|| format!("Cannot open graph file {}", path.display()))?;
+        Ok(Self {
+            path,
+            compression_flags,
+            _marker: core::marker::PhantomData,
+        })
+    }
+
+    #[inline(always)]
+    /// Return a clone of the compression flags.
+    pub fn get_compression_flags(&self) -> CompFlags {
+        self.compression_flags
+    }
+}
+
+impl<E: Endianness> BVGraphCodesReaderBuilder for DynamicCodesReaderFileBuilder<E>
+where
+    FileBitReader<E>: ReadCodes<E>,
+{
+    type Reader<'a> =
+        DynamicCodesReader<E, FileBitReader<E>>
+    where
+        Self: 'a;
+
+    fn get_reader(&self, offset: u64) -> Result<Self::Reader<'_>> {
+        use std::io::{Seek, SeekFrom};
+        let mut file = std::fs::File::open(&self.path)
+            .with_context(|| format!("Cannot open graph file {}", self.path.display()))?;
+        // start from the word containing the offset
+        let word_bits = 8 * core::mem::size_of::<u32>() as u64;
+        file.seek(SeekFrom::Start(
+            offset / word_bits * core::mem::size_of::<u32>() as u64,
+        ))?;
+        FileStreamBuilder::<E>::reader_on(
+            std::io::BufReader::new(file),
+            (offset % word_bits) as usize,
+            &self.compression_flags,
+        )
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_sampled_table_bits() -> Result<()> {
+    let graph = load_seq("tests/data/cnr-2000")?;
+    let num_nodes = graph.num_nodes();
+    let builder = graph.unwrap_codes_reader_builder();
+    let cf = builder.get_compression_flags();
+    let data: &[u32] = builder.get_data().as_ref();
+    // every width reaches a zero hit rate, and none a hit rate above one
+    for (min_hit_rate, expected) in [(0.0, Some(8)), (1.1, None)] {
+        let sampled = DynamicCodesReaderBuilder::<BE, _>::with_sampled_table_bits(
+            data,
+            cf,
+            num_nodes,
+            min_hit_rate,
+        )?;
+        let with_table_bits = DynamicCodesReaderBuilder::<BE, _>::with_table_bits(
+            data,
+            cf,
+            expected,
+        )?;
+        let sampled = BVGraphSequential::new(
+            sampled,
+            cf.compression_window,
+            cf.min_interval_length,
+            num_nodes,
+            None,
+        );
+        let expected = BVGraphSequential::new(
+            with_table_bits,
+            cf.compression_window,
+            cf.min_interval_length,
+            num_nodes,
+            None,
+        );
+        for ((node, succ), (expected_node, expected_succ)) in
+            sampled.iter_nodes().zip(expected.iter_nodes())
+        {
+            assert_eq!(node, expected_node);
+            assert!(succ.eq(expected_succ));
+        }
+    }
+    Ok(())
+}
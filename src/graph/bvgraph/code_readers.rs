@@ -55,14 +55,15 @@ impl<
         const INTERVALS: usize,
         const RESIDUALS: usize,
         const K: u64,
-    > BitSeek for ConstCodesReader<E, CR, OUTDEGREES, REFERENCES, BLOCKS, INTERVALS, RESIDUALS, K>
+    > BVGraphCodesSeek
+    for ConstCodesReader<E, CR, OUTDEGREES, REFERENCES, BLOCKS, INTERVALS, RESIDUALS, K>
 {
-    fn set_pos(&mut self, bit_index: usize) -> Result<()> {
-        self.code_reader.set_pos(bit_index)
+    fn set_pos(&mut self, bit_index: u64) -> Result<()> {
+        self.code_reader.set_pos(bit_pos(bit_index)?)
     }
 
-    fn get_pos(&self) -> usize {
-        self.code_reader.get_pos()
+    fn get_pos(&self) -> u64 {
+        self.code_reader.get_pos() as u64
     }
 }
 
@@ -107,7 +108,7 @@ impl<
 macro_rules! select_code_read {
     ($self:ident, $code:expr, $k: expr) => {
         match $code {
-            const_codes::UNARY => $self.code_reader.read_unary().unwrap(),
+            const_codes::UNARY => read_unary_burst::<E, _>(&mut $self.code_reader),
             const_codes::GAMMA => $self.code_reader.read_gamma().unwrap(),
             const_codes::DELTA => $self.code_reader.read_delta().unwrap(),
             const_codes::ZETA if $k == 1 => $self.code_reader.read_gamma().unwrap(),
@@ -121,7 +122,7 @@ macro_rules! select_code_read {
 macro_rules! select_code_skip {
     ($self:ident, $code:expr, $k: expr) => {
         match $code {
-            const_codes::UNARY => $self.code_reader.skip_unary().unwrap(),
+            const_codes::UNARY => skip_unary_burst::<E, _>(&mut $self.code_reader),
             const_codes::GAMMA => $self.code_reader.skip_gamma().unwrap(),
             const_codes::DELTA => $self.code_reader.skip_delta().unwrap(),
             const_codes::ZETA if $k == 1 => $self.code_reader.skip_gamma().unwrap(),
@@ -184,6 +185,26 @@ impl<
     fn read_residual(&mut self) -> u64 {
         select_code_read!(self, RESIDUALS, K)
     }
+
+    #[inline(always)]
+    fn read_residuals(&mut self, gaps: &mut [u64]) {
+        #[cfg(all(feature = "simd", target_arch = "x86_64", target_feature = "bmi2"))]
+        match RESIDUALS {
+            const_codes::GAMMA => {
+                return super::bmi2::read_codes::<E, CR, false>(&mut self.code_reader, gaps)
+            }
+            const_codes::ZETA if K == 1 => {
+                return super::bmi2::read_codes::<E, CR, false>(&mut self.code_reader, gaps)
+            }
+            const_codes::ZETA if K == 3 => {
+                return super::bmi2::read_codes::<E, CR, true>(&mut self.code_reader, gaps)
+            }
+            _ => {}
+        }
+        for gap in gaps {
+            *gap = select_code_read!(self, RESIDUALS, K);
+        }
+    }
 }
 
 impl<
@@ -238,6 +259,26 @@ impl<
     fn skip_residual(&mut self) {
         select_code_skip!(self, RESIDUALS, K)
     }
+    #[inline(always)]
+    fn skip_residuals(&mut self, n: usize) {
+        // the batched decoder consumes a whole window of codes with a
+        // single skip of the bit pointer
+        #[cfg(all(feature = "simd", target_arch = "x86_64", target_feature = "bmi2"))]
+        if RESIDUALS == const_codes::GAMMA || (RESIDUALS == const_codes::ZETA && (K == 1 || K == 3))
+        {
+            let mut gaps = [0; 32];
+            let mut to_skip = n;
+            while to_skip != 0 {
+                let batch = to_skip.min(32);
+                self.read_residuals(&mut gaps[..batch]);
+                to_skip -= batch;
+            }
+            return;
+        }
+        for _ in 0..n {
+            select_code_skip!(self, RESIDUALS, K)
+        }
+    }
 }
 
 #[repr(transparent)]
@@ -266,14 +307,15 @@ impl<
         const INTERVALS: usize,
         const RESIDUALS: usize,
         const K: u64,
-    > BitSeek for ConstCodesWriter<E, CW, OUTDEGREES, REFERENCES, BLOCKS, INTERVALS, RESIDUALS, K>
+    > BVGraphCodesSeek
+    for ConstCodesWriter<E, CW, OUTDEGREES, REFERENCES, BLOCKS, INTERVALS, RESIDUALS, K>
 {
-    fn set_pos(&mut self, bit_index: usize) -> Result<()> {
-        self.code_writer.set_pos(bit_index)
+    fn set_pos(&mut self, bit_index: u64) -> Result<()> {
+        self.code_writer.set_pos(bit_pos(bit_index)?)
     }
 
-    fn get_pos(&self) -> usize {
-        self.code_writer.get_pos()
+    fn get_pos(&self) -> u64 {
+        self.code_writer.get_pos() as u64
     }
 }
 
@@ -474,3 +516,67 @@ impl<
         Ok(())
     }
 }
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_read_residuals() -> Result<()> {
+    use rand::prelude::*;
+    let mut rng = SmallRng::seed_from_u64(0);
+    // mostly short codes, with some codes longer than a peek window
+    let values = (0..10_000)
+        .map(|i| {
+            if i % 100 == 0 {
+                rng.gen_range(0..1 << 40)
+            } else {
+                rng.gen_range(0..1 << (i % 12))
+            }
+        })
+        .collect::<Vec<u64>>();
+    let comp_flags = CompFlags::default();
+
+    macro_rules! check {
+        ($endianness:ty) => {{
+            let mut buffer: Vec<u64> = Vec::new();
+            {
+                let bit_write = <BufferedBitStreamWrite<$endianness, _>>::new(
+                    MemWordWriteVec::new(&mut buffer),
+                );
+                let mut codes_writer = <ConstCodesWriter<$endianness, _>>::new(bit_write);
+                for &value in &values {
+                    codes_writer.write_residual(value)?;
+                }
+                codes_writer.flush()?;
+            }
+            let buffer_32: &[u32] = unsafe { buffer.align_to().1 };
+            let bit_read = <BufferedBitStreamRead<$endianness, u64, _>>::new(
+                MemWordReadInfinite::new(buffer_32),
+            );
+            let mut codes_reader = <ConstCodesReader<$endianness, _>>::new(bit_read, &comp_flags)?;
+            // read in batches of varying length, interleaved with single reads
+            let mut read = Vec::with_capacity(values.len());
+            let mut batch = 1;
+            while read.len() < values.len() {
+                let mut gaps = vec![0; batch.min(values.len() - read.len())];
+                codes_reader.read_residuals(&mut gaps);
+                read.extend(gaps);
+                if read.len() < values.len() {
+                    read.push(codes_reader.read_residual());
+                }
+                batch = batch % 40 + 1;
+            }
+            assert_eq!(read, values);
+
+            // skip a run of residuals
+            let bit_read = <BufferedBitStreamRead<$endianness, u64, _>>::new(
+                MemWordReadInfinite::new(buffer_32),
+            );
+            let mut codes_reader = <ConstCodesReader<$endianness, _>>::new(bit_read, &comp_flags)?;
+            codes_reader.skip_residuals(5000);
+            assert_eq!(codes_reader.read_residual(), values[5000]);
+        }};
+    }
+
+    check!(BE);
+    check!(LE);
+    Ok(())
+}
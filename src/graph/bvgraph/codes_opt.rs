@@ -1,6 +1,7 @@
 use crate::prelude::*;
 use anyhow::Result;
-use dsi_bitstream::prelude::CodesStats;
+use core::sync::atomic::Ordering;
+use dsi_bitstream::prelude::{Code, CodesStats};
 
 /// A struct that keeps track of how much bits each piece would take using
 #[derive(Debug, Default)]
@@ -25,6 +26,160 @@ pub struct BVGraphCodesStats {
     pub residual: CodesStats,
 }
 
+impl BVGraphCodesStats {
+    /// Return a copy of `compression_flags` in which each code is replaced by
+    /// the one, among those supported by [`DynamicCodesWriter`], that
+    /// minimizes the total number of bits of the components it encodes.
+    pub fn best_comp_flags(&self, compression_flags: &CompFlags) -> CompFlags {
+        /// Return the best code for a group of components sharing it.
+        fn best_code(stats: &[&CodesStats]) -> Code {
+            let total = |len: &dyn Fn(&CodesStats) -> usize| -> u128 {
+                stats.iter().map(|s| len(s) as u128).sum()
+            };
+            [
+                (Code::Unary, total(&|s| s.unary.load(Ordering::Relaxed))),
+                (Code::Gamma, total(&|s| s.gamma.load(Ordering::Relaxed))),
+                (Code::Delta, total(&|s| s.delta.load(Ordering::Relaxed))),
+                (
+                    Code::Zeta { k: 3 },
+                    total(&|s| s.zeta[2].load(Ordering::Relaxed)),
+                ),
+            ]
+            .into_iter()
+            .min_by_key(|(_, bits)| *bits)
+            .unwrap()
+            .0
+        }
+
+        CompFlags {
+            outdegrees: best_code(&[&self.outdegree]),
+            references: best_code(&[&self.reference_offset]),
+            blocks: best_code(&[&self.block_count, &self.blocks]),
+            intervals: best_code(&[
+                &self.interval_count,
+                &self.interval_start,
+                &self.interval_len,
+            ]),
+            residuals: best_code(&[&self.first_residual, &self.residual]),
+            ..*compression_flags
+        }
+    }
+}
+
+/// A [`BVGraphCodesWriter`] that writes nothing, but keeps track of how much
+/// bits each written value would take using different codes.
+///
+/// The lengths returned, which drive the choices of the compressor, are those
+/// of the codes of the [`CompFlags`] the writer was created with.
+pub struct CodesWriterStats<'a> {
+    mock_writer: DynamicCodesMockWriter,
+    stats: &'a BVGraphCodesStats,
+}
+
+impl<'a> CodesWriterStats<'a> {
+    /// Create a new writer updating `stats`
+    pub fn new(compression_flags: &CompFlags, stats: &'a BVGraphCodesStats) -> Self {
+        Self {
+            mock_writer: DynamicCodesMockWriter::new(compression_flags),
+            stats,
+        }
+    }
+}
+
+impl<'a> BVGraphCodesWriter for CodesWriterStats<'a> {
+    type MockWriter = DynamicCodesMockWriter;
+    fn mock(&self) -> Self::MockWriter {
+        self.mock_writer.clone()
+    }
+
+    #[inline(always)]
+    fn write_outdegree(&mut self, value: u64) -> Result<usize> {
+        self.mock_writer
+            .write_outdegree(self.stats.outdegree.update(value))
+    }
+
+    #[inline(always)]
+    fn write_reference_offset(&mut self, value: u64) -> Result<usize> {
+        self.mock_writer
+            .write_reference_offset(self.stats.reference_offset.update(value))
+    }
+
+    #[inline(always)]
+    fn write_block_count(&mut self, value: u64) -> Result<usize> {
+        self.mock_writer
+            .write_block_count(self.stats.block_count.update(value))
+    }
+    #[inline(always)]
+    fn write_blocks(&mut self, value: u64) -> Result<usize> {
+        self.mock_writer
+            .write_blocks(self.stats.blocks.update(value))
+    }
+
+    #[inline(always)]
+    fn write_interval_count(&mut self, value: u64) -> Result<usize> {
+        self.mock_writer
+            .write_interval_count(self.stats.interval_count.update(value))
+    }
+    #[inline(always)]
+    fn write_interval_start(&mut self, value: u64) -> Result<usize> {
+        self.mock_writer
+            .write_interval_start(self.stats.interval_start.update(value))
+    }
+    #[inline(always)]
+    fn write_interval_len(&mut self, value: u64) -> Result<usize> {
+        self.mock_writer
+            .write_interval_len(self.stats.interval_len.update(value))
+    }
+
+    #[inline(always)]
+    fn write_first_residual(&mut self, value: u64) -> Result<usize> {
+        self.mock_writer
+            .write_first_residual(self.stats.first_residual.update(value))
+    }
+    #[inline(always)]
+    fn write_residual(&mut self, value: u64) -> Result<usize> {
+        self.mock_writer
+            .write_residual(self.stats.residual.update(value))
+    }
+
+    fn flush(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Scan an iterator of nodes and successors, simulating its compression with
+/// `compression_flags`, and return the flags with the codes minimizing the
+/// size of the compressed graph.
+///
+/// This is the first pass of a two-pass compression: the codes chosen by
+/// the compressor depend on the codes in use, so the statistics are
+/// gathered with the initial codes, which are usually the default ones.
+pub fn best_comp_flags<I: Iterator<Item = (usize, J)>, J: Iterator<Item = usize>>(
+    iter: I,
+    compression_flags: &CompFlags,
+) -> Result<CompFlags> {
+    let stats = BVGraphCodesStats::default();
+    let mut bvcomp = BVComp::new(
+        CodesWriterStats::new(compression_flags, &stats),
+        compression_flags.compression_window,
+        compression_flags.min_interval_length,
+        compression_flags.max_ref_count,
+        0,
+    );
+    bvcomp.extend(iter)?;
+    bvcomp.flush()?;
+    let best = stats.best_comp_flags(compression_flags);
+    log::info!(
+        "Best codes: outdegrees {:?}, references {:?}, blocks {:?}, intervals {:?}, residuals {:?}",
+        best.outdegrees,
+        best.references,
+        best.blocks,
+        best.intervals,
+        best.residuals
+    );
+    Ok(best)
+}
+
 /// A wrapper that keeps track of how much bits each piece would take using
 /// different codes for compressions for a [`BVGraphCodesReaderBuilder`]
 /// implementation and returns the stats.
@@ -77,7 +232,7 @@ where
         Self: 'a;
 
     #[inline(always)]
-    fn get_reader(&self, offset: usize) -> Result<Self::Reader<'_>> {
+    fn get_reader(&self, offset: u64) -> Result<Self::Reader<'_>> {
         Ok(CodesReaderStats::new(
             self.codes_reader_builder.get_reader(offset)?,
             &self.stats,
@@ -188,6 +343,10 @@ impl<'a, WGCR: BVGraphCodesReader + BVGraphCodesSkipper> BVGraphCodesSkipper
         self.codes_reader.skip_block()
     }
     #[inline(always)]
+    fn skip_blocks(&mut self, n: usize) {
+        self.codes_reader.skip_blocks(n)
+    }
+    #[inline(always)]
     fn skip_interval_count(&mut self) {
         self.codes_reader.skip_interval_count()
     }
@@ -207,4 +366,27 @@ impl<'a, WGCR: BVGraphCodesReader + BVGraphCodesSkipper> BVGraphCodesSkipper
     fn skip_residual(&mut self) {
         self.codes_reader.skip_residual()
     }
+    #[inline(always)]
+    fn skip_residuals(&mut self, n: usize) {
+        self.codes_reader.skip_residuals(n)
+    }
+}
+
+#[cfg_attr(test, test)]
+#[cfg(test)]
+fn test_best_comp_flags() -> Result<()> {
+    // a graph in which the residuals are large gaps
+    let arcs = (0..100)
+        .flat_map(|x| (1..4).map(move |i| (x, (x * 7919 * i) % 100_000)))
+        .collect::<Vec<_>>();
+    let g = crate::graph::vec_graph::VecGraph::from_arc_list(&arcs);
+    let flags = best_comp_flags(g.iter_nodes(), &CompFlags::default())?;
+    // the window and the other parameters are unchanged
+    assert_eq!(flags.compression_window, 7);
+    assert_eq!(flags.min_interval_length, 4);
+    assert_eq!(flags.max_ref_count, 3);
+    // the reference offsets are small, while the residuals are not
+    assert_eq!(flags.references, Code::Unary);
+    assert_ne!(flags.residuals, Code::Unary);
+    Ok(())
 }
@@ -1,9 +1,11 @@
+#[cfg(feature = "std")]
 use anyhow::{bail, Result};
 use dsi_bitstream::prelude::Code;
+#[cfg(feature = "std")]
 use std::collections::HashMap;
 
 #[derive(Clone, Copy, Debug)]
-#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 /// The compression flags for reading or compressing a graph.
 ///
 /// As documented, one code might sets multiple values. This is done for
@@ -73,6 +75,7 @@ impl CompFlags {
         }
     }
 
+    #[cfg(feature = "std")]
     pub fn to_properties(&self, num_nodes: usize, num_arcs: usize) -> String {
         let mut s = String::new();
         s.push_str("#BVGraph properties\n");
@@ -128,7 +131,28 @@ impl CompFlags {
         s
     }
 
+    /// Return the same lines as [`CompFlags::to_properties`], followed by the
+    /// digests of the files of the graph with the given basename computed
+    /// with each of the given algorithms (see [`Checksum::to_properties`]).
+    ///
+    /// The files of the graph must have been completely written.
+    #[cfg(feature = "checksums")]
+    pub fn to_properties_with_checksums<P: AsRef<std::path::Path>>(
+        &self,
+        num_nodes: usize,
+        num_arcs: usize,
+        basename: P,
+        checksums: &[super::Checksum],
+    ) -> Result<String> {
+        let mut s = self.to_properties(num_nodes, num_arcs);
+        for checksum in checksums {
+            s.push_str(&checksum.to_properties(basename.as_ref())?);
+        }
+        Ok(s)
+    }
+
     /// Convert the decoded `.properties` file into a `CompFlags` struct.
+    #[cfg(feature = "std")]
     pub fn from_properties(map: &HashMap<String, String>) -> Result<Self> {
         // Default values, same as the Java class
         let mut cf = CompFlags::default();
@@ -166,3 +190,47 @@ impl CompFlags {
         Ok(cf)
     }
 }
+
+/// The endianness of the bitstream of a graph.
+///
+/// It is stored in the `endianness` property of the `.properties` file; graphs
+/// without it, such as those written by the Java version, are big-endian.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GraphEndianness {
+    /// Big endian, the default and the only one supported by the Java version.
+    #[default]
+    Big,
+    /// Little endian, which is faster to decode on most architectures.
+    Little,
+}
+
+impl GraphEndianness {
+    /// Return the value of the `endianness` property.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GraphEndianness::Big => "big",
+            GraphEndianness::Little => "little",
+        }
+    }
+
+    /// Read the endianness from the decoded `.properties` file.
+    #[cfg(feature = "std")]
+    pub fn from_properties(map: &HashMap<String, String>) -> Result<Self> {
+        match map.get("endianness").map(|s| s.as_str()) {
+            None | Some("big") => Ok(GraphEndianness::Big),
+            Some("little") => Ok(GraphEndianness::Little),
+            Some(endianness) => bail!("Unknown endianness {}", endianness),
+        }
+    }
+
+    /// Return the line of the `.properties` file recording the endianness,
+    /// which is empty for big-endian graphs, so that they can be read by
+    /// the Java version.
+    #[cfg(feature = "std")]
+    pub fn to_properties(&self) -> String {
+        match self {
+            GraphEndianness::Big => String::new(),
+            GraphEndianness::Little => format!("endianness={}\n", self.as_str()),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use super::*;
+use crate::traits::SequentialGraph;
+use crate::utils::sort_arcs;
+use anyhow::Result;
+use std::path::Path;
+
+/// The number of pairs in a batch of [`compress_arcs`].
+pub const DEFAULT_BATCH_SIZE: usize = 1 << 24;
+
+/// Compress a graph given by its arcs, in any order and possibly with
+/// duplicates, and return the length in bits of the produced file.
+///
+/// This is a shortcut for [`compress_arcs_with`] that uses as number of
+/// nodes one plus the largest node appearing in an arc, batches of
+/// [`DEFAULT_BATCH_SIZE`] pairs and all the available threads.
+pub fn compress_arcs<P: AsRef<Path> + Send + Sync>(
+    basename: P,
+    arcs: impl IntoIterator<Item = (usize, usize)>,
+    compression_flags: CompFlags,
+) -> Result<u64> {
+    compress_arcs_with(
+        basename,
+        arcs,
+        None,
+        compression_flags,
+        DEFAULT_BATCH_SIZE,
+        rayon::current_num_threads(),
+    )
+}
+
+/// Compress a graph given by its arcs, in any order and possibly with
+/// duplicates, and return the length in bits of the produced file.
+///
+/// The arcs are sorted by [`sort_arcs`] with batches of `batch_size`
+/// pairs in a temporary directory, duplicates are removed, and the
+/// resulting graph is compressed with
+/// [`parallel_compress_sequential_iter`] using `num_threads` threads.
+///
+/// If `num_nodes` is `None`, the number of nodes is one plus the largest
+/// node appearing in an arc; otherwise, all nodes must be smaller than
+/// `num_nodes`, and nodes without arcs past the largest one are kept.
+pub fn compress_arcs_with<P: AsRef<Path> + Send + Sync>(
+    basename: P,
+    arcs: impl IntoIterator<Item = (usize, usize)>,
+    num_nodes: Option<usize>,
+    compression_flags: CompFlags,
+    batch_size: usize,
+    num_threads: usize,
+) -> Result<u64> {
+    let graph = sort_arcs(arcs, num_nodes, batch_size)?;
+    parallel_compress_sequential_iter(
+        basename,
+        graph.iter_nodes(),
+        graph.num_nodes(),
+        compression_flags,
+        num_threads,
+    )
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_compress_arcs() -> Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    use crate::traits::RandomAccessGraph;
+    let arcs = [
+        (3, 4),
+        (0, 2),
+        (1, 3),
+        (0, 1),
+        (2, 4),
+        (1, 2),
+        (0, 2),
+        (3, 4),
+    ];
+    let dir = tempfile::tempdir()?;
+
+    let basename = dir.path().join("graph");
+    compress_arcs(&basename, arcs, CompFlags::default())?;
+    let graph = load(&basename)?;
+    assert_eq!(graph.num_nodes(), 5);
+    assert_eq!(
+        VecGraph::from_node_iter(graph.iter_nodes()),
+        VecGraph::from_arc_list(&arcs)
+    );
+
+    let basename = dir.path().join("graph7");
+    compress_arcs_with(&basename, arcs, Some(7), CompFlags::default(), 3, 2)?;
+    let graph = load(&basename)?;
+    assert_eq!(graph.num_nodes(), 7);
+    assert_eq!(graph.num_arcs(), 6);
+
+    assert!(compress_arcs_with(&basename, arcs, Some(4), CompFlags::default(), 3, 2).is_err());
+    Ok(())
+}
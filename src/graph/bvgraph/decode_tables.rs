@@ -0,0 +1,421 @@
+//! Decoding tables for γ, δ and ζ codes.
+//!
+//! A [`DecodeTable`] with `BITS` bits is indexed by the next `BITS` bits of
+//! the stream, and contains, for each possible window, the value and the
+//! length of the code at the start of the window, if it fits. Tables are
+//! computed at compile time, and wider tables trade cache footprint for a
+//! higher hit rate: [`select_table_bits`] picks the narrowest width reaching
+//! a given hit rate on a sample of values, and
+//! [`DynamicCodesReaderBuilder::with_sampled_table_bits`](super::DynamicCodesReaderBuilder::with_sampled_table_bits)
+//! uses it to choose the width when a graph is loaded.
+//!
+//! Since const generics cannot yet be used in array lengths, tables have a
+//! second parameter `LEN`, which must be 2<sup>`BITS`</sup>.
+//!
+//! Codes that do not fit in a table are read by the underlying reader, but
+//! when the whole window of a γ or ζ₃ code is zero, that is, its unary part
+//! is longer than the table, the zeros are consumed 32 bits at a time by
+//! [`read_unary_burst`]. Since the bit reader is part of `dsi-bitstream`,
+//! this is done on top of it with `peek_bits` and `skip_bits`; the
+//! `huge_unary` and `huge_gamma` benchmarks in `benches/codes.rs` compare it
+//! with the reader on huge values.
+
+use anyhow::{bail, Result};
+use dsi_bitstream::prelude::*;
+
+/// The table widths used by [`DynamicCodesReaderBuilder`](super::DynamicCodesReaderBuilder).
+pub const TABLE_BITS: [usize; 3] = [8, 12, 16];
+
+/// A table decoding the codes of at most `BITS` bits from a window of
+/// `BITS` bits of a stream. `LEN` must be 2<sup>`BITS`</sup>.
+#[derive(Clone, Debug)]
+pub struct DecodeTable<const BITS: usize, const LEN: usize> {
+    /// The value of the code at the start of each window, shifted left by
+    /// eight, or-ed with the length of the code, which is zero if the code
+    /// does not fit in the window.
+    entries: [u32; LEN],
+    /// Whether the table is for big-endian streams.
+    big_endian: bool,
+}
+
+/// Append a field of `width` bits to a code of `len` bits, with the same
+/// semantics of two successive `read_bits` calls.
+const fn push(code: u64, len: u32, field: u64, width: u32, big_endian: bool) -> (u64, u32) {
+    if big_endian {
+        ((code << width) | field, len + width)
+    } else {
+        (code | (field << len), len + width)
+    }
+}
+
+/// Return a code consisting of the unary code of `n`, that is, `n` zeros
+/// followed by a one.
+const fn unary(n: u32, big_endian: bool) -> (u64, u32) {
+    if big_endian {
+        (1, n + 1)
+    } else {
+        (1 << n, n + 1)
+    }
+}
+
+/// Return the bits and the length of the code of `value`, or a length
+/// larger than 64 if the code is too long.
+const fn encode(code: Code, value: u64, big_endian: bool) -> (u64, u32) {
+    let x = value + 1;
+    let log = 63 - x.leading_zeros();
+    match code {
+        Code::Unary => {
+            if value >= 64 {
+                return (0, u32::MAX);
+            }
+            unary(value as u32, big_endian)
+        }
+        Code::Gamma => {
+            let (bits, len) = unary(log, big_endian);
+            push(bits, len, x - (1 << log), log, big_endian)
+        }
+        Code::Delta => {
+            let (bits, len) = encode(Code::Gamma, log as u64, big_endian);
+            if len + log > 64 {
+                return (0, u32::MAX);
+            }
+            push(bits, len, x - (1 << log), log, big_endian)
+        }
+        Code::Zeta { k } => {
+            let k = k as u32;
+            let h = log / k;
+            let low = 1 << (h * k);
+            if (h + 1) * k >= 64 {
+                return (0, u32::MAX);
+            }
+            let high = 1 << ((h + 1) * k);
+            let (bits, len) = unary(h, big_endian);
+            // minimal binary code of x - low in [0..high - low)
+            let max = high - low;
+            let width = 63 - max.leading_zeros();
+            let limit = (1 << (width + 1)) - max;
+            let x = x - low;
+            if x < limit {
+                push(bits, len, x, width, big_endian)
+            } else {
+                let y = x + limit;
+                let (bits, len) = push(bits, len, y >> 1, width, big_endian);
+                push(bits, len, y & 1, 1, big_endian)
+            }
+        }
+        _ => panic!("Only unary, ɣ, δ, and ζ codes have decoding tables"),
+    }
+}
+
+impl<const BITS: usize, const LEN: usize> DecodeTable<BITS, LEN> {
+    /// A table for γ codes on big-endian streams.
+    pub const GAMMA_BE: Self = Self::new(Code::Gamma, true);
+    /// A table for γ codes on little-endian streams.
+    pub const GAMMA_LE: Self = Self::new(Code::Gamma, false);
+    /// A table for δ codes on big-endian streams.
+    pub const DELTA_BE: Self = Self::new(Code::Delta, true);
+    /// A table for δ codes on little-endian streams.
+    pub const DELTA_LE: Self = Self::new(Code::Delta, false);
+    /// A table for ζ₃ codes on big-endian streams.
+    pub const ZETA3_BE: Self = Self::new(Code::Zeta { k: 3 }, true);
+    /// A table for ζ₃ codes on little-endian streams.
+    pub const ZETA3_LE: Self = Self::new(Code::Zeta { k: 3 }, false);
+
+    /// Build the table for `code` on streams of the given endianness.
+    ///
+    /// # Panics
+    /// If `LEN` is not 2<sup>`BITS`</sup>, if `BITS` is larger than 16, or
+    /// if `code` is not unary, γ, δ or ζ.
+    pub const fn new(code: Code, big_endian: bool) -> Self {
+        assert!(BITS <= 16, "Decoding tables can have at most 16 bits");
+        assert!(LEN == 1 << BITS, "LEN must be 2 to the power of BITS");
+        let mut entries = [0; LEN];
+        // code lengths are nondecreasing, so we can stop at the first code
+        // that does not fit
+        let mut value = 0;
+        loop {
+            let (bits, len) = encode(code, value, big_endian);
+            if len > BITS as u32 {
+                break;
+            }
+            // fill all the windows starting with the code
+            let free = BITS as u32 - len;
+            let mut suffix = 0;
+            while suffix < 1 << free {
+                let window = if big_endian {
+                    (bits << free) | suffix
+                } else {
+                    bits | (suffix << len)
+                };
+                entries[window as usize] = ((value as u32) << 8) | len;
+                suffix += 1;
+            }
+            value += 1;
+        }
+        Self {
+            entries,
+            big_endian,
+        }
+    }
+
+    /// Decode the next code of `reader`, if it is at most `BITS` bits long.
+    #[inline(always)]
+    pub fn read<E: Endianness, CR: ReadCodes<E>>(&self, reader: &mut CR) -> Option<u64> {
+        debug_assert_eq!(E::IS_BIG, self.big_endian);
+        let window: u64 = reader.peek_bits(BITS).unwrap().upcast();
+        let entry = self.entries[window as usize];
+        let len = entry & 0xFF;
+        if len == 0 {
+            return None;
+        }
+        reader.skip_bits(len as usize).unwrap();
+        Some((entry >> 8) as u64)
+    }
+}
+
+/// Read a γ code using a table of `BITS` bits, falling back to `reader` for
+/// longer codes.
+#[inline(always)]
+pub fn read_gamma_table<E: Endianness, CR: ReadCodes<E>, const BITS: usize, const LEN: usize>(
+    reader: &mut CR,
+) -> u64 {
+    let table: &'static DecodeTable<BITS, LEN> = if E::IS_BIG {
+        &DecodeTable::GAMMA_BE
+    } else {
+        &DecodeTable::GAMMA_LE
+    };
+    match table.read(reader) {
+        Some(value) => value,
+        None if is_long_run::<E, CR, BITS>(reader) => {
+            let len = read_unary_burst(reader);
+            reader.read_bits(len as usize).unwrap() + (1 << len) - 1
+        }
+        None => reader.read_gamma().unwrap(),
+    }
+}
+
+/// Read a δ code using a table of `BITS` bits, falling back to `reader` for
+/// longer codes.
+#[inline(always)]
+pub fn read_delta_table<E: Endianness, CR: ReadCodes<E>, const BITS: usize, const LEN: usize>(
+    reader: &mut CR,
+) -> u64 {
+    let table: &'static DecodeTable<BITS, LEN> = if E::IS_BIG {
+        &DecodeTable::DELTA_BE
+    } else {
+        &DecodeTable::DELTA_LE
+    };
+    match table.read(reader) {
+        Some(value) => value,
+        None => reader.read_delta().unwrap(),
+    }
+}
+
+/// Read a ζ₃ code using a table of `BITS` bits, falling back to `reader` for
+/// longer codes.
+#[inline(always)]
+pub fn read_zeta3_table<E: Endianness, CR: ReadCodes<E>, const BITS: usize, const LEN: usize>(
+    reader: &mut CR,
+) -> u64 {
+    let table: &'static DecodeTable<BITS, LEN> = if E::IS_BIG {
+        &DecodeTable::ZETA3_BE
+    } else {
+        &DecodeTable::ZETA3_LE
+    };
+    match table.read(reader) {
+        Some(value) => value,
+        None if is_long_run::<E, CR, BITS>(reader) => {
+            let h = read_unary_burst(reader);
+            // the minimal binary code of the interval [2^3h, 2^(3h+3)) has
+            // 3h + 2 or 3h + 3 bits, the threshold being 2^3h
+            let l = 1 << (3 * h);
+            let mut value = reader.read_bits(3 * h as usize + 2).unwrap();
+            if value >= l {
+                value = ((value << 1) | reader.read_bits(1).unwrap()) - l;
+            }
+            l + value - 1
+        }
+        None => reader.read_zeta3().unwrap(),
+    }
+}
+
+/// The number of bits examined at a time by [`read_unary_burst`].
+const BURST_BITS: usize = 32;
+const _: () = assert!(BURST_BITS <= u64::BITS as usize);
+
+/// Read a unary code, peeking at the zeros [`BURST_BITS`] at a time and
+/// counting them with a leading (big endian) or trailing (little endian)
+/// zero count.
+#[inline(always)]
+pub fn read_unary_burst<E: Endianness, CR: ReadCodes<E>>(reader: &mut CR) -> u64 {
+    let mut value = 0;
+    loop {
+        let window: u64 = reader.peek_bits(BURST_BITS).unwrap().upcast();
+        if window == 0 {
+            reader.skip_bits(BURST_BITS).unwrap();
+            value += BURST_BITS as u64;
+            continue;
+        }
+        let zeros = if E::IS_BIG {
+            // the window is in the lowest BURST_BITS bits
+            window.leading_zeros() - (u64::BITS - BURST_BITS as u32)
+        } else {
+            window.trailing_zeros()
+        };
+        reader.skip_bits(zeros as usize + 1).unwrap();
+        return value + zeros as u64;
+    }
+}
+
+/// Skip a unary code as [`read_unary_burst`] does.
+#[inline(always)]
+pub fn skip_unary_burst<E: Endianness, CR: ReadCodes<E>>(reader: &mut CR) {
+    read_unary_burst(reader);
+}
+
+/// Return whether the next `BITS` bits are zero, that is, the unary part of
+/// the next code is longer than a table of `BITS` bits.
+#[inline(always)]
+fn is_long_run<E: Endianness, CR: ReadCodes<E>, const BITS: usize>(reader: &mut CR) -> bool {
+    let window: u64 = reader.peek_bits(BITS).unwrap().upcast();
+    window == 0
+}
+
+/// Return the fraction of `values` whose `code` is at most `bits` bits long,
+/// that is, the hit rate of a decoding table with `bits` bits.
+pub fn table_hit_rate(code: Code, bits: usize, values: &[u64]) -> Result<f64> {
+    let len: fn(u64) -> usize = match code {
+        Code::Unary => len_unary,
+        Code::Gamma => len_gamma,
+        Code::Delta => len_delta,
+        Code::Zeta { k: 3 } => |x| len_zeta(x, 3),
+        code => bail!("No decoding tables for {:?}", code),
+    };
+    if values.is_empty() {
+        return Ok(1.0);
+    }
+    let hits = values.iter().filter(|&&value| len(value) <= bits).count();
+    Ok(hits as f64 / values.len() as f64)
+}
+
+/// Return the narrowest width in [`TABLE_BITS`] whose hit rate on a sample
+/// of `values` is at least `min_hit_rate`, or `None` if no width reaches it,
+/// in which case tables are not worth their cache footprint.
+pub fn select_table_bits(code: Code, values: &[u64], min_hit_rate: f64) -> Result<Option<usize>> {
+    select_table_bits_mixed(&[(code, values)], min_hit_rate)
+}
+
+/// Return the narrowest width in [`TABLE_BITS`] whose hit rate on samples
+/// of values written with different codes is at least `min_hit_rate`, or
+/// `None` if no width reaches it or there are no values.
+///
+/// The hit rate is the fraction of all the values whose code fits in the
+/// table, as a single width is used for all the codes of a graph.
+pub fn select_table_bits_mixed(
+    samples: &[(Code, &[u64])],
+    min_hit_rate: f64,
+) -> Result<Option<usize>> {
+    let num_values = samples
+        .iter()
+        .map(|(_, values)| values.len())
+        .sum::<usize>();
+    if num_values == 0 {
+        return Ok(None);
+    }
+    for bits in TABLE_BITS {
+        let mut hits = 0.0;
+        for &(code, values) in samples {
+            hits += table_hit_rate(code, bits, values)? * values.len() as f64;
+        }
+        if hits / num_values as f64 >= min_hit_rate {
+            return Ok(Some(bits));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_decode_tables() -> Result<()> {
+    macro_rules! check {
+        ($endianness:ty, $write:ident, $read:ident, $code:expr) => {{
+            let values = (0..1000).chain([1 << 20, 1 << 40]).collect::<Vec<u64>>();
+            let mut buffer: Vec<u64> = Vec::new();
+            {
+                let mut writer = <BufferedBitStreamWrite<$endianness, _>>::new(
+                    MemWordWriteVec::new(&mut buffer),
+                );
+                for &value in &values {
+                    writer.$write(value)?;
+                }
+                writer.flush()?;
+            }
+            let buffer_32: &[u32] = unsafe { buffer.align_to().1 };
+            let mut reader = <BufferedBitStreamRead<$endianness, u64, _>>::new(
+                MemWordReadInfinite::new(buffer_32),
+            );
+            for &value in &values {
+                assert_eq!($read::<$endianness, _, 12, 4096>(&mut reader), value);
+            }
+            // the table decodes exactly the short codes
+            let rate = table_hit_rate($code, 12, &values)?;
+            assert!(rate > 0.0 && rate < 1.0);
+        }};
+    }
+
+    check!(BE, write_gamma, read_gamma_table, Code::Gamma);
+    check!(LE, write_gamma, read_gamma_table, Code::Gamma);
+    check!(BE, write_delta, read_delta_table, Code::Delta);
+    check!(LE, write_delta, read_delta_table, Code::Delta);
+    check!(BE, write_zeta3, read_zeta3_table, Code::Zeta { k: 3 });
+    check!(LE, write_zeta3, read_zeta3_table, Code::Zeta { k: 3 });
+
+    assert_eq!(select_table_bits(Code::Gamma, &[0, 1, 2, 3], 1.0)?, Some(8));
+    assert_eq!(select_table_bits(Code::Gamma, &[1 << 30], 0.5)?, None);
+    // a γ code of 1000 needs 19 bits, a δ code of 1000 16 bits
+    let gamma = [1000, 1000];
+    let delta = [1000, 1000];
+    let samples = [(Code::Gamma, &gamma[..]), (Code::Delta, &delta[..])];
+    assert_eq!(select_table_bits_mixed(&samples, 0.5)?, Some(16));
+    assert_eq!(select_table_bits_mixed(&samples, 0.75)?, None);
+    assert_eq!(select_table_bits_mixed(&[], 0.0)?, None);
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_unary_burst() -> Result<()> {
+    macro_rules! check {
+        ($endianness:ty) => {{
+            let values = [0, 1, 31, 32, 33, 63, 64, 100, 1000, 100_000, 5];
+            let mut buffer: Vec<u64> = Vec::new();
+            {
+                let mut writer = <BufferedBitStreamWrite<$endianness, _>>::new(
+                    MemWordWriteVec::new(&mut buffer),
+                );
+                for &value in &values {
+                    writer.write_unary(value)?;
+                }
+                writer.flush()?;
+            }
+            let buffer_32: &[u32] = unsafe { buffer.align_to().1 };
+            let mut reader = <BufferedBitStreamRead<$endianness, u64, _>>::new(
+                MemWordReadInfinite::new(buffer_32),
+            );
+            for &value in &values {
+                assert_eq!(read_unary_burst(&mut reader), value);
+            }
+            let mut reader = <BufferedBitStreamRead<$endianness, u64, _>>::new(
+                MemWordReadInfinite::new(buffer_32),
+            );
+            for _ in 1..values.len() {
+                skip_unary_burst(&mut reader);
+            }
+            assert_eq!(reader.read_unary()?, 5);
+        }};
+    }
+
+    check!(BE);
+    check!(LE);
+    Ok(())
+}
@@ -19,7 +19,7 @@ pub struct DynamicCodesReader<E: Endianness, CR: ReadCodes<E>> {
 }
 
 impl<E: Endianness, CR: ReadCodes<E>> DynamicCodesReader<E, CR> {
-    const READ_UNARY: fn(&mut CR) -> u64 = |cr| cr.read_unary().unwrap();
+    const READ_UNARY: fn(&mut CR) -> u64 = |cr| read_unary_burst::<E, _>(cr);
     const READ_GAMMA: fn(&mut CR) -> u64 = |cr| cr.read_gamma().unwrap();
     const READ_DELTA: fn(&mut CR) -> u64 = |cr| cr.read_delta().unwrap();
     const READ_ZETA2: fn(&mut CR) -> u64 = |cr| cr.read_zeta(2).unwrap();
@@ -71,13 +71,13 @@ impl<E: Endianness, CR: ReadCodes<E>> DynamicCodesReader<E, CR> {
     }
 }
 
-impl<E: Endianness, CR: ReadCodes<E> + BitSeek> BitSeek for DynamicCodesReader<E, CR> {
-    fn set_pos(&mut self, bit_index: usize) -> Result<()> {
-        self.code_reader.set_pos(bit_index)
+impl<E: Endianness, CR: ReadCodes<E> + BitSeek> BVGraphCodesSeek for DynamicCodesReader<E, CR> {
+    fn set_pos(&mut self, bit_index: u64) -> Result<()> {
+        self.code_reader.set_pos(bit_pos(bit_index)?)
     }
 
-    fn get_pos(&self) -> usize {
-        self.code_reader.get_pos()
+    fn get_pos(&self) -> u64 {
+        self.code_reader.get_pos() as u64
     }
 }
 
@@ -153,7 +153,7 @@ pub struct DynamicCodesReaderSkipper<E: Endianness, CR: ReadCodes<E>> {
 }
 
 impl<E: Endianness, CR: ReadCodes<E>> DynamicCodesReaderSkipper<E, CR> {
-    const READ_UNARY: fn(&mut CR) -> u64 = |cr| cr.read_unary().unwrap();
+    const READ_UNARY: fn(&mut CR) -> u64 = |cr| read_unary_burst::<E, _>(cr);
     const READ_GAMMA: fn(&mut CR) -> u64 = |cr| cr.read_gamma().unwrap();
     const READ_DELTA: fn(&mut CR) -> u64 = |cr| cr.read_delta().unwrap();
     const READ_ZETA2: fn(&mut CR) -> u64 = |cr| cr.read_zeta(2).unwrap();
@@ -164,7 +164,7 @@ impl<E: Endianness, CR: ReadCodes<E>> DynamicCodesReaderSkipper<E, CR> {
     const READ_ZETA7: fn(&mut CR) -> u64 = |cr| cr.read_zeta(7).unwrap();
     const READ_ZETA1: fn(&mut CR) -> u64 = Self::READ_GAMMA;
 
-    const SKIP_UNARY: fn(&mut CR) = |cr| cr.skip_unary().unwrap();
+    const SKIP_UNARY: fn(&mut CR) = |cr| skip_unary_burst::<E, _>(cr);
     const SKIP_GAMMA: fn(&mut CR) = |cr| cr.skip_gamma().unwrap();
     const SKIP_DELTA: fn(&mut CR) = |cr| cr.skip_delta().unwrap();
     const SKIP_ZETA2: fn(&mut CR) = |cr| cr.skip_zeta(2).unwrap();
@@ -245,13 +245,15 @@ impl<E: Endianness, CR: ReadCodes<E>> DynamicCodesReaderSkipper<E, CR> {
     }
 }
 
-impl<E: Endianness, CR: ReadCodes<E> + BitSeek> BitSeek for DynamicCodesReaderSkipper<E, CR> {
-    fn set_pos(&mut self, bit_index: usize) -> Result<()> {
-        self.code_reader.set_pos(bit_index)
+impl<E: Endianness, CR: ReadCodes<E> + BitSeek> BVGraphCodesSeek
+    for DynamicCodesReaderSkipper<E, CR>
+{
+    fn set_pos(&mut self, bit_index: u64) -> Result<()> {
+        self.code_reader.set_pos(bit_pos(bit_index)?)
     }
 
-    fn get_pos(&self) -> usize {
-        self.code_reader.get_pos()
+    fn get_pos(&self) -> u64 {
+        self.code_reader.get_pos() as u64
     }
 }
 
@@ -317,6 +319,14 @@ impl<E: Endianness, CR: ReadCodes<E>> BVGraphCodesSkipper for DynamicCodesReader
     fn skip_block(&mut self) {
         (self.skip_blocks)(&mut self.code_reader)
     }
+    #[inline(always)]
+    fn skip_blocks(&mut self, n: usize) {
+        // resolve the function pointer once for the whole run
+        let skip = self.skip_blocks;
+        for _ in 0..n {
+            skip(&mut self.code_reader);
+        }
+    }
 
     #[inline(always)]
     fn skip_interval_count(&mut self) {
@@ -339,6 +349,14 @@ impl<E: Endianness, CR: ReadCodes<E>> BVGraphCodesSkipper for DynamicCodesReader
     fn skip_residual(&mut self) {
         (self.skip_residuals)(&mut self.code_reader)
     }
+    #[inline(always)]
+    fn skip_residuals(&mut self, n: usize) {
+        // resolve the function pointer once for the whole run
+        let skip = self.skip_residuals;
+        for _ in 0..n {
+            skip(&mut self.code_reader);
+        }
+    }
 }
 
 /// An implementation of [`BVGraphCodesWriter`] with the most commonly used codes
@@ -387,13 +405,15 @@ impl<E: Endianness, CW: WriteCodes<E>> DynamicCodesWriter<E, CW> {
     }
 }
 
-impl<E: Endianness, CW: WriteCodes<E> + BitSeek + Clone> BitSeek for DynamicCodesWriter<E, CW> {
-    fn set_pos(&mut self, bit_index: usize) -> Result<()> {
-        self.code_writer.set_pos(bit_index)
+impl<E: Endianness, CW: WriteCodes<E> + BitSeek + Clone> BVGraphCodesSeek
+    for DynamicCodesWriter<E, CW>
+{
+    fn set_pos(&mut self, bit_index: u64) -> Result<()> {
+        self.code_writer.set_pos(bit_pos(bit_index)?)
     }
 
-    fn get_pos(&self) -> usize {
-        self.code_writer.get_pos()
+    fn get_pos(&self) -> u64 {
+        self.code_writer.get_pos() as u64
     }
 }
 
@@ -0,0 +1,125 @@
+use super::*;
+use crate::utils::{HttpRangeFile, HttpWordReader};
+use anyhow::{Context, Result};
+use dsi_bitstream::prelude::*;
+use std::io::Write;
+use std::sync::Arc;
+
+type HttpBitReader<E> = BufferedBitStreamRead<E, u64, HttpWordReader>;
+
+/// A codes reader builder on a BVGraph bitstream accessed through HTTP range
+/// requests.
+///
+/// All the readers share the block cache of the underlying [`HttpRangeFile`].
+pub struct HttpCodesReaderBuilder<E: Endianness> {
+    /// The remote `.graph` file.
+    file: Arc<HttpRangeFile>,
+    /// The compression flags.
+    compression_flags: CompFlags,
+    /// Tell the compiler that's Ok that we don't store `E` but we need it
+    /// for typing.
+    _marker: core::marker::PhantomData<E>,
+}
+
+impl<E: Endianness> HttpCodesReaderBuilder<E>
+where
+    HttpBitReader<E>: ReadCodes<E> + BitSeek,
+{
+    /// Create a new builder from a remote file and the compression flags.
+    pub fn new(file: Arc<HttpRangeFile>, compression_flags: CompFlags) -> Result<Self> {
+        // check early that the codes are supported
+        DynamicCodesReader::new(
+            HttpBitReader::<E>::new(HttpWordReader::new(file.clone())),
+            &compression_flags,
+        )?;
+        Ok(Self {
+            file,
+            compression_flags,
+            _marker: core::marker::PhantomData,
+        })
+    }
+
+    #[inline(always)]
+    /// Return a clone of the compression flags.
+    pub fn get_compression_flags(&self) -> CompFlags {
+        self.compression_flags
+    }
+}
+
+impl<E: Endianness> BVGraphCodesReaderBuilder for HttpCodesReaderBuilder<E>
+where
+    HttpBitReader<E>: ReadCodes<E> + BitSeek,
+{
+    type Reader<'a> =
+        DynamicCodesReader<E, HttpBitReader<E>>
+    where
+        Self: 'a;
+
+    fn get_reader(&self, offset: u64) -> Result<Self::Reader<'_>> {
+        let mut code_reader = HttpBitReader::<E>::new(HttpWordReader::new(self.file.clone()));
+        code_reader.set_pos(bit_pos(offset)?)?;
+        DynamicCodesReader::new(code_reader, &self.compression_flags)
+    }
+}
+
+/// Load for random access a BVGraph published on a web server (or an object
+/// storage) under the URL `base_url`, that is, the files
+/// `{base_url}.properties`, `{base_url}.graph` and `{base_url}.ef` must be
+/// reachable.
+///
+/// The bitstream is fetched on demand through HTTP range requests in blocks
+/// of `block_size` bytes, keeping at most `cache_blocks` of them in memory.
+/// The offsets are much smaller than the graph, so the `.ef` file is
+/// downloaded once in a temporary file and memory-mapped.
+pub fn load_http(
+    base_url: &str,
+    block_size: usize,
+    cache_blocks: usize,
+) -> Result<BVGraph<HttpCodesReaderBuilder<BE>, crate::EF<&'static [u64]>>> {
+    let properties_url = format!("{}.properties", base_url);
+    let properties = HttpRangeFile::new(&properties_url)?;
+    let properties = properties.fetch(0, properties.len())?;
+    let map = java_properties::read(properties.as_slice())
+        .with_context(|| "cannot parse the .properties file as a java properties file")?;
+
+    let num_nodes = map
+        .get("nodes")
+        .with_context(|| "Missing nodes property")?
+        .parse::<u64>()
+        .with_context(|| "Cannot parse nodes as u64")?;
+    let num_arcs = map
+        .get("arcs")
+        .with_context(|| "Missing arcs property")?
+        .parse::<u64>()
+        .with_context(|| "Cannot parse arcs as u64")?;
+
+    let ef_url = format!("{}.ef", base_url);
+    let ef = HttpRangeFile::new(&ef_url)?;
+    let mut ef_file = tempfile::NamedTempFile::new()?;
+    let mut start = 0;
+    while start < ef.len() {
+        let end = (start + (1 << 24)).min(ef.len());
+        ef_file.write_all(&ef.fetch(start, end)?)?;
+        start = end;
+    }
+    ef_file.flush()?;
+    // the mapping stays valid after the temporary file is deleted
+    let offsets = sux::prelude::map::<_, crate::EF<&[u64]>>(
+        ef_file.path(),
+        &sux::prelude::Flags::TRANSPARENT_HUGE_PAGES,
+    )
+    .with_context(|| format!("Cannot map the elias-fano file downloaded from {}", ef_url))?;
+
+    let graph = HttpRangeFile::with_cache(format!("{}.graph", base_url), block_size, cache_blocks)?;
+    let comp_flags = CompFlags::from_properties(&map)?;
+    let code_reader_builder = HttpCodesReaderBuilder::<BE>::new(Arc::new(graph), comp_flags)?;
+
+    Ok(BVGraph::new(
+        code_reader_builder,
+        offsets,
+        comp_flags.min_interval_length,
+        comp_flags.compression_window,
+        num_nodes as usize,
+        num_arcs as usize,
+    ))
+}
@@ -1,6 +1,6 @@
 use super::*;
 use crate::prelude::*;
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use dsi_bitstream::prelude::*;
 use java_properties;
 use std::fs::*;
@@ -8,110 +8,250 @@ use std::io::*;
 use std::path::Path;
 
 macro_rules! impl_loads {
-    ($builder:ident, $reader:ident, $load_name:ident, $load_seq_name:ident) => {
+    ($builder:ident, $endianness:ident, |$loader:ident| $configure:expr, $load_name:ident, $load_seq_name:ident) => {
         /// Load a BVGraph for random access
+        ///
+        /// Use [`BVGraphLoader`] to configure the loading, for example to
+        /// verify the digests of the files of the graph.
         pub fn $load_name<P: AsRef<std::path::Path>>(
             basename: P,
-        ) -> Result<BVGraph<$builder<BE, MmapBackend<u32>>, crate::EF<&'static [u64]>>> {
-            let basename = basename.as_ref();
-            let properties_path = format!("{}.properties", basename.to_string_lossy());
-            let f = File::open(&properties_path)
-                .with_context(|| format!("Cannot open property file {}", properties_path))?;
-            let map = java_properties::read(BufReader::new(f))
-                .with_context(|| "cannot parse the .properties file as a java properties file")?;
-
-            let num_nodes = map
-                .get("nodes")
-                .with_context(|| "Missing nodes property")?
-                .parse::<u64>()
-                .with_context(|| "Cannot parse nodes as u64")?;
-            let num_arcs = map
-                .get("arcs")
-                .with_context(|| "Missing arcs property")?
-                .parse::<u64>()
-                .with_context(|| "Cannot parse arcs as u64")?;
-
-            let graph_path_str = format!("{}.graph", basename.to_string_lossy());
-            let graph_path = Path::new(&graph_path_str);
-            let file_len = graph_path.metadata()?.len();
-            let file = std::fs::File::open(graph_path).with_context(|| "Cannot open graph file")?;
-
-            let graph = MmapBackend::new(unsafe {
-                mmap_rs::MmapOptions::new(file_len as _)?
-                    .with_flags((sux::prelude::Flags::TRANSPARENT_HUGE_PAGES).mmap_flags())
-                    .with_file(file, 0)
-                    .map()?
-            });
-
-            let ef_path = format!("{}.ef", basename.to_string_lossy());
-            let offsets = sux::prelude::map::<_, crate::EF<&[u64]>>(
-                &ef_path,
-                &sux::prelude::Flags::TRANSPARENT_HUGE_PAGES,
-            )
-            .with_context(|| format!("Cannot open the elias-fano file {}", ef_path))?;
-
-            let comp_flags = CompFlags::from_properties(&map)?;
-            let code_reader_builder = <$builder<BE, MmapBackend<u32>>>::new(graph, comp_flags)?;
-
-            Ok(BVGraph::new(
-                code_reader_builder,
-                offsets,
-                comp_flags.min_interval_length,
-                comp_flags.compression_window,
-                num_nodes as usize,
-                num_arcs as usize,
-            ))
+        ) -> Result<BVGraph<$builder<$endianness, LoadBackend<u32>>, LoadedOffsets>> {
+            let $loader = BVGraphLoader::new(basename).endianness::<$endianness>();
+            $configure.load()
         }
 
         /// Load a BVGraph sequentially
+        ///
+        /// Use [`BVGraphLoader`] to configure the loading, for example to
+        /// verify the digests of the files of the graph.
         pub fn $load_seq_name<P: AsRef<std::path::Path>>(
             basename: P,
-        ) -> Result<BVGraphSequential<$builder<BE, MmapBackend<u32>>>> {
-            let basename = basename.as_ref();
-            let properties_path = format!("{}.properties", basename.to_string_lossy());
-            let f = File::open(&properties_path)
-                .with_context(|| format!("Cannot open property file {}", properties_path))?;
-            let map = java_properties::read(BufReader::new(f))
-                .with_context(|| "cannot parse the .properties file as a java properties file")?;
+        ) -> Result<BVGraphSequential<$builder<$endianness, LoadBackend<u32>>>> {
+            let $loader = BVGraphLoader::new(basename)
+                .sequential()
+                .endianness::<$endianness>();
+            $configure.load()
+        }
+    };
+}
 
-            let num_nodes = map
-                .get("nodes")
-                .with_context(|| "Missing nodes property")?
-                .parse::<u64>()
-                .with_context(|| "Cannot parse nodes as u64")?;
-            let num_arcs = map
-                .get("arcs")
-                .with_context(|| "Missing arcs property")?
-                .parse::<u64>()
-                .with_context(|| "Cannot parse arcs as u64")?;
+impl_loads! {DynamicCodesReaderBuilder, BE, |loader| loader, load, load_seq}
+impl_loads! {DynamicCodesReaderBuilder, LE, |loader| loader, load_le, load_seq_le}
+impl_loads! {ConstCodesReaderBuilder, BE, |loader| loader.const_codes::<
+    { const_codes::GAMMA },
+    { const_codes::UNARY },
+    { const_codes::GAMMA },
+    { const_codes::GAMMA },
+    { const_codes::ZETA },
+    3,
+>(), load_const, load_seq_const}
 
-            let graph_path_str = format!("{}.graph", basename.to_string_lossy());
-            let graph_path = Path::new(&graph_path_str);
-            let file_len = graph_path.metadata()?.len();
-            let file = std::fs::File::open(graph_path)?;
+/// Read the `.properties` file of the graph with the given basename, returning
+/// the number of nodes, the number of arcs and the compression flags.
+pub(crate) fn parse_properties<P: AsRef<Path>>(basename: P) -> Result<(usize, usize, CompFlags)> {
+    let properties_path = format!("{}.properties", basename.as_ref().to_string_lossy());
+    let f = File::open(&properties_path)
+        .with_context(|| format!("Cannot open property file {}", properties_path))?;
+    let map = java_properties::read(BufReader::new(f))
+        .with_context(|| "cannot parse the .properties file as a java properties file")?;
+    parse_properties_map(&map)
+}
 
-            let graph = MmapBackend::new(unsafe {
-                mmap_rs::MmapOptions::new(file_len as _)?
-                    .with_flags((sux::prelude::Flags::TRANSPARENT_HUGE_PAGES).mmap_flags())
-                    .with_file(file, 0)
-                    .map()?
-            });
+/// Return the number of nodes, the number of arcs and the compression flags
+/// recorded in the parsed `.properties` file of a graph.
+fn parse_properties_map(
+    map: &std::collections::HashMap<String, String>,
+) -> Result<(usize, usize, CompFlags)> {
+    let num_nodes = map
+        .get("nodes")
+        .with_context(|| "Missing nodes property")?
+        .parse::<u64>()
+        .with_context(|| "Cannot parse nodes as u64")?;
+    let num_arcs = map
+        .get("arcs")
+        .with_context(|| "Missing arcs property")?
+        .parse::<u64>()
+        .with_context(|| "Cannot parse arcs as u64")?;
+    let comp_flags = CompFlags::from_properties(map)?;
+    Ok((num_nodes as usize, num_arcs as usize, comp_flags))
+}
 
-            let comp_flags = CompFlags::from_properties(&map)?;
-            let code_reader_builder = <$builder<BE, MmapBackend<u32>>>::new(graph, comp_flags)?;
+/// Load a BVGraph sequentially reading the bitstream from `reader` instead
+/// of memory-mapping the `.graph` file; the `.properties` file is still read
+/// from `basename`.
+///
+/// This makes it possible to stream a graph from a pipe, from the network or
+/// through a decompressor. Since the stream cannot be rewound, the returned
+/// graph can be iterated only once.
+pub fn load_seq_from_read<P: AsRef<Path>, R: Read>(
+    basename: P,
+    reader: R,
+) -> Result<BVGraphSequential<DynamicCodesReaderStreamBuilder<BE, R>>> {
+    let (num_nodes, num_arcs, comp_flags) = parse_properties(basename)?;
+    Ok(BVGraphSequential::new(
+        DynamicCodesReaderStreamBuilder::new(reader, comp_flags),
+        comp_flags.compression_window,
+        comp_flags.min_interval_length,
+        num_nodes,
+        Some(num_arcs),
+    ))
+}
 
-            let seq_reader = BVGraphSequential::new(
-                code_reader_builder,
-                comp_flags.compression_window,
-                comp_flags.min_interval_length,
-                num_nodes as usize,
-                Some(num_arcs as _),
-            );
+/// Load a big-endian BVGraph sequentially from the contents of its
+/// `.properties` and `.graph` files, without accessing the filesystem.
+///
+/// This is useful on platforms without memory mapping or without a
+/// filesystem, where the files are usually downloaded or embedded in the
+/// binary. The bitstream is copied in memory.
+///
+/// Note that the crate does not build yet for `wasm32-unknown-unknown` with
+/// the `std` feature, as `sux` and `epserde` depend unconditionally on
+/// `mmap-rs`.
+pub fn load_seq_from_bytes(
+    properties: &str,
+    graph: &[u8],
+) -> Result<BVGraphSequential<DynamicCodesReaderBuilder<BE, Vec<u32>>>> {
+    let map = java_properties::read(properties.as_bytes())
+        .with_context(|| "cannot parse the properties as java properties")?;
+    let endianness = GraphEndianness::from_properties(&map)?;
+    if endianness != GraphEndianness::Big {
+        bail!(
+            "The graph is {}-endian, but it is being loaded as big-endian",
+            endianness.as_str()
+        );
+    }
+    let (num_nodes, num_arcs, comp_flags) = parse_properties_map(&map)?;
+    // the words are read in native byte order, as when loading the file
+    let data = graph
+        .chunks(4)
+        .map(|chunk| {
+            let mut word = [0; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            u32::from_ne_bytes(word)
+        })
+        .collect::<Vec<_>>();
+    Ok(BVGraphSequential::new(
+        DynamicCodesReaderBuilder::new(data, comp_flags)?,
+        comp_flags.compression_window,
+        comp_flags.min_interval_length,
+        num_nodes,
+        Some(num_arcs),
+    ))
+}
 
-            Ok(seq_reader)
-        }
+/// Load a big-endian BVGraph sequentially reading the `.graph` file through
+/// buffered I/O instead of memory-mapping it (see
+/// [`DynamicCodesReaderFileBuilder`]).
+pub fn load_seq_file<P: AsRef<Path>>(
+    basename: P,
+) -> Result<BVGraphSequential<DynamicCodesReaderFileBuilder<BE>>> {
+    load_seq_file_endianness(basename)
+}
+
+/// Load a little-endian BVGraph sequentially reading the `.graph` file
+/// through buffered I/O instead of memory-mapping it (see
+/// [`DynamicCodesReaderFileBuilder`]).
+pub fn load_seq_file_le<P: AsRef<Path>>(
+    basename: P,
+) -> Result<BVGraphSequential<DynamicCodesReaderFileBuilder<LE>>> {
+    load_seq_file_endianness(basename)
+}
+
+fn load_seq_file_endianness<E: Endianness, P: AsRef<Path>>(
+    basename: P,
+) -> Result<BVGraphSequential<DynamicCodesReaderFileBuilder<E>>>
+where
+    DynamicCodesReaderFileBuilder<E>: BVGraphCodesReaderBuilder,
+{
+    let basename = basename.as_ref();
+    let expected = if E::IS_BIG {
+        GraphEndianness::Big
+    } else {
+        GraphEndianness::Little
     };
+    let endianness = BVGraphLoader::new(basename).graph_endianness()?;
+    if endianness != expected {
+        bail!(
+            "The graph is {}-endian, but it is being loaded as {}-endian",
+            endianness.as_str(),
+            expected.as_str()
+        );
+    }
+    let (num_nodes, num_arcs, comp_flags) = parse_properties(basename)?;
+    Ok(BVGraphSequential::new(
+        DynamicCodesReaderFileBuilder::new(
+            format!("{}.graph", basename.to_string_lossy()),
+            comp_flags,
+        )?,
+        comp_flags.compression_window,
+        comp_flags.min_interval_length,
+        num_nodes,
+        Some(num_arcs),
+    ))
+}
+
+/// Load a BVGraph for random access as a [`DynRandomAccessGraph`], choosing
+/// the endianness recorded in the `.properties` file.
+///
+/// The type of the result does not depend on the endianness of the graph,
+/// at the cost of a dynamic dispatch for each method call.
+pub fn load_dyn<P: AsRef<Path>>(basename: P) -> Result<DynRandomAccessGraph<'static>> {
+    let basename = basename.as_ref();
+    Ok(match BVGraphLoader::new(basename).graph_endianness()? {
+        GraphEndianness::Big => Box::new(load(basename)?),
+        GraphEndianness::Little => Box::new(load_le(basename)?),
+    })
+}
+
+/// Load a BVGraph sequentially as a [`DynSequentialGraph`], choosing the
+/// endianness recorded in the `.properties` file.
+///
+/// The type of the result does not depend on the endianness of the graph,
+/// at the cost of a dynamic dispatch for each method call.
+pub fn load_seq_dyn<P: AsRef<Path>>(basename: P) -> Result<DynSequentialGraph<'static>> {
+    let basename = basename.as_ref();
+    Ok(match BVGraphLoader::new(basename).graph_endianness()? {
+        GraphEndianness::Big => Box::new(load_seq(basename)?),
+        GraphEndianness::Little => Box::new(load_seq_le(basename)?),
+    })
 }
 
-impl_loads! {DynamicCodesReaderBuilder, DynamicCodesReader, load, load_seq}
-impl_loads! {ConstCodesReaderBuilder, ConstCodesReader, load_const, load_seq_const}
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_load_dyn() -> Result<()> {
+    let basename = "tests/data/cnr-2000";
+    let graph = load(basename)?;
+    let dyn_graph = load_dyn(basename)?;
+    assert_eq!(dyn_graph.num_nodes(), graph.num_nodes());
+    assert_eq!(dyn_graph.num_arcs(), graph.num_arcs());
+    for node in (0..graph.num_nodes()).step_by(97) {
+        assert_eq!(dyn_graph.outdegree(node), graph.outdegree(node));
+        assert!(dyn_graph.successors(node).eq(graph.successors(node)));
+    }
+    let seq_graph = load_seq_dyn(basename)?;
+    assert_eq!(seq_graph.num_nodes(), graph.num_nodes());
+    for ((node, succ), (dyn_node, dyn_succ)) in graph.iter_nodes().zip(seq_graph.iter_nodes()) {
+        assert_eq!(node, dyn_node);
+        assert!(succ.eq(dyn_succ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_load_seq_from_bytes() -> Result<()> {
+    let basename = "tests/data/cnr-2000";
+    let properties = std::fs::read_to_string(format!("{}.properties", basename))?;
+    let bytes = std::fs::read(format!("{}.graph", basename))?;
+    let graph = load_seq_from_bytes(&properties, &bytes)?;
+    let expected = load_seq(basename)?;
+    assert_eq!(graph.num_nodes(), expected.num_nodes());
+    assert_eq!(graph.num_arcs_hint(), expected.num_arcs_hint());
+    for ((node, succ), (other, other_succ)) in graph.iter_nodes().zip(expected.iter_nodes()) {
+        assert_eq!(node, other);
+        assert!(succ.eq(other_succ));
+    }
+    let little = format!("{}endianness=little\n", properties);
+    assert!(load_seq_from_bytes(&little, &bytes).is_err());
+    Ok(())
+}
@@ -0,0 +1,730 @@
+use super::*;
+use crate::utils::LoadBackend;
+#[cfg(feature = "mmap")]
+use crate::utils::{MmapAdvice, MmapBackend};
+use anyhow::{bail, Context, Result};
+use dsi_bitstream::prelude::*;
+#[cfg(feature = "mmap")]
+use mmap_rs::MmapFlags;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use sux::prelude::{encase_mem, Flags, MemCase};
+use sux::traits::IndexedDict;
+
+/// How the files of a graph are accessed.
+///
+/// Without the `mmap` feature all modes read the files into memory
+/// allocated on the heap.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LoadMode {
+    /// Memory-map the files, so that they are paged in lazily by the
+    /// operating system.
+    #[default]
+    Mmap,
+    /// Read the files fully into memory allocated on the heap at load time.
+    LoadInMemory,
+    /// Read the files fully at load time into anonymous memory mapped with
+    /// the flags set by [`BVGraphLoader::graph_mmap_flags`] and
+    /// [`BVGraphLoader::offsets_mmap_flags`], so that it can be
+    /// backed by huge pages. After loading, accessing the graph never causes
+    /// a page fault to disk.
+    LoadInAnonymousMemory,
+}
+
+/// The offsets of a graph loaded for random access by a [`BVGraphLoader`].
+pub enum LoadedOffsets {
+    /// The offsets in the `.ef` file.
+    Stored(MemCase<crate::EF<&'static [u64]>>),
+    /// The offsets built in memory because the `.ef` file was missing.
+    Built(crate::EF<Vec<u64>>),
+}
+
+impl IndexedDict for LoadedOffsets {
+    type Value = u64;
+
+    #[inline(always)]
+    unsafe fn get_unchecked(&self, index: usize) -> u64 {
+        match self {
+            Self::Stored(ef) => IndexedDict::get_unchecked(&**ef, index),
+            Self::Built(ef) => IndexedDict::get_unchecked(ef, index),
+        }
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        match self {
+            Self::Stored(ef) => IndexedDict::len(&**ef),
+            Self::Built(ef) => IndexedDict::len(ef),
+        }
+    }
+}
+
+/// Marker for a [`BVGraphLoader`] loading a graph for random access.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RandomAccess;
+
+/// Marker for a [`BVGraphLoader`] loading a graph for sequential access.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SequentialAccess;
+
+/// Marker for a [`BVGraphLoader`] using a [`DynamicCodesReaderBuilder`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DynamicCodes;
+
+/// Marker for a [`BVGraphLoader`] using a [`ConstCodesReaderBuilder`] with
+/// the given codes, which must match those in the `.properties` file.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ConstCodes<
+    const OUTDEGREES: usize,
+    const REFERENCES: usize,
+    const BLOCKS: usize,
+    const INTERVALS: usize,
+    const RESIDUALS: usize,
+    const K: u64,
+>;
+
+/// A builder to configure how a BVGraph is loaded.
+///
+/// By default the graph is big-endian, it is loaded for random access
+/// using a [`DynamicCodesReaderBuilder`] reading 32-bit words, and its files
+/// are memory-mapped with transparent huge pages:
+/// ```ignore
+/// let graph = BVGraphLoader::new("BASENAME")
+///     .sequential()
+///     .mode(LoadMode::LoadInMemory)
+///     .endianness::<LE>()
+///     .word::<u64>()
+///     .load()?;
+/// ```
+/// The endianness, the codes and the word type are part of the type of the
+/// loaded graph,
+/// so they must be known at compile time; use [`BVGraphLoader::graph_endianness`]
+/// to choose the endianness according to the `.properties` file.
+#[derive(Clone, Debug)]
+pub struct BVGraphLoader<E: Endianness = BE, A = RandomAccess, C = DynamicCodes, W = u32> {
+    basename: PathBuf,
+    mode: LoadMode,
+    #[cfg(feature = "mmap")]
+    graph_flags: Option<MmapFlags>,
+    offsets_flags: Flags,
+    verify: bool,
+    cache_ef: bool,
+    table_hit_rate: Option<f64>,
+    _marker: PhantomData<(E, A, C, W)>,
+}
+
+impl BVGraphLoader {
+    /// Create a loader for the graph with the given basename.
+    pub fn new<P: AsRef<Path>>(basename: P) -> Self {
+        Self {
+            basename: basename.as_ref().to_owned(),
+            mode: LoadMode::default(),
+            #[cfg(feature = "mmap")]
+            graph_flags: None,
+            offsets_flags: Flags::TRANSPARENT_HUGE_PAGES,
+            verify: false,
+            cache_ef: false,
+            table_hit_rate: None,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<E: Endianness, A, C, W: BitStreamWord> BVGraphLoader<E, A, C, W> {
+    /// Change the type parameters, keeping the configuration.
+    fn cast<E2: Endianness, A2, C2, W2>(self) -> BVGraphLoader<E2, A2, C2, W2> {
+        BVGraphLoader {
+            basename: self.basename,
+            mode: self.mode,
+            #[cfg(feature = "mmap")]
+            graph_flags: self.graph_flags,
+            offsets_flags: self.offsets_flags,
+            verify: self.verify,
+            cache_ef: self.cache_ef,
+            table_hit_rate: self.table_hit_rate,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Load the graph for random access (the default).
+    pub fn random_access(self) -> BVGraphLoader<E, RandomAccess, C, W> {
+        self.cast()
+    }
+
+    /// Load the graph for sequential access, without the `.ef` file.
+    pub fn sequential(self) -> BVGraphLoader<E, SequentialAccess, C, W> {
+        self.cast()
+    }
+
+    /// Set the endianness of the graph, which is checked against the
+    /// `.properties` file.
+    pub fn endianness<E2: Endianness>(self) -> BVGraphLoader<E2, A, C, W> {
+        self.cast()
+    }
+
+    /// Use a [`DynamicCodesReaderBuilder`] (the default).
+    pub fn dynamic_codes(self) -> BVGraphLoader<E, A, DynamicCodes, W> {
+        self.cast()
+    }
+
+    /// Use a [`ConstCodesReaderBuilder`] with the given codes.
+    pub fn const_codes<
+        const OUTDEGREES: usize,
+        const REFERENCES: usize,
+        const BLOCKS: usize,
+        const INTERVALS: usize,
+        const RESIDUALS: usize,
+        const K: u64,
+    >(
+        self,
+    ) -> BVGraphLoader<E, A, ConstCodes<OUTDEGREES, REFERENCES, BLOCKS, INTERVALS, RESIDUALS, K>, W>
+    {
+        self.cast()
+    }
+
+    /// Set the type of the words in which the bitstream is read (`u32` by
+    /// default).
+    ///
+    /// Reading `u64` words halves the number of refills of the bit buffer,
+    /// which usually makes sequential scans faster on 64-bit CPUs.
+    pub fn word<W2: BitStreamWord>(self) -> BVGraphLoader<E, A, C, W2> {
+        self.cast()
+    }
+
+    /// Set how the files of the graph are accessed.
+    pub fn mode(mut self, mode: LoadMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the flags used to memory-map all the files of the graph, or the
+    /// anonymous memory they are loaded into; they are ignored if the files
+    /// are loaded on the heap.
+    pub fn mmap_flags(mut self, flags: Flags) -> Self {
+        #[cfg(feature = "mmap")]
+        {
+            self.graph_flags = Some(flags.mmap_flags());
+        }
+        self.offsets_flags = flags;
+        self
+    }
+
+    /// Set the flags used to memory-map the `.graph` file, or the anonymous
+    /// memory it is loaded into.
+    ///
+    /// By default, we use transparent huge pages, and we advise the kernel
+    /// that the file will be accessed sequentially or randomly, depending on
+    /// how the graph is loaded. Use [`MmapFlags::POPULATE`] to fault in the
+    /// whole file at load time.
+    #[cfg(feature = "mmap")]
+    pub fn graph_mmap_flags(mut self, flags: MmapFlags) -> Self {
+        self.graph_flags = Some(flags);
+        self
+    }
+
+    /// Set the flags used to memory-map the `.ef` file, or the anonymous
+    /// memory it is loaded into. By default, we use transparent huge pages.
+    pub fn offsets_mmap_flags(mut self, flags: Flags) -> Self {
+        self.offsets_flags = flags;
+        self
+    }
+
+    /// Set whether to check the digests of the files of the graph recorded
+    /// in the `.properties` file (see [`verify_checksums`]), which is
+    /// possible only with the `checksums` feature.
+    pub fn verify_checksums(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
+
+    /// Set whether to store the `.ef` file next to the graph when it is
+    /// missing and it must be built to load the graph for random access (see
+    /// [`build_ef`]), so that it is not built again at the next load.
+    pub fn cache_ef(mut self, cache_ef: bool) -> Self {
+        self.cache_ef = cache_ef;
+        self
+    }
+
+    /// Decode γ, δ and ζ₃ codes using decoding tables, whose width is chosen
+    /// at load time by sampling the codes of the graph (see
+    /// [`DynamicCodesReaderBuilder::with_sampled_table_bits`]); no tables are
+    /// used if no width reaches `min_hit_rate`. This setting is ignored by
+    /// [`ConstCodesReaderBuilder`].
+    pub fn decode_tables(mut self, min_hit_rate: f64) -> Self {
+        self.table_hit_rate = Some(min_hit_rate);
+        self
+    }
+
+    /// Return the endianness recorded in the `.properties` file.
+    pub fn graph_endianness(&self) -> Result<GraphEndianness> {
+        GraphEndianness::from_properties(&self.read_properties()?)
+    }
+
+    /// Return the compression flags recorded in the `.properties` file, so
+    /// that a graph derived from this one can be compressed in the same way.
+    pub fn comp_flags(&self) -> Result<CompFlags> {
+        CompFlags::from_properties(&self.read_properties()?)
+    }
+
+    /// Return the paths of the files containing the bitstream, which is
+    /// either in the `.graph` file or split in segments.
+    fn graph_paths(&self, segments: Option<Segments>) -> Vec<PathBuf> {
+        match segments {
+            Some(segments) => segments.paths(&self.basename),
+            None => vec![PathBuf::from(format!(
+                "{}.graph",
+                self.basename.to_string_lossy()
+            ))],
+        }
+    }
+
+    /// Return the total length in bytes of the files containing the
+    /// bitstream.
+    pub(crate) fn graph_len(&self) -> Result<u64> {
+        let segments = Segments::from_properties(&self.read_properties()?)?;
+        self.graph_paths(segments)
+            .iter()
+            .map(|path| {
+                Ok(path
+                    .metadata()
+                    .with_context(|| format!("Cannot open graph file {}", path.display()))?
+                    .len())
+            })
+            .sum()
+    }
+
+    fn read_properties(&self) -> Result<HashMap<String, String>> {
+        let properties_path = format!("{}.properties", self.basename.to_string_lossy());
+        let f = File::open(&properties_path)
+            .with_context(|| format!("Cannot open property file {}", properties_path))?;
+        java_properties::read(BufReader::new(f))
+            .with_context(|| "cannot parse the .properties file as a java properties file")
+    }
+
+    /// Read the `.properties` file, checking the endianness and optionally
+    /// the digests, and access the `.graph` file, returning its contents,
+    /// the number of nodes, the number of arcs and the compression flags.
+    ///
+    /// `sequential` is whether the graph will be accessed sequentially, which
+    /// is advised to the kernel if the file is memory-mapped.
+    fn load_graph(&self, sequential: bool) -> Result<(LoadBackend<W>, usize, usize, CompFlags)> {
+        let map = self.read_properties()?;
+        let endianness = GraphEndianness::from_properties(&map)?;
+        let expected = if E::IS_BIG {
+            GraphEndianness::Big
+        } else {
+            GraphEndianness::Little
+        };
+        if endianness != expected {
+            bail!(
+                "The graph is {}-endian, but it is being loaded as {}-endian",
+                endianness.as_str(),
+                expected.as_str()
+            );
+        }
+        if self.verify {
+            #[cfg(feature = "checksums")]
+            verify_checksums(&self.basename, &map)?;
+            #[cfg(not(feature = "checksums"))]
+            bail!("Verifying the digests of a graph requires the checksums feature");
+        }
+
+        let num_nodes = map
+            .get("nodes")
+            .with_context(|| "Missing nodes property")?
+            .parse::<u64>()
+            .with_context(|| "Cannot parse nodes as u64")?;
+        let num_arcs = map
+            .get("arcs")
+            .with_context(|| "Missing arcs property")?
+            .parse::<u64>()
+            .with_context(|| "Cannot parse arcs as u64")?;
+        let comp_flags = CompFlags::from_properties(&map)?;
+
+        #[cfg(feature = "mmap")]
+        let graph_flags = self.graph_flags.unwrap_or(
+            MmapFlags::TRANSPARENT_HUGE_PAGES
+                | if sequential {
+                    MmapFlags::SEQUENTIAL
+                } else {
+                    MmapFlags::RANDOM_ACCESS
+                },
+        );
+        #[cfg(not(feature = "mmap"))]
+        let _ = sequential;
+        let segments = Segments::from_properties(&map)?;
+        let paths = self.graph_paths(segments);
+        let file_len = paths
+            .iter()
+            .map(|path| {
+                Ok(path
+                    .metadata()
+                    .with_context(|| format!("Cannot open graph file {}", path.display()))?
+                    .len() as usize)
+            })
+            .sum::<Result<usize>>()?;
+        let data = match (self.mode, segments) {
+            #[cfg(feature = "mmap")]
+            (LoadMode::Mmap, None) => {
+                let file = File::open(&paths[0])
+                    .with_context(|| format!("Cannot open graph file {}", paths[0].display()))?;
+                LoadBackend::Mmap(MmapBackend::new(unsafe {
+                    mmap_rs::MmapOptions::new(file_len)?
+                        .with_flags(graph_flags)
+                        .with_file(file, 0)
+                        .map()?
+                }))
+            }
+            #[cfg(all(feature = "mmap", unix))]
+            (LoadMode::Mmap, Some(segments)) => {
+                let backend = MmapBackend::from_segments(&paths, segments.segment_size)?;
+                // the segments are mapped without flags, but we can still
+                // advise the access pattern
+                if graph_flags.contains(MmapFlags::SEQUENTIAL) {
+                    backend.advise(MmapAdvice::Sequential)?;
+                }
+                if graph_flags.contains(MmapFlags::RANDOM_ACCESS) {
+                    backend.advise(MmapAdvice::Random)?;
+                }
+                LoadBackend::Mmap(backend)
+            }
+            #[cfg(all(feature = "mmap", not(unix)))]
+            (LoadMode::Mmap, Some(_)) => {
+                bail!("Segmented graphs can be memory-mapped only on Unix systems")
+            }
+            #[cfg(feature = "mmap")]
+            (LoadMode::LoadInAnonymousMemory, _) => {
+                let mut mmap = mmap_rs::MmapOptions::new(file_len)?
+                    .with_flags(graph_flags)
+                    .map_mut()?;
+                read_files(&paths, mmap.as_mut_slice())?;
+                LoadBackend::Mmap(MmapBackend::new(
+                    mmap.make_read_only().map_err(|(_, err)| err)?,
+                ))
+            }
+            // LoadMode::LoadInMemory, or any mode without the mmap feature
+            _ => {
+                let len = file_len.div_ceil(core::mem::size_of::<W>());
+                let mut data = Vec::<W>::with_capacity(len);
+                // SAFETY: the words are within the allocation, and any bit
+                // pattern, including zero, is a valid unsigned integer
+                let bytes = unsafe {
+                    core::ptr::write_bytes(data.as_mut_ptr(), 0, len);
+                    data.set_len(len);
+                    core::slice::from_raw_parts_mut(data.as_mut_ptr() as *mut u8, file_len)
+                };
+                read_files(&paths, bytes)?;
+                LoadBackend::Memory(data)
+            }
+        };
+
+        Ok((data, num_nodes as usize, num_arcs as usize, comp_flags))
+    }
+
+    /// Access the `.ef` file.
+    ///
+    /// If the file is missing, the offsets are built from the `.offsets`
+    /// file, or by scanning the graph, and they are either stored next to the
+    /// graph, if [`BVGraphLoader::cache_ef`] was set, or kept in memory.
+    fn load_offsets(&self) -> Result<MemCase<LoadedOffsets>> {
+        let ef_path = format!("{}.ef", self.basename.to_string_lossy());
+        if !Path::new(&ef_path).exists() {
+            if self.cache_ef {
+                log::info!("The file {} is missing, building it", ef_path);
+                build_ef(&self.basename)?;
+            } else {
+                log::info!("The file {} is missing, building it in memory", ef_path);
+                return Ok(encase_mem(LoadedOffsets::Built(build_ef_in_memory(
+                    &self.basename,
+                )?)));
+            }
+        }
+        let ef = match self.mode {
+            #[cfg(feature = "mmap")]
+            LoadMode::Mmap => {
+                sux::prelude::map::<_, crate::EF<&[u64]>>(&ef_path, &self.offsets_flags)
+            }
+            // sux allocates anonymous memory with the given flags
+            #[cfg(feature = "mmap")]
+            LoadMode::LoadInAnonymousMemory => {
+                sux::prelude::load::<_, crate::EF<&[u64]>>(&ef_path, &self.offsets_flags)
+            }
+            // LoadMode::LoadInMemory, or any mode without the mmap feature
+            _ => sux::prelude::load::<_, crate::EF<&[u64]>>(&ef_path, &Flags::empty()),
+        }
+        .with_context(|| format!("Cannot open the elias-fano file {}", ef_path))?;
+        Ok(encase_mem(LoadedOffsets::Stored(ef)))
+    }
+}
+
+impl<E: Endianness, A, W: BitStreamWord> BVGraphLoader<E, A, DynamicCodes, W>
+where
+    for<'a> BitReader<'a, E, W>: ReadCodes<E> + BitSeek,
+{
+    /// Create the codes reader builder, sampling the codes of the graph if
+    /// decoding tables were requested.
+    fn codes_reader_builder(
+        &self,
+        data: LoadBackend<W>,
+        comp_flags: CompFlags,
+        num_nodes: usize,
+    ) -> Result<DynamicCodesReaderBuilder<E, LoadBackend<W>, W>> {
+        match self.table_hit_rate {
+            Some(min_hit_rate) => DynamicCodesReaderBuilder::with_sampled_table_bits(
+                data,
+                comp_flags,
+                num_nodes,
+                min_hit_rate,
+            ),
+            None => DynamicCodesReaderBuilder::new(data, comp_flags),
+        }
+    }
+}
+
+impl<E: Endianness, W: BitStreamWord> BVGraphLoader<E, RandomAccess, DynamicCodes, W>
+where
+    for<'a> BitReader<'a, E, W>: ReadCodes<E> + BitSeek,
+{
+    /// Load the graph.
+    pub fn load(
+        self,
+    ) -> Result<BVGraph<DynamicCodesReaderBuilder<E, LoadBackend<W>, W>, LoadedOffsets>> {
+        let (data, num_nodes, num_arcs, comp_flags) = self.load_graph(false)?;
+        Ok(BVGraph::new(
+            self.codes_reader_builder(data, comp_flags, num_nodes)?,
+            self.load_offsets()?,
+            comp_flags.min_interval_length,
+            comp_flags.compression_window,
+            num_nodes,
+            num_arcs,
+        ))
+    }
+}
+
+impl<E: Endianness, W: BitStreamWord> BVGraphLoader<E, SequentialAccess, DynamicCodes, W>
+where
+    for<'a> BitReader<'a, E, W>: ReadCodes<E> + BitSeek,
+{
+    /// Load the graph.
+    pub fn load(
+        self,
+    ) -> Result<BVGraphSequential<DynamicCodesReaderBuilder<E, LoadBackend<W>, W>>> {
+        let (data, num_nodes, num_arcs, comp_flags) = self.load_graph(true)?;
+        Ok(BVGraphSequential::new(
+            self.codes_reader_builder(data, comp_flags, num_nodes)?,
+            comp_flags.compression_window,
+            comp_flags.min_interval_length,
+            num_nodes,
+            Some(num_arcs),
+        ))
+    }
+}
+
+impl<
+        E: Endianness,
+        const OUTDEGREES: usize,
+        const REFERENCES: usize,
+        const BLOCKS: usize,
+        const INTERVALS: usize,
+        const RESIDUALS: usize,
+        const K: u64,
+        W: BitStreamWord,
+    >
+    BVGraphLoader<
+        E,
+        RandomAccess,
+        ConstCodes<OUTDEGREES, REFERENCES, BLOCKS, INTERVALS, RESIDUALS, K>,
+        W,
+    >
+where
+    for<'a> BitReader<'a, E, W>: ReadCodes<E> + BitSeek,
+{
+    /// Load the graph.
+    #[allow(clippy::type_complexity)]
+    pub fn load(
+        self,
+    ) -> Result<
+        BVGraph<
+            ConstCodesReaderBuilder<
+                E,
+                LoadBackend<W>,
+                OUTDEGREES,
+                REFERENCES,
+                BLOCKS,
+                INTERVALS,
+                RESIDUALS,
+                K,
+                W,
+            >,
+            LoadedOffsets,
+        >,
+    > {
+        let (data, num_nodes, num_arcs, comp_flags) = self.load_graph(false)?;
+        Ok(BVGraph::new(
+            ConstCodesReaderBuilder::new(data, comp_flags)?,
+            self.load_offsets()?,
+            comp_flags.min_interval_length,
+            comp_flags.compression_window,
+            num_nodes,
+            num_arcs,
+        ))
+    }
+}
+
+impl<
+        E: Endianness,
+        const OUTDEGREES: usize,
+        const REFERENCES: usize,
+        const BLOCKS: usize,
+        const INTERVALS: usize,
+        const RESIDUALS: usize,
+        const K: u64,
+        W: BitStreamWord,
+    >
+    BVGraphLoader<
+        E,
+        SequentialAccess,
+        ConstCodes<OUTDEGREES, REFERENCES, BLOCKS, INTERVALS, RESIDUALS, K>,
+        W,
+    >
+where
+    for<'a> BitReader<'a, E, W>: ReadCodes<E> + BitSeek,
+{
+    /// Load the graph.
+    #[allow(clippy::type_complexity)]
+    pub fn load(
+        self,
+    ) -> Result<
+        BVGraphSequential<
+            ConstCodesReaderBuilder<
+                E,
+                LoadBackend<W>,
+                OUTDEGREES,
+                REFERENCES,
+                BLOCKS,
+                INTERVALS,
+                RESIDUALS,
+                K,
+                W,
+            >,
+        >,
+    > {
+        let (data, num_nodes, num_arcs, comp_flags) = self.load_graph(true)?;
+        Ok(BVGraphSequential::new(
+            ConstCodesReaderBuilder::new(data, comp_flags)?,
+            comp_flags.compression_window,
+            comp_flags.min_interval_length,
+            num_nodes,
+            Some(num_arcs),
+        ))
+    }
+}
+
+/// Read the concatenation of the given files into `buffer`, which must be
+/// exactly as long as their total length.
+fn read_files(paths: &[PathBuf], mut buffer: &mut [u8]) -> Result<()> {
+    for path in paths {
+        let mut file = File::open(path)
+            .with_context(|| format!("Cannot open graph file {}", path.display()))?;
+        let len = file.metadata()?.len() as usize;
+        let (head, tail) = buffer.split_at_mut(len);
+        file.read_exact(head)
+            .with_context(|| format!("Cannot read graph file {}", path.display()))?;
+        buffer = tail;
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "mmap"))]
+#[cfg_attr(test, test)]
+fn test_loader() -> Result<()> {
+    let mmap = BVGraphLoader::new("tests/data/cnr-2000").load()?;
+    let in_memory = BVGraphLoader::new("tests/data/cnr-2000")
+        .mode(LoadMode::LoadInMemory)
+        .load()?;
+    let anonymous = BVGraphLoader::new("tests/data/cnr-2000")
+        .mode(LoadMode::LoadInAnonymousMemory)
+        .graph_mmap_flags(MmapFlags::POPULATE)
+        .load()?;
+    let u64_words = BVGraphLoader::new("tests/data/cnr-2000")
+        .word::<u64>()
+        .load()?;
+    let tables = BVGraphLoader::new("tests/data/cnr-2000")
+        .decode_tables(0.9)
+        .load()?;
+    mmap.get_codes_reader_builder()
+        .get_data()
+        .advise(crate::utils::MmapAdvice::WillNeed)?;
+    for node in (0..mmap.num_nodes()).step_by(97) {
+        assert!(mmap.successors(node).eq(in_memory.successors(node)));
+        assert!(mmap.successors(node).eq(anonymous.successors(node)));
+        assert!(mmap.successors(node).eq(u64_words.successors(node)));
+        assert!(mmap.successors(node).eq(tables.successors(node)));
+    }
+
+    let seq = BVGraphLoader::new("tests/data/cnr-2000")
+        .sequential()
+        .mode(LoadMode::LoadInMemory)
+        .const_codes::<
+            { const_codes::GAMMA },
+            { const_codes::UNARY },
+            { const_codes::GAMMA },
+            { const_codes::GAMMA },
+            { const_codes::ZETA },
+            3,
+        >()
+        .load()?;
+    for (node, successors) in seq.iter_nodes() {
+        assert!(successors.eq(mmap.successors(node)));
+    }
+
+    // the last word of the graph is padded with zeros also in memory
+    let seq = BVGraphLoader::new("tests/data/cnr-2000")
+        .sequential()
+        .mode(LoadMode::LoadInMemory)
+        .word::<u64>()
+        .load()?;
+    for (node, successors) in seq.iter_nodes() {
+        assert!(successors.eq(mmap.successors(node)));
+    }
+
+    assert!(BVGraphLoader::new("tests/data/cnr-2000")
+        .endianness::<LE>()
+        .load()
+        .is_err());
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_loader_without_ef() -> Result<()> {
+    let graph = BVGraphLoader::new("tests/data/cnr-2000").load()?;
+    // with and without the .offsets file, in which case the graph is scanned
+    for exts in [
+        &["graph", "properties", "offsets"][..],
+        &["graph", "properties"],
+    ] {
+        let dir = tempfile::tempdir()?;
+        for ext in exts {
+            std::fs::copy(
+                format!("tests/data/cnr-2000.{}", ext),
+                dir.path().join(format!("cnr-2000.{}", ext)),
+            )?;
+        }
+        let basename = dir.path().join("cnr-2000");
+        let ef_path = dir.path().join("cnr-2000.ef");
+
+        let in_memory = BVGraphLoader::new(&basename).load()?;
+        assert!(!ef_path.exists());
+        let cached = BVGraphLoader::new(&basename).cache_ef(true).load()?;
+        assert!(ef_path.exists());
+        for node in (0..graph.num_nodes()).step_by(97) {
+            assert!(graph.successors(node).eq(in_memory.successors(node)));
+            assert!(graph.successors(node).eq(cached.successors(node)));
+        }
+    }
+    Ok(())
+}
@@ -1,37 +1,104 @@
+//! The BVGraph compressed format.
+//!
+//! The instantaneous codes used by BVGraph, that is, the [code
+//! readers](DynamicCodesReader) and [writers](DynamicCodesWriter), the
+//! [decoding tables](DecodeTable) and the [compression flags](CompFlags),
+//! do not need the standard library: without the `std` feature only this
+//! core is compiled, so that bitstreams can be decoded on `no_std` targets
+//! from slices of words.
 use crate::traits::*;
 
+mod code_readers;
+pub use code_readers::*;
+
+mod decode_tables;
+pub use decode_tables::*;
+
+#[cfg(all(feature = "simd", target_arch = "x86_64", target_feature = "bmi2"))]
+mod bmi2;
+
+mod dyn_bv_code_readers;
+pub use dyn_bv_code_readers::*;
+
+mod comp_flags;
+pub use comp_flags::*;
+
+#[cfg(feature = "std")]
 mod reader_degrees;
+#[cfg(feature = "std")]
 pub use reader_degrees::*;
 
+#[cfg(feature = "std")]
 mod bvgraph_sequential;
+#[cfg(feature = "std")]
 pub use bvgraph_sequential::*;
 
+#[cfg(feature = "std")]
 pub mod bvgraph_random_access;
+#[cfg(feature = "std")]
 pub use bvgraph_random_access::*;
 
+#[cfg(feature = "std")]
 mod bvgraph_writer;
+#[cfg(feature = "std")]
 pub use bvgraph_writer::*;
 
+#[cfg(feature = "std")]
 mod bvgraph_writer_par;
+#[cfg(feature = "std")]
 pub use bvgraph_writer_par::*;
 
-mod code_readers;
-pub use code_readers::*;
-
-mod dyn_bv_code_readers;
-pub use dyn_bv_code_readers::*;
+#[cfg(feature = "std")]
+mod compress_arcs;
+#[cfg(feature = "std")]
+pub use compress_arcs::*;
 
+#[cfg(feature = "std")]
 mod masked_iterator;
+#[cfg(feature = "std")]
 pub use masked_iterator::*;
 
+#[cfg(feature = "std")]
 mod codes_opt;
+#[cfg(feature = "std")]
 pub use codes_opt::*;
 
+#[cfg(feature = "std")]
 mod code_reader_builder;
+#[cfg(feature = "std")]
 pub use code_reader_builder::*;
 
+#[cfg(feature = "std")]
 mod load;
+#[cfg(feature = "std")]
 pub use load::*;
 
-mod comp_flags;
-pub use comp_flags::*;
+#[cfg(feature = "std")]
+mod loader;
+#[cfg(feature = "std")]
+pub use loader::*;
+
+#[cfg(feature = "checksums")]
+mod checksums;
+#[cfg(feature = "checksums")]
+pub use checksums::*;
+
+#[cfg(feature = "std")]
+mod outdegrees;
+#[cfg(feature = "std")]
+pub use outdegrees::*;
+
+#[cfg(feature = "std")]
+mod offsets;
+#[cfg(feature = "std")]
+pub use offsets::*;
+
+#[cfg(feature = "std")]
+mod segments;
+#[cfg(feature = "std")]
+pub use segments::*;
+
+#[cfg(feature = "http")]
+mod http_load;
+#[cfg(feature = "http")]
+pub use http_load::*;
@@ -0,0 +1,188 @@
+use super::*;
+use anyhow::{bail, Context, Result};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::ProgressLogger;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+/// Write the `.offsets` file of the graph with the given basename, in the
+/// format of the Java version of WebGraph, and return the number of bits
+/// written.
+///
+/// The file contains the offset in bits of the successor list of each node,
+/// plus the length of the bitstream, as γ-coded gaps from the previous
+/// offset; the Java `BVGraph` needs it to load a graph for random access.
+/// The Java version caches the offsets in a `.obl` file containing a
+/// serialized Java object, which we do not write: it is rebuilt by Java from
+/// the `.offsets` file when it is missing.
+///
+/// Since the Java version reads only big-endian graphs, little-endian graphs
+/// are rejected.
+pub fn build_offsets<P: AsRef<Path>>(basename: P) -> Result<usize> {
+    let basename = basename.as_ref();
+    if BVGraphLoader::new(basename).graph_endianness()? != GraphEndianness::Big {
+        bail!(
+            "The graph {} is little-endian, but the Java version supports only big-endian graphs",
+            basename.display()
+        );
+    }
+    let seq_graph = load_seq(basename)?;
+    let seq_graph = seq_graph.map_codes_reader_builder(DynamicCodesReaderSkipperBuilder::from);
+
+    let path = format!("{}.offsets", basename.to_string_lossy());
+    let file = File::create(&path).with_context(|| format!("Cannot create {}", path))?;
+    let mut writer = <BufferedBitStreamWrite<BE, _>>::new(<FileBackend<u64, _>>::new(
+        BufWriter::with_capacity(1 << 20, file),
+    ));
+
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "offset";
+    pl.expected_updates = Some(seq_graph.num_nodes() + 1);
+    pl.start("Writing offsets...");
+    let mut written_bits = 0;
+    let mut offset = 0;
+    let mut degrees = seq_graph.iter_degrees_with_offsets();
+    for (new_offset, _node, _outdegree) in &mut degrees {
+        written_bits += writer.write_gamma(new_offset - offset)?;
+        offset = new_offset;
+        pl.light_update();
+    }
+    // the length of the bitstream, so that the last successor list can be
+    // skipped without decoding it
+    written_bits += writer.write_gamma(degrees.get_pos() - offset)?;
+    pl.light_update();
+    writer.flush()?;
+    pl.done();
+    Ok(written_bits)
+}
+
+/// Push into `efb` the offsets of the successor lists of a graph, plus the
+/// length of its bitstream, reading them from its `.offsets` file.
+fn push_read_offsets(
+    basename: &Path,
+    num_nodes: usize,
+    efb: &mut sux::prelude::EliasFanoBuilder,
+) -> Result<()> {
+    let path = format!("{}.offsets", basename.to_string_lossy());
+    let file = std::io::BufReader::with_capacity(
+        1 << 20,
+        File::open(&path).with_context(|| format!("Cannot open {}", path))?,
+    );
+    let mut reader = BufferedBitStreamRead::<BE, u64, _>::new(<FileBackend<u32, _>>::new(file));
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "offset";
+    pl.expected_updates = Some(num_nodes + 1);
+    pl.start("Translating offsets to Elias–Fano...");
+    let mut offset = 0;
+    for _ in 0..num_nodes + 1 {
+        offset += reader
+            .read_gamma()
+            .with_context(|| format!("Cannot read the offsets in {}", path))?;
+        efb.push(offset)?;
+        pl.light_update();
+    }
+    pl.done();
+    Ok(())
+}
+
+/// Push into `efb` the offsets of the successor lists of a graph, plus the
+/// length of its bitstream, computing them by scanning it.
+fn push_scanned_offsets<CRB: BVGraphCodesReaderBuilder>(
+    graph: &BVGraphSequential<CRB>,
+    efb: &mut sux::prelude::EliasFanoBuilder,
+) -> Result<()>
+where
+    for<'a> CRB::Reader<'a>: BVGraphCodesSkipper + BVGraphCodesSeek,
+{
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.item_name = "node";
+    pl.expected_updates = Some(graph.num_nodes());
+    pl.start("Building Elias–Fano by scanning the graph...");
+    let mut degrees = graph.iter_degrees_with_offsets();
+    for (offset, _node, _outdegree) in &mut degrees {
+        efb.push(offset)?;
+        pl.light_update();
+    }
+    efb.push(degrees.get_pos())?;
+    pl.done();
+    Ok(())
+}
+
+/// Build in memory the Elias–Fano representation of the offsets of the
+/// successor lists of the graph with the given basename, plus the length of
+/// its bitstream.
+///
+/// The offsets are read from the `.offsets` file, if it exists, and
+/// computed by scanning the graph otherwise. In both cases they are pushed
+/// directly into the builder, using the length in bits of the files of the
+/// bitstream as upper bound, so they are never materialized.
+pub(crate) fn build_ef_in_memory(basename: &Path) -> Result<crate::EF<Vec<u64>>> {
+    use sux::prelude::*;
+    let (num_nodes, _num_arcs, _comp_flags) = parse_properties(basename)?;
+    let loader = BVGraphLoader::new(basename);
+    let mut efb = EliasFanoBuilder::new(8 * loader.graph_len()? + 1, num_nodes as u64 + 1);
+    let offsets_path = format!("{}.offsets", basename.to_string_lossy());
+    if Path::new(&offsets_path).exists() {
+        push_read_offsets(basename, num_nodes, &mut efb)?;
+    } else {
+        match loader.graph_endianness()? {
+            GraphEndianness::Big => push_scanned_offsets(
+                &load_seq(basename)?
+                    .map_codes_reader_builder(DynamicCodesReaderSkipperBuilder::from),
+                &mut efb,
+            )?,
+            GraphEndianness::Little => push_scanned_offsets(
+                &load_seq_le(basename)?
+                    .map_codes_reader_builder(DynamicCodesReaderSkipperBuilder::from),
+                &mut efb,
+            )?,
+        }
+    }
+
+    let mut pl = ProgressLogger::default().display_memory();
+    pl.start("Building the index over the ones in the high bits...");
+    let ef: crate::EF<_> = efb.build().convert_to().unwrap();
+    pl.done();
+    Ok(ef)
+}
+
+/// Build the `.ef` file of the graph with the given basename, which contains
+/// the Elias–Fano representation of the offsets of its successor lists and
+/// is needed to load the graph for random access.
+///
+/// The offsets are read from the `.offsets` file, if it exists, as it
+/// happens for graphs compressed by the Java version, and computed by
+/// scanning the graph otherwise.
+pub fn build_ef<P: AsRef<Path>>(basename: P) -> Result<()> {
+    use sux::prelude::*;
+    let basename = basename.as_ref();
+    let ef = build_ef_in_memory(basename)?;
+    let path = format!("{}.ef", basename.to_string_lossy());
+    let mut file =
+        BufWriter::new(File::create(&path).with_context(|| format!("Cannot create {}", path))?);
+    ef.serialize(&mut file)?;
+    std::io::Write::flush(&mut file)?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_build_offsets() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    for ext in ["graph", "properties"] {
+        std::fs::copy(
+            format!("tests/data/cnr-2000.{}", ext),
+            dir.path().join(format!("cnr-2000.{}", ext)),
+        )?;
+    }
+    let basename = dir.path().join("cnr-2000");
+    build_offsets(&basename)?;
+    // the offsets written by the Java version, which pads the file only to
+    // a byte boundary
+    let expected = std::fs::read("tests/data/cnr-2000.offsets")?;
+    let offsets = std::fs::read(dir.path().join("cnr-2000.offsets"))?;
+    assert_eq!(&offsets[..expected.len()], &expected[..]);
+    assert!(offsets[expected.len()..].iter().all(|&byte| byte == 0));
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use super::*;
+use anyhow::{ensure, Context, Result};
+use dsi_progress_logger::ProgressLogger;
+use std::io::BufWriter;
+use std::path::Path;
+use sux::prelude::{CompactArray, Flags, MemCase};
+use sux::traits::{VSlice, VSliceMut};
+
+/// The outdegrees of the nodes of a graph, stored in a sux [`CompactArray`]
+/// using as many bits per node as needed by the maximum outdegree.
+///
+/// Computing the outdegree of a node of a [`BVGraph`] requires creating a
+/// reader and decoding a code; with [`BVGraph::with_outdegrees`] it becomes
+/// an array access instead. The array is built with a single pass over the
+/// degrees of the graph, and it can be stored in a sidecar file
+/// `BASENAME.outdegrees`, which can be loaded in memory or memory-mapped,
+/// like the `.ef` file.
+pub struct Outdegrees(OutdegreesArray);
+
+/// The array of an [`Outdegrees`], depending on where it comes from.
+enum OutdegreesArray {
+    /// Built in memory.
+    Built(CompactArray<Vec<u64>>),
+    /// Loaded or mapped from a file.
+    Stored(MemCase<CompactArray<&'static [u64]>>),
+}
+
+impl Outdegrees {
+    /// Pack the given outdegrees.
+    pub fn new(outdegrees: &[usize]) -> Self {
+        let max = outdegrees.iter().copied().max().unwrap_or(0);
+        let mut array = CompactArray::new(bit_width(max), outdegrees.len());
+        for (node, &outdegree) in outdegrees.iter().enumerate() {
+            array.set(node, outdegree as u64);
+        }
+        Self(OutdegreesArray::Built(array))
+    }
+
+    /// Compute the outdegrees of a graph by skipping over its successor lists.
+    pub fn build<CRB: BVGraphCodesReaderBuilder>(graph: &BVGraphSequential<CRB>) -> Self
+    where
+        for<'a> CRB::Reader<'a>: BVGraphCodesSkipper,
+    {
+        let mut pl = ProgressLogger::default();
+        pl.item_name = "node";
+        pl.expected_updates = Some(graph.num_nodes());
+        // a first pass to compute the maximum outdegree, which is cheap
+        let max = graph
+            .iter_degrees()
+            .map(|(_, outdegree)| outdegree)
+            .max()
+            .unwrap_or(0);
+        let mut array = CompactArray::new(bit_width(max), graph.num_nodes());
+        pl.start("Packing outdegrees...");
+        for (node, outdegree) in graph.iter_degrees() {
+            array.set(node, outdegree as u64);
+            pl.light_update();
+        }
+        pl.done();
+        Self(OutdegreesArray::Built(array))
+    }
+
+    /// Return the outdegree of `node`.
+    ///
+    /// # Panics
+    /// If `node` is not smaller than the number of nodes.
+    #[inline(always)]
+    pub fn get(&self, node: usize) -> usize {
+        assert!(node < self.len(), "Node {} does not exist", node);
+        (match &self.0 {
+            OutdegreesArray::Built(array) => array.get(node),
+            OutdegreesArray::Stored(array) => array.get(node),
+        }) as usize
+    }
+
+    /// Return the number of nodes.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        match &self.0 {
+            OutdegreesArray::Built(array) => array.len(),
+            OutdegreesArray::Stored(array) => array.len(),
+        }
+    }
+
+    /// Return whether there are no nodes.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the number of bits used for each outdegree.
+    #[inline(always)]
+    pub fn bit_width(&self) -> usize {
+        match &self.0 {
+            OutdegreesArray::Built(array) => array.bit_width(),
+            OutdegreesArray::Stored(array) => array.bit_width(),
+        }
+    }
+
+    /// Serialize the outdegrees in the file `BASENAME.outdegrees`.
+    pub fn store<P: AsRef<Path>>(&self, basename: P) -> Result<()> {
+        use sux::prelude::*;
+        let path = format!("{}.outdegrees", basename.as_ref().to_string_lossy());
+        let mut file = BufWriter::new(
+            std::fs::File::create(&path).with_context(|| format!("Cannot create {}", path))?,
+        );
+        match &self.0 {
+            OutdegreesArray::Built(array) => array.serialize(&mut file)?,
+            OutdegreesArray::Stored(array) => array.serialize(&mut file)?,
+        };
+        std::io::Write::flush(&mut file)?;
+        Ok(())
+    }
+
+    /// Load in memory the outdegrees from the file `BASENAME.outdegrees`.
+    pub fn load<P: AsRef<Path>>(basename: P) -> Result<Self> {
+        let path = format!("{}.outdegrees", basename.as_ref().to_string_lossy());
+        let array = sux::prelude::load::<_, CompactArray<&[u64]>>(&path, &Flags::empty())
+            .with_context(|| format!("Cannot deserialize {}", path))?;
+        Self::stored(array, &path)
+    }
+
+    /// Memory-map the outdegrees from the file `BASENAME.outdegrees`, so
+    /// that they are paged in lazily by the operating system.
+    #[cfg(feature = "mmap")]
+    pub fn map<P: AsRef<Path>>(basename: P) -> Result<Self> {
+        let path = format!("{}.outdegrees", basename.as_ref().to_string_lossy());
+        let array = sux::prelude::map::<_, CompactArray<&[u64]>>(&path, &Flags::empty())
+            .with_context(|| format!("Cannot map {}", path))?;
+        Self::stored(array, &path)
+    }
+
+    fn stored(array: MemCase<CompactArray<&'static [u64]>>, path: &str) -> Result<Self> {
+        ensure!(
+            array.bit_width() <= usize::BITS as usize,
+            "The outdegrees in {} use {} bits",
+            path,
+            array.bit_width()
+        );
+        Ok(Self(OutdegreesArray::Stored(array)))
+    }
+}
+
+/// Return the number of bits needed to represent `max`, but at least one, so
+/// that the array is never degenerate.
+fn bit_width(max: usize) -> usize {
+    (usize::BITS as usize - max.leading_zeros() as usize).max(1)
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_outdegrees() -> Result<()> {
+    use rand::prelude::*;
+    let mut rng = SmallRng::seed_from_u64(0);
+    for max in [0, 1, 5, 1000, usize::MAX] {
+        let degrees = (0..1000)
+            .map(|_| rng.gen_range(0..=max))
+            .collect::<Vec<_>>();
+        let outdegrees = Outdegrees::new(&degrees);
+        assert_eq!(outdegrees.len(), degrees.len());
+        for (node, &degree) in degrees.iter().enumerate() {
+            assert_eq!(outdegrees.get(node), degree);
+        }
+    }
+
+    let seq_graph = load_seq("tests/data/cnr-2000")?
+        .map_codes_reader_builder(DynamicCodesReaderSkipperBuilder::from);
+    let outdegrees = Outdegrees::build(&seq_graph);
+    let dir = tempfile::tempdir()?;
+    let basename = dir.path().join("cnr-2000");
+    outdegrees.store(&basename)?;
+    let outdegrees = Outdegrees::load(&basename)?;
+
+    let graph = load("tests/data/cnr-2000")?;
+    let cached = load("tests/data/cnr-2000")?.with_outdegrees(outdegrees)?;
+    for node in 0..graph.num_nodes() {
+        assert_eq!(cached.outdegree(node), graph.outdegree(node));
+    }
+    #[cfg(feature = "mmap")]
+    {
+        let mapped = load("tests/data/cnr-2000")?.with_outdegrees(Outdegrees::map(&basename)?)?;
+        for node in 0..graph.num_nodes() {
+            assert_eq!(mapped.outdegree(node), graph.outdegree(node));
+        }
+    }
+    assert!(Outdegrees::load(dir.path().join("none")).is_err());
+    Ok(())
+}
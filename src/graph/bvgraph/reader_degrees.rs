@@ -15,16 +15,18 @@ pub struct WebgraphDegreesIter<CR: BVGraphCodesReader + BVGraphCodesSkipper> {
     number_of_nodes: usize,
 }
 
-impl<CR: BVGraphCodesReader + BVGraphCodesSkipper + BitSeek> WebgraphDegreesIter<CR> {
+impl<CR: BVGraphCodesReader + BVGraphCodesSkipper + BVGraphCodesSeek> WebgraphDegreesIter<CR> {
     /// Get the current bit-offset in the bitstream
-    pub fn get_pos(&self) -> usize {
+    pub fn get_pos(&self) -> u64 {
         self.codes_reader.get_pos()
     }
 }
 
-impl<CR: BVGraphCodesReader + BVGraphCodesSkipper + BitSeek> Iterator for WebgraphDegreesIter<CR> {
-    type Item = (usize, usize, usize);
-    fn next(&mut self) -> Option<(usize, usize, usize)> {
+impl<CR: BVGraphCodesReader + BVGraphCodesSkipper + BVGraphCodesSeek> Iterator
+    for WebgraphDegreesIter<CR>
+{
+    type Item = (u64, usize, usize);
+    fn next(&mut self) -> Option<(u64, usize, usize)> {
         if self.node_id >= self.number_of_nodes {
             return None;
         }
@@ -119,7 +121,7 @@ impl<CR: BVGraphCodesReader + BVGraphCodesSkipper> WebgraphDegreesIter<CR> {
             if number_of_intervals != 0 {
                 // pre-allocate with capacity for efficency
                 #[cfg(feature = "skips")]
-                let _ = self.codes_reader.skip_interval_starts(1);
+                self.codes_reader.skip_interval_start();
                 #[cfg(not(feature = "skips"))]
                 let _ = self.codes_reader.read_interval_start();
                 let mut delta = self.codes_reader.read_interval_len() as usize;
@@ -129,7 +131,7 @@ impl<CR: BVGraphCodesReader + BVGraphCodesSkipper> WebgraphDegreesIter<CR> {
                 // decode the intervals
                 for _ in 1..number_of_intervals {
                     #[cfg(feature = "skips")]
-                    let _ = self.codes_reader.skip_interval_starts(1);
+                    self.codes_reader.skip_interval_start();
                     #[cfg(not(feature = "skips"))]
                     let _ = self.codes_reader.read_interval_start();
                     delta = self.codes_reader.read_interval_len() as usize;
@@ -144,14 +146,13 @@ impl<CR: BVGraphCodesReader + BVGraphCodesSkipper> WebgraphDegreesIter<CR> {
         if nodes_left_to_decode != 0 {
             // pre-allocate with capacity for efficency
             #[cfg(feature = "skips")]
-            let _ = self.codes_reader.skip_first_residuals(1);
+            self.codes_reader.skip_first_residual();
             #[cfg(not(feature = "skips"))]
             let _ = self.codes_reader.read_first_residual();
 
+            // skip the whole list at once
             #[cfg(feature = "skips")]
-            let _ = self
-                .codes_reader
-                .skip_residuals(nodes_left_to_decode.saturating_sub(1));
+            self.codes_reader.skip_residuals(nodes_left_to_decode - 1);
             #[cfg(not(feature = "skips"))]
             for _ in 1..nodes_left_to_decode {
                 let _ = self.codes_reader.read_residual();
@@ -163,3 +164,51 @@ impl<CR: BVGraphCodesReader + BVGraphCodesSkipper> WebgraphDegreesIter<CR> {
         Ok(degree)
     }
 }
+
+/// An iterator over pairs `(node, outdegree)`, returned by
+/// [`BVGraphSequential::iter_degrees`].
+///
+/// Unlike [`WebgraphDegreesIter`], it does not report the bit offsets of the
+/// nodes, so it does not need a [`BVGraphCodesSeek`] reader.
+pub struct WebgraphOutdegreesIter<CR: BVGraphCodesReader + BVGraphCodesSkipper> {
+    pub(crate) degrees: WebgraphDegreesIter<CR>,
+}
+
+impl<CR: BVGraphCodesReader + BVGraphCodesSkipper> Iterator for WebgraphOutdegreesIter<CR> {
+    type Item = (usize, usize);
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<(usize, usize)> {
+        if self.degrees.node_id >= self.degrees.number_of_nodes {
+            return None;
+        }
+        let node_id = self.degrees.node_id;
+        Some((node_id, self.degrees.next_degree().unwrap()))
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.degrees.number_of_nodes - self.degrees.node_id;
+        (len, Some(len))
+    }
+}
+
+impl<CR: BVGraphCodesReader + BVGraphCodesSkipper> ExactSizeIterator
+    for WebgraphOutdegreesIter<CR>
+{
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_iter_degrees() -> Result<()> {
+    let seq_graph = crate::graph::bvgraph::load_seq("tests/data/cnr-2000")?;
+    let degrees_graph = crate::graph::bvgraph::load_seq("tests/data/cnr-2000")?
+        .map_codes_reader_builder(DynamicCodesReaderSkipperBuilder::from);
+    let mut degrees = degrees_graph.iter_degrees();
+    assert_eq!(degrees.len(), seq_graph.num_nodes());
+    for (node, successors) in &seq_graph {
+        assert_eq!(degrees.next(), Some((node, successors.count())));
+    }
+    assert_eq!(degrees.next(), None);
+    Ok(())
+}
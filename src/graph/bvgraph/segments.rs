@@ -0,0 +1,194 @@
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// The size of the segments must be a multiple of this number of bytes,
+/// which is a multiple of the page size on all common systems, so that
+/// segments can be memory-mapped next to each other.
+pub const SEGMENT_ALIGN: usize = 1 << 16;
+
+/// The layout of a bitstream split across the segment files
+/// `BASENAME.graph-0000`, `BASENAME.graph-0001`, ... instead of a single
+/// `BASENAME.graph` file.
+///
+/// All segments but the last one contain exactly `segment_size` bytes. The
+/// layout is recorded in the `.properties` file under the keys `segments`
+/// and `segmentsize`; the Java version ignores them, but it cannot read the
+/// segments.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Segments {
+    /// The number of segments.
+    pub num_segments: usize,
+    /// The size in bytes of each segment but the last one.
+    pub segment_size: usize,
+}
+
+impl Segments {
+    /// Return the layout recorded in the decoded `.properties` file, or
+    /// `None` if the bitstream is in a single `.graph` file.
+    pub fn from_properties(map: &HashMap<String, String>) -> Result<Option<Self>> {
+        let (Some(num_segments), Some(segment_size)) =
+            (map.get("segments"), map.get("segmentsize"))
+        else {
+            return Ok(None);
+        };
+        let segments = Self {
+            num_segments: num_segments
+                .parse()
+                .with_context(|| "Cannot parse segments as usize")?,
+            segment_size: segment_size
+                .parse()
+                .with_context(|| "Cannot parse segmentsize as usize")?,
+        };
+        if segments.num_segments == 0 || segments.segment_size % SEGMENT_ALIGN != 0 {
+            bail!("Invalid segments {:?}", segments);
+        }
+        Ok(Some(segments))
+    }
+
+    /// Return the lines of the `.properties` file recording the layout.
+    pub fn to_properties(&self) -> String {
+        format!(
+            "segments={}\nsegmentsize={}\n",
+            self.num_segments, self.segment_size
+        )
+    }
+
+    /// Return the paths of the segments of the graph with the given basename.
+    pub fn paths<P: AsRef<Path>>(&self, basename: P) -> Vec<PathBuf> {
+        (0..self.num_segments)
+            .map(|index| segment_path(basename.as_ref(), index))
+            .collect()
+    }
+}
+
+/// Return the path of the segment of index `index` of the graph with the
+/// given basename.
+pub fn segment_path<P: AsRef<Path>>(basename: P, index: usize) -> PathBuf {
+    PathBuf::from(format!(
+        "{}.graph-{:04}",
+        basename.as_ref().to_string_lossy(),
+        index
+    ))
+}
+
+/// A writer splitting the bytes written into segments of the given size.
+///
+/// The first segment is created immediately, so there is at least one
+/// segment even if nothing is written.
+pub struct SegmentedWriter {
+    basename: PathBuf,
+    segment_size: usize,
+    writer: BufWriter<File>,
+    /// The number of segments created so far.
+    num_segments: usize,
+    /// The number of bytes written in the last segment.
+    written: usize,
+}
+
+impl SegmentedWriter {
+    /// Create a writer on the segments of the graph with the given basename.
+    ///
+    /// `segment_size` must be a positive multiple of [`SEGMENT_ALIGN`].
+    pub fn new<P: AsRef<Path>>(basename: P, segment_size: usize) -> Result<Self> {
+        if segment_size == 0 || segment_size % SEGMENT_ALIGN != 0 {
+            bail!(
+                "The segment size {} is not a positive multiple of {}",
+                segment_size,
+                SEGMENT_ALIGN
+            );
+        }
+        let basename = basename.as_ref().to_owned();
+        let writer = Self::create(&basename, 0).with_context(|| {
+            format!(
+                "Cannot create the segment {}",
+                segment_path(&basename, 0).display()
+            )
+        })?;
+        Ok(Self {
+            basename,
+            segment_size,
+            writer,
+            num_segments: 1,
+            written: 0,
+        })
+    }
+
+    fn create(basename: &Path, index: usize) -> std::io::Result<BufWriter<File>> {
+        File::create(segment_path(basename, index)).map(BufWriter::new)
+    }
+
+    /// Flush the last segment and return the layout of the segments.
+    pub fn finish(mut self) -> Result<Segments> {
+        self.writer.flush()?;
+        Ok(Segments {
+            num_segments: self.num_segments,
+            segment_size: self.segment_size,
+        })
+    }
+}
+
+impl Write for SegmentedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.written == self.segment_size {
+            self.writer.flush()?;
+            self.writer = Self::create(&self.basename, self.num_segments)?;
+            self.num_segments += 1;
+            self.written = 0;
+        }
+        let len = buf.len().min(self.segment_size - self.written);
+        let written = self.writer.write(&buf[..len])?;
+        self.written += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_segments() -> Result<()> {
+    use crate::prelude::*;
+    use dsi_bitstream::prelude::*;
+
+    let dir = tempfile::tempdir()?;
+    let basename = dir.path().join("cnr-2000");
+    let graph = load_seq("tests/data/cnr-2000")?;
+    parallel_compress_sequential_iter_segmented(
+        &basename,
+        graph.iter_nodes(),
+        &node_balanced_bounds(graph.num_nodes(), 3),
+        CompFlags::default(),
+        GraphEndianness::Big,
+        SEGMENT_ALIGN,
+    )?;
+    let properties = std::fs::read_to_string(format!("{}.properties", basename.display()))?;
+    let map = java_properties::read(properties.as_bytes())?;
+    let segments = Segments::from_properties(&map)?.unwrap();
+    assert!(segments.num_segments > 1);
+    for path in &segments.paths(&basename)[..segments.num_segments - 1] {
+        assert_eq!(std::fs::metadata(path)?.len() as usize, SEGMENT_ALIGN);
+    }
+
+    for mode in [
+        LoadMode::Mmap,
+        LoadMode::LoadInMemory,
+        LoadMode::LoadInAnonymousMemory,
+    ] {
+        let segmented = BVGraphLoader::new(&basename)
+            .sequential()
+            .endianness::<BE>()
+            .mode(mode)
+            .load()?;
+        assert_eq!(segmented.num_nodes(), graph.num_nodes());
+        for ((node, a), (other, b)) in segmented.iter_nodes().zip(graph.iter_nodes()) {
+            assert_eq!(node, other);
+            assert!(a.eq(b));
+        }
+    }
+    Ok(())
+}
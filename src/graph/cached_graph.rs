@@ -0,0 +1,252 @@
+use crate::traits::*;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// The memory we account for each cached list, besides its successors.
+const ENTRY_OVERHEAD: usize = 64;
+
+/// A wrapper around a random-access graph keeping in memory the most
+/// recently decoded lists of successors, up to a given memory budget.
+///
+/// Lists are evicted in least-recently-used order. This is useful for
+/// workloads, such as random walks or personalized PageRank, that revisit
+/// often the same nodes, as for such nodes the cost of decoding is replaced
+/// by a hash-table lookup; for workloads without locality, the cache is pure
+/// overhead. Sequential iteration is delegated to the underlying graph and
+/// does not use the cache.
+///
+/// The cache is protected by a mutex, so the graph can be shared between
+/// threads, but lists are decoded outside of the critical section.
+pub struct CachedGraph<G: RandomAccessGraph> {
+    graph: G,
+    cache: Mutex<LruCache>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+/// A least-recently-used cache of lists of successors.
+struct LruCache {
+    /// The memory budget in bytes.
+    budget: usize,
+    /// The memory currently used, in bytes.
+    used: usize,
+    /// The successors of each cached node, and the time of its last use.
+    lists: HashMap<usize, (Arc<[usize]>, u64)>,
+    /// The cached nodes, by time of last use.
+    by_time: BTreeMap<u64, usize>,
+    /// The current time.
+    time: u64,
+}
+
+impl LruCache {
+    fn new(budget: usize) -> Self {
+        Self {
+            budget,
+            used: 0,
+            lists: HashMap::new(),
+            by_time: BTreeMap::new(),
+            time: 0,
+        }
+    }
+
+    /// Return the memory accounted for a list of `len` successors.
+    fn cost(len: usize) -> usize {
+        ENTRY_OVERHEAD + len * core::mem::size_of::<usize>()
+    }
+
+    fn get(&mut self, node: usize) -> Option<Arc<[usize]>> {
+        let (list, time) = self.lists.get_mut(&node)?;
+        self.by_time.remove(time);
+        self.time += 1;
+        *time = self.time;
+        self.by_time.insert(self.time, node);
+        Some(list.clone())
+    }
+
+    fn insert(&mut self, node: usize, list: Arc<[usize]>) {
+        let cost = Self::cost(list.len());
+        // lists larger than the budget, or inserted concurrently by another
+        // thread, are not cached
+        if cost > self.budget || self.lists.contains_key(&node) {
+            return;
+        }
+        while self.used + cost > self.budget {
+            let (_, evicted) = self.by_time.pop_first().unwrap();
+            let (evicted, _) = self.lists.remove(&evicted).unwrap();
+            self.used -= Self::cost(evicted.len());
+        }
+        self.time += 1;
+        self.lists.insert(node, (list, self.time));
+        self.by_time.insert(self.time, node);
+        self.used += cost;
+    }
+}
+
+impl<G: RandomAccessGraph> CachedGraph<G> {
+    /// Wrap `graph` with a cache using at most (approximately) `budget`
+    /// bytes.
+    pub fn new(graph: G, budget: usize) -> Self {
+        Self {
+            graph,
+            cache: Mutex::new(LruCache::new(budget)),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+
+    /// Return the number of calls to
+    /// [`successors`](RandomAccessGraph::successors) that found the list in
+    /// the cache.
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Return the number of calls to
+    /// [`successors`](RandomAccessGraph::successors) that had to decode the
+    /// list.
+    pub fn misses(&self) -> usize {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Empty the cache, keeping the budget.
+    pub fn clear(&self) {
+        let mut cache = self.cache.lock().unwrap();
+        *cache = LruCache::new(cache.budget);
+    }
+
+    /// Consume the wrapper and return the underlying graph.
+    pub fn into_inner(self) -> G {
+        self.graph
+    }
+
+    /// Return the successors of `node_id` if they are in the cache.
+    fn cached(&self, node_id: usize) -> Option<Arc<[usize]>> {
+        self.cache.lock().unwrap().get(node_id)
+    }
+}
+
+impl<G: RandomAccessGraph> SequentialGraph for CachedGraph<G> {
+    type NodesIter<'a> = G::NodesIter<'a>
+		where Self: 'a;
+    type SequentialSuccessorIter<'a> = G::SequentialSuccessorIter<'a>
+		where Self: 'a;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.graph.num_nodes()
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<usize> {
+        self.graph.num_arcs_hint()
+    }
+
+    #[inline(always)]
+    fn iter_nodes(&self) -> Self::NodesIter<'_> {
+        self.graph.iter_nodes()
+    }
+
+    #[inline(always)]
+    fn iter_nodes_from(&self, start_node: usize) -> Self::NodesIter<'_> {
+        self.graph.iter_nodes_from(start_node)
+    }
+}
+
+impl<G: RandomAccessGraph> RandomAccessGraph for CachedGraph<G> {
+    type RandomSuccessorIter<'a> = CachedSuccessorIter
+		where Self: 'a;
+
+    #[inline(always)]
+    fn num_arcs(&self) -> usize {
+        self.graph.num_arcs()
+    }
+
+    fn successors(&self, node_id: usize) -> Self::RandomSuccessorIter<'_> {
+        let list = match self.cached(node_id) {
+            Some(list) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                list
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                let list: Arc<[usize]> = self.graph.successors(node_id).collect();
+                self.cache.lock().unwrap().insert(node_id, list.clone());
+                list
+            }
+        };
+        CachedSuccessorIter { list, pos: 0 }
+    }
+
+    /// Return the outdegree of a node, using the cache if the node is there,
+    /// but without caching its successors otherwise.
+    fn outdegree(&self, node_id: usize) -> usize {
+        match self.cached(node_id) {
+            Some(list) => list.len(),
+            None => self.graph.outdegree(node_id),
+        }
+    }
+
+    /// Return whether an arc exists, using the cache if the source is there,
+    /// but without caching its successors otherwise.
+    fn has_arc(&self, src_node_id: usize, dst_node_id: usize) -> bool {
+        match self.cached(src_node_id) {
+            Some(list) => list.binary_search(&dst_node_id).is_ok(),
+            None => self.graph.has_arc(src_node_id, dst_node_id),
+        }
+    }
+}
+
+/// An iterator over a list of successors cached by a [`CachedGraph`].
+#[derive(Clone, Debug)]
+pub struct CachedSuccessorIter {
+    list: Arc<[usize]>,
+    pos: usize,
+}
+
+impl Iterator for CachedSuccessorIter {
+    type Item = usize;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<usize> {
+        let succ = *self.list.get(self.pos)?;
+        self.pos += 1;
+        Some(succ)
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.list.len() - self.pos;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for CachedSuccessorIter {}
+
+/// The lists of successors of a random-access graph are sorted
+unsafe impl SortedIterator for CachedSuccessorIter {}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_cached_graph() {
+    use crate::graph::vec_graph::VecGraph;
+    let graph = VecGraph::from_arc_list(&[(0, 1), (0, 2), (1, 2), (2, 0), (2, 1), (3, 0)]);
+    // room for two small lists
+    let cached = CachedGraph::new(graph.clone(), 2 * LruCache::cost(2));
+
+    for node in [0, 1, 0, 2, 0, 3, 0, 1] {
+        assert!(cached.successors(node).eq(graph.successors(node)));
+        assert_eq!(cached.outdegree(node), graph.outdegree(node));
+        for dst in 0..4 {
+            assert_eq!(cached.has_arc(node, dst), graph.has_arc(node, dst));
+        }
+    }
+    // 0 is used every other time, so it is never the least recently used
+    // node, and it is never evicted
+    assert_eq!(cached.misses(), 5);
+    assert_eq!(cached.hits(), 3);
+
+    cached.clear();
+    cached.successors(0).for_each(drop);
+    assert_eq!(cached.misses(), 6);
+}
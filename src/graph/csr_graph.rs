@@ -0,0 +1,415 @@
+use crate::traits::*;
+use anyhow::{ensure, Context, Result};
+use core::ops::Deref;
+use epserde::prelude::*;
+use std::path::Path;
+
+/// An immutable, possibly labelled graph in compressed sparse row format.
+///
+/// The successors of node `v` are `successors[offsets[v]..offsets[v + 1]]`,
+/// in increasing order, and their labels are at the same positions of
+/// `labels`. Unlabelled graphs have `()` labels, which take no space.
+///
+/// Graphs with unit or numeric labels can be stored with ε-serde in the file
+/// `BASENAME.csr` using [`CsrGraph::store`], and then either deserialized
+/// with [`CsrGraph::load`] or memory-mapped with [`CsrGraph::map`], in which
+/// case the three arrays are slices of the mapped file and the graph is
+/// paged in lazily by the operating system.
+#[derive(Epserde, Debug, Clone, PartialEq, Eq)]
+pub struct CsrGraph<O = Vec<usize>, S = Vec<usize>, L = Vec<()>> {
+    /// The `num_nodes + 1` positions of the successor lists in `successors`.
+    offsets: O,
+    /// The concatenated successor lists.
+    successors: S,
+    /// The labels of the arcs, in the same order as `successors`.
+    labels: L,
+}
+
+/// A [`CsrGraph`] memory-mapped by [`CsrGraph::map`].
+pub type MappedCsrGraph<T = ()> = CsrGraph<&'static [usize], &'static [usize], &'static [T]>;
+
+impl<T: Clone> CsrGraph<Vec<usize>, Vec<usize>, Vec<T>> {
+    /// Build a graph by scanning a labelled sequential graph, sorting the
+    /// successors of each node.
+    pub fn from_labelled_seq_graph<G>(graph: &G) -> Self
+    where
+        G: SequentialGraph + Labelled<Label = T>,
+        for<'a> G::SequentialSuccessorIter<'a>: LabelledIterator<Label = T>,
+    {
+        let mut offsets = Vec::with_capacity(graph.num_nodes() + 1);
+        let mut successors = Vec::with_capacity(graph.num_arcs_hint().unwrap_or(0));
+        let mut labels = Vec::with_capacity(graph.num_arcs_hint().unwrap_or(0));
+        let mut succ = Vec::new();
+        offsets.push(0);
+        for (_, iter) in graph.iter_nodes() {
+            succ.extend(iter.labelled());
+            succ.sort_by_key(|(dst, _)| *dst);
+            for (dst, label) in succ.drain(..) {
+                successors.push(dst);
+                labels.push(label);
+            }
+            offsets.push(successors.len());
+        }
+        Self {
+            offsets,
+            successors,
+            labels,
+        }
+    }
+}
+
+impl CsrGraph {
+    /// Build an unlabelled graph by scanning a sequential graph, sorting the
+    /// successors of each node.
+    pub fn from_seq_graph<G: SequentialGraph>(graph: &G) -> Self {
+        let mut offsets = Vec::with_capacity(graph.num_nodes() + 1);
+        let mut successors = Vec::with_capacity(graph.num_arcs_hint().unwrap_or(0));
+        offsets.push(0);
+        for (_, iter) in graph.iter_nodes() {
+            let start = successors.len();
+            successors.extend(iter);
+            successors[start..].sort_unstable();
+            offsets.push(successors.len());
+        }
+        Self {
+            labels: vec![(); successors.len()],
+            offsets,
+            successors,
+        }
+    }
+}
+
+impl<O: AsRef<[usize]>, S: AsRef<[usize]>, L: Deref<Target = [T]>, T> CsrGraph<O, S, L> {
+    /// Check that the arrays describe a graph, so that a corrupted file is
+    /// reported when it is loaded rather than by a panic later.
+    ///
+    /// The offsets must be non-decreasing, start from zero and end at the
+    /// number of arcs, and the successors of each node must be nodes of the
+    /// graph, in increasing order.
+    fn check(&self) -> Result<()> {
+        let offsets = self.offsets.as_ref();
+        let successors = self.successors.as_ref();
+        ensure!(!offsets.is_empty(), "The offsets of the graph are empty");
+        ensure!(
+            offsets[0] == 0,
+            "The first offset ({}) is not zero",
+            offsets[0]
+        );
+        ensure!(
+            offsets[offsets.len() - 1] == successors.len(),
+            "The last offset ({}) is not the number of arcs ({})",
+            offsets[offsets.len() - 1],
+            successors.len()
+        );
+        ensure!(
+            self.labels.len() == successors.len(),
+            "The number of labels ({}) is not the number of arcs ({})",
+            self.labels.len(),
+            successors.len()
+        );
+        for (node, bounds) in offsets.windows(2).enumerate() {
+            ensure!(
+                bounds[0] <= bounds[1],
+                "The offsets of nodes {} and {} ({} and {}) are decreasing",
+                node,
+                node + 1,
+                bounds[0],
+                bounds[1]
+            );
+        }
+        // now all offsets are at most the number of arcs
+        let num_nodes = offsets.len() - 1;
+        for (node, bounds) in offsets.windows(2).enumerate() {
+            let succ = &successors[bounds[0]..bounds[1]];
+            if let Some(&last) = succ.last() {
+                ensure!(
+                    last < num_nodes,
+                    "Node {} has successor {}, but the graph has {} nodes",
+                    node,
+                    last,
+                    num_nodes
+                );
+            }
+            ensure!(
+                succ.windows(2).all(|pair| pair[0] <= pair[1]),
+                "The successors of node {} are not sorted",
+                node
+            );
+        }
+        Ok(())
+    }
+}
+
+macro_rules! impl_csr_store {
+    ($($ty:ty),*) => {$(
+        impl CsrGraph<Vec<usize>, Vec<usize>, Vec<$ty>> {
+            /// Serialize the graph with ε-serde in the file `BASENAME.csr`.
+            pub fn store<P: AsRef<Path>>(&self, basename: P) -> Result<()> {
+                let path = format!("{}.csr", basename.as_ref().to_string_lossy());
+                let mut file = std::io::BufWriter::new(
+                    std::fs::File::create(&path)
+                        .with_context(|| format!("Cannot create {}", path))?,
+                );
+                self.serialize(&mut file)?;
+                std::io::Write::flush(&mut file)?;
+                Ok(())
+            }
+
+            /// Deserialize a graph stored by [`CsrGraph::store`] from the file
+            /// `BASENAME.csr`.
+            pub fn load<P: AsRef<Path>>(basename: P) -> Result<Self> {
+                let path = format!("{}.csr", basename.as_ref().to_string_lossy());
+                let graph = <Self>::load_full(&path)
+                    .with_context(|| format!("Cannot deserialize {}", path))?;
+                graph.check().with_context(|| format!("Invalid graph in {}", path))?;
+                Ok(graph)
+            }
+
+            /// Memory-map a graph stored by [`CsrGraph::store`] from the file
+            /// `BASENAME.csr`.
+            #[cfg(feature = "mmap")]
+            pub fn map<P: AsRef<Path>>(basename: P) -> Result<MemCase<MappedCsrGraph<$ty>>> {
+                let path = format!("{}.csr", basename.as_ref().to_string_lossy());
+                let graph = <Self>::mmap(&path, Flags::empty())
+                    .with_context(|| format!("Cannot map {}", path))?;
+                graph.check().with_context(|| format!("Invalid graph in {}", path))?;
+                Ok(graph)
+            }
+        }
+    )*};
+}
+
+impl_csr_store!((), u8, u16, u32, u64, usize, i32, i64, f32, f64);
+
+impl<O: AsRef<[usize]>, S: AsRef<[usize]>, L: Deref<Target = [T]>, T: Clone> Labelled
+    for CsrGraph<O, S, L>
+{
+    type Label = T;
+}
+
+impl<O: AsRef<[usize]>, S: AsRef<[usize]>, L: Deref<Target = [T]>, T: Clone> SequentialGraph
+    for CsrGraph<O, S, L>
+{
+    type NodesIter<'a> = SequentialGraphImplIter<'a, Self>
+        where
+            Self: 'a;
+
+    type SequentialSuccessorIter<'a> = CsrSuccessorIter<'a, L>
+        where
+            Self: 'a;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.offsets.as_ref().len().saturating_sub(1)
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<usize> {
+        Some(self.num_arcs())
+    }
+
+    #[inline(always)]
+    fn iter_nodes(&self) -> Self::NodesIter<'_> {
+        SequentialGraphImplIter {
+            graph: self,
+            nodes: (0..self.num_nodes()),
+        }
+    }
+
+    #[inline(always)]
+    fn iter_nodes_from(&self, start_node: usize) -> Self::NodesIter<'_> {
+        SequentialGraphImplIter {
+            graph: self,
+            nodes: (start_node.min(self.num_nodes())..self.num_nodes()),
+        }
+    }
+}
+
+impl<O: AsRef<[usize]>, S: AsRef<[usize]>, L: Deref<Target = [T]>, T: Clone> SplitSequentialGraph
+    for CsrGraph<O, S, L>
+{
+}
+
+impl<O: AsRef<[usize]>, S: AsRef<[usize]>, L: Deref<Target = [T]>, T: Clone> RandomAccessGraph
+    for CsrGraph<O, S, L>
+{
+    type RandomSuccessorIter<'a> = CsrSuccessorIter<'a, L>
+        where
+            Self: 'a;
+
+    #[inline(always)]
+    fn num_arcs(&self) -> usize {
+        self.successors.as_ref().len()
+    }
+
+    #[inline(always)]
+    fn outdegree(&self, node: usize) -> usize {
+        let offsets = self.offsets.as_ref();
+        offsets[node + 1] - offsets[node]
+    }
+
+    #[inline(always)]
+    fn successors(&self, node: usize) -> Self::RandomSuccessorIter<'_> {
+        let offsets = self.offsets.as_ref();
+        let (start, end) = (offsets[node], offsets[node + 1]);
+        CsrSuccessorIter {
+            successors: self.successors.as_ref()[start..end].iter(),
+            labels: &self.labels,
+            first: start,
+            pos: start,
+        }
+    }
+}
+
+impl<O: AsRef<[usize]>, S: AsRef<[usize]>, L: Deref<Target = [T]>, T: Clone> SeekableGraph
+    for CsrGraph<O, S, L>
+{
+    fn successors_from(&self, node: usize, lower_bound: usize) -> Self::RandomSuccessorIter<'_> {
+        let mut successors = self.successors(node);
+        let skip = successors
+            .successors
+            .as_slice()
+            .partition_point(|&succ| succ < lower_bound);
+        successors.successors = successors.successors.as_slice()[skip..].iter();
+        successors.first += skip;
+        successors.pos += skip;
+        successors
+    }
+}
+
+/// An iterator over the successors of a node of a [`CsrGraph`].
+pub struct CsrSuccessorIter<'a, L> {
+    successors: core::slice::Iter<'a, usize>,
+    /// The labels of all the arcs of the graph.
+    labels: &'a L,
+    /// The position in `labels` of the first successor.
+    first: usize,
+    /// The position in `labels` of the next successor.
+    pos: usize,
+}
+
+impl<'a, L> Iterator for CsrSuccessorIter<'a, L> {
+    type Item = usize;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let succ = *self.successors.next()?;
+        self.pos += 1;
+        Some(succ)
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.successors.size_hint()
+    }
+}
+
+impl<'a, L> ExactSizeIterator for CsrSuccessorIter<'a, L> {}
+
+impl<'a, L: Deref<Target = [T]>, T: Clone> Labelled for CsrSuccessorIter<'a, L> {
+    type Label = T;
+}
+
+impl<'a, L: Deref<Target = [T]>, T: Clone> LabelledIterator for CsrSuccessorIter<'a, L> {
+    fn label(&self) -> Self::Label {
+        assert!(
+            self.pos > self.first,
+            "You cannot call label() on an iterator that has not been advanced yet!"
+        );
+        self.labels[self.pos - 1].clone()
+    }
+}
+
+/// Successors are stored in increasing order
+unsafe impl<'a, L> SortedIterator for CsrSuccessorIter<'a, L> {}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_csr_graph() -> Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    let g = VecGraph::from_arc_list(&[(0, 3), (0, 1), (1, 2), (2, 0), (2, 1), (2, 4), (4, 4)]);
+    let csr = CsrGraph::from_seq_graph(&g);
+    assert_eq!(csr.num_nodes(), 5);
+    assert_eq!(csr.num_arcs(), 7);
+    assert_eq!(csr.outdegree(3), 0);
+    assert!(csr.iter_arcs().eq(g.iter_arcs()));
+    assert!(csr.successors_from(2, 1).eq([1, 4]));
+    assert!(csr.successors_from(2, 5).eq([]));
+    assert!(csr.has_arc(4, 4));
+
+    let dir = tempfile::tempdir()?;
+    let basename = dir.path().join("graph");
+    csr.store(&basename)?;
+    assert_eq!(<CsrGraph>::load(&basename)?, csr);
+    #[cfg(feature = "mmap")]
+    {
+        let mapped = <CsrGraph>::map(&basename)?;
+        assert!(mapped.iter_arcs().eq(csr.iter_arcs()));
+        assert!(mapped.successors_from(0, 2).eq([3]));
+    }
+    assert!(<CsrGraph>::load(dir.path().join("none")).is_err());
+
+    let g = VecGraph::from_arc_and_label_list(&[(0, 1, 1.5), (1, 2, -2.0), (1, 0, 0.25)]);
+    let csr = CsrGraph::from_labelled_seq_graph(&g);
+    assert!(csr.successors(1).labelled().eq([(0, 0.25), (2, -2.0)]));
+    assert!(csr.successors_from(1, 1).labelled().eq([(2, -2.0)]));
+    csr.store(&basename)?;
+    assert_eq!(
+        <CsrGraph<Vec<usize>, Vec<usize>, Vec<f64>>>::load(&basename)?,
+        csr
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_csr_graph_check_offsets() -> Result<()> {
+    let csr = |offsets: Vec<usize>| -> CsrGraph {
+        CsrGraph {
+            offsets,
+            successors: vec![1, 2, 0],
+            labels: vec![(); 3],
+        }
+    };
+    csr(vec![0, 2, 2, 3]).check()?;
+    // node 1 would have successors 2..1
+    assert!(csr(vec![0, 2, 1, 3]).check().is_err());
+    // node 0 would have successors 0..4
+    assert!(csr(vec![0, 4, 2, 3]).check().is_err());
+    assert!(csr(vec![1, 2, 2, 3]).check().is_err());
+    assert!(csr(vec![0, 2, 2, 4]).check().is_err());
+
+    // a corrupted file is rejected when loaded
+    let dir = tempfile::tempdir()?;
+    let basename = dir.path().join("graph");
+    csr(vec![0, 2, 1, 3]).store(&basename)?;
+    assert!(<CsrGraph>::load(&basename).is_err());
+    #[cfg(feature = "mmap")]
+    assert!(<CsrGraph>::map(&basename).is_err());
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_csr_graph_check_successors() -> Result<()> {
+    let csr = |successors: Vec<usize>| -> CsrGraph {
+        CsrGraph {
+            offsets: vec![0, 2, 2, 3],
+            successors,
+            labels: vec![(); 3],
+        }
+    };
+    csr(vec![1, 2, 0]).check()?;
+    assert!(csr(vec![1, 3, 0]).check().is_err());
+    assert!(csr(vec![1, 2, usize::MAX]).check().is_err());
+    // successors must be sorted
+    assert!(csr(vec![2, 1, 0]).check().is_err());
+
+    let dir = tempfile::tempdir()?;
+    let basename = dir.path().join("graph");
+    csr(vec![1, 3, 0]).store(&basename)?;
+    assert!(<CsrGraph>::load(&basename).is_err());
+    #[cfg(feature = "mmap")]
+    assert!(<CsrGraph>::map(&basename).is_err());
+    Ok(())
+}
@@ -1,9 +1,33 @@
+#[cfg(feature = "std")]
+pub mod ascii_graph;
 pub mod bvgraph;
+#[cfg(feature = "std")]
+pub mod cached_graph;
+#[cfg(feature = "std")]
+pub mod csr_graph;
+#[cfg(feature = "std")]
 pub mod permuted_graph;
+#[cfg(feature = "std")]
+pub mod random;
+#[cfg(feature = "std")]
+pub mod slice_graph;
+#[cfg(feature = "std")]
 pub mod vec_graph;
 
 pub mod prelude {
+    #[cfg(feature = "std")]
+    pub use super::ascii_graph::*;
     pub use super::bvgraph::*;
+    #[cfg(feature = "std")]
+    pub use super::cached_graph::*;
+    #[cfg(feature = "std")]
+    pub use super::csr_graph::*;
+    #[cfg(feature = "std")]
     pub use super::permuted_graph::*;
+    #[cfg(feature = "std")]
+    pub use super::random::*;
+    #[cfg(feature = "std")]
+    pub use super::slice_graph::*;
+    #[cfg(feature = "std")]
     pub use super::vec_graph::*;
 }
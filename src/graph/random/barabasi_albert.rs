@@ -0,0 +1,146 @@
+use crate::traits::*;
+use rand::prelude::*;
+use rand_xoshiro::Xoshiro256PlusPlus;
+
+/// A random graph in the preferential-attachment model of Barabási and
+/// Albert.
+///
+/// Nodes are added one at a time, and each new node has arcs towards
+/// `num_arcs_per_node` distinct previous nodes, chosen with probability
+/// proportional to their current degree (indegree plus outdegree); the first
+/// `num_arcs_per_node` nodes have arcs towards all the previous nodes. The
+/// result has a power-law degree distribution, and all arcs point backwards.
+///
+/// The graph is generated lazily during the iteration, but since the choice
+/// of the successors of a node depends on all the previous nodes, it can only
+/// be generated from the start, and the iterator keeps in memory the
+/// endpoints of all the arcs generated so far.
+#[derive(Clone, Copy, Debug)]
+pub struct BarabasiAlbert {
+    num_nodes: usize,
+    num_arcs_per_node: usize,
+    seed: u64,
+}
+
+impl BarabasiAlbert {
+    /// Create a random graph with `num_nodes` nodes in which each node
+    /// attaches to `num_arcs_per_node` previous nodes, generated from the
+    /// given seed.
+    pub fn new(num_nodes: usize, num_arcs_per_node: usize, seed: u64) -> Self {
+        Self {
+            num_nodes,
+            num_arcs_per_node,
+            seed,
+        }
+    }
+}
+
+impl SequentialGraph for BarabasiAlbert {
+    type NodesIter<'a> = BarabasiAlbertNodesIter;
+    type SequentialSuccessorIter<'a> = std::vec::IntoIter<usize>;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.num_nodes
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<usize> {
+        let m = self.num_arcs_per_node;
+        // node v has min(v, m) successors
+        let clique = m.min(self.num_nodes);
+        Some(clique * clique.saturating_sub(1) / 2 + (self.num_nodes - clique) * m)
+    }
+
+    #[inline(always)]
+    fn iter_nodes(&self) -> Self::NodesIter<'_> {
+        BarabasiAlbertNodesIter {
+            graph: *self,
+            rng: Xoshiro256PlusPlus::seed_from_u64(self.seed),
+            endpoints: Vec::new(),
+            next_node: 0,
+        }
+    }
+}
+
+/// An iterator over the nodes of a [`BarabasiAlbert`] graph.
+#[derive(Clone, Debug)]
+pub struct BarabasiAlbertNodesIter {
+    graph: BarabasiAlbert,
+    rng: Xoshiro256PlusPlus,
+    /// Both endpoints of every arc generated so far: sampling uniformly from
+    /// this list chooses a node with probability proportional to its degree.
+    endpoints: Vec<usize>,
+    next_node: usize,
+}
+
+impl Iterator for BarabasiAlbertNodesIter {
+    type Item = (usize, std::vec::IntoIter<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next_node;
+        if node >= self.graph.num_nodes {
+            return None;
+        }
+        self.next_node += 1;
+
+        let successors = if node <= self.graph.num_arcs_per_node {
+            (0..node).collect::<Vec<_>>()
+        } else {
+            let mut successors = Vec::with_capacity(self.graph.num_arcs_per_node);
+            // all previous nodes have positive degree, so there are enough
+            // distinct candidates
+            while successors.len() < self.graph.num_arcs_per_node {
+                let candidate = self.endpoints[self.rng.gen_range(0..self.endpoints.len())];
+                if !successors.contains(&candidate) {
+                    successors.push(candidate);
+                }
+            }
+            successors.sort_unstable();
+            successors
+        };
+        for &succ in &successors {
+            self.endpoints.push(node);
+            self.endpoints.push(succ);
+        }
+        Some((node, successors.into_iter()))
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.graph.num_nodes - self.next_node;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for BarabasiAlbertNodesIter {}
+
+/// Nodes are generated in increasing order
+unsafe impl SortedIterator for BarabasiAlbertNodesIter {}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_barabasi_albert() {
+    let graph = BarabasiAlbert::new(1000, 3, 0);
+    let mut num_arcs = 0;
+    let mut indegrees = vec![0; graph.num_nodes()];
+    for (node, successors) in graph.iter_nodes() {
+        let successors = successors.collect::<Vec<_>>();
+        assert_eq!(successors.len(), node.min(3));
+        assert!(successors.windows(2).all(|w| w[0] < w[1]));
+        assert!(successors.iter().all(|&succ| succ < node));
+        for succ in successors {
+            indegrees[succ] += 1;
+        }
+        num_arcs += node.min(3);
+    }
+    assert_eq!(graph.num_arcs_hint(), Some(num_arcs));
+    // preferential attachment creates hubs among the oldest nodes
+    assert!(*indegrees.iter().max().unwrap() > 30);
+
+    // the graph is the same at every iteration
+    assert!(graph
+        .iter_nodes()
+        .zip(graph.iter_nodes())
+        .all(|((_, a), (_, b))| a.eq(b)));
+}
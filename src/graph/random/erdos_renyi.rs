@@ -0,0 +1,182 @@
+use crate::traits::*;
+use rand::prelude::*;
+use rand_xoshiro::Xoshiro256PlusPlus;
+
+/// Return a seed for the generator of the successors of `node` mixing
+/// `seed` and `node` with SplitMix64, so that the successors of each node
+/// can be generated independently.
+#[inline(always)]
+pub(crate) fn node_seed(seed: u64, node: usize) -> u64 {
+    let mut z = seed.wrapping_add((node as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// A random directed graph in the G(*n*, *p*) model of Erdős and Rényi: each
+/// of the *n*(*n* − 1) possible arcs (loops are excluded) is present
+/// independently with probability *p*.
+///
+/// The graph is never materialized: the successors of each node are
+/// generated lazily, in increasing order, by jumping directly from one
+/// successor to the next with geometrically distributed gaps. Each node has
+/// its own generator, derived from the seed of the graph, so the graph is the
+/// same at every iteration, and skipping nodes costs nothing.
+#[derive(Clone, Copy, Debug)]
+pub struct ErdosRenyi {
+    num_nodes: usize,
+    p: f64,
+    seed: u64,
+}
+
+impl ErdosRenyi {
+    /// Create a random graph with `num_nodes` nodes and arc probability `p`,
+    /// generated from the given seed.
+    ///
+    /// # Panics
+    /// If `p` is not in `[0, 1]`.
+    pub fn new(num_nodes: usize, p: f64, seed: u64) -> Self {
+        assert!((0.0..=1.0).contains(&p), "Invalid arc probability {}", p);
+        Self { num_nodes, p, seed }
+    }
+}
+
+impl SequentialGraph for ErdosRenyi {
+    type NodesIter<'a> = ErdosRenyiNodesIter;
+    type SequentialSuccessorIter<'a> = ErdosRenyiSuccessorIter;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.num_nodes
+    }
+
+    #[inline(always)]
+    fn iter_nodes(&self) -> Self::NodesIter<'_> {
+        self.iter_nodes_from(0)
+    }
+
+    #[inline(always)]
+    fn iter_nodes_from(&self, start_node: usize) -> Self::NodesIter<'_> {
+        ErdosRenyiNodesIter {
+            graph: *self,
+            nodes: start_node.min(self.num_nodes)..self.num_nodes,
+        }
+    }
+}
+
+/// An iterator over the nodes of an [`ErdosRenyi`] graph.
+#[derive(Clone, Debug)]
+pub struct ErdosRenyiNodesIter {
+    graph: ErdosRenyi,
+    nodes: core::ops::Range<usize>,
+}
+
+impl Iterator for ErdosRenyiNodesIter {
+    type Item = (usize, ErdosRenyiSuccessorIter);
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.nodes.next()?;
+        Some((
+            node,
+            ErdosRenyiSuccessorIter {
+                rng: Xoshiro256PlusPlus::seed_from_u64(node_seed(self.graph.seed, node)),
+                node,
+                pos: 0,
+                // loops are excluded, so there are n - 1 candidate successors
+                len: self.graph.num_nodes - 1,
+                p: self.graph.p,
+                log_q: (1.0 - self.graph.p).ln(),
+            },
+        ))
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.nodes.size_hint()
+    }
+}
+
+impl ExactSizeIterator for ErdosRenyiNodesIter {}
+
+/// We iter on the node ids in a range so it is sorted
+unsafe impl SortedIterator for ErdosRenyiNodesIter {}
+
+/// An iterator over the successors of a node of an [`ErdosRenyi`] graph.
+#[derive(Clone, Debug)]
+pub struct ErdosRenyiSuccessorIter {
+    rng: Xoshiro256PlusPlus,
+    /// The node whose successors are generated.
+    node: usize,
+    /// The next candidate position, where positions skip `node`.
+    pos: usize,
+    /// The number of candidate positions.
+    len: usize,
+    p: f64,
+    /// The logarithm of 1 − *p*.
+    log_q: f64,
+}
+
+impl Iterator for ErdosRenyiSuccessorIter {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.len || self.p == 0.0 {
+            return None;
+        }
+        if self.p < 1.0 {
+            // the number of missing arcs before the next one is geometric
+            let u: f64 = self.rng.gen();
+            let skip = ((1.0 - u).ln() / self.log_q).floor();
+            if skip >= (self.len - self.pos) as f64 {
+                self.pos = self.len;
+                return None;
+            }
+            self.pos += skip as usize;
+        }
+        let pos = self.pos;
+        self.pos += 1;
+        // skip the node itself
+        Some(if pos < self.node { pos } else { pos + 1 })
+    }
+}
+
+unsafe impl SortedIterator for ErdosRenyiSuccessorIter {}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_erdos_renyi() {
+    let n = 1000;
+    let graph = ErdosRenyi::new(n, 0.01, 0);
+    let mut num_arcs = 0;
+    for (node, successors) in graph.iter_nodes() {
+        let successors = successors.collect::<Vec<_>>();
+        assert!(successors.windows(2).all(|w| w[0] < w[1]));
+        assert!(successors.iter().all(|&succ| succ < n && succ != node));
+        num_arcs += successors.len();
+    }
+    // the expected number of arcs is 9990, with standard deviation about 100
+    assert!((9000..11000).contains(&num_arcs), "{}", num_arcs);
+
+    // the graph is the same at every iteration, from any starting node
+    let (node, successors) = graph.iter_nodes_from(500).next().unwrap();
+    assert_eq!(node, 500);
+    assert_eq!(
+        successors.collect::<Vec<_>>(),
+        graph.iter_nodes().nth(500).unwrap().1.collect::<Vec<_>>()
+    );
+
+    // extreme probabilities
+    let empty = ErdosRenyi::new(10, 0.0, 0);
+    assert!(empty
+        .iter_nodes()
+        .all(|(_, mut succ)| succ.next().is_none()));
+    let complete = ErdosRenyi::new(10, 1.0, 0);
+    for (node, successors) in complete.iter_nodes() {
+        assert_eq!(
+            successors.collect::<Vec<_>>(),
+            (0..10).filter(|&x| x != node).collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,17 @@
+//! Seeded generators of random graphs.
+//!
+//! The generators expose their graphs through [`SequentialGraph`](crate::traits::SequentialGraph)
+//! without materializing them, so that tests and benchmarks can run on
+//! synthetic graphs of arbitrary size.
+
+mod erdos_renyi;
+pub use erdos_renyi::*;
+
+mod rmat;
+pub use rmat::*;
+
+mod barabasi_albert;
+pub use barabasi_albert::*;
+
+mod watts_strogatz;
+pub use watts_strogatz::*;
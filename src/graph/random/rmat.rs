@@ -0,0 +1,146 @@
+use crate::utils::{sort_arcs, SortedArcsGraph};
+use anyhow::Result;
+use rand::prelude::*;
+use rand_xoshiro::Xoshiro256PlusPlus;
+
+/// The number of arcs per node generated by the Graph500 benchmark.
+pub const GRAPH500_EDGE_FACTOR: usize = 16;
+
+/// A generator of arcs in the R-MAT model of Chakrabarti, Zhan and Faloutsos.
+///
+/// The graph has 2<sup>`scale`</sup> nodes, and each arc is placed by
+/// descending `scale` times into one of the four quadrants of the adjacency
+/// matrix, chosen with probabilities `a`, `b`, `c` and `1 - a - b - c`
+/// (top-left, top-right, bottom-left and bottom-right, respectively). Skewed
+/// probabilities yield the heavy-tailed degree distributions and community
+/// structure of real-world graphs.
+#[derive(Clone, Copy, Debug)]
+pub struct Rmat {
+    scale: u32,
+    a: f64,
+    b: f64,
+    c: f64,
+    seed: u64,
+}
+
+impl Rmat {
+    /// Create an R-MAT generator with 2<sup>`scale`</sup> nodes and the given
+    /// quadrant probabilities.
+    ///
+    /// # Panics
+    /// If the probabilities are negative or their sum exceeds one, or if
+    /// `scale` is too large for a `usize`.
+    pub fn new(scale: u32, a: f64, b: f64, c: f64, seed: u64) -> Self {
+        assert!(
+            a >= 0.0 && b >= 0.0 && c >= 0.0 && a + b + c <= 1.0,
+            "Invalid quadrant probabilities {}, {}, {}",
+            a,
+            b,
+            c
+        );
+        assert!(scale < usize::BITS, "Scale {} is too large", scale);
+        Self {
+            scale,
+            a,
+            b,
+            c,
+            seed,
+        }
+    }
+
+    /// Create an R-MAT generator with the parameters of the Graph500
+    /// benchmark, that is, probabilities 0.57, 0.19, 0.19 and 0.05.
+    pub fn graph500(scale: u32, seed: u64) -> Self {
+        Self::new(scale, 0.57, 0.19, 0.19, seed)
+    }
+
+    /// Return the number of nodes.
+    pub fn num_nodes(&self) -> usize {
+        1 << self.scale
+    }
+
+    /// Return an endless iterator over random arcs; the same seed always
+    /// yields the same sequence. Arcs may repeat, and loops are possible.
+    pub fn arcs(&self) -> RmatArcs {
+        RmatArcs {
+            rmat: *self,
+            rng: Xoshiro256PlusPlus::seed_from_u64(self.seed),
+        }
+    }
+
+    /// Return a sequential graph view of the graph formed by the first
+    /// `num_arcs` arcs returned by [`Rmat::arcs`], without duplicates, so the
+    /// result might have fewer arcs.
+    ///
+    /// The arcs are sorted out of core by [`sort_arcs`] with batches of
+    /// `batch_size` pairs, so the graph can be arbitrarily large.
+    pub fn to_graph(&self, num_arcs: usize, batch_size: usize) -> Result<SortedArcsGraph> {
+        sort_arcs(
+            self.arcs().take(num_arcs),
+            Some(self.num_nodes()),
+            batch_size,
+        )
+    }
+}
+
+/// An endless iterator over the arcs generated by an [`Rmat`] generator.
+#[derive(Clone, Debug)]
+pub struct RmatArcs {
+    rmat: Rmat,
+    rng: Xoshiro256PlusPlus,
+}
+
+impl Iterator for RmatArcs {
+    type Item = (usize, usize);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let ab = self.rmat.a + self.rmat.b;
+        let abc = ab + self.rmat.c;
+        let (mut src, mut dst) = (0, 0);
+        for _ in 0..self.rmat.scale {
+            let r: f64 = self.rng.gen();
+            let (src_bit, dst_bit) = if r < self.rmat.a {
+                (0, 0)
+            } else if r < ab {
+                (0, 1)
+            } else if r < abc {
+                (1, 0)
+            } else {
+                (1, 1)
+            };
+            src = (src << 1) | src_bit;
+            dst = (dst << 1) | dst_bit;
+        }
+        Some((src, dst))
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_rmat() -> Result<()> {
+    use crate::traits::SequentialGraph;
+    let rmat = Rmat::graph500(10, 0);
+    assert_eq!(rmat.num_nodes(), 1024);
+    let num_arcs = GRAPH500_EDGE_FACTOR * rmat.num_nodes();
+    let arcs = rmat.arcs().take(num_arcs).collect::<Vec<_>>();
+    assert!(arcs
+        .iter()
+        .all(|&(src, dst)| src < rmat.num_nodes() && dst < rmat.num_nodes()));
+    // the same seed yields the same arcs
+    assert_eq!(rmat.arcs().take(num_arcs).collect::<Vec<_>>(), arcs);
+
+    let graph = rmat.to_graph(num_arcs, 1000)?;
+    let mut degrees = vec![0; graph.num_nodes()];
+    let mut total = 0;
+    for (node, successors) in graph.iter_nodes() {
+        let successors = successors.collect::<Vec<_>>();
+        assert!(successors.windows(2).all(|w| w[0] < w[1]));
+        degrees[node] = successors.len();
+        total += successors.len();
+    }
+    assert!(total <= num_arcs);
+    // the top-left quadrant is the most likely, so node 0 is a hub
+    assert_eq!(degrees.iter().max(), Some(&degrees[0]));
+    Ok(())
+}
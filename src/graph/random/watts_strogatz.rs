@@ -0,0 +1,175 @@
+use super::erdos_renyi::node_seed;
+use crate::traits::*;
+use rand::prelude::*;
+use rand_xoshiro::Xoshiro256PlusPlus;
+
+/// A random directed graph in the small-world model of Watts and Strogatz.
+///
+/// Nodes are placed on a ring, and each node has arcs towards the
+/// `num_neighbors / 2` nodes preceding it and the `num_neighbors / 2` nodes
+/// following it; then, each arc is independently rewired with probability
+/// `beta` towards a node chosen uniformly at random, avoiding loops and
+/// duplicate arcs. Small values of `beta` yield graphs with both high
+/// clustering and short paths. Since rewiring is performed on each arc
+/// independently, the result is not symmetric.
+///
+/// The graph is never materialized: each node has its own generator, derived
+/// from the seed of the graph, so the graph is the same at every iteration,
+/// and skipping nodes costs nothing.
+#[derive(Clone, Copy, Debug)]
+pub struct WattsStrogatz {
+    num_nodes: usize,
+    num_neighbors: usize,
+    beta: f64,
+    seed: u64,
+}
+
+impl WattsStrogatz {
+    /// Create a random graph with `num_nodes` nodes, each with
+    /// `num_neighbors` successors, and rewiring probability `beta`,
+    /// generated from the given seed.
+    ///
+    /// # Panics
+    /// If `beta` is not in `[0, 1]`, or if `num_neighbors` is not even or is
+    /// not smaller than `num_nodes`.
+    pub fn new(num_nodes: usize, num_neighbors: usize, beta: f64, seed: u64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&beta),
+            "Invalid rewiring probability {}",
+            beta
+        );
+        assert!(
+            num_neighbors % 2 == 0,
+            "The number of neighbors ({}) must be even",
+            num_neighbors
+        );
+        assert!(
+            num_neighbors < num_nodes.max(1),
+            "The number of neighbors ({}) must be smaller than the number of nodes ({})",
+            num_neighbors,
+            num_nodes
+        );
+        Self {
+            num_nodes,
+            num_neighbors,
+            beta,
+            seed,
+        }
+    }
+}
+
+impl SequentialGraph for WattsStrogatz {
+    type NodesIter<'a> = WattsStrogatzNodesIter;
+    type SequentialSuccessorIter<'a> = std::vec::IntoIter<usize>;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.num_nodes
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<usize> {
+        Some(self.num_nodes * self.num_neighbors)
+    }
+
+    #[inline(always)]
+    fn iter_nodes(&self) -> Self::NodesIter<'_> {
+        self.iter_nodes_from(0)
+    }
+
+    #[inline(always)]
+    fn iter_nodes_from(&self, start_node: usize) -> Self::NodesIter<'_> {
+        WattsStrogatzNodesIter {
+            graph: *self,
+            nodes: start_node.min(self.num_nodes)..self.num_nodes,
+        }
+    }
+}
+
+/// An iterator over the nodes of a [`WattsStrogatz`] graph.
+#[derive(Clone, Debug)]
+pub struct WattsStrogatzNodesIter {
+    graph: WattsStrogatz,
+    nodes: core::ops::Range<usize>,
+}
+
+impl Iterator for WattsStrogatzNodesIter {
+    type Item = (usize, std::vec::IntoIter<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.nodes.next()?;
+        let n = self.graph.num_nodes;
+        let half = self.graph.num_neighbors / 2;
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(node_seed(self.graph.seed, node));
+
+        let lattice = (1..=half).flat_map(|d| [(node + n - d) % n, (node + d) % n]);
+        let mut successors = Vec::with_capacity(self.graph.num_neighbors);
+        let mut rewired = 0;
+        for succ in lattice {
+            if rng.gen_bool(self.graph.beta) {
+                rewired += 1;
+            } else {
+                successors.push(succ);
+            }
+        }
+        // rewired arcs must avoid loops and the arcs already present
+        for _ in 0..rewired {
+            loop {
+                let succ = rng.gen_range(0..n);
+                if succ != node && !successors.contains(&succ) {
+                    successors.push(succ);
+                    break;
+                }
+            }
+        }
+        successors.sort_unstable();
+        Some((node, successors.into_iter()))
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.nodes.size_hint()
+    }
+}
+
+impl ExactSizeIterator for WattsStrogatzNodesIter {}
+
+/// We iter on the node ids in a range so it is sorted
+unsafe impl SortedIterator for WattsStrogatzNodesIter {}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_watts_strogatz() {
+    // without rewiring we get the ring lattice
+    let lattice = WattsStrogatz::new(10, 4, 0.0, 0);
+    let (node, successors) = lattice.iter_nodes().next().unwrap();
+    assert_eq!(node, 0);
+    assert_eq!(successors.collect::<Vec<_>>(), vec![1, 2, 8, 9]);
+
+    let graph = WattsStrogatz::new(1000, 6, 0.1, 0);
+    let mut num_arcs = 0;
+    let mut num_rewired = 0;
+    for (node, successors) in graph.iter_nodes() {
+        let successors = successors.collect::<Vec<_>>();
+        assert_eq!(successors.len(), 6);
+        assert!(successors.windows(2).all(|w| w[0] < w[1]));
+        assert!(successors.iter().all(|&succ| succ < 1000 && succ != node));
+        num_rewired += successors
+            .iter()
+            .filter(|&&succ| {
+                let d = (succ + 1000 - node) % 1000;
+                (4..997).contains(&d)
+            })
+            .count();
+        num_arcs += successors.len();
+    }
+    assert_eq!(graph.num_arcs_hint(), Some(num_arcs));
+    // about 10% of the arcs are rewired
+    assert!((400..800).contains(&num_rewired), "{}", num_rewired);
+
+    // the graph is the same at every iteration, from any starting node
+    assert!(graph
+        .iter_nodes_from(500)
+        .zip(graph.iter_nodes().skip(500))
+        .all(|((x, a), (y, b))| x == y && a.eq(b)));
+}
@@ -0,0 +1,172 @@
+use crate::traits::*;
+
+#[derive(Clone)]
+/// A graph wrapper exposing the subgraph of a random-access graph induced by
+/// the nodes in the range `[start, end)`, whose ids are remapped to
+/// `0..end - start`.
+///
+/// The successors of each node are obtained by random access, so only the
+/// part of the underlying graph containing the nodes of the range is decoded.
+/// Since successors are sorted, each list is scanned only until the end of
+/// the range.
+pub struct SliceGraph<'a, G: RandomAccessGraph> {
+    graph: &'a G,
+    start: usize,
+    end: usize,
+}
+
+impl<'a, G: RandomAccessGraph> SliceGraph<'a, G> {
+    /// Create the slice of `graph` induced by the nodes in `[start, end)`.
+    ///
+    /// # Panics
+    /// If `start > end` or `end` is larger than the number of nodes of `graph`.
+    pub fn new(graph: &'a G, start: usize, end: usize) -> Self {
+        assert!(
+            start <= end,
+            "The start of the slice ({}) is after its end ({})",
+            start,
+            end
+        );
+        assert!(
+            end <= graph.num_nodes(),
+            "The end of the slice ({}) is larger than the number of nodes ({})",
+            end,
+            graph.num_nodes()
+        );
+        Self { graph, start, end }
+    }
+}
+
+impl<'a, G: RandomAccessGraph> SequentialGraph for SliceGraph<'a, G> {
+    type NodesIter<'b> = SliceNodesIter<'b, G>
+		where Self: 'b;
+    type SequentialSuccessorIter<'b> = SliceSuccessorIter<G::RandomSuccessorIter<'b>>
+		where Self: 'b;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.end - self.start
+    }
+
+    #[inline(always)]
+    fn iter_nodes(&self) -> Self::NodesIter<'_> {
+        self.iter_nodes_from(0)
+    }
+
+    #[inline(always)]
+    fn iter_nodes_from(&self, start_node: usize) -> Self::NodesIter<'_> {
+        SliceNodesIter {
+            graph: self.graph,
+            nodes: (self.start + start_node).min(self.end)..self.end,
+            start: self.start,
+        }
+    }
+}
+
+/// An iterator over the nodes of a [`SliceGraph`].
+pub struct SliceNodesIter<'a, G: RandomAccessGraph> {
+    graph: &'a G,
+    /// The nodes still to be returned, in the ids of the underlying graph.
+    nodes: core::ops::Range<usize>,
+    /// The first node of the slice.
+    start: usize,
+}
+
+impl<'a, G: RandomAccessGraph> Clone for SliceNodesIter<'a, G> {
+    fn clone(&self) -> Self {
+        Self {
+            graph: self.graph,
+            nodes: self.nodes.clone(),
+            start: self.start,
+        }
+    }
+}
+
+impl<'a, G: RandomAccessGraph> Iterator for SliceNodesIter<'a, G> {
+    type Item = (usize, SliceSuccessorIter<G::RandomSuccessorIter<'a>>);
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.nodes.next()?;
+        Some((
+            node - self.start,
+            SliceSuccessorIter {
+                iter: self.graph.successors(node),
+                start: self.start,
+                end: self.nodes.end,
+            },
+        ))
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.nodes.size_hint()
+    }
+}
+
+impl<'a, G: RandomAccessGraph> ExactSizeIterator for SliceNodesIter<'a, G> {}
+
+/// We iter on the node ids in a range so it is sorted
+unsafe impl<'a, G: RandomAccessGraph> SortedIterator for SliceNodesIter<'a, G> {}
+
+/// An iterator over the successors of a node of a [`SliceGraph`]: it skips
+/// the successors before the start of the slice, stops at the first successor
+/// after its end, and remaps the remaining ones.
+pub struct SliceSuccessorIter<I: Iterator<Item = usize>> {
+    iter: I,
+    start: usize,
+    end: usize,
+}
+
+impl<I: Iterator<Item = usize>> Iterator for SliceSuccessorIter<I> {
+    type Item = usize;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let succ = self.iter.next()?;
+            if succ >= self.end {
+                return None;
+            }
+            if succ >= self.start {
+                return Some(succ - self.start);
+            }
+        }
+    }
+}
+
+unsafe impl<I: Iterator<Item = usize> + SortedIterator> SortedIterator for SliceSuccessorIter<I> {}
+
+#[cfg(test)]
+#[test]
+fn test_slice_graph() -> anyhow::Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    let g = VecGraph::from_arc_list(&[
+        (0, 1),
+        (0, 3),
+        (1, 2),
+        (1, 4),
+        (2, 0),
+        (2, 1),
+        (2, 3),
+        (3, 3),
+        (4, 2),
+    ]);
+    let s = SliceGraph::new(&g, 1, 4);
+    assert_eq!(s.num_nodes(), 3);
+    let v = VecGraph::from_node_iter(s.iter_nodes());
+    assert_eq!(v.num_nodes(), 3);
+    assert_eq!(v.successors(0).collect::<Vec<_>>(), vec![1]);
+    assert_eq!(v.successors(1).collect::<Vec<_>>(), vec![0, 2]);
+    assert_eq!(v.successors(2).collect::<Vec<_>>(), vec![2]);
+
+    let nodes = s
+        .iter_nodes_from(2)
+        .map(|(node, _)| node)
+        .collect::<Vec<_>>();
+    assert_eq!(nodes, vec![2]);
+
+    let empty = SliceGraph::new(&g, 2, 2);
+    assert_eq!(empty.iter_nodes().count(), 0);
+    Ok(())
+}
@@ -1,16 +1,60 @@
+use crate::graph::csr_graph::CsrGraph;
 use crate::traits::*;
 use alloc::collections::BTreeSet;
+use anyhow::Result;
+use std::path::Path;
 
 /// Vector-based mutable [`Graph`] implementation.
 /// Successors are represented using a [`BTreeSet`].
+///
+/// With the `serde` feature, graphs implement `Serialize` and `Deserialize`
+/// as the list of the successor lists of the nodes, in which each successor
+/// is paired with its label. The number of arcs is not serialized, but
+/// recomputed on deserialization, and nodes appearing only as successors are
+/// added to the graph.
+///
+/// Graphs with unit or numeric labels can also be stored with ε-serde using
+/// [`VecGraph::store`], in the format of [`CsrGraph`], so that they can be
+/// memory-mapped with [`CsrGraph::map`].
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "VecGraphSuccessors<L>"))]
 pub struct VecGraph<L: Clone> {
     /// The number of arcs in the graph.
+    #[cfg_attr(feature = "serde", serde(skip))]
     number_of_arcs: usize,
     /// For each node, its list of successors.
     succ: Vec<BTreeSet<DstWithLabel<L>>>,
 }
 
+/// The serialized fields of a [`VecGraph`], from which the number of arcs is
+/// recomputed on deserialization.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct VecGraphSuccessors<L> {
+    succ: Vec<BTreeSet<DstWithLabel<L>>>,
+}
+
+#[cfg(feature = "serde")]
+impl<L: Clone> From<VecGraphSuccessors<L>> for VecGraph<L> {
+    fn from(value: VecGraphSuccessors<L>) -> Self {
+        let mut g = Self {
+            number_of_arcs: value.succ.iter().map(BTreeSet::len).sum(),
+            succ: value.succ,
+        };
+        if let Some(max_dst) = g
+            .succ
+            .iter()
+            .filter_map(|succ| succ.last())
+            .map(|x| x.0)
+            .max()
+        {
+            g.add_node(max_dst);
+        }
+        g
+    }
+}
+
 impl<L: Clone> core::default::Default for VecGraph<L> {
     fn default() -> Self {
         Self::new()
@@ -34,6 +78,71 @@ impl<L: Clone> VecGraph<L> {
         }
     }
 
+    /// Create a new empty graph with room for `num_nodes` nodes.
+    pub fn with_capacity(num_nodes: usize) -> Self {
+        Self {
+            number_of_arcs: 0,
+            succ: Vec::with_capacity(num_nodes),
+        }
+    }
+
+    /// Build a graph with at least `num_nodes` nodes from an iterator on
+    /// labelled arcs sorted by source and destination.
+    ///
+    /// The successors of each node are collected in a vector and the set of
+    /// successors is bulk-built from it, which is much faster than inserting
+    /// the arcs one by one as [`VecGraph::from_arc_and_label_list`] does.
+    /// Nodes larger than `num_nodes` appearing in the arcs are added to the
+    /// graph; of duplicate arcs, only the label of the first one is kept.
+    ///
+    /// # Panics
+    /// If the arcs are not sorted.
+    pub fn from_sorted_labelled_arcs<I: IntoIterator<Item = (usize, usize, L)>>(
+        num_nodes: usize,
+        arcs: I,
+    ) -> Self {
+        let mut g = Self::with_capacity(num_nodes);
+        let mut succ = Vec::new();
+        let mut prev = None;
+        let mut max_dst = 0;
+        for (src, dst, label) in arcs {
+            if let Some((prev_src, prev_dst)) = prev {
+                assert!(
+                    (prev_src, prev_dst) <= (src, dst),
+                    "The arcs are not sorted: ({}, {}) follows ({}, {})",
+                    src,
+                    dst,
+                    prev_src,
+                    prev_dst
+                );
+                if prev_src != src {
+                    g.set_sorted_successors(prev_src, &mut succ);
+                } else if prev_dst == dst {
+                    continue;
+                }
+            }
+            succ.push(DstWithLabel(dst, label));
+            max_dst = max_dst.max(dst);
+            prev = Some((src, dst));
+        }
+        if let Some((src, _)) = prev {
+            g.set_sorted_successors(src, &mut succ);
+            g.add_node(max_dst);
+        }
+        if num_nodes > 0 {
+            g.add_node(num_nodes - 1);
+        }
+        g
+    }
+
+    /// Set the successors of a node without successors from a sorted vector
+    /// without duplicates, which is left empty.
+    fn set_sorted_successors(&mut self, node: usize, succ: &mut Vec<DstWithLabel<L>>) {
+        self.add_node(node);
+        self.number_of_arcs += succ.len();
+        self.succ[node] = succ.drain(..).collect();
+    }
+
     /// Convert a COO arc list into a graph by sorting and deduplicating.
     pub fn from_arc_and_label_list(arcs: &[(usize, usize, L)]) -> Self {
         let mut g = Self::new();
@@ -113,6 +222,20 @@ impl VecGraph<()> {
         g
     }
 
+    /// Build a graph with at least `num_nodes` nodes from an iterator on arcs
+    /// sorted by source and destination.
+    ///
+    /// See [`VecGraph::from_sorted_labelled_arcs`].
+    ///
+    /// # Panics
+    /// If the arcs are not sorted.
+    pub fn from_sorted_arcs<I: IntoIterator<Item = (usize, usize)>>(
+        num_nodes: usize,
+        arcs: I,
+    ) -> Self {
+        Self::from_sorted_labelled_arcs(num_nodes, arcs.into_iter().map(|(u, v)| (u, v, ())))
+    }
+
     /// Add an arc to the graph and return a reference to self to allow a
     /// builder-like usage.
     pub fn add_arc_list(&mut self, arcs: &[(usize, usize)]) -> &mut Self {
@@ -169,6 +292,45 @@ impl VecGraph<()> {
     }
 }
 
+macro_rules! impl_vec_graph_store {
+    ($($ty:ty),*) => {$(
+        impl VecGraph<$ty> {
+            /// Serialize the graph with ε-serde as a [`CsrGraph`] in the file
+            /// `BASENAME.csr`.
+            pub fn store<P: AsRef<Path>>(&self, basename: P) -> Result<()> {
+                CsrGraph::from_labelled_seq_graph(self).store(basename)
+            }
+
+            /// Deserialize a graph stored by [`VecGraph::store`] or
+            /// [`CsrGraph::store`] from the file `BASENAME.csr`.
+            pub fn load<P: AsRef<Path>>(basename: P) -> Result<Self> {
+                let csr = <CsrGraph<Vec<usize>, Vec<usize>, Vec<$ty>>>::load(basename)?;
+                let arcs = csr
+                    .iter_nodes()
+                    .flat_map(|(node, succ)| succ.labelled().map(move |(dst, l)| (node, dst, l)));
+                Ok(Self::from_sorted_labelled_arcs(csr.num_nodes(), arcs))
+            }
+        }
+    )*};
+}
+
+impl_vec_graph_store!((), u8, u16, u32, u64, usize, i32, i64, f32, f64);
+
+#[cfg(feature = "arbitrary")]
+/// Graphs have at most 256 nodes, so that random inputs yield graphs dense
+/// enough to exercise references and intervals.
+impl<'a> arbitrary::Arbitrary<'a> for VecGraph<()> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let num_nodes = u.arbitrary::<u8>()? as usize + 1;
+        let mut graph = Self::empty(num_nodes);
+        for arc in u.arbitrary_iter::<(u8, u8)>()? {
+            let (src, dst) = arc?;
+            graph.add_arc(src as usize % num_nodes, dst as usize % num_nodes);
+        }
+        Ok(graph)
+    }
+}
+
 impl<L: Clone> Labelled for VecGraph<L> {
     type Label = L;
 }
@@ -189,11 +351,19 @@ impl<L: Clone> RandomAccessGraph for VecGraph<L> {
     #[inline(always)]
     fn successors(&self, node: usize) -> Self::RandomSuccessorIter<'_> {
         VecGraphIter {
-            iter: self.succ[node].iter(),
-            label: unsafe {
-                #[allow(clippy::uninit_assumed_init)]
-                core::mem::MaybeUninit::uninit().assume_init()
-            },
+            iter: self.succ[node].range::<usize, _>(..),
+            len: Some(self.succ[node].len()),
+            label: None,
+        }
+    }
+}
+
+impl<L: Clone> SeekableGraph for VecGraph<L> {
+    fn successors_from(&self, node: usize, lower_bound: usize) -> Self::RandomSuccessorIter<'_> {
+        VecGraphIter {
+            iter: self.succ[node].range(lower_bound..),
+            len: None,
+            label: None,
         }
     }
 }
@@ -224,11 +394,27 @@ impl<L: Clone> SequentialGraph for VecGraph<L> {
             nodes: (0..self.num_nodes()),
         }
     }
+
+    #[inline(always)]
+    fn iter_nodes_from(&self, start_node: usize) -> Self::NodesIter<'_> {
+        SequentialGraphImplIter {
+            graph: self,
+            nodes: (start_node.min(self.num_nodes())..self.num_nodes()),
+        }
+    }
 }
 
+impl<L: Clone> SplitSequentialGraph for VecGraph<L> {}
+
 pub struct VecGraphIter<'a, L: Clone> {
-    label: L,
-    iter: std::collections::btree_set::Iter<'a, DstWithLabel<L>>,
+    /// The label of the last successor returned, if any
+    label: Option<L>,
+    iter: std::collections::btree_set::Range<'a, DstWithLabel<L>>,
+    /// The number of successors still to be returned, if known; it is
+    /// computed on demand for the iterators returned by
+    /// [`SeekableGraph::successors_from`], as ranges of a [`BTreeSet`] do
+    /// not know their length.
+    len: Option<usize>,
 }
 
 impl<'a, T: Clone> Iterator for VecGraphIter<'a, T> {
@@ -236,9 +422,18 @@ impl<'a, T: Clone> Iterator for VecGraphIter<'a, T> {
     #[inline(always)]
     fn next(&mut self) -> Option<Self::Item> {
         let x = self.iter.next()?;
-        self.label = x.1.clone();
+        if let Some(len) = &mut self.len {
+            *len -= 1;
+        }
+        self.label = Some(x.1.clone());
         Some(x.0)
     }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len.unwrap_or_else(|| self.iter.clone().count());
+        (len, Some(len))
+    }
 }
 
 impl<'a, L: Clone> Labelled for VecGraphIter<'a, L> {
@@ -247,20 +442,18 @@ impl<'a, L: Clone> Labelled for VecGraphIter<'a, L> {
 
 impl<'a, T: Clone> LabelledIterator for VecGraphIter<'a, T> {
     fn label(&self) -> Self::Label {
-        self.label.clone()
+        self.label
+            .clone()
+            .expect("You cannot call label() on an iterator that has not been advanced yet!")
     }
 }
 
 unsafe impl<'a, T: Clone> SortedIterator for VecGraphIter<'a, T> {}
 
-impl<'a, T: Clone> ExactSizeIterator for VecGraphIter<'a, T> {
-    #[inline(always)]
-    fn len(&self) -> usize {
-        self.iter.len()
-    }
-}
+impl<'a, T: Clone> ExactSizeIterator for VecGraphIter<'a, T> {}
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct DstWithLabel<L>(usize, L);
 
 impl<L> PartialEq for DstWithLabel<L> {
@@ -285,3 +478,128 @@ impl<L> Ord for DstWithLabel<L> {
         self.0.cmp(&other.0)
     }
 }
+
+/// Arcs are compared by destination only, so sets of arcs can be searched
+/// by destination.
+impl<L> core::borrow::Borrow<usize> for DstWithLabel<L> {
+    #[inline(always)]
+    fn borrow(&self) -> &usize {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_store_load() -> Result<()> {
+    let g = VecGraph::from_arc_list(&[(0, 1), (1, 2), (2, 0), (2, 1), (4, 0)]);
+    let dir = tempfile::tempdir()?;
+    let basename = dir.path().join("graph");
+    g.store(&basename)?;
+    let g2 = VecGraph::load(&basename)?;
+    assert_eq!(g2, g);
+    assert_eq!(g2.num_arcs(), g.num_arcs());
+    assert!(VecGraph::load(dir.path().join("none")).is_err());
+    // the stored graph is a CSR graph
+    assert!(<crate::graph::csr_graph::CsrGraph>::load(&basename)?
+        .iter_arcs()
+        .eq(g.iter_arcs()));
+
+    let g = VecGraph::from_arc_and_label_list(&[(0, 1, 3_u32), (2, 0, 1), (2, 1, 4)]);
+    g.store(&basename)?;
+    let g2 = VecGraph::<u32>::load(&basename)?;
+    assert_eq!(g2, g);
+    assert!(g2.successors(2).labelled().eq([(0, 1), (1, 4)]));
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_serde() -> Result<()> {
+    let g = VecGraph::from_arc_and_label_list(&[(0, 1, 1.5), (1, 2, -2.0), (3, 0, 0.25)]);
+    let json = serde_json::to_string(&g)?;
+    let g2: VecGraph<f64> = serde_json::from_str(&json)?;
+    assert_eq!(g2, g);
+    assert_eq!(g2.num_arcs(), g.num_arcs());
+    for node in 0..g.num_nodes() {
+        assert!(g
+            .successors(node)
+            .labelled()
+            .eq(g2.successors(node).labelled()));
+    }
+    // the number of arcs is recomputed and successors become nodes
+    let g3: VecGraph<()> = serde_json::from_str(r#"{"succ":[[[3,null],[1,null]]]}"#)?;
+    assert_eq!(g3.num_nodes(), 4);
+    assert_eq!(g3.num_arcs(), 2);
+    assert!(!json.contains("number_of_arcs"));
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_from_sorted_arcs() {
+    use rand::prelude::*;
+    let mut rng = SmallRng::seed_from_u64(0);
+    let mut arcs = (0..1000)
+        .map(|_| (rng.gen_range(0..100), rng.gen_range(0..120)))
+        .collect::<Vec<_>>();
+    arcs.sort();
+    let g = VecGraph::from_sorted_arcs(150, arcs.iter().copied());
+    let mut expected = VecGraph::from_arc_list(&arcs);
+    expected.add_node(149);
+    assert_eq!(g, expected);
+    assert_eq!(g.num_arcs(), expected.num_arcs());
+
+    let g = VecGraph::from_sorted_labelled_arcs(0, [(0, 1, 'a'), (0, 1, 'b'), (2, 0, 'c')]);
+    assert_eq!(g.num_nodes(), 3);
+    assert_eq!(g.num_arcs(), 2);
+    assert!(g.successors(0).labelled().eq([(1, 'a')]));
+    assert!(g.successors(2).labelled().eq([(0, 'c')]));
+    assert_eq!(VecGraph::from_sorted_arcs(5, []), VecGraph::empty(5));
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_par_iter_nodes() {
+    use rayon::prelude::*;
+    let arcs = (0..100)
+        .flat_map(|src| (0..src % 7).map(move |i| (src, (src + i) % 100)))
+        .collect::<Vec<_>>();
+    let g = VecGraph::from_arc_list(&arcs);
+    let num_arcs = g
+        .par_iter_nodes(8)
+        .map(|iter| iter.map(|(_, succ)| succ.count()).sum::<usize>())
+        .sum::<usize>();
+    assert_eq!(num_arcs, g.num_arcs());
+    assert!(g.split_iter(3)[2].clone().map(|(node, _)| node).eq(68..100));
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_successors_from() {
+    let g =
+        VecGraph::from_arc_and_label_list(&[(0, 1, 'a'), (0, 3, 'b'), (0, 4, 'c'), (1, 0, 'd')]);
+    let iter = g.successors_from(0, 2);
+    assert_eq!(iter.len(), 2);
+    assert!(iter.labelled().eq([(3, 'b'), (4, 'c')]));
+    assert!(g.successors_from(0, 0).eq([1, 3, 4]));
+    assert!(g.successors_from(0, 5).eq([]));
+    assert!(g.successors_from(2, 0).eq([]));
+    let mut iter = g.successors(0);
+    iter.next();
+    assert_eq!(iter.len(), 2);
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_iter_arcs() {
+    use rayon::prelude::*;
+    let arcs = [(0, 1), (0, 3), (2, 0), (2, 1), (2, 4), (4, 4)];
+    let g = VecGraph::from_arc_list(&arcs);
+    let iter = g.iter_arcs();
+    assert_eq!(iter.size_hint(), (arcs.len(), Some(arcs.len())));
+    assert!(iter.eq(arcs));
+    let mut par_arcs = g.par_iter_arcs(2).collect::<Vec<_>>();
+    par_arcs.sort();
+    assert_eq!(par_arcs, arcs);
+}
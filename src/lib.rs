@@ -26,25 +26,39 @@
 //#![deny(missing_debug_implementations)]
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "std")]
 use sux::prelude::*;
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[cfg(feature = "std")]
 pub mod algorithms;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 #[cfg(feature = "fuzz")]
 pub mod fuzz;
 pub mod graph;
+#[cfg(feature = "std")]
+pub mod properties;
+#[cfg(feature = "proptest")]
+pub mod strategies;
 pub mod traits;
+#[cfg(feature = "std")]
 pub mod utils;
 
 /// The default veinarsion of EliasFano we use for the CLI
+#[cfg(feature = "std")]
 pub type EF<Memory> = EliasFano<SparseIndex<BitMap<Memory>, Memory, 8>, CompactArray<Memory>>;
 
 /// Prelude module to import everything from this crate
 pub mod prelude {
+    #[cfg(feature = "std")]
     pub use crate::algorithms::*;
     pub use crate::graph::prelude::*;
+    #[cfg(feature = "std")]
+    pub use crate::properties::*;
     pub use crate::traits::*;
+    #[cfg(feature = "std")]
     pub use crate::utils::*;
 }
@@ -0,0 +1,213 @@
+use super::*;
+use crate::utils::{FrontCodedList, FrontCodedListBuilder, MinimalPerfectHash};
+use dsi_progress_logger::ProgressLogger;
+use epserde::prelude::*;
+use std::io::{BufRead, BufReader};
+
+/// A bidirectional map between the external identifiers of the nodes of a
+/// graph (e.g., URLs, SWHIDs or usernames) and their node ids.
+///
+/// The identifier of node `i` is the `i`-th identifier used to build the
+/// map, and identifiers must be sorted, as it happens with graphs whose
+/// nodes are numbered in the lexicographical order of their identifiers.
+/// Identifiers are stored in a [`FrontCodedList`], which compresses well
+/// sorted strings sharing long prefixes, and they are mapped to node ids by
+/// a [`MinimalPerfectHash`], whose result is checked against the list so
+/// that unknown identifiers are rejected.
+///
+/// The map can be stored in the sidecar file `BASENAME.idmap`, serialized
+/// with ε-serde.
+#[derive(Epserde, Debug, Clone, Default, PartialEq, Eq)]
+pub struct NodeIdMap {
+    /// The identifiers, in node order.
+    names: FrontCodedList,
+    /// The hash function on the identifiers.
+    mph: MinimalPerfectHash,
+    /// The node id of the identifier hashed to each value.
+    nodes: Vec<usize>,
+}
+
+impl NodeIdMap {
+    /// Return the number of nodes.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Return whether the map is empty.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Return the node id with the given identifier, or `None` if no node
+    /// has this identifier.
+    pub fn id_of(&self, name: &str) -> Option<usize> {
+        if self.is_empty() {
+            return None;
+        }
+        let node = self.nodes[self.mph.get(name.as_bytes())];
+        if self.names.get(node) == name {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    /// Return the identifier of a node.
+    ///
+    /// # Panics
+    /// If `node` is not smaller than the number of nodes.
+    pub fn name_of(&self, node: usize) -> String {
+        self.names.get(node)
+    }
+
+    /// Return the identifiers of the nodes, in node order.
+    pub fn names(&self) -> &FrontCodedList {
+        &self.names
+    }
+
+    /// Serialize the map in the file `BASENAME.idmap`.
+    pub fn store<P: AsRef<Path>>(&self, basename: P) -> Result<()> {
+        let path = property_path(basename, "idmap")?;
+        let mut file = create(&path)?;
+        self.serialize(&mut file)?;
+        std::io::Write::flush(&mut file)?;
+        Ok(())
+    }
+
+    /// Deserialize the map from the file `BASENAME.idmap`.
+    pub fn load<P: AsRef<Path>>(basename: P) -> Result<Self> {
+        let path = property_path(basename, "idmap")?;
+        <Self>::load_full(&path).with_context(|| format!("Cannot deserialize {}", path.display()))
+    }
+}
+
+/// A builder for a [`NodeIdMap`].
+#[derive(Debug, Clone)]
+pub struct NodeIdMapBuilder {
+    /// The number of strings in a block of the front-coded list.
+    ratio: usize,
+}
+
+impl Default for NodeIdMapBuilder {
+    fn default() -> Self {
+        Self { ratio: 32 }
+    }
+}
+
+impl NodeIdMapBuilder {
+    /// Create a builder with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of identifiers in a block of the front-coded list:
+    /// larger blocks make the map smaller and the lookups slower.
+    pub fn ratio(mut self, ratio: usize) -> Self {
+        self.ratio = ratio;
+        self
+    }
+
+    /// Build a map from identifiers sorted in increasing order, the
+    /// identifier of node `i` being the `i`-th one.
+    pub fn build<S: AsRef<str>>(&self, names: impl IntoIterator<Item = S>) -> Result<NodeIdMap> {
+        self.try_build(names.into_iter().map(Ok))
+    }
+
+    /// Build a map from a file containing one identifier per line, sorted in
+    /// increasing order.
+    ///
+    /// The file is read once, and the identifiers are pushed into the
+    /// front-coded list while reading, so that only the compressed
+    /// identifiers are kept in memory.
+    pub fn build_from_file<P: AsRef<Path>>(&self, path: P) -> Result<NodeIdMap> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Cannot open the identifiers {}", path.display()))?;
+        self.try_build(BufReader::new(file).lines().map(|line| {
+            line.with_context(|| format!("Cannot read the identifiers {}", path.display()))
+        }))
+    }
+
+    /// Build a map from a sequence of identifiers that might fail.
+    fn try_build<S: AsRef<str>>(
+        &self,
+        names: impl IntoIterator<Item = Result<S>>,
+    ) -> Result<NodeIdMap> {
+        let mut builder = FrontCodedListBuilder::new(self.ratio);
+        let mut last: Option<S> = None;
+        for name in names {
+            let name = name?;
+            if let Some(last) = &last {
+                ensure!(
+                    last.as_ref() < name.as_ref(),
+                    "The identifiers of nodes {} and {} are not strictly increasing: {:?} >= {:?}",
+                    builder.len() - 1,
+                    builder.len(),
+                    last.as_ref(),
+                    name.as_ref()
+                );
+            }
+            builder.push(name.as_ref());
+            last = Some(name);
+        }
+        let names = builder.build();
+
+        // the hash function iterates over the compressed identifiers, which
+        // are decoded again at each restart of the construction
+        let mut pl = ProgressLogger::default();
+        pl.start("Building the minimal perfect hash function...");
+        let mph = MinimalPerfectHash::from_keys(|| names.iter())?;
+        pl.done();
+
+        let mut nodes = vec![0; names.len()];
+        for (node, name) in names.iter().enumerate() {
+            nodes[mph.get(name.as_bytes())] = node;
+        }
+        Ok(NodeIdMap { names, mph, nodes })
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_node_id_map() -> Result<()> {
+    use std::io::Write;
+    let names: Vec<String> = (0..1000)
+        .map(|i| format!("swh:1:cnt:{:040x}", i * 7919))
+        .collect();
+    let dir = tempfile::tempdir()?;
+    let ids_path = dir.path().join("ids.txt");
+    let mut ids = std::fs::File::create(&ids_path)?;
+    for name in &names {
+        writeln!(ids, "{}", name)?;
+    }
+    drop(ids);
+
+    let map = NodeIdMapBuilder::new()
+        .ratio(8)
+        .build_from_file(&ids_path)?;
+    assert_eq!(map.len(), names.len());
+    for (node, name) in names.iter().enumerate() {
+        assert_eq!(map.id_of(name), Some(node));
+        assert_eq!(&map.name_of(node), name);
+    }
+    assert_eq!(map.id_of("swh:1:cnt:unknown"), None);
+
+    let basename = dir.path().join("graph");
+    map.store(&basename)?;
+    assert_eq!(NodeIdMap::load(&basename)?, map);
+
+    assert!(NodeIdMapBuilder::new().build(["b", "a"]).is_err());
+    assert!(NodeIdMapBuilder::new().build(["a", "b", "b"]).is_err());
+    let unsorted_path = dir.path().join("unsorted.txt");
+    std::fs::write(&unsorted_path, "a\nc\nb\n")?;
+    assert!(NodeIdMapBuilder::new()
+        .build_from_file(&unsorted_path)
+        .is_err());
+    assert!(NodeIdMapBuilder::new()
+        .build_from_file(dir.path().join("missing.txt"))
+        .is_err());
+    assert_eq!(NodeIdMapBuilder::new().build::<&str>([])?.id_of("a"), None);
+    Ok(())
+}
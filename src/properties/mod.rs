@@ -0,0 +1,50 @@
+//! Attributes of the nodes of a graph.
+//!
+//! A node property is an array with one value per node, such as the
+//! indegrees, the PageRank or the URLs of the nodes, stored next to the
+//! graph in a file named after the basename of the graph and the name of
+//! the property: the property `pagerank` of the graph with basename
+//! `graph` is stored in `graph.pagerank`.
+//!
+//! Numeric properties (see [`PropertyValue`]) are stored as ε-serde
+//! serialized vectors, so they can be memory-mapped with
+//! [`map_property`] instead of being loaded in memory. String properties are
+//! stored as ε-serde serialized [`FrontCodedList`](crate::utils::FrontCodedList)s,
+//! which can be memory-mapped with [`map_string_property`].
+//!
+//! A [`NodeIdMap`] maps external identifiers of the nodes, such as URLs, to
+//! node ids and back.
+use anyhow::{ensure, Context, Result};
+use std::path::{Path, PathBuf};
+
+mod numeric;
+pub use numeric::*;
+
+mod strings;
+pub use strings::*;
+
+mod id_map;
+pub use id_map::*;
+
+/// Return the path of the file storing the property `name` of the graph
+/// with the given basename, that is, `BASENAME.NAME`.
+pub fn property_path<P: AsRef<Path>>(basename: P, name: &str) -> Result<PathBuf> {
+    ensure!(
+        !name.is_empty() && !name.contains(std::path::is_separator),
+        "Invalid property name {:?}",
+        name
+    );
+    Ok(PathBuf::from(format!(
+        "{}.{}",
+        basename.as_ref().to_string_lossy(),
+        name
+    )))
+}
+
+/// Create the file of a property, with a context for errors.
+fn create(path: &Path) -> Result<std::io::BufWriter<std::fs::File>> {
+    Ok(std::io::BufWriter::new(
+        std::fs::File::create(path)
+            .with_context(|| format!("Cannot create the property {}", path.display()))?,
+    ))
+}
@@ -0,0 +1,96 @@
+use super::*;
+use epserde::prelude::*;
+
+/// The type of the values of a numeric node property.
+///
+/// Values are stored as an ε-serde serialized `Vec<Self>`.
+pub trait PropertyValue: Copy + Send + Sync + core::fmt::Debug + 'static {
+    /// Serialize `values` in the file at `path`.
+    fn store<P: AsRef<Path>>(path: P, values: &[Self]) -> Result<()>;
+    /// Deserialize the values in the file at `path`.
+    fn load<P: AsRef<Path>>(path: P) -> Result<Vec<Self>>;
+    /// Memory-map the values in the file at `path`.
+    #[cfg(feature = "mmap")]
+    fn map<P: AsRef<Path>>(path: P) -> Result<MemCase<&'static [Self]>>;
+}
+
+macro_rules! impl_property_value {
+    ($($ty:ty),*) => {$(
+        impl PropertyValue for $ty {
+            fn store<P: AsRef<Path>>(path: P, values: &[Self]) -> Result<()> {
+                let path = path.as_ref();
+                let mut file = create(path)?;
+                values.to_vec().serialize(&mut file)?;
+                std::io::Write::flush(&mut file)?;
+                Ok(())
+            }
+
+            fn load<P: AsRef<Path>>(path: P) -> Result<Vec<Self>> {
+                let path = path.as_ref();
+                <Vec<$ty>>::load_full(path)
+                    .with_context(|| format!("Cannot deserialize the property {}", path.display()))
+            }
+
+            #[cfg(feature = "mmap")]
+            fn map<P: AsRef<Path>>(path: P) -> Result<MemCase<&'static [Self]>> {
+                let path = path.as_ref();
+                <Vec<$ty>>::mmap(path, Flags::empty())
+                    .with_context(|| format!("Cannot map the property {}", path.display()))
+            }
+        }
+    )*};
+}
+
+impl_property_value!(u8, u16, u32, u64, usize, i32, i64, f32, f64);
+
+/// Store the property `name` of the graph with the given basename.
+pub fn store_property<T: PropertyValue, P: AsRef<Path>>(
+    basename: P,
+    name: &str,
+    values: &[T],
+) -> Result<()> {
+    T::store(property_path(basename, name)?, values)
+}
+
+/// Load in memory the property `name` of the graph with the given basename.
+pub fn load_property<T: PropertyValue, P: AsRef<Path>>(basename: P, name: &str) -> Result<Vec<T>> {
+    T::load(property_path(basename, name)?)
+}
+
+/// Memory-map the property `name` of the graph with the given basename.
+///
+/// The values are paged in lazily by the operating system, so large
+/// properties can be accessed without reading them fully.
+#[cfg(feature = "mmap")]
+pub fn map_property<T: PropertyValue, P: AsRef<Path>>(
+    basename: P,
+    name: &str,
+) -> Result<MemCase<&'static [T]>> {
+    T::map(property_path(basename, name)?)
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_numeric_properties() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let basename = dir.path().join("graph");
+    let indegrees = vec![0_u32, 3, 1, 4, 1, 5];
+    let pagerank = vec![0.1_f32, 0.2, 0.05, 0.3, 0.15, 0.2];
+    store_property(&basename, "indegree", &indegrees)?;
+    store_property(&basename, "pagerank", &pagerank)?;
+    assert!(dir.path().join("graph.indegree").exists());
+
+    assert_eq!(load_property::<u32, _>(&basename, "indegree")?, indegrees);
+    assert_eq!(load_property::<f32, _>(&basename, "pagerank")?, pagerank);
+    #[cfg(feature = "mmap")]
+    {
+        let mapped = map_property::<u32, _>(&basename, "indegree")?;
+        assert_eq!(&**mapped, &indegrees[..]);
+        let mapped = map_property::<f32, _>(&basename, "pagerank")?;
+        assert_eq!(&**mapped, &pagerank[..]);
+    }
+
+    assert!(load_property::<u32, _>(&basename, "none").is_err());
+    assert!(store_property(&basename, "a/b", &indegrees).is_err());
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use super::*;
+use crate::utils::FrontCodedList;
+use epserde::prelude::*;
+
+/// The number of strings in a block of the [`FrontCodedList`] of a string
+/// property.
+pub const STRING_PROPERTY_RATIO: usize = 8;
+
+/// Store the string property `name` of the graph with the given basename,
+/// with one string per node.
+pub fn store_string_property<S: AsRef<str>, P: AsRef<Path>>(
+    basename: P,
+    name: &str,
+    strings: impl IntoIterator<Item = S>,
+) -> Result<()> {
+    let path = property_path(basename, name)?;
+    let list = FrontCodedList::from_strings(STRING_PROPERTY_RATIO, strings);
+    let mut file = create(&path)?;
+    list.serialize(&mut file)?;
+    std::io::Write::flush(&mut file)?;
+    Ok(())
+}
+
+/// Load the string property `name` of the graph with the given basename.
+pub fn load_string_property<P: AsRef<Path>>(basename: P, name: &str) -> Result<FrontCodedList> {
+    let path = property_path(basename, name)?;
+    <FrontCodedList>::load_full(&path)
+        .with_context(|| format!("Cannot deserialize the property {}", path.display()))
+}
+
+/// Memory-map the string property `name` of the graph with the given
+/// basename.
+#[cfg(feature = "mmap")]
+pub fn map_string_property<P: AsRef<Path>>(
+    basename: P,
+    name: &str,
+) -> Result<MemCase<crate::utils::MappedFrontCodedList>> {
+    let path = property_path(basename, name)?;
+    <FrontCodedList>::mmap(&path, Flags::empty())
+        .with_context(|| format!("Cannot map the property {}", path.display()))
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_string_properties() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let basename = dir.path().join("graph");
+    let urls: Vec<String> = (0..100)
+        .map(|i| format!("http://example.com/page{}.html", i))
+        .collect();
+    store_string_property(&basename, "urls", &urls)?;
+    let list = load_string_property(&basename, "urls")?;
+    assert_eq!(list.len(), urls.len());
+    assert!(list.iter().eq(urls.iter().cloned()));
+    assert_eq!(list.get(42), urls[42]);
+    #[cfg(feature = "mmap")]
+    {
+        let mapped = map_string_property(&basename, "urls")?;
+        assert_eq!(mapped.len(), urls.len());
+        assert!(mapped.iter().eq(urls.iter().cloned()));
+        assert_eq!(mapped.get(42), urls[42]);
+    }
+    Ok(())
+}
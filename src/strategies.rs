@@ -0,0 +1,104 @@
+//! [Proptest](https://docs.rs/proptest) strategies generating graphs and
+//! compression flags, to property-test adapters and algorithms.
+//!
+//! ```ignore
+//! use proptest::prelude::*;
+//! use webgraph::strategies;
+//!
+//! proptest! {
+//!     #[test]
+//!     fn my_property(graph in strategies::vec_graph(100, 1000)) {
+//!         // ...
+//!     }
+//! }
+//! ```
+
+use crate::graph::bvgraph::CompFlags;
+use crate::graph::vec_graph::VecGraph;
+use dsi_bitstream::prelude::Code;
+use proptest::prelude::*;
+
+/// Return a strategy generating the codes supported by the compression
+/// flags.
+pub fn code() -> impl Strategy<Value = Code> {
+    prop_oneof![
+        Just(Code::Unary),
+        Just(Code::Gamma),
+        Just(Code::Delta),
+        Just(Code::Zeta { k: 3 }),
+    ]
+}
+
+/// Return a strategy generating compression flags with arbitrary codes and
+/// small, but possibly zero, window size, minimum interval length and
+/// maximum reference count.
+pub fn comp_flags() -> impl Strategy<Value = CompFlags> {
+    (
+        (code(), code(), code(), code(), code()),
+        0..8_usize,
+        0..16_usize,
+        0..8_usize,
+    )
+        .prop_map(
+            |(
+                (outdegrees, references, blocks, intervals, residuals),
+                min_interval_length,
+                compression_window,
+                max_ref_count,
+            )| CompFlags {
+                outdegrees,
+                references,
+                blocks,
+                intervals,
+                residuals,
+                min_interval_length,
+                compression_window,
+                max_ref_count,
+            },
+        )
+}
+
+/// Return a strategy generating lists of at most `max_arcs` arcs between
+/// nodes smaller than `num_nodes`, possibly with duplicates and loops.
+///
+/// # Panics
+/// If `num_nodes` is zero.
+pub fn arc_list(num_nodes: usize, max_arcs: usize) -> impl Strategy<Value = Vec<(usize, usize)>> {
+    assert!(num_nodes > 0, "Arcs need at least one node");
+    prop::collection::vec((0..num_nodes, 0..num_nodes), 0..=max_arcs)
+}
+
+/// Return a strategy generating graphs with at most `max_nodes` nodes and
+/// `max_arcs` arcs.
+///
+/// Graphs shrink towards fewer nodes and arcs. Since arcs are generated
+/// independently, graphs with many arcs per node are unlikely: use
+/// [`arc_list`] with a small number of nodes to test dense graphs.
+pub fn vec_graph(max_nodes: usize, max_arcs: usize) -> impl Strategy<Value = VecGraph<()>> {
+    (0..=max_nodes).prop_flat_map(move |num_nodes| {
+        let arcs = if num_nodes == 0 {
+            Just(vec![]).boxed()
+        } else {
+            arc_list(num_nodes, max_arcs).boxed()
+        };
+        arcs.prop_map(move |arcs| {
+            let mut graph = VecGraph::empty(num_nodes);
+            graph.add_arc_list(&arcs);
+            graph
+        })
+    })
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn test_transpose_involution(graph in vec_graph(50, 300), batch_size in 1..100_usize) {
+        use crate::algorithms::transpose;
+        use crate::traits::*;
+        let transposed = transpose(&graph, batch_size).unwrap();
+        let transposed = VecGraph::from_node_iter(transposed.iter_nodes());
+        let back = transpose(&transposed, batch_size).unwrap();
+        prop_assert_eq!(graph.num_nodes(), back.num_nodes());
+        prop_assert_eq!(VecGraph::from_node_iter(back.iter_nodes()), graph);
+    }
+}
@@ -8,8 +8,37 @@ pub trait BVGraphCodesReaderBuilder {
     where
         Self: 'a;
 
-    /// Create a new reader at bit-offset `offset`
-    fn get_reader(&self, offset: usize) -> Result<Self::Reader<'_>>;
+    /// Create a new reader at bit-offset `offset`.
+    ///
+    /// Bit offsets are `u64` so that bitstreams longer than 2³² bits can be
+    /// addressed also on 32-bit targets; implementations must fail if the
+    /// offset cannot be represented by the underlying reader.
+    fn get_reader(&self, offset: u64) -> Result<Self::Reader<'_>>;
+}
+
+/// Seeking in the bitstream of a codes reader or writer.
+///
+/// Bit offsets are `u64`, as in [`BVGraphCodesReaderBuilder::get_reader`],
+/// so that bitstreams longer than 2³² bits can be addressed also on 32-bit
+/// targets; implementations must fail if the offset cannot be represented by
+/// the underlying bitstream.
+pub trait BVGraphCodesSeek {
+    /// Move to bit offset `bit_index`.
+    fn set_pos(&mut self, bit_index: u64) -> Result<()>;
+    /// Return the current bit offset.
+    fn get_pos(&self) -> u64;
+}
+
+/// Convert a bit offset to the `usize` used by the bitstreams of
+/// `dsi-bitstream`, failing if it does not fit, as it happens for long
+/// bitstreams on 32-bit targets.
+pub(crate) fn bit_pos(offset: u64) -> Result<usize> {
+    usize::try_from(offset).map_err(|_| {
+        anyhow::anyhow!(
+            "The bit offset {} does not fit in the address space of this platform",
+            offset
+        )
+    })
 }
 
 /// The generic interface we need to skip codes
@@ -24,6 +53,14 @@ pub trait BVGraphCodesSkipper {
     fn skip_block_count(&mut self);
     /// skip a block code
     fn skip_block(&mut self);
+    /// skip `n` consecutive block codes; implementations can override this
+    /// method to skip runs of blocks faster than one at a time
+    #[inline(always)]
+    fn skip_blocks(&mut self, n: usize) {
+        for _ in 0..n {
+            self.skip_block();
+        }
+    }
 
     /// skip a interval count code
     fn skip_interval_count(&mut self);
@@ -36,6 +73,14 @@ pub trait BVGraphCodesSkipper {
     fn skip_first_residual(&mut self);
     /// skip a residual code
     fn skip_residual(&mut self);
+    /// skip `n` consecutive residual codes; implementations can override
+    /// this method to skip runs of residuals faster than one at a time
+    #[inline(always)]
+    fn skip_residuals(&mut self, n: usize) {
+        for _ in 0..n {
+            self.skip_residual();
+        }
+    }
 }
 
 /// The generic interface we need to read codes to decode a [`BVGraph`]
@@ -62,6 +107,16 @@ pub trait BVGraphCodesReader {
     fn read_first_residual(&mut self) -> u64;
     /// read a residual code
     fn read_residual(&mut self) -> u64;
+
+    /// read `gaps.len()` consecutive residual codes; implementations can
+    /// override this method to decode runs of residuals faster than one at
+    /// a time
+    #[inline(always)]
+    fn read_residuals(&mut self, gaps: &mut [u64]) {
+        for gap in gaps {
+            *gap = self.read_residual();
+        }
+    }
 }
 
 /// The generic interface we need to write codes to write a [`BVGraph`] to
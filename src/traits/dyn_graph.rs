@@ -0,0 +1,188 @@
+use super::*;
+
+/// A boxed iterator over the successors of a node.
+pub type DynSuccessorIter<'a> = Box<dyn Iterator<Item = usize> + 'a>;
+
+/// A boxed iterator over the successors of a node of known length.
+pub type DynRandomSuccessorIter<'a> = Box<dyn ExactSizeIterator<Item = usize> + 'a>;
+
+/// A boxed iterator over the nodes of a graph and their successors.
+pub type DynNodesIter<'a> = Box<dyn Iterator<Item = (usize, DynSuccessorIter<'a>)> + 'a>;
+
+/// A sequential graph of any type, which implements [`SequentialGraph`].
+pub type DynSequentialGraph<'a> = Box<dyn SequentialGraphDyn + 'a>;
+
+/// A random-access graph of any type, which implements
+/// [`RandomAccessGraph`].
+pub type DynRandomAccessGraph<'a> = Box<dyn RandomAccessGraphDyn + 'a>;
+
+/// An object-safe version of [`SequentialGraph`], in which iterators are
+/// boxed.
+///
+/// The trait is implemented for every [`SequentialGraph`], and
+/// [`SequentialGraph`] is implemented in turn for [`DynSequentialGraph`], so
+/// graphs of different types (e.g., of different endianness) can be stored
+/// in the same variable or struct, at the cost of a dynamic dispatch and an
+/// allocation for each successor list.
+pub trait SequentialGraphDyn {
+    /// See [`SequentialGraph::num_nodes`].
+    fn num_nodes_dyn(&self) -> usize;
+    /// See [`SequentialGraph::num_arcs_hint`].
+    fn num_arcs_hint_dyn(&self) -> Option<usize>;
+    /// See [`SequentialGraph::iter_nodes`].
+    fn iter_nodes_dyn(&self) -> DynNodesIter<'_>;
+    /// See [`SequentialGraph::iter_nodes_from`].
+    fn iter_nodes_from_dyn(&self, start_node: usize) -> DynNodesIter<'_>;
+}
+
+/// An object-safe version of [`RandomAccessGraph`], in which iterators are
+/// boxed.
+///
+/// The trait is implemented for every [`RandomAccessGraph`], and
+/// [`RandomAccessGraph`] is implemented in turn for
+/// [`DynRandomAccessGraph`].
+pub trait RandomAccessGraphDyn: SequentialGraphDyn {
+    /// See [`RandomAccessGraph::num_arcs`].
+    fn num_arcs_dyn(&self) -> usize;
+    /// See [`RandomAccessGraph::outdegree`].
+    fn outdegree_dyn(&self, node_id: usize) -> usize;
+    /// See [`RandomAccessGraph::successors`].
+    fn successors_dyn(&self, node_id: usize) -> DynRandomSuccessorIter<'_>;
+}
+
+/// Box the successors iterators of a nodes iterator.
+fn box_nodes<'a, S: Iterator<Item = usize> + 'a>(
+    iter: impl Iterator<Item = (usize, S)> + 'a,
+) -> DynNodesIter<'a> {
+    Box::new(iter.map(|(node, succ)| (node, Box::new(succ) as DynSuccessorIter<'a>)))
+}
+
+impl<G: SequentialGraph> SequentialGraphDyn for G {
+    #[inline(always)]
+    fn num_nodes_dyn(&self) -> usize {
+        self.num_nodes()
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint_dyn(&self) -> Option<usize> {
+        self.num_arcs_hint()
+    }
+
+    fn iter_nodes_dyn(&self) -> DynNodesIter<'_> {
+        box_nodes(self.iter_nodes())
+    }
+
+    fn iter_nodes_from_dyn(&self, start_node: usize) -> DynNodesIter<'_> {
+        box_nodes(self.iter_nodes_from(start_node))
+    }
+}
+
+impl<G: RandomAccessGraph> RandomAccessGraphDyn for G {
+    #[inline(always)]
+    fn num_arcs_dyn(&self) -> usize {
+        self.num_arcs()
+    }
+
+    #[inline(always)]
+    fn outdegree_dyn(&self, node_id: usize) -> usize {
+        self.outdegree(node_id)
+    }
+
+    fn successors_dyn(&self, node_id: usize) -> DynRandomSuccessorIter<'_> {
+        Box::new(self.successors(node_id))
+    }
+}
+
+impl<'a> SequentialGraph for DynSequentialGraph<'a> {
+    type NodesIter<'b> = DynNodesIter<'b> where Self: 'b;
+    type SequentialSuccessorIter<'b> = DynSuccessorIter<'b> where Self: 'b;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        (**self).num_nodes_dyn()
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<usize> {
+        (**self).num_arcs_hint_dyn()
+    }
+
+    #[inline(always)]
+    fn iter_nodes(&self) -> Self::NodesIter<'_> {
+        (**self).iter_nodes_dyn()
+    }
+
+    #[inline(always)]
+    fn iter_nodes_from(&self, start_node: usize) -> Self::NodesIter<'_> {
+        (**self).iter_nodes_from_dyn(start_node)
+    }
+}
+
+impl<'a> SequentialGraph for DynRandomAccessGraph<'a> {
+    type NodesIter<'b> = DynNodesIter<'b> where Self: 'b;
+    type SequentialSuccessorIter<'b> = DynSuccessorIter<'b> where Self: 'b;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        (**self).num_nodes_dyn()
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<usize> {
+        (**self).num_arcs_hint_dyn()
+    }
+
+    #[inline(always)]
+    fn iter_nodes(&self) -> Self::NodesIter<'_> {
+        (**self).iter_nodes_dyn()
+    }
+
+    #[inline(always)]
+    fn iter_nodes_from(&self, start_node: usize) -> Self::NodesIter<'_> {
+        (**self).iter_nodes_from_dyn(start_node)
+    }
+}
+
+impl<'a> RandomAccessGraph for DynRandomAccessGraph<'a> {
+    type RandomSuccessorIter<'b> = DynRandomSuccessorIter<'b> where Self: 'b;
+
+    #[inline(always)]
+    fn num_arcs(&self) -> usize {
+        (**self).num_arcs_dyn()
+    }
+
+    #[inline(always)]
+    fn outdegree(&self, node_id: usize) -> usize {
+        (**self).outdegree_dyn(node_id)
+    }
+
+    #[inline(always)]
+    fn successors(&self, node_id: usize) -> Self::RandomSuccessorIter<'_> {
+        (**self).successors_dyn(node_id)
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_dyn_graph() -> anyhow::Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    let arcs = [(0, 1), (0, 2), (1, 2), (1, 3), (2, 4), (3, 4)];
+    let g = VecGraph::from_arc_list(&arcs);
+    let graphs: Vec<DynRandomAccessGraph> = vec![
+        Box::new(g.clone()),
+        Box::new(VecGraph::from_node_iter(g.iter_nodes())),
+    ];
+    for graph in &graphs {
+        assert_eq!(graph.num_nodes(), g.num_nodes());
+        assert_eq!(graph.num_arcs(), g.num_arcs());
+        assert_eq!(VecGraph::from_node_iter(graph.iter_nodes()), g);
+        for node in 0..g.num_nodes() {
+            assert_eq!(graph.outdegree(node), g.outdegree(node));
+            assert!(graph.successors(node).eq(g.successors(node)));
+        }
+        assert!(graph.iter_nodes_from(3).map(|(node, _)| node).eq(3..5));
+    }
+    let seq: DynSequentialGraph = Box::new(g.clone());
+    assert_eq!(VecGraph::from_node_iter(seq.iter_nodes()), g);
+    Ok(())
+}
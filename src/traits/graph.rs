@@ -5,6 +5,15 @@ pub struct SequentialGraphImplIter<'a, G: RandomAccessGraph> {
     pub nodes: core::ops::Range<usize>,
 }
 
+impl<'a, G: RandomAccessGraph> Clone for SequentialGraphImplIter<'a, G> {
+    fn clone(&self) -> Self {
+        Self {
+            graph: self.graph,
+            nodes: self.nodes.clone(),
+        }
+    }
+}
+
 impl<'a, G> Iterator for SequentialGraphImplIter<'a, G>
 where
     G: RandomAccessGraph
@@ -54,6 +63,13 @@ pub trait SequentialGraph {
         }
         iter
     }
+
+    /// Get an iterator over the arcs of the graph as pairs `(src, dst)`,
+    /// whose size hint is exact if [`SequentialGraph::num_arcs_hint`] is
+    /// available.
+    fn iter_arcs(&self) -> ArcsIter<Self::NodesIter<'_>, Self::SequentialSuccessorIter<'_>> {
+        ArcsIter::new(self.iter_nodes(), self.num_arcs_hint())
+    }
 }
 
 /// A graph that can be accessed randomly
@@ -90,6 +106,75 @@ pub trait RandomAccessGraph: SequentialGraph {
     }
 }
 
+/// The iterators over ranges of nodes returned by
+/// [`SplitSequentialGraph::split_iter`].
+pub type SplitNodesIter<'a, G> = core::iter::Take<<G as SequentialGraph>::NodesIter<'a>>;
+
+/// A sequential graph whose iteration can start efficiently at any node
+/// through [`SequentialGraph::iter_nodes_from`], so that the nodes can be
+/// split into ranges scanned independently, for example, in parallel; the
+/// ranges of a labelled graph are labelled, too.
+pub trait SplitSequentialGraph: SequentialGraph {
+    /// Return `how_many` iterators over consecutive, disjoint ranges of
+    /// nodes of similar size covering all the nodes, in increasing order.
+    fn split_iter(&self, how_many: usize) -> Vec<SplitNodesIter<'_, Self>> {
+        assert_ne!(how_many, 0, "Cannot split the nodes in zero ranges");
+        let num_nodes = self.num_nodes();
+        let nodes_per_iter = num_nodes.div_ceil(how_many);
+        (0..how_many)
+            .map(|i| {
+                let start = (i * nodes_per_iter).min(num_nodes);
+                let end = (start + nodes_per_iter).min(num_nodes);
+                self.iter_nodes_from(start).take(end - start)
+            })
+            .collect()
+    }
+
+    /// Return a parallel iterator on the iterators returned by
+    /// [`SplitSequentialGraph::split_iter`], so that the graph can be
+    /// scanned by `how_many` rayon tasks.
+    fn par_iter_nodes<'a>(
+        &'a self,
+        how_many: usize,
+    ) -> rayon::vec::IntoIter<SplitNodesIter<'a, Self>>
+    where
+        Self::NodesIter<'a>: Send,
+    {
+        use rayon::prelude::*;
+        self.split_iter(how_many).into_par_iter()
+    }
+
+    /// Return a parallel iterator over the arcs of the graph as pairs
+    /// `(src, dst)`, scanning the nodes with `how_many` rayon tasks as in
+    /// [`SplitSequentialGraph::par_iter_nodes`].
+    #[allow(clippy::type_complexity)]
+    fn par_iter_arcs<'a>(
+        &'a self,
+        how_many: usize,
+    ) -> rayon::iter::FlatMapIter<
+        rayon::vec::IntoIter<SplitNodesIter<'a, Self>>,
+        fn(
+            SplitNodesIter<'a, Self>,
+        ) -> ArcsIter<SplitNodesIter<'a, Self>, Self::SequentialSuccessorIter<'a>>,
+    >
+    where
+        Self::NodesIter<'a>: Send,
+    {
+        use rayon::prelude::*;
+        let arcs: fn(SplitNodesIter<'a, Self>) -> ArcsIter<_, _> =
+            |nodes| ArcsIter::new(nodes, None);
+        self.par_iter_nodes(how_many).flat_map_iter(arcs)
+    }
+}
+
+/// A random-access graph whose successors can be enumerated starting from a
+/// lower bound, which is useful to intersect sorted lists of successors.
+pub trait SeekableGraph: RandomAccessGraph {
+    /// Get a sorted iterator over the successors of `node_id` that are
+    /// greater than or equal to `lower_bound`
+    fn successors_from(&self, node_id: usize, lower_bound: usize) -> Self::RandomSuccessorIter<'_>;
+}
+
 /// A graph where each arc has a label
 pub trait Labelled {
     /// The type of the label on the arcs
@@ -209,3 +294,59 @@ unsafe impl<I: LabelledIterator + Iterator<Item = usize> + SortedIterator> Sorte
     for LabelledIteratorWrapper<I>
 {
 }
+
+/// An iterator over the arcs of a graph as pairs `(src, dst)`, built from an
+/// iterator over its nodes; see [`SequentialGraph::iter_arcs`].
+pub struct ArcsIter<N: Iterator<Item = (usize, S)>, S: Iterator<Item = usize>> {
+    nodes: N,
+    /// The current node and the iterator over its remaining successors
+    curr: Option<(usize, S)>,
+    /// The number of arcs still to be returned, if known
+    remaining: Option<usize>,
+}
+
+impl<N: Iterator<Item = (usize, S)>, S: Iterator<Item = usize>> ArcsIter<N, S> {
+    /// Create a new iterator over the arcs of the given nodes, whose number,
+    /// if known, is used as size hint.
+    pub fn new(nodes: N, num_arcs: Option<usize>) -> Self {
+        Self {
+            nodes,
+            curr: None,
+            remaining: num_arcs,
+        }
+    }
+}
+
+impl<N: Iterator<Item = (usize, S)>, S: Iterator<Item = usize>> Iterator for ArcsIter<N, S> {
+    type Item = (usize, usize);
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((src, succ)) = &mut self.curr {
+                if let Some(dst) = succ.next() {
+                    if let Some(remaining) = &mut self.remaining {
+                        *remaining = remaining.saturating_sub(1);
+                    }
+                    return Some((*src, dst));
+                }
+            }
+            self.curr = Some(self.nodes.next()?);
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(remaining) => (remaining, Some(remaining)),
+            None => (0, None),
+        }
+    }
+}
+
+/// The arcs are sorted if the nodes and the successors are
+unsafe impl<N, S> SortedIterator for ArcsIter<N, S>
+where
+    N: Iterator<Item = (usize, S)> + SortedIterator,
+    S: Iterator<Item = usize> + SortedIterator,
+{
+}
@@ -6,5 +6,12 @@
 mod bvgraph_codes;
 pub use bvgraph_codes::*;
 
+#[cfg(feature = "std")]
 pub(crate) mod graph;
+#[cfg(feature = "std")]
 pub use graph::*;
+
+#[cfg(feature = "std")]
+mod dyn_graph;
+#[cfg(feature = "std")]
+pub use dyn_graph::*;
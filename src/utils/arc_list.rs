@@ -0,0 +1,261 @@
+use super::COOIterToGraph;
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// The format of a file containing an arc list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArcListFormat {
+    /// Pairs of nodes as 64-bit little-endian integers.
+    Binary,
+    /// One arc per line, as two nodes in decimal notation separated by
+    /// whitespace; empty lines and lines starting with `#` or `%` are
+    /// skipped, as are further fields after the two nodes.
+    Text,
+}
+
+/// An iterator on the arcs of an arc list read from a [`BufRead`], such as
+/// the standard input.
+///
+/// # Panics
+/// The iterator panics if the reader fails, returns a malformed text arc, or
+/// ends in the middle of a binary arc.
+#[derive(Debug)]
+pub struct ArcListReader<R: BufRead> {
+    /// A description of the source of the arcs, for error messages.
+    source: String,
+    format: ArcListFormat,
+    reader: R,
+    /// The position in the input of the next arc.
+    offset: u64,
+    /// The number of lines of a text input read so far, for error messages.
+    line_number: usize,
+    /// The buffer for the lines of a text input.
+    line: String,
+}
+
+impl<R: BufRead> ArcListReader<R> {
+    /// Read an arc list in the given format; `source` describes the input
+    /// in error messages.
+    pub fn new(reader: R, format: ArcListFormat, source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            format,
+            reader,
+            offset: 0,
+            line_number: 0,
+            line: String::new(),
+        }
+    }
+
+    fn next_binary(&mut self) -> Option<(usize, usize)> {
+        let mut buffer = [0; 16];
+        // read_exact would not tell a clean end of input from a truncated
+        // record
+        let mut filled = 0;
+        while filled < buffer.len() {
+            match self.reader.read(&mut buffer[filled..]) {
+                Ok(0) => break,
+                Ok(len) => filled += len,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(err) => panic!("Cannot read {}: {}", self.source, err),
+            }
+        }
+        match filled {
+            0 => return None,
+            16 => {}
+            _ => panic!(
+                "Truncated arc at byte {} of {}: {} bytes instead of 16",
+                self.offset, self.source, filled
+            ),
+        }
+        self.offset += 16;
+        let src = u64::from_le_bytes(buffer[..8].try_into().unwrap());
+        let dst = u64::from_le_bytes(buffer[8..].try_into().unwrap());
+        Some((src as usize, dst as usize))
+    }
+
+    fn next_text(&mut self) -> Option<(usize, usize)> {
+        loop {
+            self.line.clear();
+            let len = self
+                .reader
+                .read_line(&mut self.line)
+                .unwrap_or_else(|err| panic!("Cannot read {}: {}", self.source, err));
+            if len == 0 {
+                return None;
+            }
+            self.offset += len as u64;
+            self.line_number += 1;
+            let line = self.line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with('%') {
+                continue;
+            }
+            let mut fields = line
+                .split_ascii_whitespace()
+                .map(|field| field.parse::<usize>());
+            match (fields.next(), fields.next()) {
+                (Some(Ok(src)), Some(Ok(dst))) => return Some((src, dst)),
+                _ => panic!(
+                    "Malformed arc at line {} of {}: {}",
+                    self.line_number, self.source, line
+                ),
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for ArcListReader<R> {
+    type Item = (usize, usize);
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.format {
+            ArcListFormat::Binary => self.next_binary(),
+            ArcListFormat::Text => self.next_text(),
+        }
+    }
+}
+
+/// An iterator on the arcs of an arc list read lazily from a file.
+///
+/// Cloning the iterator reopens the file and seeks to the current position,
+/// so an [`ArcListGraph`] can scan the file as many times as needed without
+/// keeping the arcs in memory.
+///
+/// # Panics
+/// The iterator panics if the file cannot be read or contains a malformed
+/// arc.
+#[derive(Debug)]
+pub struct ArcListIter {
+    path: PathBuf,
+    reader: ArcListReader<BufReader<File>>,
+}
+
+impl ArcListIter {
+    /// Open an arc list in the given format.
+    pub fn new<P: AsRef<Path>>(path: P, format: ArcListFormat) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .with_context(|| format!("Cannot open arc list {}", path.to_string_lossy()))?;
+        Ok(Self {
+            path: path.to_owned(),
+            reader: ArcListReader::new(
+                BufReader::with_capacity(1 << 20, file),
+                format,
+                path.to_string_lossy(),
+            ),
+        })
+    }
+}
+
+impl Clone for ArcListIter {
+    fn clone(&self) -> Self {
+        let mut res = Self::new(&self.path, self.reader.format).unwrap();
+        res.reader
+            .reader
+            .seek(SeekFrom::Start(self.reader.offset))
+            .unwrap();
+        res.reader.offset = self.reader.offset;
+        res.reader.line_number = self.reader.line_number;
+        res
+    }
+}
+
+impl Iterator for ArcListIter {
+    type Item = (usize, usize);
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader.next()
+    }
+}
+
+/// A sequential graph whose arcs are read from a file each time its nodes
+/// are iterated upon.
+///
+/// The arcs must be sorted by source and destination, and there must be no
+/// duplicates, as in a [`COOIterToGraph`]. Unsorted arc lists can be sorted
+/// with a [`SortPairs`](super::SortPairs).
+pub type ArcListGraph = COOIterToGraph<ArcListIter>;
+
+impl ArcListGraph {
+    /// Create a graph with `num_nodes` nodes from a sorted arc list in the
+    /// given format.
+    pub fn from_file<P: AsRef<Path>>(
+        num_nodes: usize,
+        path: P,
+        format: ArcListFormat,
+    ) -> Result<Self> {
+        Ok(Self::new(num_nodes, ArcListIter::new(path, format)?))
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_arc_list_graph() -> Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    use crate::traits::SequentialGraph;
+    use std::io::Write;
+    let arcs = [(0, 1), (0, 2), (1, 2), (1, 3), (2, 4), (3, 4)];
+    let expected = VecGraph::from_arc_list(&arcs);
+
+    let dir = tempfile::tempdir()?;
+    let text_path = dir.path().join("arcs.txt");
+    let mut text = File::create(&text_path)?;
+    writeln!(text, "# a comment")?;
+    for (src, dst) in arcs {
+        writeln!(text, "{}\t{}", src, dst)?;
+    }
+    writeln!(text)?;
+    drop(text);
+    let binary_path = dir.path().join("arcs.bin");
+    let mut binary = File::create(&binary_path)?;
+    for (src, dst) in arcs {
+        binary.write_all(&(src as u64).to_le_bytes())?;
+        binary.write_all(&(dst as u64).to_le_bytes())?;
+    }
+    drop(binary);
+
+    for (path, format) in [
+        (&text_path, ArcListFormat::Text),
+        (&binary_path, ArcListFormat::Binary),
+    ] {
+        let graph = ArcListGraph::from_file(5, path, format)?;
+        // twice, to check that the file is read again
+        for _ in 0..2 {
+            assert_eq!(VecGraph::from_node_iter(graph.iter_nodes()), expected);
+        }
+        let mut iter = ArcListIter::new(path, format)?;
+        iter.next();
+        assert!(iter.clone().eq(arcs[1..].iter().copied()));
+    }
+    assert!(ArcListIter::new(dir.path().join("none"), ArcListFormat::Text).is_err());
+
+    let text = b"0 1\n% a comment\n2 3 extra\n";
+    let reader = ArcListReader::new(&text[..], ArcListFormat::Text, "memory");
+    assert!(reader.eq([(0, 1), (2, 3)]));
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+#[should_panic(expected = "Truncated arc at byte 16 of memory")]
+fn test_truncated_binary_arc_list() {
+    let mut binary = Vec::new();
+    for value in [0_u64, 1, 2] {
+        binary.extend_from_slice(&value.to_le_bytes());
+    }
+    let mut reader = ArcListReader::new(&binary[..], ArcListFormat::Binary, "memory");
+    assert_eq!(reader.next(), Some((0, 1)));
+    reader.next();
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+#[should_panic(expected = "Malformed arc at line 2 of memory")]
+fn test_malformed_text_arc_list() {
+    let text = b"0 1\n2\n";
+    ArcListReader::new(&text[..], ArcListFormat::Text, "memory").for_each(drop);
+}
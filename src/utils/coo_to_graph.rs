@@ -18,7 +18,7 @@ impl<I: Iterator<Item = (usize, usize)> + Clone> COOIterToGraph<I> {
 
 impl<I: Iterator<Item = (usize, usize)> + Clone> SequentialGraph for COOIterToGraph<I> {
     type NodesIter<'b> = SortedNodePermutedIterator<'b, I> where Self: 'b;
-    type SequentialSuccessorIter<'b> = SortedSequentialPermutedIterator<'b, I> where Self: 'b;
+    type SequentialSuccessorIter<'b> = SortedSequentialPermutedIterator where Self: 'b;
 
     #[inline(always)]
     fn num_nodes(&self) -> usize {
@@ -36,7 +36,7 @@ impl<I: Iterator<Item = (usize, usize)> + Clone> SequentialGraph for COOIterToGr
         SortedNodePermutedIterator {
             num_nodes: self.num_nodes,
             curr_node: 0_usize.wrapping_sub(1), // No node seen yet
-            next_pair: iter.next().unwrap_or((usize::MAX, usize::MAX)),
+            next_pair: iter.next(),
             iter,
             _marker: PhantomData,
         }
@@ -44,60 +44,75 @@ impl<I: Iterator<Item = (usize, usize)> + Clone> SequentialGraph for COOIterToGr
 }
 
 #[derive(Debug, Clone)]
+/// The nodes iterator of a [`COOIterToGraph`].
+///
+/// The successors of each node are read from the underlying iterator into a
+/// buffer owned by the successors iterator, so the two iterators are
+/// independent and can be sent to other threads.
 pub struct SortedNodePermutedIterator<'a, I: Iterator<Item = (usize, usize)>> {
     num_nodes: usize,
     curr_node: usize,
-    next_pair: (usize, usize),
+    /// The next pair, or `None` if the pairs are finished
+    next_pair: Option<(usize, usize)>,
     iter: I,
     _marker: std::marker::PhantomData<&'a ()>,
 }
 
 impl<'a, I: Iterator<Item = (usize, usize)>> Iterator for SortedNodePermutedIterator<'a, I> {
-    type Item = (usize, SortedSequentialPermutedIterator<'a, I>);
+    type Item = (usize, SortedSequentialPermutedIterator);
     fn next(&mut self) -> Option<Self::Item> {
         self.curr_node = self.curr_node.wrapping_add(1);
         if self.curr_node == self.num_nodes {
             return None;
         }
 
-        // This happens if the user doesn't use the successors iter
-        while self.next_pair.0 < self.curr_node {
-            self.next_pair = self.iter.next().unwrap_or((usize::MAX, usize::MAX));
+        // This happens if the pairs are not sorted
+        while matches!(self.next_pair, Some((src, _)) if src < self.curr_node) {
+            self.next_pair = self.iter.next();
+        }
+
+        // read the successors of curr_node
+        let mut succ = Vec::new();
+        while let Some((src, dst)) = self.next_pair {
+            if src != self.curr_node {
+                break;
+            }
+            succ.push(dst);
+            self.next_pair = self.iter.next();
         }
 
         Some((
             self.curr_node,
             SortedSequentialPermutedIterator {
-                node_iter_ptr: {
-                    let self_ptr: *mut Self = self;
-                    self_ptr
-                },
+                iter: succ.into_iter(),
             },
         ))
     }
 }
 
 #[derive(Debug, Clone)]
-/// Iter until we found a triple with src different than curr_node
-pub struct SortedSequentialPermutedIterator<'a, I: Iterator<Item = (usize, usize)>> {
-    node_iter_ptr: *mut SortedNodePermutedIterator<'a, I>,
+/// The successors iterator of a [`COOIterToGraph`]
+pub struct SortedSequentialPermutedIterator {
+    iter: std::vec::IntoIter<usize>,
 }
 
-impl<'a, I: Iterator<Item = (usize, usize)>> Iterator for SortedSequentialPermutedIterator<'a, I> {
+impl Iterator for SortedSequentialPermutedIterator {
     type Item = usize;
+    #[inline(always)]
     fn next(&mut self) -> Option<Self::Item> {
-        let node_iter = unsafe { &mut *self.node_iter_ptr };
-        // if we reached a new node, the successors of curr_node are finished
-        if node_iter.next_pair.0 != node_iter.curr_node {
-            None
-        } else {
-            // get the next triple
-            let pair = node_iter.iter.next().unwrap_or((usize::MAX, usize::MAX));
-            // store the triple and return the previous successor
-            // storing the label since it should be one step behind the successor
-            let (_src, dst) = core::mem::replace(&mut node_iter.next_pair, pair);
-            Some(dst)
-        }
+        self.iter.next()
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl ExactSizeIterator for SortedSequentialPermutedIterator {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.iter.len()
     }
 }
 
@@ -112,3 +127,25 @@ fn test_coo_iter() -> anyhow::Result<()> {
     assert_eq!(g, g2);
     Ok(())
 }
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_coo_iter_send() {
+    use rayon::prelude::*;
+    let arcs = vec![(0, 1), (0, 2), (1, 2), (1, 3), (2, 4), (3, 4)];
+    let coo = COOIterToGraph::new(5, arcs.clone().into_iter());
+    // the successors iterators can outlive the nodes iterator, and both can
+    // be moved to other threads
+    let succ = coo.iter_nodes().collect::<Vec<_>>();
+    let degrees = succ
+        .into_par_iter()
+        .map(|(_, succ)| succ.len())
+        .collect::<Vec<_>>();
+    assert_eq!(degrees, vec![2, 2, 1, 1, 0]);
+    let num_arcs = coo
+        .iter_nodes()
+        .par_bridge()
+        .map(|(_, succ)| succ.count())
+        .sum::<usize>();
+    assert_eq!(num_arcs, arcs.len());
+}
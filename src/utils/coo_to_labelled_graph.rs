@@ -1,6 +1,5 @@
 use crate::traits::*;
 use core::marker::PhantomData;
-use core::mem::MaybeUninit;
 
 /// A Sequential graph built on an iterator of pairs of nodes and their labels
 #[derive(Debug, Clone)]
@@ -27,7 +26,7 @@ impl<L: Clone + 'static, I: Iterator<Item = (usize, usize, L)> + Clone> Sequenti
     for COOIterToLabelledGraph<I>
 {
     type NodesIter<'b> = SortedLabelledNodePermutedIterator<'b, L, I> where Self: 'b;
-    type SequentialSuccessorIter<'b> = SortedLabelledSequentialPermutedIterator<'b, L, I> where Self: 'b;
+    type SequentialSuccessorIter<'b> = SortedLabelledSequentialPermutedIterator<L> where Self: 'b;
 
     #[inline(always)]
     fn num_nodes(&self) -> usize {
@@ -45,14 +44,7 @@ impl<L: Clone + 'static, I: Iterator<Item = (usize, usize, L)> + Clone> Sequenti
         SortedLabelledNodePermutedIterator {
             num_nodes: self.num_nodes,
             curr_node: 0_usize.wrapping_sub(1), // No node seen yet
-            next_pair: iter.next().unwrap_or((usize::MAX, usize::MAX, unsafe {
-                #[allow(clippy::uninit_assumed_init)]
-                MaybeUninit::uninit().assume_init()
-            })),
-            label: unsafe {
-                #[allow(clippy::uninit_assumed_init)]
-                MaybeUninit::uninit().assume_init()
-            },
+            next_pair: iter.next(),
             iter,
             _marker: PhantomData,
         }
@@ -60,11 +52,16 @@ impl<L: Clone + 'static, I: Iterator<Item = (usize, usize, L)> + Clone> Sequenti
 }
 
 #[derive(Debug, Clone)]
+/// The nodes iterator of a [`COOIterToLabelledGraph`].
+///
+/// The successors of each node and their labels are read from the
+/// underlying iterator into a buffer owned by the successors iterator, so
+/// the two iterators are independent and can be sent to other threads.
 pub struct SortedLabelledNodePermutedIterator<'a, L, I: Iterator<Item = (usize, usize, L)>> {
     num_nodes: usize,
     curr_node: usize,
-    next_pair: (usize, usize, L),
-    label: L,
+    /// The next triple, or `None` if the triples are finished
+    next_pair: Option<(usize, usize, L)>,
     iter: I,
     _marker: std::marker::PhantomData<&'a ()>,
 }
@@ -72,82 +69,77 @@ pub struct SortedLabelledNodePermutedIterator<'a, L, I: Iterator<Item = (usize,
 impl<'a, L, I: Iterator<Item = (usize, usize, L)>> Iterator
     for SortedLabelledNodePermutedIterator<'a, L, I>
 {
-    type Item = (usize, SortedLabelledSequentialPermutedIterator<'a, L, I>);
+    type Item = (usize, SortedLabelledSequentialPermutedIterator<L>);
     fn next(&mut self) -> Option<Self::Item> {
         self.curr_node = self.curr_node.wrapping_add(1);
         if self.curr_node == self.num_nodes {
             return None;
         }
 
-        // This happens if the user doesn't use the successors iter
-        while self.next_pair.0 < self.curr_node {
-            self.next_pair = self.iter.next().unwrap_or((usize::MAX, usize::MAX, unsafe {
-                #[allow(clippy::uninit_assumed_init)]
-                MaybeUninit::uninit().assume_init()
-            }));
+        // This happens if the triples are not sorted
+        while matches!(self.next_pair, Some((src, _, _)) if src < self.curr_node) {
+            self.next_pair = self.iter.next();
+        }
+
+        // read the successors of curr_node and their labels
+        let mut succ = Vec::new();
+        while matches!(self.next_pair, Some((src, _, _)) if src == self.curr_node) {
+            let next_pair = self.iter.next();
+            if let Some((_src, dst, label)) = core::mem::replace(&mut self.next_pair, next_pair) {
+                succ.push((dst, label));
+            }
         }
 
         Some((
             self.curr_node,
             SortedLabelledSequentialPermutedIterator {
-                node_iter_ptr: {
-                    let self_ptr: *mut Self = self;
-                    self_ptr
-                },
+                iter: succ.into_iter(),
+                label: None,
             },
         ))
     }
 }
 
 #[derive(Debug, Clone)]
-/// Iter until we found a triple with src different than curr_node
-pub struct SortedLabelledSequentialPermutedIterator<'a, L, I: Iterator<Item = (usize, usize, L)>> {
-    node_iter_ptr: *mut SortedLabelledNodePermutedIterator<'a, L, I>,
+/// The successors iterator of a [`COOIterToLabelledGraph`]
+pub struct SortedLabelledSequentialPermutedIterator<L> {
+    iter: std::vec::IntoIter<(usize, L)>,
+    /// The label of the last successor returned, if any
+    label: Option<L>,
 }
 
-impl<'a, L, I: Iterator<Item = (usize, usize, L)>> Iterator
-    for SortedLabelledSequentialPermutedIterator<'a, L, I>
-{
+impl<L> Iterator for SortedLabelledSequentialPermutedIterator<L> {
     type Item = usize;
+    #[inline(always)]
     fn next(&mut self) -> Option<Self::Item> {
-        let node_iter = unsafe { &mut *self.node_iter_ptr };
-        // if we reached a new node, the successors of curr_node are finished
-        if node_iter.next_pair.0 != node_iter.curr_node {
-            None
-        } else {
-            // get the next triple
-            let pair = node_iter
-                .iter
-                .next()
-                .unwrap_or((usize::MAX, usize::MAX, unsafe {
-                    #[allow(clippy::uninit_assumed_init)]
-                    MaybeUninit::uninit().assume_init()
-                }));
-            // store the triple and return the previous successor
-            // storing the label since it should be one step behind the successor
-            let (_src, dst, label) = core::mem::replace(&mut node_iter.next_pair, pair);
-            node_iter.label = label;
-            Some(dst)
-        }
+        let (dst, label) = self.iter.next()?;
+        self.label = Some(label);
+        Some(dst)
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
     }
 }
 
-impl<'a, L, I: Iterator<Item = (usize, usize, L)>> Labelled
-    for SortedLabelledSequentialPermutedIterator<'a, L, I>
-{
+impl<L> ExactSizeIterator for SortedLabelledSequentialPermutedIterator<L> {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<L> Labelled for SortedLabelledSequentialPermutedIterator<L> {
     type Label = L;
 }
 
-impl<'a, L: Clone, I: Iterator<Item = (usize, usize, L)>> LabelledIterator
-    for SortedLabelledSequentialPermutedIterator<'a, L, I>
-{
+impl<L: Clone> LabelledIterator for SortedLabelledSequentialPermutedIterator<L> {
     #[inline(always)]
     fn label(&self) -> Self::Label {
-        let node_iter = unsafe { &*self.node_iter_ptr };
-        if node_iter.curr_node == usize::MAX {
-            panic!("You cannot call label() on an iterator that has not been advanced yet!");
-        }
-        node_iter.label.clone()
+        self.label
+            .clone()
+            .expect("You cannot call label() on an iterator that has not been advanced yet!")
     }
 }
 
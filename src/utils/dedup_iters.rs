@@ -0,0 +1,108 @@
+use crate::traits::SortedIterator;
+
+/// An adapter on a sorted iterator of triples that collapses runs of triples
+/// with the same pair of nodes, returning only the first triple of each run.
+///
+/// This is useful to turn a multigraph, possibly obtained by merging several
+/// arc lists, into a simple graph. Which payload is kept is well defined only
+/// if the underlying iterator is stable, as [`KMergeIters`](crate::utils::KMergeIters) is.
+#[derive(Clone, Debug)]
+pub struct DedupIter<T, I: Iterator<Item = (usize, usize, T)> + SortedIterator> {
+    iter: I,
+    next: Option<(usize, usize, T)>,
+}
+
+impl<T, I: Iterator<Item = (usize, usize, T)> + SortedIterator> DedupIter<T, I> {
+    /// Wrap a sorted iterator of triples.
+    pub fn new(mut iter: I) -> Self {
+        let next = iter.next();
+        Self { iter, next }
+    }
+}
+
+impl<T, I: Iterator<Item = (usize, usize, T)> + SortedIterator> Iterator for DedupIter<T, I> {
+    type Item = (usize, usize, T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.next.take()?;
+        // skip the triples with the same pair of nodes
+        self.next = self
+            .iter
+            .find(|(src, dst, _)| (*src, *dst) != (result.0, result.1));
+        Some(result)
+    }
+}
+
+unsafe impl<T, I: Iterator<Item = (usize, usize, T)> + SortedIterator> SortedIterator
+    for DedupIter<T, I>
+{
+}
+
+/// An adapter on a sorted iterator of triples that collapses runs of triples
+/// with the same pair of nodes, returning the pair with the length of the run
+/// as payload.
+///
+/// The original payloads are discarded. This makes it possible to build a
+/// weighted graph from a multigraph, using the multiplicity of each arc as
+/// its weight.
+#[derive(Clone, Debug)]
+pub struct CountDupsIter<T, I: Iterator<Item = (usize, usize, T)> + SortedIterator> {
+    iter: I,
+    next: Option<(usize, usize)>,
+}
+
+impl<T, I: Iterator<Item = (usize, usize, T)> + SortedIterator> CountDupsIter<T, I> {
+    /// Wrap a sorted iterator of triples.
+    pub fn new(mut iter: I) -> Self {
+        let next = iter.next().map(|(src, dst, _)| (src, dst));
+        Self { iter, next }
+    }
+}
+
+impl<T, I: Iterator<Item = (usize, usize, T)> + SortedIterator> Iterator for CountDupsIter<T, I> {
+    type Item = (usize, usize, usize);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (src, dst) = self.next.take()?;
+        let mut count = 1;
+        for (next_src, next_dst, _) in &mut self.iter {
+            if (next_src, next_dst) != (src, dst) {
+                self.next = Some((next_src, next_dst));
+                break;
+            }
+            count += 1;
+        }
+        Some((src, dst, count))
+    }
+}
+
+unsafe impl<T, I: Iterator<Item = (usize, usize, T)> + SortedIterator> SortedIterator
+    for CountDupsIter<T, I>
+{
+}
+
+#[cfg_attr(test, test)]
+#[cfg(test)]
+fn test_dedup_iters() -> anyhow::Result<()> {
+    use crate::utils::SortPairs;
+    let dir = tempfile::tempdir()?;
+    let mut sp = <SortPairs<()>>::new(4, dir.path())?;
+    let arcs = [(2, 1), (0, 1), (0, 1), (1, 3), (0, 1), (2, 1), (0, 2)];
+    for &(src, dst) in &arcs {
+        sp.push(src, dst, ())?;
+    }
+    assert_eq!(
+        sp.iter()?
+            .dedup()
+            .map(|(src, dst, _)| (src, dst))
+            .collect::<Vec<_>>(),
+        vec![(0, 1), (0, 2), (1, 3), (2, 1)]
+    );
+    assert_eq!(
+        sp.iter()?.count_dups().collect::<Vec<_>>(),
+        vec![(0, 1, 3), (0, 2, 1), (1, 3, 1), (2, 1, 2)]
+    );
+    Ok(())
+}
@@ -0,0 +1,580 @@
+use crate::utils::LoserTree;
+use anyhow::{bail, Context, Result};
+use dsi_bitstream::prelude::*;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// A codec writing records to a bitstream and reading them back.
+///
+/// A new codec is created for each batch, and records are written and read
+/// in sorted order, so the codec can keep the previous record and write
+/// just the difference from it, as the one of [`SortPairs`](super::SortPairs)
+/// does with gaps.
+pub trait RecordCodec<R>: Default + Send + 'static {
+    /// Write a record to the bitstream and return the number of bits written.
+    fn write<E: Endianness, B: WriteCodes<E>>(
+        &mut self,
+        record: &R,
+        bitstream: &mut B,
+    ) -> Result<usize>;
+    /// Read the next record from the bitstream.
+    fn read<E: Endianness, B: ReadCodes<E>>(&mut self, bitstream: &mut B) -> Result<R>;
+}
+
+/// A record that can be sorted in external memory by an [`ExternalSorter`].
+pub trait SortRecord: Send + Copy + 'static {
+    /// The part of the record by which records are sorted.
+    type Key: Ord + Copy + Send;
+    /// The codec used to write the records to disk.
+    type Codec: RecordCodec<Self>;
+    /// Return the key of the record.
+    fn key(&self) -> Self::Key;
+}
+
+/// The codec of arrays of integers, whose first component is written as a
+/// gap from the previous record, and whose other components are written as
+/// gaps if the previous components are equal to those of the previous
+/// record, and as they are otherwise.
+///
+/// For pairs this is the format of the batches of
+/// [`SortPairs`](super::SortPairs).
+#[derive(Clone, Copy, Debug)]
+pub struct LexCodec<const N: usize> {
+    prev: [usize; N],
+}
+
+impl<const N: usize> Default for LexCodec<N> {
+    fn default() -> Self {
+        Self { prev: [0; N] }
+    }
+}
+
+impl<const N: usize> RecordCodec<[usize; N]> for LexCodec<N> {
+    #[inline(always)]
+    fn write<E: Endianness, B: WriteCodes<E>>(
+        &mut self,
+        record: &[usize; N],
+        bitstream: &mut B,
+    ) -> Result<usize> {
+        let mut written_bits = 0;
+        let mut same_prefix = true;
+        for (&x, &prev) in record.iter().zip(&self.prev) {
+            let value = if same_prefix { x - prev } else { x };
+            written_bits += bitstream.write_gamma(value as u64)?;
+            same_prefix &= x == prev;
+        }
+        self.prev = *record;
+        Ok(written_bits)
+    }
+
+    #[inline(always)]
+    fn read<E: Endianness, B: ReadCodes<E>>(&mut self, bitstream: &mut B) -> Result<[usize; N]> {
+        let mut same_prefix = true;
+        for prev in &mut self.prev {
+            let value = bitstream.read_gamma()? as usize;
+            if same_prefix {
+                same_prefix = value == 0;
+                *prev += value;
+            } else {
+                *prev = value;
+            }
+        }
+        Ok(self.prev)
+    }
+}
+
+/// Arrays of integers are sorted lexicographically; for example, triples
+/// `[label, src, dst]`.
+impl<const N: usize> SortRecord for [usize; N] {
+    type Key = [usize; N];
+    type Codec = LexCodec<N>;
+    #[inline(always)]
+    fn key(&self) -> Self::Key {
+        *self
+    }
+}
+
+/// An external-memory sorter: it ingests records, sorts them in batches of
+/// `batch_size` records that are written to disk with the [codec of the
+/// records](SortRecord::Codec), and finally merges the batches.
+///
+/// Batches are sorted in place by an unstable sort, so records with the
+/// same key are returned in an unspecified order.
+/// [`SortPairs`](super::SortPairs) is an external sorter of pairs of nodes
+/// with a payload.
+pub struct ExternalSorter<R: SortRecord> {
+    /// The batch size
+    batch_size: usize,
+    /// The length of each batch dumped so far
+    batch_lens: Vec<usize>,
+    /// The batch of records we are currently building
+    batch: Vec<R>,
+    /// were we are going to store the tmp files; batches are assigned
+    /// round-robin to the directories
+    dirs: Vec<PathBuf>,
+    /// The zstd compression level of the batches, if compressed
+    compression: Option<i32>,
+    /// The thread writing the previous batch, which returns the buffer
+    pending: Option<std::thread::JoinHandle<Result<Vec<R>>>>,
+}
+
+impl<R: SortRecord> core::ops::Drop for ExternalSorter<R> {
+    fn drop(&mut self) {
+        let _ = self.dump();
+        let _ = self.wait_pending();
+    }
+}
+
+impl<R: SortRecord> ExternalSorter<R> {
+    /// Create a new `ExternalSorter` with a given batch size
+    pub fn new<P: AsRef<Path>>(batch_size: usize, dir: P) -> Result<Self> {
+        Ok(ExternalSorter {
+            batch_size,
+            batch_lens: Vec::new(),
+            batch: Vec::with_capacity(batch_size),
+            dirs: vec![dir.as_ref().to_owned()],
+            compression: None,
+            pending: None,
+        })
+    }
+
+    /// Spread the batches round-robin across the given directories, which
+    /// replace the one passed to the constructor.
+    ///
+    /// With one directory per scratch disk, I/O is parallelized and no single
+    /// volume has to hold all the batches. Must be called before pushing any
+    /// record.
+    pub fn with_dirs<P: AsRef<Path>>(mut self, dirs: impl IntoIterator<Item = P>) -> Result<Self> {
+        debug_assert!(self.batch_lens.is_empty() && self.batch.is_empty());
+        let dirs = dirs
+            .into_iter()
+            .map(|dir| dir.as_ref().to_owned())
+            .collect::<Vec<_>>();
+        if dirs.is_empty() {
+            bail!("At least one directory is needed");
+        }
+        self.dirs = dirs;
+        Ok(self)
+    }
+
+    /// Return the path of the file of the given batch.
+    fn batch_path(&self, batch_idx: usize) -> PathBuf {
+        self.dirs[batch_idx % self.dirs.len()].join(format!("{:06x}", batch_idx))
+    }
+
+    #[cfg(feature = "zstd")]
+    /// Compress the batches written to disk with zstd at the given level.
+    ///
+    /// This trades some CPU time for a large reduction of the temporary disk
+    /// space, as gaps between sorted records compress well. Must be called
+    /// before pushing any record.
+    pub fn with_zstd(mut self, level: i32) -> Self {
+        debug_assert!(self.batch_lens.is_empty() && self.batch.is_empty());
+        self.compression = Some(level);
+        self
+    }
+
+    /// Create a new `ExternalSorter` whose batch size is derived from a
+    /// memory budget of `memory_budget` bytes for the buffered records.
+    ///
+    /// Each record takes `size_of::<R>()` bytes in memory, padding included,
+    /// so the budget is converted into a number of records. The budget is
+    /// split between the batch being filled and the one being written in
+    /// background, and the buffers are allocated upfront.
+    ///
+    /// Records are [`Copy`], so they do not own heap memory, and batches are
+    /// sorted in place, so the record buffers do not grow past the budget.
+    /// The budget is nonetheless approximate: it does not include the
+    /// fixed-size I/O buffer (and, if batches are compressed, the zstd
+    /// state) used to write a batch to disk.
+    pub fn new_with_memory_budget<P: AsRef<Path>>(memory_budget: usize, dir: P) -> Result<Self> {
+        let record_size = core::mem::size_of::<R>();
+        if memory_budget < 2 * record_size {
+            bail!(
+                "The memory budget ({} bytes) cannot hold two records ({} bytes each)",
+                memory_budget,
+                record_size
+            );
+        }
+        Self::new(memory_budget / (2 * record_size), dir)
+    }
+
+    /// Return the number of bytes allocated for the batch buffers, including
+    /// the one that is being written in background.
+    pub fn memory_usage(&self) -> usize {
+        2 * self.batch.capacity() * core::mem::size_of::<R>()
+    }
+
+    /// Return the length of each batch dumped so far.
+    pub fn batch_lens(&self) -> &[usize] {
+        &self.batch_lens
+    }
+
+    /// Add a record.
+    pub fn push(&mut self, record: R) -> Result<()> {
+        self.batch.push(record);
+        if self.batch.len() >= self.batch_size {
+            self.dump()?;
+        }
+        Ok(())
+    }
+
+    /// Sort the current batch and hand it to a background thread that writes
+    /// it to disk, so that the caller can keep pushing records in the spare
+    /// buffer in the meantime (double buffering).
+    ///
+    /// Errors of the background write are returned by the next call of
+    /// `dump` or by [`ExternalSorter::iter`].
+    fn dump(&mut self) -> Result<()> {
+        // early exit
+        if self.batch.is_empty() {
+            return Ok(());
+        }
+        // an unstable sort works in place, without a scratch buffer
+        self.batch.par_sort_unstable_by_key(|record| record.key());
+        // wait for the previous batch and recover its buffer
+        let spare = self
+            .wait_pending()?
+            .unwrap_or_else(|| Vec::with_capacity(self.batch_size));
+        let batch = core::mem::replace(&mut self.batch, spare);
+        // create a batch file where to dump
+        let batch_name = self.batch_path(self.batch_lens.len());
+        let compression = self.compression;
+        self.batch_lens.push(batch.len());
+        self.pending = Some(std::thread::spawn(move || {
+            let mut batch = batch;
+            write_batch(&batch_name, &batch, compression)?;
+            batch.clear();
+            Ok(batch)
+        }));
+        Ok(())
+    }
+
+    /// Wait for the batch being written in background, if any, returning
+    /// its (empty) buffer.
+    fn wait_pending(&mut self) -> Result<Option<Vec<R>>> {
+        match self.pending.take() {
+            None => Ok(None),
+            Some(handle) => match handle.join() {
+                Ok(batch) => batch.map(Some),
+                Err(_) => bail!("The thread writing a batch panicked"),
+            },
+        }
+    }
+
+    /// Cancel all the files that were created
+    pub fn cancel_batches(&mut self) -> Result<()> {
+        self.wait_pending()?;
+        for i in 0..self.batch_lens.len() {
+            let batch_name = self.batch_path(i);
+            // It's OK if something is not OK here
+            std::fs::remove_file(batch_name)?;
+        }
+        self.batch_lens.clear();
+        self.batch.clear();
+        Ok(())
+    }
+
+    /// Dump the current batch and return an iterator on each batch written
+    /// so far.
+    ///
+    /// This is useful to merge the batches of several sorters, for example,
+    /// one per thread, with a single [`RecordMerge`].
+    pub fn batches(&mut self) -> Result<Vec<RecordBatchIterator<R>>> {
+        self.dump()?;
+        self.wait_pending()?;
+        self.batch_lens
+            .iter()
+            .enumerate()
+            .map(|(batch_idx, &len)| {
+                RecordBatchIterator::open(
+                    self.batch_path(batch_idx),
+                    len,
+                    self.compression.is_some(),
+                )
+            })
+            .collect()
+    }
+
+    /// Dump the current batch and return an iterator on all the records
+    /// pushed so far, in sorted order.
+    pub fn iter(&mut self) -> Result<RecordMerge<R, RecordBatchIterator<R>>> {
+        Ok(RecordMerge::new(self.batches()?.into_iter()))
+    }
+}
+
+/// Write a sorted batch to the given file, possibly compressing it.
+fn write_batch<R: SortRecord>(path: &Path, batch: &[R], compression: Option<i32>) -> Result<()> {
+    let file = std::io::BufWriter::with_capacity(1 << 22, std::fs::File::create(path)?);
+    let mut file = match compression {
+        None => BatchWriter::Plain(file),
+        #[cfg(feature = "zstd")]
+        Some(level) => BatchWriter::Zstd(zstd::Encoder::new(file, level)?),
+        #[cfg(not(feature = "zstd"))]
+        Some(_) => bail!("Compressed batches require the zstd feature"),
+    };
+    // createa bitstream to write to the file
+    let mut stream = <BufferedBitStreamWrite<LE, _>>::new(FileBackend::new(&mut file));
+    let mut codec = R::Codec::default();
+    for record in batch {
+        codec.write(record, &mut stream)?;
+    }
+    // flush the stream
+    stream.flush()?;
+    drop(stream);
+    file.finish()
+        .with_context(|| format!("Cannot write batch {}", path.display()))
+}
+
+/// The writer of a batch file, possibly compressed.
+enum BatchWriter {
+    Plain(std::io::BufWriter<std::fs::File>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::Encoder<'static, std::io::BufWriter<std::fs::File>>),
+}
+
+impl BatchWriter {
+    /// Write the final zstd frame, if compressed, and flush the file,
+    /// returning the errors that would be lost when dropping the writer.
+    fn finish(self) -> Result<()> {
+        let mut file = match self {
+            BatchWriter::Plain(file) => file,
+            #[cfg(feature = "zstd")]
+            BatchWriter::Zstd(encoder) => encoder.finish()?,
+        };
+        std::io::Write::flush(&mut file)?;
+        Ok(())
+    }
+}
+
+impl std::io::Write for BatchWriter {
+    #[inline(always)]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            BatchWriter::Plain(w) => w.write(buf),
+            #[cfg(feature = "zstd")]
+            BatchWriter::Zstd(w) => w.write(buf),
+        }
+    }
+
+    #[inline(always)]
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            BatchWriter::Plain(w) => w.flush(),
+            #[cfg(feature = "zstd")]
+            BatchWriter::Zstd(w) => w.flush(),
+        }
+    }
+}
+
+/// The reader of a batch file, possibly compressed.
+pub enum BatchReader {
+    Plain(std::io::BufReader<std::fs::File>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::Decoder<'static, std::io::BufReader<std::fs::File>>),
+}
+
+impl BatchReader {
+    fn open(file_path: &Path, compressed: bool) -> Result<Self> {
+        let file = std::fs::File::open(file_path)
+            .with_context(|| format!("Cannot open batch {}", file_path.to_string_lossy()))?;
+        if !compressed {
+            return Ok(BatchReader::Plain(std::io::BufReader::new(file)));
+        }
+        #[cfg(feature = "zstd")]
+        return Ok(BatchReader::Zstd(zstd::Decoder::new(file)?));
+        #[cfg(not(feature = "zstd"))]
+        bail!("Compressed batches require the zstd feature");
+    }
+}
+
+impl core::fmt::Debug for BatchReader {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BatchReader::Plain(_) => f.write_str("BatchReader::Plain"),
+            #[cfg(feature = "zstd")]
+            BatchReader::Zstd(_) => f.write_str("BatchReader::Zstd"),
+        }
+    }
+}
+
+impl std::io::Read for BatchReader {
+    #[inline(always)]
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            BatchReader::Plain(r) => r.read(buf),
+            #[cfg(feature = "zstd")]
+            BatchReader::Zstd(r) => r.read(buf),
+        }
+    }
+}
+
+/// An iterator that can read the batch files generated by an
+/// [`ExternalSorter`] and iterate over the records
+pub struct RecordBatchIterator<R: SortRecord> {
+    file_path: PathBuf,
+    compressed: bool,
+    stream: BufferedBitStreamRead<LE, u64, FileBackend<u32, BatchReader>>,
+    len: usize,
+    current: usize,
+    codec: R::Codec,
+}
+
+impl<R: SortRecord> RecordBatchIterator<R> {
+    /// Open an uncompressed batch file containing `len` records.
+    pub fn new<P: AsRef<std::path::Path>>(file_path: P, len: usize) -> Result<Self> {
+        Self::open(file_path, len, false)
+    }
+
+    /// Open a batch file containing `len` records, possibly compressed with
+    /// zstd.
+    pub fn open<P: AsRef<std::path::Path>>(
+        file_path: P,
+        len: usize,
+        compressed: bool,
+    ) -> Result<Self> {
+        let file_path = file_path.as_ref();
+        let file = BatchReader::open(file_path, compressed)?;
+        let stream = <BufferedBitStreamRead<LE, u64, _>>::new(FileBackend::new(file));
+        Ok(RecordBatchIterator {
+            file_path: file_path.to_owned(),
+            compressed,
+            stream,
+            len,
+            current: 0,
+            codec: R::Codec::default(),
+        })
+    }
+}
+
+impl<R: SortRecord> core::fmt::Debug for RecordBatchIterator<R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RecordBatchIterator")
+            .field("file_path", &self.file_path)
+            .field("compressed", &self.compressed)
+            .field("len", &self.len)
+            .field("current", &self.current)
+            .finish()
+    }
+}
+
+impl<R: SortRecord> Clone for RecordBatchIterator<R> {
+    fn clone(&self) -> Self {
+        // we can't directly clone the stream, and compressed streams cannot
+        // seek, so we reopen the file and skip the records already returned
+        let mut res = Self::open(&self.file_path, self.len, self.compressed).unwrap();
+        for _ in 0..self.current {
+            res.next();
+        }
+        res
+    }
+}
+
+impl<R: SortRecord> Iterator for RecordBatchIterator<R> {
+    type Item = R;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current == self.len {
+            return None;
+        }
+        self.current += 1;
+        Some(self.codec.read(&mut self.stream).unwrap())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len - self.current;
+        (len, Some(len))
+    }
+}
+
+impl<R: SortRecord> ExactSizeIterator for RecordBatchIterator<R> {}
+
+/// Private struct that can be used to sort records based only on their keys
+#[derive(Clone, Debug)]
+struct RecordHeadTail<R: SortRecord, I: Iterator<Item = R>> {
+    head: R,
+    tail: I,
+}
+
+impl<R: SortRecord, I: Iterator<Item = R>> PartialEq for RecordHeadTail<R, I> {
+    fn eq(&self, other: &Self) -> bool {
+        self.head.key() == other.head.key()
+    }
+}
+
+impl<R: SortRecord, I: Iterator<Item = R>> PartialOrd for RecordHeadTail<R, I> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.head.key().cmp(&other.head.key()))
+    }
+}
+
+/// Merge K different iterators of records sorted by key using a
+/// [`LoserTree`]; records with the same key are returned in the order of the
+/// iterators.
+///
+/// The batches of [`SortPairs`](super::SortPairs) are merged by
+/// [`KMergeIters`](super::KMergeIters), which is a `RecordMerge` of triples.
+#[derive(Clone, Debug)]
+pub struct RecordMerge<R: SortRecord, I: Iterator<Item = R>> {
+    tree: LoserTree<RecordHeadTail<R, I>>,
+}
+
+impl<R: SortRecord, I: Iterator<Item = R>> RecordMerge<R, I> {
+    pub fn new(iters: impl Iterator<Item = I>) -> Self {
+        let heads = iters
+            .filter_map(|mut tail| tail.next().map(|head| RecordHeadTail { head, tail }))
+            .collect();
+        RecordMerge {
+            tree: LoserTree::new(heads),
+        }
+    }
+}
+
+impl<R: SortRecord, I: Iterator<Item = R>> Iterator for RecordMerge<R, I> {
+    type Item = R;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Read the winner of the tree
+        let head_tail = self.tree.peek_mut()?;
+        let result = head_tail.head;
+        match head_tail.tail.next() {
+            None => {
+                // Exhaust the leaf if the iterator ended
+                self.tree.pop();
+            }
+            Some(head) => {
+                head_tail.head = head;
+                // replay the matches of the leaf
+                self.tree.replay();
+            }
+        }
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+#[test]
+pub fn test_external_sorter() -> Result<()> {
+    use rand::prelude::*;
+    let dir = tempfile::tempdir()?;
+    let mut sorter = <ExternalSorter<[usize; 3]>>::new(100, dir.path())?;
+    let mut rng = SmallRng::seed_from_u64(0);
+    let mut records = (0..1000)
+        .map(|_| {
+            [
+                rng.gen_range(0..10),
+                rng.gen_range(0..100),
+                rng.gen_range(0..100),
+            ]
+        })
+        .collect::<Vec<_>>();
+    for &record in &records {
+        sorter.push(record)?;
+    }
+    records.sort();
+    assert_eq!(sorter.batch_lens().len(), 10);
+    let iter = sorter.iter()?;
+    let cloned = iter.clone();
+    assert_eq!(iter.collect::<Vec<_>>(), records);
+    assert_eq!(cloned.collect::<Vec<_>>(), records);
+    Ok(())
+}
@@ -0,0 +1,279 @@
+use epserde::prelude::*;
+
+/// An immutable list of strings compressed by front coding.
+///
+/// The strings are divided in blocks of `ratio` strings: the first string
+/// of each block is written in full, and each of the others as the length of
+/// the prefix it shares with the previous string followed by the rest of its
+/// bytes. Lengths are written as variable-length bytes. Front coding works
+/// best on sorted strings, such as URLs in lexicographical order, but any
+/// sequence of strings can be stored.
+///
+/// Lists are built with a [`FrontCodedListBuilder`], or with
+/// [`FrontCodedList::from_strings`]. The encoded strings and the pointers to
+/// the blocks are generic, so that a list serialized with ε-serde can be
+/// memory-mapped as a [`MappedFrontCodedList`].
+///
+/// Accessing a string requires decoding at most `ratio` strings; iterating
+/// over the list decodes each string once.
+#[derive(Epserde, Debug, Clone, Default, PartialEq, Eq)]
+pub struct FrontCodedList<D = Vec<u8>, P = Vec<usize>> {
+    /// The number of strings in a block.
+    ratio: usize,
+    /// The number of strings.
+    len: usize,
+    /// The encoded strings.
+    data: D,
+    /// The position in `data` of the first string of each block.
+    pointers: P,
+}
+
+/// A [`FrontCodedList`] whose arrays are slices of a memory-mapped file.
+pub type MappedFrontCodedList = FrontCodedList<&'static [u8], &'static [usize]>;
+
+/// A builder for a [`FrontCodedList`], to which strings are pushed one at a
+/// time.
+#[derive(Debug, Clone)]
+pub struct FrontCodedListBuilder {
+    /// The list being built.
+    list: FrontCodedList,
+    /// The last string pushed.
+    last: Vec<u8>,
+}
+
+impl FrontCodedListBuilder {
+    /// Create a builder for an empty list writing a string in full every
+    /// `ratio` strings.
+    pub fn new(ratio: usize) -> Self {
+        assert!(ratio > 0, "The ratio must be positive");
+        Self {
+            list: FrontCodedList {
+                ratio,
+                ..Default::default()
+            },
+            last: Vec::new(),
+        }
+    }
+
+    /// Append a string to the list.
+    pub fn push(&mut self, string: &str) {
+        let string = string.as_bytes();
+        let list = &mut self.list;
+        if list.len % list.ratio == 0 {
+            list.pointers.push(list.data.len());
+            write_vbyte(&mut list.data, string.len());
+            list.data.extend_from_slice(string);
+        } else {
+            let common = self
+                .last
+                .iter()
+                .zip(string)
+                .take_while(|(a, b)| a == b)
+                .count();
+            write_vbyte(&mut list.data, common);
+            write_vbyte(&mut list.data, string.len() - common);
+            list.data.extend_from_slice(&string[common..]);
+        }
+        self.last.clear();
+        self.last.extend_from_slice(string);
+        list.len += 1;
+    }
+
+    /// Return the number of strings pushed so far.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.list.len
+    }
+
+    /// Return whether no string has been pushed.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.list.len == 0
+    }
+
+    /// Return the list of the strings pushed.
+    pub fn build(self) -> FrontCodedList {
+        self.list
+    }
+}
+
+impl FrontCodedList {
+    /// Create a list from an iterator of strings.
+    pub fn from_strings<S: AsRef<str>>(ratio: usize, strings: impl IntoIterator<Item = S>) -> Self {
+        let mut builder = FrontCodedListBuilder::new(ratio);
+        for string in strings {
+            builder.push(string.as_ref());
+        }
+        builder.build()
+    }
+}
+
+impl<D: AsRef<[u8]>, P: AsRef<[usize]>> FrontCodedList<D, P> {
+    /// Return the number of strings in the list.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return whether the list is empty.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the number of bytes used by the encoded strings.
+    pub fn data_len(&self) -> usize {
+        self.data.as_ref().len()
+    }
+
+    /// Return the string of given index.
+    ///
+    /// # Panics
+    /// If `index` is not smaller than the length of the list.
+    pub fn get(&self, index: usize) -> String {
+        assert!(
+            index < self.len,
+            "Index {} out of bounds for a list of {} strings",
+            index,
+            self.len
+        );
+        let block = index / self.ratio;
+        let mut iter = FrontCodedListIter {
+            list: self,
+            pos: self.pointers.as_ref()[block],
+            index: block * self.ratio,
+            string: Vec::new(),
+        };
+        iter.nth(index % self.ratio).unwrap()
+    }
+
+    /// Return an iterator on the strings of the list.
+    pub fn iter(&self) -> FrontCodedListIter<'_, D, P> {
+        FrontCodedListIter {
+            list: self,
+            pos: 0,
+            index: 0,
+            string: Vec::new(),
+        }
+    }
+}
+
+/// Write `value` as a sequence of bytes containing seven bits each, the
+/// highest bit marking the bytes that are followed by another one.
+#[inline(always)]
+fn write_vbyte(data: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        data.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+    data.push(value as u8);
+}
+
+/// Read a value written by [`write_vbyte`] at position `pos`, advancing it.
+#[inline(always)]
+fn read_vbyte(data: &[u8], pos: &mut usize) -> usize {
+    let mut value = 0;
+    let mut shift = 0;
+    loop {
+        let byte = data[*pos];
+        *pos += 1;
+        value |= ((byte & 0x7F) as usize) << shift;
+        if byte & 0x80 == 0 {
+            return value;
+        }
+        shift += 7;
+    }
+}
+
+/// An iterator on the strings of a [`FrontCodedList`].
+#[derive(Debug, Clone)]
+pub struct FrontCodedListIter<'a, D = Vec<u8>, P = Vec<usize>> {
+    list: &'a FrontCodedList<D, P>,
+    /// The position in the data of the next string.
+    pos: usize,
+    /// The index of the next string.
+    index: usize,
+    /// The last string decoded.
+    string: Vec<u8>,
+}
+
+impl<'a, D: AsRef<[u8]>, P: AsRef<[usize]>> Iterator for FrontCodedListIter<'a, D, P> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index == self.list.len {
+            return None;
+        }
+        let data = self.list.data.as_ref();
+        if self.index % self.list.ratio == 0 {
+            self.string.clear();
+        } else {
+            let common = read_vbyte(data, &mut self.pos);
+            self.string.truncate(common);
+        }
+        let suffix_len = read_vbyte(data, &mut self.pos);
+        self.string
+            .extend_from_slice(&data[self.pos..self.pos + suffix_len]);
+        self.pos += suffix_len;
+        self.index += 1;
+        // the bytes of a string are always the bytes of a pushed &str
+        Some(String::from_utf8(self.string.clone()).expect("The list contains invalid UTF-8"))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.list.len - self.index;
+        (len, Some(len))
+    }
+}
+
+impl<'a, D: AsRef<[u8]>, P: AsRef<[usize]>> ExactSizeIterator for FrontCodedListIter<'a, D, P> {}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_front_coded_list() -> anyhow::Result<()> {
+    let mut strings = vec![
+        String::new(),
+        "a".to_string(),
+        "ab".to_string(),
+        "abc".to_string(),
+        "abd".to_string(),
+        "b".to_string(),
+        "bè".to_string(),
+        "bé".to_string(),
+        "http://example.com/".to_string(),
+        "http://example.com/index.html".to_string(),
+    ];
+    strings.push("x".repeat(1000));
+    for ratio in [1, 2, 3, 16] {
+        let list = FrontCodedList::from_strings(ratio, &strings);
+        assert_eq!(list.len(), strings.len());
+        assert!(list.iter().eq(strings.iter().cloned()));
+        for (index, string) in strings.iter().enumerate() {
+            assert_eq!(&list.get(index), string);
+        }
+    }
+
+    let list = FrontCodedList::from_strings(4, &strings);
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("strings.fcl");
+    let mut file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+    list.serialize(&mut file)?;
+    std::io::Write::flush(&mut file)?;
+    drop(file);
+    assert_eq!(<FrontCodedList>::load_full(&path)?, list);
+    #[cfg(feature = "mmap")]
+    {
+        let mapped = <FrontCodedList>::mmap(&path, Flags::empty())?;
+        assert!(mapped.iter().eq(strings.iter().cloned()));
+        assert_eq!(mapped.get(9), strings[9]);
+    }
+
+    let mut builder = FrontCodedListBuilder::new(3);
+    assert!(builder.is_empty());
+    for string in &strings {
+        builder.push(string);
+    }
+    assert_eq!(builder.len(), strings.len());
+    assert_eq!(builder.build(), FrontCodedList::from_strings(3, &strings));
+    Ok(())
+}
@@ -0,0 +1,469 @@
+use anyhow::{bail, Context, Result};
+use dsi_bitstream::prelude::*;
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+
+/// Default size in bytes of the blocks fetched by [`HttpRangeFile`].
+pub const DEFAULT_HTTP_BLOCK_SIZE: usize = 1 << 16;
+/// Default number of blocks kept in the cache of [`HttpRangeFile`].
+pub const DEFAULT_HTTP_CACHE_BLOCKS: usize = 1 << 12;
+
+/// A remote file accessed through HTTP range requests.
+///
+/// The file is split in blocks of `block_size` bytes which are fetched on
+/// demand and kept in a LRU cache of `cache_blocks` blocks shared by all the
+/// readers created on the file. Any server supporting `Range` headers works,
+/// including S3-compatible object storages (use presigned URLs for private
+/// buckets).
+pub struct HttpRangeFile {
+    agent: ureq::Agent,
+    url: String,
+    len: usize,
+    block_size: usize,
+    cache_blocks: usize,
+    cache: Mutex<BlockCache>,
+}
+
+/// A LRU cache of the blocks of a [`HttpRangeFile`].
+///
+/// The entries form a doubly-linked list, from the most to the least
+/// recently used, threaded through a vector by indices, so that lookups,
+/// insertions and evictions take constant time.
+#[derive(Default)]
+struct BlockCache {
+    /// The position in `entries` of the entry of each cached block.
+    index: HashMap<usize, usize>,
+    entries: Vec<CacheEntry>,
+    /// The position of the most recently used entry.
+    head: Option<usize>,
+    /// The position of the least recently used entry.
+    tail: Option<usize>,
+}
+
+/// An entry of a [`BlockCache`].
+struct CacheEntry {
+    block_idx: usize,
+    block: Arc<[u32]>,
+    /// The position of the previous, more recently used, entry.
+    prev: Option<usize>,
+    /// The position of the next, less recently used, entry.
+    next: Option<usize>,
+}
+
+impl BlockCache {
+    /// Remove the entry at position `pos` from the list.
+    fn unlink(&mut self, pos: usize) {
+        let (prev, next) = (self.entries[pos].prev, self.entries[pos].next);
+        match prev {
+            Some(prev) => self.entries[prev].next = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(next) => self.entries[next].prev = prev,
+            None => self.tail = prev,
+        }
+    }
+
+    /// Make the entry at position `pos` the most recently used one.
+    fn push_front(&mut self, pos: usize) {
+        self.entries[pos].prev = None;
+        self.entries[pos].next = self.head;
+        if let Some(head) = self.head {
+            self.entries[head].prev = Some(pos);
+        }
+        self.head = Some(pos);
+        if self.tail.is_none() {
+            self.tail = Some(pos);
+        }
+    }
+
+    fn get(&mut self, block_idx: usize) -> Option<Arc<[u32]>> {
+        let pos = *self.index.get(&block_idx)?;
+        self.unlink(pos);
+        self.push_front(pos);
+        Some(self.entries[pos].block.clone())
+    }
+
+    fn insert(&mut self, block_idx: usize, block: Arc<[u32]>, capacity: usize) {
+        let pos = if let Some(&pos) = self.index.get(&block_idx) {
+            // another reader fetched the block in the meantime
+            self.unlink(pos);
+            self.entries[pos].block = block;
+            pos
+        } else if self.entries.len() < capacity.max(1) {
+            self.entries.push(CacheEntry {
+                block_idx,
+                block,
+                prev: None,
+                next: None,
+            });
+            self.entries.len() - 1
+        } else {
+            // reuse the entry of the least recently used block
+            let pos = self.tail.unwrap();
+            self.unlink(pos);
+            self.index.remove(&self.entries[pos].block_idx);
+            self.entries[pos].block_idx = block_idx;
+            self.entries[pos].block = block;
+            pos
+        };
+        self.index.insert(block_idx, pos);
+        self.push_front(pos);
+    }
+}
+
+impl HttpRangeFile {
+    /// Open the remote file at `url` with the default block and cache sizes.
+    pub fn new(url: impl Into<String>) -> Result<Self> {
+        Self::with_cache(url, DEFAULT_HTTP_BLOCK_SIZE, DEFAULT_HTTP_CACHE_BLOCKS)
+    }
+
+    /// Open the remote file at `url` fetching blocks of `block_size` bytes and
+    /// caching at most `cache_blocks` of them.
+    pub fn with_cache(
+        url: impl Into<String>,
+        block_size: usize,
+        cache_blocks: usize,
+    ) -> Result<Self> {
+        let url = url.into();
+        if block_size == 0 || block_size % core::mem::size_of::<u32>() != 0 {
+            bail!(
+                "The block size must be a positive multiple of 4, got {}",
+                block_size
+            );
+        }
+        let agent = ureq::AgentBuilder::new().build();
+        let response = agent
+            .head(&url)
+            .call()
+            .with_context(|| format!("Cannot HEAD {}", url))?;
+        let len = response
+            .header("Content-Length")
+            .with_context(|| format!("Missing Content-Length for {}", url))?
+            .parse::<usize>()
+            .with_context(|| format!("Cannot parse the Content-Length of {}", url))?;
+        Ok(Self {
+            agent,
+            url,
+            len,
+            block_size,
+            cache_blocks,
+            cache: Mutex::new(BlockCache::default()),
+        })
+    }
+
+    /// Return the length of the remote file in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return whether the remote file is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Download the bytes in the given range of the remote file.
+    ///
+    /// The server must answer with the requested range: servers or proxies
+    /// ignoring the `Range` header, and answering with the whole file, are
+    /// reported as errors.
+    pub fn fetch(&self, start: usize, end: usize) -> Result<Vec<u8>> {
+        let mut data = Vec::with_capacity(end - start);
+        if start == end {
+            return Ok(data);
+        }
+        let response = self
+            .agent
+            .get(&self.url)
+            .set("Range", &format!("bytes={}-{}", start, end - 1))
+            .call()
+            .with_context(|| format!("Cannot fetch bytes {}..{} of {}", start, end, self.url))?;
+        if response.status() != 206 {
+            bail!(
+                "Bytes {}..{} of {} were requested, but the server answered {} {} instead of 206 Partial Content",
+                start,
+                end,
+                self.url,
+                response.status(),
+                response.status_text()
+            );
+        }
+        let content_range = response.header("Content-Range").with_context(|| {
+            format!(
+                "Missing Content-Range fetching bytes {}..{} of {}",
+                start, end, self.url
+            )
+        })?;
+        if !content_range.starts_with(&format!("bytes {}-{}/", start, end - 1)) {
+            bail!(
+                "Bytes {}..{} of {} were requested, but the server answered with the range {}",
+                start,
+                end,
+                self.url,
+                content_range
+            );
+        }
+        response
+            .into_reader()
+            .take((end - start) as u64)
+            .read_to_end(&mut data)?;
+        if data.len() != end - start {
+            bail!(
+                "Short read from {}: expected {} bytes, got {}",
+                self.url,
+                end - start,
+                data.len()
+            );
+        }
+        Ok(data)
+    }
+
+    /// Return the block of words with the given index, fetching it if it is
+    /// not in the cache. The last block is padded with zeros.
+    fn block(&self, block_idx: usize) -> Result<Arc<[u32]>> {
+        if let Some(block) = self.cache.lock().unwrap().get(block_idx) {
+            return Ok(block);
+        }
+        // we do not hold the lock while downloading, at worst two readers
+        // fetch the same block
+        let start = block_idx * self.block_size;
+        let end = (start + self.block_size).min(self.len);
+        let bytes = self.fetch(start, end)?;
+        let mut words = vec![0_u32; self.block_size / core::mem::size_of::<u32>()];
+        for (word, chunk) in words
+            .iter_mut()
+            .zip(bytes.chunks(core::mem::size_of::<u32>()))
+        {
+            let mut buffer = [0_u8; 4];
+            buffer[..chunk.len()].copy_from_slice(chunk);
+            // same layout we would see by memory-mapping the file
+            *word = u32::from_ne_bytes(buffer);
+        }
+        let block: Arc<[u32]> = words.into();
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(block_idx, block.clone(), self.cache_blocks);
+        Ok(block)
+    }
+}
+
+/// A word reader on a [`HttpRangeFile`].
+///
+/// Like [`MemWordReadInfinite`], it returns zeros after the end of the file,
+/// so that codes at the end of the bitstream can be decoded with a single
+/// buffer refill.
+pub struct HttpWordReader {
+    file: Arc<HttpRangeFile>,
+    word_pos: usize,
+    block: Option<(usize, Arc<[u32]>)>,
+}
+
+impl HttpWordReader {
+    /// Create a new reader starting at the beginning of the file.
+    pub fn new(file: Arc<HttpRangeFile>) -> Self {
+        Self {
+            file,
+            word_pos: 0,
+            block: None,
+        }
+    }
+}
+
+impl WordRead for HttpWordReader {
+    type Word = u32;
+
+    #[inline]
+    fn read_word(&mut self) -> Result<u32> {
+        let words_per_block = self.file.block_size / core::mem::size_of::<u32>();
+        let block_idx = self.word_pos / words_per_block;
+        let word = if self.word_pos * core::mem::size_of::<u32>() >= self.file.len {
+            0
+        } else {
+            match &self.block {
+                Some((idx, block)) if *idx == block_idx => block[self.word_pos % words_per_block],
+                _ => {
+                    let block = self.file.block(block_idx)?;
+                    let word = block[self.word_pos % words_per_block];
+                    self.block = Some((block_idx, block));
+                    word
+                }
+            }
+        };
+        self.word_pos += 1;
+        Ok(word)
+    }
+}
+
+impl WordStream for HttpWordReader {
+    #[inline]
+    fn len(&self) -> usize {
+        (self.file.len + core::mem::size_of::<u32>() - 1) / core::mem::size_of::<u32>()
+    }
+
+    #[inline]
+    fn get_position(&self) -> usize {
+        self.word_pos
+    }
+
+    #[inline]
+    fn set_position(&mut self, word_index: usize) -> Result<()> {
+        self.word_pos = word_index;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_block_cache() {
+    let block = |value: u32| -> Arc<[u32]> { vec![value].into() };
+    let mut cache = BlockCache::default();
+    cache.insert(0, block(0), 2);
+    cache.insert(1, block(1), 2);
+    // 0 becomes the most recently used block, so 1 is evicted
+    assert_eq!(cache.get(0).as_deref(), Some(&[0][..]));
+    cache.insert(2, block(2), 2);
+    assert!(cache.get(1).is_none());
+    assert_eq!(cache.get(0).as_deref(), Some(&[0][..]));
+    assert_eq!(cache.get(2).as_deref(), Some(&[2][..]));
+    // reinserting a block replaces it without evicting others
+    cache.insert(2, block(3), 2);
+    assert_eq!(cache.get(2).as_deref(), Some(&[3][..]));
+    assert_eq!(cache.get(0).as_deref(), Some(&[0][..]));
+    assert_eq!(cache.entries.len(), 2);
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_http_range_file() -> Result<()> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Answer a single HEAD or ranged GET request for `data`; if `ranges`
+    /// is false, ranges are ignored and the whole of `data` is returned, as
+    /// some servers and proxies do.
+    fn serve(
+        stream: TcpStream,
+        data: &[u8],
+        ranges: bool,
+        gets: &AtomicUsize,
+    ) -> std::io::Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut request = String::new();
+        reader.read_line(&mut request)?;
+        let mut range = None;
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header)?;
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(": ") {
+                if name.eq_ignore_ascii_case("range") {
+                    let (start, end) = value.trim_start_matches("bytes=").split_once('-').unwrap();
+                    range = Some((
+                        start.parse::<usize>().unwrap(),
+                        end.parse::<usize>().unwrap(),
+                    ));
+                }
+            }
+        }
+        let mut stream = stream;
+        if request.starts_with("HEAD") {
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                data.len()
+            )?;
+        } else if !ranges {
+            gets.fetch_add(1, Ordering::SeqCst);
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                data.len()
+            )?;
+            stream.write_all(data)?;
+        } else {
+            gets.fetch_add(1, Ordering::SeqCst);
+            let (start, end) = range.unwrap();
+            let end = end.min(data.len() - 1);
+            write!(
+                stream,
+                "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {}-{}/{}\r\nConnection: close\r\n\r\n",
+                end + 1 - start,
+                start,
+                end,
+                data.len()
+            )?;
+            stream.write_all(&data[start..=end])?;
+        }
+        stream.flush()
+    }
+
+    /// Start a server for `data`, returning its URL and the number of GET
+    /// requests it answered.
+    fn spawn_server(data: &[u8], ranges: bool) -> Result<(String, Arc<AtomicUsize>)> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}/graph.graph", listener.local_addr()?);
+        let gets = Arc::new(AtomicUsize::new(0));
+        let data = data.to_vec();
+        let server_gets = gets.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else {
+                    break;
+                };
+                serve(stream, &data, ranges, &server_gets).unwrap();
+            }
+        });
+        Ok((url, gets))
+    }
+
+    let data: Vec<u8> = (0..1000_u32).map(|i| (i * 7) as u8).collect();
+    let (url, gets) = spawn_server(&data, true)?;
+
+    // blocks of 64 bytes, of which 2 are cached
+    let file = Arc::new(HttpRangeFile::with_cache(url.as_str(), 64, 2)?);
+    assert_eq!(file.len(), data.len());
+    assert_eq!(file.fetch(10, 20)?, &data[10..20]);
+    assert_eq!(gets.load(Ordering::SeqCst), 1);
+
+    let words: Vec<u32> = data
+        .chunks(4)
+        .map(|chunk| u32::from_ne_bytes(chunk.try_into().unwrap()))
+        .collect();
+    let mut reader = HttpWordReader::new(file.clone());
+    assert_eq!(reader.len(), words.len());
+    for &word in &words {
+        assert_eq!(reader.read_word()?, word);
+    }
+    // zeros after the end of the file
+    assert_eq!(reader.read_word()?, 0);
+    // each block was fetched once
+    assert_eq!(gets.load(Ordering::SeqCst), 1 + 16);
+
+    // blocks 14 and 15 are cached: reading block 0 evicts block 14
+    let read_at = |word_pos: usize| -> Result<u32> {
+        let mut reader = HttpWordReader::new(file.clone());
+        reader.set_position(word_pos)?;
+        reader.read_word()
+    };
+    assert_eq!(read_at(0)?, words[0]);
+    assert_eq!(gets.load(Ordering::SeqCst), 1 + 17);
+    assert_eq!(read_at(words.len() - 1)?, words[words.len() - 1]);
+    assert_eq!(gets.load(Ordering::SeqCst), 1 + 17);
+    assert_eq!(read_at(14 * 16)?, words[14 * 16]);
+    assert_eq!(gets.load(Ordering::SeqCst), 1 + 18);
+
+    // a server ignoring ranges would return the start of the file
+    let (url, gets) = spawn_server(&data, false)?;
+    let file = Arc::new(HttpRangeFile::with_cache(url.as_str(), 64, 2)?);
+    assert!(file.fetch(10, 20).is_err());
+    assert!(file.fetch(0, 10).is_err());
+    assert!(HttpWordReader::new(file).read_word().is_err());
+    assert_eq!(gets.load(Ordering::SeqCst), 3);
+    Ok(())
+}
@@ -7,11 +7,19 @@ pub fn unchecked_swap<T: Copy>(vec: &mut [T], a: usize, b: usize) {
     }
 }
 
+/// The default arity of a [`KAryHeap`].
+pub const DEFAULT_HEAP_ARITY: usize = 4;
+
 /// A k-ary heap implementation
+///
+/// The heap contains the indices of the values, so that values are never
+/// moved; the slots of popped values are reused by the following pushes.
 #[derive(Clone, Debug)]
-pub struct KAryHeap<T: PartialOrd, const ARITY: usize = 4> {
+pub struct KAryHeap<T: PartialOrd, const ARITY: usize = DEFAULT_HEAP_ARITY> {
     values: Vec<T>,
     heap: Vec<usize>,
+    /// The indices of the slots of `values` of popped values.
+    free: Vec<usize>,
 }
 
 impl<const ARITY: usize, T: PartialOrd> Default for KAryHeap<T, ARITY> {
@@ -26,6 +34,7 @@ impl<const ARITY: usize, T: PartialOrd> KAryHeap<T, ARITY> {
         KAryHeap {
             values: Vec::new(),
             heap: Vec::new(),
+            free: Vec::new(),
         }
     }
 
@@ -35,6 +44,7 @@ impl<const ARITY: usize, T: PartialOrd> KAryHeap<T, ARITY> {
         KAryHeap {
             values: Vec::with_capacity(capacity),
             heap: Vec::with_capacity(capacity),
+            free: Vec::new(),
         }
     }
 
@@ -56,22 +66,36 @@ impl<const ARITY: usize, T: PartialOrd> KAryHeap<T, ARITY> {
         self.heap.is_empty()
     }
 
+    /// Return the number of values in the heap
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
     /// add a value to the heap
     #[inline]
     pub fn push(&mut self, value: T) {
-        // Insert the value and get its index
-        let mut idx = self.values.len();
-        self.values.push(value);
-        self.heap.push(idx);
-        let value = &self.values[idx];
+        // Store the value in a free slot, if any, and get its index
+        let value_idx = match self.free.pop() {
+            Some(value_idx) => {
+                self.values[value_idx] = value;
+                value_idx
+            }
+            None => {
+                self.values.push(value);
+                self.values.len() - 1
+            }
+        };
+        // the value starts as the last leaf of the heap
+        let mut idx = self.heap.len();
+        self.heap.push(value_idx);
+        let value = &self.values[value_idx];
 
         // bubble up the value until the heap property holds
-        loop {
+        while idx > 0 {
             let parent_idx = Self::parent(idx);
 
             // The heap condition is respected so we can stop.
-            // This also handles the case of the node at the root since
-            // self.parent(0) == 0 => current_value == parent_value
             if value >= &self.values[self.heap[parent_idx]] {
                 break;
             }
@@ -98,12 +122,13 @@ impl<const ARITY: usize, T: PartialOrd> KAryHeap<T, ARITY> {
     #[inline]
     pub fn pop(&mut self) {
         // if the queue is empty we can early-stop.
-        if self.values.is_empty() {
+        if self.heap.is_empty() {
             return;
         }
 
         // remove the minimum from the tree and put the last value as the head
-        self.heap.swap_remove(0);
+        let value_idx = self.heap.swap_remove(0);
+        self.free.push(value_idx);
 
         // if there are values left, bubble down the new head to fix the heap
         if !self.heap.is_empty() {
@@ -151,4 +176,41 @@ impl<const ARITY: usize, T: PartialOrd> KAryHeap<T, ARITY> {
 
 #[cfg_attr(test, test)]
 #[cfg(test)]
-fn test_kary_heap() {}
+fn test_kary_heap() {
+    fn check<const ARITY: usize>() {
+        let mut heap = KAryHeap::<u64, ARITY>::new();
+        let mut expected = Vec::new();
+        let mut state = 0x9E37_79B9_7F4A_7C15_u64;
+        for round in 0..1000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            // pushes and pops are interleaved, with phases of growth and
+            // shrinkage of the heap
+            if heap.is_empty() || (state % 3 < 2) == (round % 200 < 100) {
+                let value = state % 100;
+                heap.push(value);
+                expected.push(value);
+            } else {
+                let min = *expected.iter().min().unwrap();
+                let pos = expected.iter().position(|&v| v == min).unwrap();
+                expected.swap_remove(pos);
+                assert_eq!(*heap.peek(), min);
+                heap.pop();
+            }
+            assert_eq!(heap.len(), expected.len());
+        }
+        let mut drained = Vec::new();
+        while !heap.is_empty() {
+            drained.push(*heap.peek());
+            heap.pop();
+        }
+        expected.sort();
+        assert_eq!(drained, expected);
+        heap.pop();
+        assert!(heap.is_empty());
+    }
+    check::<2>();
+    check::<4>();
+    check::<7>();
+}
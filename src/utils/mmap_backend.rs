@@ -0,0 +1,208 @@
+//! Memory-mapped backends, available with the `mmap` feature.
+use super::MmapAdvice;
+use dsi_bitstream::prelude::*;
+use mmap_rs::*;
+
+/// Treat an mmap as a slice.
+/// Mmap only implements [`AsRef<[u8]>`] but we need also other types
+/// to be able to read bigger words.
+/// This wrapper struct just implement this behaviour.
+pub struct MmapBackend<W: Word> {
+    mmap: Mapping,
+    len: usize,
+    _marker: core::marker::PhantomData<W>,
+}
+
+/// The memory mapped by a [`MmapBackend`].
+enum Mapping {
+    /// A single mapping.
+    Mmap(Mmap),
+    /// Several files mapped next to each other.
+    #[cfg(unix)]
+    Segments(SegmentsMapping),
+}
+
+impl Mapping {
+    fn as_ptr(&self) -> *const u8 {
+        match self {
+            Mapping::Mmap(mmap) => mmap.as_ptr(),
+            #[cfg(unix)]
+            Mapping::Segments(segments) => segments.ptr,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Mapping::Mmap(mmap) => mmap.len(),
+            #[cfg(unix)]
+            Mapping::Segments(segments) => segments.len,
+        }
+    }
+}
+
+/// A region of address space in which files have been mapped with
+/// `MAP_FIXED`, unmapped on drop.
+#[cfg(unix)]
+struct SegmentsMapping {
+    ptr: *mut u8,
+    len: usize,
+}
+
+// SAFETY: the region is mapped read-only, and it is owned by the struct
+#[cfg(unix)]
+unsafe impl Send for SegmentsMapping {}
+#[cfg(unix)]
+unsafe impl Sync for SegmentsMapping {}
+
+#[cfg(unix)]
+impl Drop for SegmentsMapping {
+    fn drop(&mut self) {
+        // SAFETY: the region was mapped by MmapBackend::from_segments
+        unsafe {
+            libc::munmap(self.ptr as *mut libc::c_void, self.len);
+        }
+    }
+}
+
+impl<W: Word> MmapBackend<W> {
+    /// Create a new FileBackend
+    pub fn new(mmap: Mmap) -> Self {
+        Self {
+            len: (mmap.len() + core::mem::size_of::<W>() - 1) / core::mem::size_of::<W>(),
+            mmap: Mapping::Mmap(mmap),
+            _marker: core::marker::PhantomData,
+        }
+    }
+
+    /// Map the given files next to each other, so that they are presented as
+    /// the single word stream of their concatenation.
+    ///
+    /// All files but the last one must be `segment_size` bytes long, and
+    /// `segment_size` must be a multiple of the page size.
+    #[cfg(unix)]
+    pub fn from_segments<P: AsRef<std::path::Path>>(
+        paths: &[P],
+        segment_size: usize,
+    ) -> anyhow::Result<Self> {
+        use anyhow::{bail, Context};
+        use std::os::unix::io::AsRawFd;
+
+        // SAFETY: sysconf has no preconditions
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        if segment_size == 0 || segment_size % page_size != 0 {
+            bail!(
+                "The segment size {} is not a multiple of the page size {}",
+                segment_size,
+                page_size
+            );
+        }
+        let mut files = Vec::with_capacity(paths.len());
+        let mut total_len = 0;
+        for (index, path) in paths.iter().enumerate() {
+            let path = path.as_ref();
+            let file = std::fs::File::open(path)
+                .with_context(|| format!("Cannot open the segment {}", path.display()))?;
+            let len = file.metadata()?.len() as usize;
+            if len > segment_size || (index + 1 < paths.len() && len != segment_size) {
+                bail!(
+                    "The segment {} is {} bytes long, but segments are {} bytes long",
+                    path.display(),
+                    len,
+                    segment_size
+                );
+            }
+            total_len += len;
+            files.push((file, len));
+        }
+        if total_len == 0 {
+            bail!("The segments are empty");
+        }
+
+        // reserve a region of address space, and map the files over it
+        // SAFETY: we are asking for a new anonymous mapping
+        let ptr = unsafe {
+            libc::mmap(
+                core::ptr::null_mut(),
+                total_len,
+                libc::PROT_NONE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        // from now on, the region is unmapped on error
+        let segments = SegmentsMapping {
+            ptr: ptr as *mut u8,
+            len: total_len,
+        };
+        for (index, (file, len)) in files.iter().enumerate() {
+            if *len == 0 {
+                continue;
+            }
+            // SAFETY: the target range is within the reserved region
+            let res = unsafe {
+                libc::mmap(
+                    segments.ptr.add(index * segment_size) as *mut libc::c_void,
+                    *len,
+                    libc::PROT_READ,
+                    libc::MAP_SHARED | libc::MAP_FIXED,
+                    file.as_raw_fd(),
+                    0,
+                )
+            };
+            if res == libc::MAP_FAILED {
+                return Err(std::io::Error::last_os_error().into());
+            }
+        }
+
+        Ok(Self {
+            len: total_len.div_ceil(core::mem::size_of::<W>()),
+            mmap: Mapping::Segments(segments),
+            _marker: core::marker::PhantomData,
+        })
+    }
+}
+
+impl<W: Word> MmapBackend<W> {
+    /// Advise the kernel about the access pattern of the mapping, replacing
+    /// the advice given by the flags used to create it; for example, after
+    /// a sequential scan of a graph loaded for random access.
+    ///
+    /// This is a hint: on systems other than Linux it does nothing.
+    pub fn advise(&self, advice: MmapAdvice) -> anyhow::Result<()> {
+        #[cfg(target_os = "linux")]
+        {
+            let advice = match advice {
+                MmapAdvice::Normal => libc::MADV_NORMAL,
+                MmapAdvice::Sequential => libc::MADV_SEQUENTIAL,
+                MmapAdvice::Random => libc::MADV_RANDOM,
+                MmapAdvice::WillNeed => libc::MADV_WILLNEED,
+                MmapAdvice::HugePage => libc::MADV_HUGEPAGE,
+            };
+            // SAFETY: the range is exactly the mapping, and none of the
+            // advice above changes its contents
+            let res = unsafe {
+                libc::madvise(
+                    self.mmap.as_ptr() as *mut libc::c_void,
+                    self.mmap.len(),
+                    advice,
+                )
+            };
+            if res != 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = advice;
+        Ok(())
+    }
+}
+
+impl<W: Word> AsRef<[W]> for MmapBackend<W> {
+    fn as_ref(&self) -> &[W] {
+        unsafe { core::slice::from_raw_parts(self.mmap.as_ptr() as *const W, self.len) }
+    }
+}
@@ -1,6 +1,5 @@
 //! Collection of common functions we use throughout the codebase
 use dsi_bitstream::prelude::*;
-use mmap_rs::*;
 
 /// Bijective mapping from isize to u64 as defined in <https://github.com/vigna/dsiutils/blob/master/src/it/unimi/dsi/bits/Fast.java>
 pub const fn int2nat(x: i64) -> u64 {
@@ -28,6 +27,9 @@ pub use coo_to_graph::*;
 mod coo_to_labelled_graph;
 pub use coo_to_labelled_graph::*;
 
+mod arc_list;
+pub use arc_list::*;
+
 mod circular_buffer;
 pub(crate) use circular_buffer::*;
 
@@ -37,32 +39,89 @@ pub(crate) use circular_buffer::*;
 mod kary_heap;
 pub use kary_heap::*;
 
+mod loser_tree;
+pub use loser_tree::*;
+
+mod external_sort;
+pub use external_sort::*;
+
 mod sort_pairs;
 pub use sort_pairs::*;
 
-/// Treat an mmap as a slice.
-/// Mmap only implements [`AsRef<[u8]>`] but we need also other types
-/// to be able to read bigger words.
-/// This wrapper struct just implement this behaviour.
-pub struct MmapBackend<W: Word> {
-    mmap: Mmap,
-    len: usize,
-    _marker: core::marker::PhantomData<W>,
+mod sorted_arcs;
+pub use sorted_arcs::*;
+
+mod dedup_iters;
+pub use dedup_iters::*;
+
+mod perm;
+pub use perm::*;
+
+mod front_coded_list;
+pub use front_coded_list::*;
+
+mod mph;
+pub use mph::*;
+
+#[cfg(feature = "http")]
+mod http_backend;
+#[cfg(feature = "http")]
+pub use http_backend::*;
+
+#[cfg(feature = "mmap")]
+mod mmap_backend;
+#[cfg(feature = "mmap")]
+pub use mmap_backend::*;
+
+/// Advice about the access pattern of a memory mapping, see `madvise(2)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MmapAdvice {
+    /// No special treatment.
+    Normal,
+    /// Pages will be accessed sequentially, so read-ahead can be aggressive.
+    Sequential,
+    /// Pages will be accessed randomly, so read-ahead is useless.
+    Random,
+    /// Pages will be needed soon, so they should be read ahead.
+    WillNeed,
+    /// Pages should be backed by transparent huge pages.
+    HugePage,
 }
 
-impl<W: Word> MmapBackend<W> {
-    /// Create a new FileBackend
-    pub fn new(mmap: Mmap) -> Self {
-        Self {
-            len: (mmap.len() + core::mem::size_of::<W>() - 1) / core::mem::size_of::<W>(),
-            mmap,
-            _marker: core::marker::PhantomData,
+/// The words of a file, either memory-mapped or loaded in memory.
+///
+/// Without the `mmap` feature, for example on WebAssembly, files are always
+/// loaded in memory.
+pub enum LoadBackend<W: Word> {
+    /// The file is memory-mapped.
+    #[cfg(feature = "mmap")]
+    Mmap(MmapBackend<W>),
+    /// The file has been read into memory, padded with zeros to a multiple
+    /// of the word size.
+    Memory(Vec<W>),
+}
+
+impl<W: Word> LoadBackend<W> {
+    /// Advise the kernel about the access pattern of the data, if it is
+    /// memory-mapped (see [`MmapBackend::advise`]).
+    pub fn advise(&self, advice: MmapAdvice) -> anyhow::Result<()> {
+        match self {
+            #[cfg(feature = "mmap")]
+            LoadBackend::Mmap(mmap) => mmap.advise(advice),
+            LoadBackend::Memory(_) => {
+                let _ = advice;
+                Ok(())
+            }
         }
     }
 }
 
-impl<W: Word> AsRef<[W]> for MmapBackend<W> {
+impl<W: Word> AsRef<[W]> for LoadBackend<W> {
     fn as_ref(&self) -> &[W] {
-        unsafe { core::slice::from_raw_parts(self.mmap.as_ptr() as *const W, self.len) }
+        match self {
+            #[cfg(feature = "mmap")]
+            LoadBackend::Mmap(mmap) => mmap.as_ref(),
+            LoadBackend::Memory(data) => data.as_ref(),
+        }
     }
 }
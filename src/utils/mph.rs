@@ -0,0 +1,278 @@
+use anyhow::{bail, ensure, Result};
+use epserde::prelude::*;
+use xxhash_rust::xxh3::xxh3_128_with_seed;
+
+/// The average number of keys in a bucket.
+const BUCKET_SIZE: usize = 4;
+/// The number of pilots tried for a bucket before restarting the
+/// construction with another seed.
+const MAX_PILOT: u32 = 1 << 20;
+/// The number of seeds tried before giving up.
+const MAX_SEEDS: u64 = 16;
+
+/// A minimal perfect hash function on a set of strings, that is, a
+/// bijection between the strings and `0..n`, where `n` is the number of
+/// strings, built with the hash-and-displace technique of PTHash.
+///
+/// Keys are divided in buckets by a hash; then, starting from the largest
+/// bucket, a *pilot* is searched for each bucket so that the positions of
+/// its keys, obtained by hashing them together with the pilot, are distinct
+/// and not taken by the keys of previous buckets. The positions range in a
+/// table 1% larger than the number of keys, which makes the search much
+/// faster, and the positions past `n` are remapped onto the free positions
+/// smaller than `n`.
+///
+/// The function returns an arbitrary value in `0..n` for strings that are
+/// not in the set: membership must be checked separately, as
+/// [`NodeIdMap`](crate::properties::NodeIdMap) does.
+#[derive(Epserde, Debug, Clone, Default, PartialEq, Eq)]
+pub struct MinimalPerfectHash {
+    /// The seed of the hash of the keys.
+    seed: u64,
+    /// The number of keys.
+    len: usize,
+    /// The size of the table of positions.
+    table_size: usize,
+    /// The pilot of each bucket.
+    pilots: Vec<u32>,
+    /// The positions smaller than `len` assigned to positions past `len`.
+    remap: Vec<usize>,
+}
+
+impl MinimalPerfectHash {
+    /// Build a minimal perfect hash function on the given strings, which must
+    /// be distinct.
+    pub fn new<S: AsRef<[u8]>>(keys: &[S]) -> Result<Self> {
+        Self::from_keys(|| keys.iter())
+    }
+
+    /// Build a minimal perfect hash function on the strings returned by
+    /// `keys`, which must be distinct.
+    ///
+    /// The keys are not kept in memory: `keys` is called to iterate over
+    /// them again each time the construction restarts with a new seed, and
+    /// it must return the same keys every time.
+    pub fn from_keys<S: AsRef<[u8]>, I: IntoIterator<Item = S>>(
+        mut keys: impl FnMut() -> I,
+    ) -> Result<Self> {
+        let mut hashes: Vec<(u64, u64)> = keys()
+            .into_iter()
+            .map(|key| hash(key.as_ref(), 0))
+            .collect();
+        let len = hashes.len();
+        let table_size = len + len / 100 + 1;
+        let num_buckets = len / BUCKET_SIZE + 1;
+        for seed in 0..MAX_SEEDS {
+            if seed > 0 {
+                hashes.clear();
+                hashes.extend(keys().into_iter().map(|key| hash(key.as_ref(), seed)));
+                ensure!(
+                    hashes.len() == len,
+                    "The keys changed between iterations: {} keys instead of {}",
+                    hashes.len(),
+                    len
+                );
+            }
+            if let Some((pilots, taken)) = search_pilots(&hashes, num_buckets, table_size) {
+                // the free positions smaller than len, one for each taken
+                // position past len
+                let mut free = (0..len).filter(|&pos| !taken[pos]);
+                let remap = (len..table_size)
+                    .map(|pos| if taken[pos] { free.next().unwrap() } else { 0 })
+                    .collect();
+                return Ok(Self {
+                    seed,
+                    len,
+                    table_size,
+                    pilots,
+                    remap,
+                });
+            }
+        }
+        bail!("Cannot build a minimal perfect hash function: are there duplicate keys?");
+    }
+
+    /// Return the number of keys.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return whether there are no keys.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the value associated with a key.
+    ///
+    /// If the key was not in the set used to build the function, the result
+    /// is an arbitrary value in `0..n`, or `0` if there are no keys.
+    pub fn get(&self, key: &[u8]) -> usize {
+        if self.len == 0 {
+            return 0;
+        }
+        let (bucket_hash, pos_hash) = hash(key, self.seed);
+        let bucket = bucket_hash as usize % self.pilots.len();
+        let pos = position(pos_hash, self.pilots[bucket], self.table_size);
+        if pos < self.len {
+            pos
+        } else {
+            self.remap[pos - self.len]
+        }
+    }
+}
+
+/// Hash a key into the hashes used to choose its bucket and its position.
+#[inline(always)]
+fn hash(key: &[u8], seed: u64) -> (u64, u64) {
+    let hash = xxh3_128_with_seed(key, seed);
+    ((hash >> 64) as u64, hash as u64)
+}
+
+/// Return the position of a key with the given position hash in a bucket
+/// with the given pilot.
+#[inline(always)]
+fn position(pos_hash: u64, pilot: u32, table_size: usize) -> usize {
+    // mix the pilot, so that consecutive pilots give unrelated positions
+    let pilot_hash = (pilot as u64 ^ 0x9E37_79B9_7F4A_7C15).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    ((pos_hash ^ pilot_hash) % table_size as u64) as usize
+}
+
+/// Search a pilot for each bucket, returning the pilots and the taken
+/// positions, or `None` if some bucket has no pilot.
+fn search_pilots(
+    hashes: &[(u64, u64)],
+    num_buckets: usize,
+    table_size: usize,
+) -> Option<(Vec<u32>, Vec<bool>)> {
+    let mut buckets: Vec<Vec<u64>> = vec![Vec::new(); num_buckets];
+    for &(bucket_hash, pos_hash) in hashes {
+        buckets[bucket_hash as usize % num_buckets].push(pos_hash);
+    }
+    // keys with the same hashes can never be separated
+    for keys in &mut buckets {
+        keys.sort_unstable();
+        if keys.windows(2).any(|pair| pair[0] == pair[1]) {
+            return None;
+        }
+    }
+    let mut order: Vec<usize> = (0..num_buckets).collect();
+    order.sort_by_key(|&bucket| core::cmp::Reverse(buckets[bucket].len()));
+
+    let mut pilots = vec![0; num_buckets];
+    let mut taken = vec![false; table_size];
+    let mut positions = Vec::with_capacity(BUCKET_SIZE);
+    for bucket in order {
+        let keys = &buckets[bucket];
+        if keys.is_empty() {
+            break;
+        }
+        let pilot = (0..MAX_PILOT).find(|&pilot| {
+            positions.clear();
+            for &pos_hash in keys {
+                let pos = position(pos_hash, pilot, table_size);
+                if taken[pos] || positions.contains(&pos) {
+                    return false;
+                }
+                positions.push(pos);
+            }
+            true
+        })?;
+        for &pos in &positions {
+            taken[pos] = true;
+        }
+        pilots[bucket] = pilot;
+    }
+    Some((pilots, taken))
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_mph() -> Result<()> {
+    for n in [0, 1, 2, 10, 1000, 100_000] {
+        let keys: Vec<String> = (0..n).map(|i| format!("key{}", i)).collect();
+        let mph = MinimalPerfectHash::new(&keys)?;
+        assert_eq!(mph.len(), n);
+        let mut seen = vec![false; n];
+        for key in &keys {
+            let value = mph.get(key.as_bytes());
+            assert!(value < n);
+            assert!(!seen[value]);
+            seen[value] = true;
+        }
+    }
+    assert!(MinimalPerfectHash::new(&["a", "b", "a"]).is_err());
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_mph_failures() -> Result<()> {
+    // duplicates can never be separated, whatever the seed
+    assert!(MinimalPerfectHash::new(&["a", "a"]).is_err());
+    assert!(MinimalPerfectHash::new(&[""; 100]).is_err());
+    let mut keys: Vec<String> = (0..10_000).map(|i| format!("key{}", i)).collect();
+    keys.push("key5000".to_string());
+    assert!(MinimalPerfectHash::new(&keys).is_err());
+
+    // keys that change between iterations are rejected
+    let mut count = 0;
+    let result = MinimalPerfectHash::from_keys(|| {
+        count += 1;
+        // the duplicate forces a restart, which returns fewer keys
+        if count == 1 {
+            vec!["a", "b", "a"]
+        } else {
+            vec!["a"]
+        }
+    });
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_mph_adversarial() -> Result<()> {
+    let check = |keys: &[Vec<u8>]| -> Result<()> {
+        let mph = MinimalPerfectHash::new(keys)?;
+        let mut seen = vec![false; keys.len()];
+        for key in keys {
+            let value = mph.get(key);
+            assert!(!seen[value]);
+            seen[value] = true;
+        }
+        // the same function is built from an iterator
+        assert_eq!(MinimalPerfectHash::from_keys(|| keys.iter())?, mph);
+        Ok(())
+    };
+    // the empty key and keys that are prefixes of each other
+    check(&(0..1000).map(|i| vec![b'a'; i]).collect::<Vec<_>>())?;
+    // long shared prefixes differing in the last byte only
+    check(
+        &(0..=255_u8)
+            .map(|b| {
+                let mut key = vec![b'x'; 4096];
+                key.push(b);
+                key
+            })
+            .collect::<Vec<_>>(),
+    )?;
+    // binary keys, with zeros and all byte values
+    check(
+        &(0..10_000_u32)
+            .map(|i| i.to_le_bytes().to_vec())
+            .collect::<Vec<_>>(),
+    )?;
+    // keys that differ in a single bit
+    check(
+        &(0..512)
+            .map(|bit| {
+                let mut key = vec![0_u8; 64];
+                key[bit / 8] ^= 1 << (bit % 8);
+                key
+            })
+            .collect::<Vec<_>>(),
+    )?;
+    Ok(())
+}
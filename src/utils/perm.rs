@@ -0,0 +1,438 @@
+//! Reading and writing permutations of the nodes of a graph.
+//!
+//! The native permutation file is the sequence of the images of the nodes,
+//! stored as `usize` in native byte order, which is the layout of a
+//! memory-mapped `&[usize]`, so large permutations can be memory-mapped with
+//! [`map_perm`] instead of being loaded in memory.
+//!
+//! To exchange permutations with other tools, [`load_perm`] also reads the
+//! other formats listed in [`PermFormat`], detecting them automatically, and
+//! [`store_perm_as`] writes them.
+use anyhow::{bail, Context, Result};
+use epserde::prelude::*;
+#[cfg(feature = "mmap")]
+use mmap_rs::{Mmap, MmapOptions};
+use rand::prelude::*;
+use rand_xoshiro::Xoshiro256PlusPlus;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// The bytes at the start of an ε-serde file.
+const EPSERDE_MAGIC: &[u8] = b"epserde ";
+
+/// The format of a permutation file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PermFormat {
+    /// `usize`s in native byte order, as written by [`store_perm`]. This is
+    /// the only format that can be memory-mapped with [`map_perm`].
+    Raw,
+    /// An ε-serde serialized `Vec<usize>`.
+    Epserde,
+    /// Big-endian 64-bit integers, as written by Java's `DataOutputStream`
+    /// (e.g., by `BinIO.storeLongs` in the Java version of WebGraph).
+    Java,
+    /// One decimal integer per line.
+    Text,
+}
+
+impl PermFormat {
+    /// Guess the format of the permutation file at `path` from its content.
+    ///
+    /// ε-serde files are recognized by their magic cookie and text files by
+    /// containing only digits and whitespace. Binary files are recognized as
+    /// [`PermFormat::Raw`] or [`PermFormat::Java`] by checking that the file
+    /// length is a multiple of the size of their integers, and that their
+    /// first values are valid nodes; if the file is ambiguous, as it happens
+    /// if the two formats coincide, [`PermFormat::Raw`] is returned.
+    pub fn detect<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Cannot open the permutation {}", path.display()))?;
+        let file_len = file.metadata()?.len() as usize;
+        let mut head = Vec::with_capacity(1 << 12);
+        file.take(1 << 12).read_to_end(&mut head)?;
+
+        if head.starts_with(EPSERDE_MAGIC) {
+            return Ok(PermFormat::Epserde);
+        }
+        if !head.is_empty()
+            && head
+                .iter()
+                .all(|b| b.is_ascii_digit() || b.is_ascii_whitespace())
+        {
+            return Ok(PermFormat::Text);
+        }
+
+        // whether the first values, as integers of `word_size` bytes, are
+        // all nodes of a permutation filling the file
+        let is_valid = |word_size: usize, decode: fn(&[u8]) -> u64| {
+            file_len % word_size == 0
+                && head
+                    .chunks_exact(word_size)
+                    .all(|chunk| decode(chunk) < (file_len / word_size) as u64)
+        };
+        let raw: fn(&[u8]) -> u64 = |chunk| usize::from_ne_bytes(chunk.try_into().unwrap()) as u64;
+        let java: fn(&[u8]) -> u64 = |chunk| u64::from_be_bytes(chunk.try_into().unwrap());
+        if is_valid(core::mem::size_of::<usize>(), raw) {
+            return Ok(PermFormat::Raw);
+        }
+        if is_valid(8, java) {
+            return Ok(PermFormat::Java);
+        }
+        bail!(
+            "The permutation file {} is neither text nor a sequence of integers smaller than the number of nodes",
+            path.display()
+        )
+    }
+}
+
+/// Read a permutation from the file at `path`, detecting its format with
+/// [`PermFormat::detect`].
+pub fn load_perm<P: AsRef<Path>>(path: P) -> Result<Vec<usize>> {
+    let path = path.as_ref();
+    load_perm_as(path, PermFormat::detect(path)?)
+}
+
+/// Read a permutation in the given format from the file at `path`.
+pub fn load_perm_as<P: AsRef<Path>>(path: P, format: PermFormat) -> Result<Vec<usize>> {
+    let path = path.as_ref();
+    if format == PermFormat::Epserde {
+        return <Vec<usize>>::load_full(path)
+            .with_context(|| format!("Cannot deserialize the permutation {}", path.display()));
+    }
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("Cannot open the permutation {}", path.display()))?;
+    let file_len = file.metadata()?.len() as usize;
+    let mut reader = BufReader::new(file);
+
+    if format == PermFormat::Text {
+        let mut perm = Vec::new();
+        for (line_num, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            perm.push(line.parse::<usize>().with_context(|| {
+                format!("Cannot parse line {} of {}", line_num + 1, path.display())
+            })?);
+        }
+        return Ok(perm);
+    }
+
+    let word_size = match format {
+        PermFormat::Java => 8,
+        _ => core::mem::size_of::<usize>(),
+    };
+    if file_len % word_size != 0 {
+        bail!(
+            "The length of the permutation file {} ({} bytes) is not a multiple of {}",
+            path.display(),
+            file_len,
+            word_size
+        );
+    }
+    let mut perm = Vec::with_capacity(file_len / word_size);
+    if format == PermFormat::Java {
+        let mut buffer = [0_u8; 8];
+        for _ in 0..perm.capacity() {
+            reader.read_exact(&mut buffer)?;
+            perm.push(u64::from_be_bytes(buffer) as usize);
+        }
+    } else {
+        let mut buffer = [0_u8; core::mem::size_of::<usize>()];
+        for _ in 0..perm.capacity() {
+            reader.read_exact(&mut buffer)?;
+            perm.push(usize::from_ne_bytes(buffer));
+        }
+    }
+    Ok(perm)
+}
+
+/// Write the permutation `perm` to the file at `path` in the native format.
+pub fn store_perm<P: AsRef<Path>>(path: P, perm: &[usize]) -> Result<()> {
+    store_perm_iter(path, perm.iter().copied())
+}
+
+/// Write the permutation `perm` in the given format to the file at `path`.
+pub fn store_perm_as<P: AsRef<Path>>(path: P, perm: &[usize], format: PermFormat) -> Result<()> {
+    let path = path.as_ref();
+    if format == PermFormat::Raw {
+        return store_perm(path, perm);
+    }
+    let mut writer = BufWriter::new(
+        std::fs::File::create(path)
+            .with_context(|| format!("Cannot create the permutation {}", path.display()))?,
+    );
+    match format {
+        PermFormat::Epserde => {
+            perm.to_vec().serialize(&mut writer)?;
+        }
+        PermFormat::Java => {
+            for &x in perm {
+                writer.write_all(&(x as u64).to_be_bytes())?;
+            }
+        }
+        PermFormat::Text => {
+            for &x in perm {
+                writeln!(writer, "{}", x)?;
+            }
+        }
+        PermFormat::Raw => unreachable!(),
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Write the permutation returned by an iterator to the file at `path`,
+/// without materializing it in memory.
+pub fn store_perm_iter<P: AsRef<Path>>(
+    path: P,
+    perm: impl IntoIterator<Item = usize>,
+) -> Result<()> {
+    let path = path.as_ref();
+    let mut writer = BufWriter::new(
+        std::fs::File::create(path)
+            .with_context(|| format!("Cannot create the permutation {}", path.display()))?,
+    );
+    for x in perm {
+        writer.write_all(&x.to_ne_bytes())?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// A memory-mapped permutation file, which can be used as a `&[usize]`.
+///
+/// Without the `mmap` feature the file is loaded in memory.
+pub struct MmapPerm {
+    /// The mapping, which is missing if the file is empty as empty mappings
+    /// are not allowed.
+    #[cfg(feature = "mmap")]
+    mmap: Option<Mmap>,
+    #[cfg(feature = "mmap")]
+    len: usize,
+    /// The permutation loaded in memory.
+    #[cfg(not(feature = "mmap"))]
+    data: Vec<usize>,
+}
+
+impl core::ops::Deref for MmapPerm {
+    type Target = [usize];
+
+    #[inline(always)]
+    fn deref(&self) -> &[usize] {
+        #[cfg(feature = "mmap")]
+        match &self.mmap {
+            None => &[],
+            // mappings are page-aligned, so the cast is safe
+            Some(mmap) => unsafe {
+                core::slice::from_raw_parts(mmap.as_ptr() as *const usize, self.len)
+            },
+        }
+        #[cfg(not(feature = "mmap"))]
+        &self.data
+    }
+}
+
+impl AsRef<[usize]> for MmapPerm {
+    #[inline(always)]
+    fn as_ref(&self) -> &[usize] {
+        self
+    }
+}
+
+/// Memory-map the permutation file at `path`.
+pub fn map_perm<P: AsRef<Path>>(path: P) -> Result<MmapPerm> {
+    let path = path.as_ref();
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("Cannot open the permutation {}", path.display()))?;
+    let file_len = file.metadata()?.len() as usize;
+    if file_len % core::mem::size_of::<usize>() != 0 {
+        bail!(
+            "The length of the permutation file {} ({} bytes) is not a multiple of {}",
+            path.display(),
+            file_len,
+            core::mem::size_of::<usize>()
+        );
+    }
+    #[cfg(feature = "mmap")]
+    {
+        let mmap = if file_len == 0 {
+            None
+        } else {
+            Some(unsafe {
+                MmapOptions::new(file_len)?
+                    .with_flags((sux::prelude::Flags::TRANSPARENT_HUGE_PAGES).mmap_flags())
+                    .with_file(file, 0)
+                    .map()
+                    .with_context(|| format!("Cannot map the permutation {}", path.display()))?
+            })
+        };
+        Ok(MmapPerm {
+            mmap,
+            len: file_len / core::mem::size_of::<usize>(),
+        })
+    }
+    #[cfg(not(feature = "mmap"))]
+    {
+        let mut file = file;
+        let mut data = vec![0_usize; file_len / core::mem::size_of::<usize>()];
+        // SAFETY: the bytes are within the allocation, and any bit pattern
+        // is a valid usize
+        let bytes =
+            unsafe { core::slice::from_raw_parts_mut(data.as_mut_ptr() as *mut u8, file_len) };
+        file.read_exact(bytes)
+            .with_context(|| format!("Cannot read the permutation {}", path.display()))?;
+        Ok(MmapPerm { data })
+    }
+}
+
+/// Return a uniformly random permutation of `n` elements, generated by a
+/// Fisher–Yates shuffle driven by a Xoshiro256++ generator seeded with
+/// `seed`, so that the same seed always yields the same permutation, on
+/// every platform.
+///
+/// Random permutations are the usual baseline when evaluating orderings.
+pub fn random_perm(n: usize, seed: u64) -> Vec<usize> {
+    let mut rng = Xoshiro256PlusPlus::seed_from_u64(seed);
+    let mut perm = (0..n).collect::<Vec<_>>();
+    perm.shuffle(&mut rng);
+    perm
+}
+
+/// Write to `dest` the inverse of `perm`.
+///
+/// `perm` is scanned sequentially, while the result is written randomly on a
+/// memory-mapped file, so only the pages being modified need to be in memory.
+/// Without the `mmap` feature, the result is built in memory.
+pub fn invert<P: AsRef<Path>>(perm: &[usize], dest: P) -> Result<()> {
+    let dest = dest.as_ref();
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(dest)
+        .with_context(|| format!("Cannot create the permutation {}", dest.display()))?;
+    if perm.is_empty() {
+        return Ok(());
+    }
+    let file_len = perm.len() * core::mem::size_of::<usize>();
+    file.set_len(file_len as u64)?;
+    #[cfg(feature = "mmap")]
+    let mut mmap = unsafe {
+        MmapOptions::new(file_len)?
+            .with_file(file, 0)
+            .map_mut()
+            .with_context(|| format!("Cannot map the permutation {}", dest.display()))?
+    };
+    #[cfg(feature = "mmap")]
+    let inv =
+        unsafe { core::slice::from_raw_parts_mut(mmap.as_mut_ptr() as *mut usize, perm.len()) };
+    // without memory mapping, the inverse is built in memory and written at
+    // the end
+    #[cfg(not(feature = "mmap"))]
+    let mut inv = vec![0_usize; perm.len()];
+    // the file is zero-filled, so we can use a bit vector to detect repeats
+    let mut seen = vec![0_u64; (perm.len() + 63) / 64];
+    for (x, &y) in perm.iter().enumerate() {
+        if y >= perm.len() || seen[y / 64] & (1 << (y % 64)) != 0 {
+            bail!(
+                "The input is not a permutation: {} appears at index {}",
+                y,
+                x
+            );
+        }
+        seen[y / 64] |= 1 << (y % 64);
+        inv[y] = x;
+    }
+    #[cfg(not(feature = "mmap"))]
+    {
+        let mut writer = BufWriter::new(file);
+        for x in inv {
+            writer.write_all(&x.to_ne_bytes())?;
+        }
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg_attr(test, test)]
+#[cfg(test)]
+fn test_load_store_perm() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("test.perm");
+    let perm = vec![3, 0, 2, 1];
+    store_perm(&path, &perm)?;
+    assert_eq!(load_perm(&path)?, perm);
+    Ok(())
+}
+
+#[cfg_attr(test, test)]
+#[cfg(test)]
+fn test_compose_invert() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let a = dir.path().join("a.perm");
+    let b = dir.path().join("b.perm");
+    store_perm(&a, &[3, 0, 2, 1])?;
+    store_perm(&b, &[1, 2, 3, 0])?;
+
+    let composed = dir.path().join("composed.perm");
+    let perms = [map_perm(&a)?, map_perm(&b)?];
+    let perms = [&*perms[0], &*perms[1]];
+    store_perm_iter(&composed, crate::algorithms::compose_orders(&perms)?)?;
+    assert_eq!(load_perm(&composed)?, vec![0, 1, 3, 2]);
+
+    let inverse = dir.path().join("inverse.perm");
+    invert(&map_perm(&a)?, &inverse)?;
+    assert_eq!(&*map_perm(&inverse)?, &[1, 3, 2, 0]);
+
+    assert!(invert(&[0, 0], &inverse).is_err());
+    Ok(())
+}
+
+#[cfg_attr(test, test)]
+#[cfg(test)]
+fn test_random_perm() {
+    let perm = random_perm(1000, 0);
+    let mut sorted = perm.clone();
+    sorted.sort();
+    assert_eq!(sorted, (0..1000).collect::<Vec<_>>());
+    assert_eq!(perm, random_perm(1000, 0));
+    assert_ne!(perm, random_perm(1000, 1));
+}
+
+#[cfg_attr(test, test)]
+#[cfg(test)]
+fn test_perm_formats() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let perm = (0..1000).map(|x| (x * 7 + 3) % 1000).collect::<Vec<_>>();
+    for format in [
+        PermFormat::Raw,
+        PermFormat::Epserde,
+        PermFormat::Java,
+        PermFormat::Text,
+    ] {
+        let path = dir.path().join(format!("{:?}.perm", format));
+        store_perm_as(&path, &perm, format)?;
+        assert_eq!(PermFormat::detect(&path)?, format);
+        assert_eq!(load_perm(&path)?, perm);
+        assert_eq!(load_perm_as(&path, format)?, perm);
+    }
+
+    // an odd number of nodes, whose raw file on 32-bit targets is not a
+    // sequence of 64-bit integers
+    let perm = vec![2, 0, 1];
+    for format in [PermFormat::Raw, PermFormat::Java] {
+        let path = dir.path().join(format!("odd-{:?}.perm", format));
+        store_perm_as(&path, &perm, format)?;
+        assert_eq!(PermFormat::detect(&path)?, format);
+        assert_eq!(load_perm(&path)?, perm);
+    }
+
+    let path = dir.path().join("invalid.perm");
+    std::fs::write(&path, [0xff; 12])?;
+    assert!(PermFormat::detect(&path).is_err());
+    Ok(())
+}
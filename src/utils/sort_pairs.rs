@@ -1,13 +1,18 @@
-use crate::{traits::SortedIterator, utils::KAryHeap};
-use anyhow::{Context, Result};
+use crate::{
+    traits::SortedIterator,
+    utils::{
+        int2nat, nat2int, CountDupsIter, DedupIter, ExternalSorter, RecordBatchIterator,
+        RecordCodec, RecordMerge, SortRecord,
+    },
+};
+use anyhow::Result;
 use core::marker::PhantomData;
 use dsi_bitstream::prelude::*;
-use rayon::prelude::*;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 /// To be able to sort a payload, we must be able to write and read it back from
 /// a bitstream
-pub trait SortPairsPayload: Send + Copy {
+pub trait SortPairsPayload: Send + Copy + 'static {
     /// write self to the bitsream and return the number of bits written
     fn to_bitstream<E: Endianness, B: WriteCodes<E>>(&self, bitstream: &mut B) -> Result<usize>;
     /// deserialize Self from the bitstream and return its
@@ -25,265 +30,357 @@ impl SortPairsPayload for () {
     }
 }
 
-/// A struct that ingests paris of nodes and a generic payload and sort them
-/// in chunks of `batch_size` triples, then dumps them to disk.
-pub struct SortPairs<T: SortPairsPayload = ()> {
-    /// The batch size
-    batch_size: usize,
-    /// The length of the last batch might be smaller than `batch_size`
-    last_batch_len: usize,
-    /// The batch of triples we are currently building
-    batch: Vec<(usize, usize, T)>,
-    /// were we are going to store the tmp files
-    dir: PathBuf,
-    /// keep track of how many batches we created
-    num_batches: usize,
+/// Write an integer as the number of its significant bits in γ code,
+/// followed by the bits after the most significant one.
+///
+/// This is a variant of the δ code in which the whole range of `u64` can be
+/// represented, as no one is added to the value.
+#[inline(always)]
+fn write_full_delta<E: Endianness, B: WriteCodes<E>>(
+    bitstream: &mut B,
+    value: u64,
+) -> Result<usize> {
+    let len = (u64::BITS - value.leading_zeros()) as usize;
+    let mut written_bits = bitstream.write_gamma(len as u64)?;
+    if len > 1 {
+        written_bits += bitstream.write_bits(value & (u64::MAX >> (65 - len)), len - 1)?;
+    }
+    Ok(written_bits)
 }
 
-impl<T: SortPairsPayload> core::ops::Drop for SortPairs<T> {
-    fn drop(&mut self) {
-        let _ = self.dump();
-    }
+/// Read an integer written by [`write_full_delta`].
+#[inline(always)]
+fn read_full_delta<E: Endianness, B: ReadCodes<E>>(bitstream: &mut B) -> Result<u64> {
+    let len = bitstream.read_gamma()? as usize;
+    Ok(match len {
+        0 => 0,
+        1 => 1,
+        _ => (1 << (len - 1)) | bitstream.read_bits(len - 1)?,
+    })
 }
 
-impl<T: SortPairsPayload> SortPairs<T> {
-    /// Create a new `SortPairs` with a given batch size
-    pub fn new<P: AsRef<Path>>(batch_size: usize, dir: P) -> Result<Self> {
-        Ok(SortPairs {
-            batch_size,
-            last_batch_len: 0,
-            batch: Vec::with_capacity(batch_size),
-            dir: dir.as_ref().to_owned(),
-            num_batches: 0,
-        })
-    }
+/// Unsigned integers are written in a variant of the δ code, so small
+/// values, such as counts, take few bits.
+macro_rules! impl_unsigned_payload {
+    ($($ty:ty),*) => {$(
+        impl SortPairsPayload for $ty {
+            #[inline(always)]
+            fn to_bitstream<E: Endianness, B: WriteCodes<E>>(
+                &self,
+                bitstream: &mut B,
+            ) -> Result<usize> {
+                write_full_delta(bitstream, *self as u64)
+            }
+            #[inline(always)]
+            fn from_bitstream<E: Endianness, B: ReadCodes<E>>(
+                bitstream: &mut B,
+            ) -> Result<Self> {
+                Ok(read_full_delta(bitstream)? as $ty)
+            }
+        }
+    )*};
+}
 
-    /// Add a triple to the graph.
-    pub fn push(&mut self, x: usize, y: usize, t: T) -> Result<()> {
-        self.batch.push((x, y, t));
-        if self.batch.len() >= self.batch_size {
-            self.dump()?;
+impl_unsigned_payload!(u8, u16, u32, u64, usize);
+
+/// Signed integers are mapped to unsigned ones by [`int2nat`] and written as
+/// unsigned integers, so values of small absolute value take few bits.
+macro_rules! impl_signed_payload {
+    ($($ty:ty),*) => {$(
+        impl SortPairsPayload for $ty {
+            #[inline(always)]
+            fn to_bitstream<E: Endianness, B: WriteCodes<E>>(
+                &self,
+                bitstream: &mut B,
+            ) -> Result<usize> {
+                write_full_delta(bitstream, int2nat(*self as i64))
+            }
+            #[inline(always)]
+            fn from_bitstream<E: Endianness, B: ReadCodes<E>>(
+                bitstream: &mut B,
+            ) -> Result<Self> {
+                Ok(nat2int(read_full_delta(bitstream)?) as $ty)
+            }
         }
-        Ok(())
+    )*};
+}
+
+impl_signed_payload!(i8, i16, i32, i64, isize);
+
+impl SortPairsPayload for bool {
+    #[inline(always)]
+    fn to_bitstream<E: Endianness, B: WriteCodes<E>>(&self, bitstream: &mut B) -> Result<usize> {
+        bitstream.write_bits(*self as u64, 1)
+    }
+    #[inline(always)]
+    fn from_bitstream<E: Endianness, B: ReadCodes<E>>(bitstream: &mut B) -> Result<Self> {
+        Ok(bitstream.read_bits(1)? != 0)
     }
+}
 
-    /// Dump the current batch to disk
-    fn dump(&mut self) -> Result<()> {
-        // early exit
-        if self.batch.is_empty() {
-            return Ok(());
+/// Floating-point numbers are written as their raw bits, as instantaneous
+/// codes would not make them shorter.
+macro_rules! impl_float_payload {
+    ($($ty:ty: $bits:ty),*) => {$(
+        impl SortPairsPayload for $ty {
+            #[inline(always)]
+            fn to_bitstream<E: Endianness, B: WriteCodes<E>>(
+                &self,
+                bitstream: &mut B,
+            ) -> Result<usize> {
+                bitstream.write_bits(self.to_bits() as u64, <$bits>::BITS as usize)
+            }
+            #[inline(always)]
+            fn from_bitstream<E: Endianness, B: ReadCodes<E>>(
+                bitstream: &mut B,
+            ) -> Result<Self> {
+                Ok(<$ty>::from_bits(
+                    bitstream.read_bits(<$bits>::BITS as usize)? as $bits,
+                ))
+            }
         }
-        // sort ignoring the payload
-        self.batch.par_sort_unstable_by_key(|(x, y, _)| (*x, *y));
-        // create a batch file where to dump
-        let batch_name = self.dir.join(format!("{:06x}", self.num_batches));
-        let file = std::io::BufWriter::with_capacity(1 << 22, std::fs::File::create(&batch_name)?);
-        // createa bitstream to write to the file
-        let mut stream = <BufferedBitStreamWrite<LE, _>>::new(FileBackend::new(file));
-        // Dump the triples to the bitstream
-        let (mut prev_src, mut prev_dst) = (0, 0);
-        for &(src, dst, payload) in &self.batch {
-            // write the src gap as gamma
-            stream.write_gamma((src - prev_src) as _)?;
-            if src != prev_src {
-                // Reset prev_y
-                prev_dst = 0;
+    )*};
+}
+
+impl_float_payload!(f32: u32, f64: u64);
+
+/// Tuples are written component by component.
+macro_rules! impl_tuple_payload {
+    ($($name:ident),*) => {
+        impl<$($name: SortPairsPayload),*> SortPairsPayload for ($($name,)*) {
+            #[inline(always)]
+            #[allow(non_snake_case)]
+            fn to_bitstream<E: Endianness, B: WriteCodes<E>>(
+                &self,
+                bitstream: &mut B,
+            ) -> Result<usize> {
+                let ($($name,)*) = self;
+                let mut written_bits = 0;
+                $(written_bits += $name.to_bitstream(bitstream)?;)*
+                Ok(written_bits)
+            }
+            #[inline(always)]
+            fn from_bitstream<E: Endianness, B: ReadCodes<E>>(
+                bitstream: &mut B,
+            ) -> Result<Self> {
+                Ok(($($name::from_bitstream(bitstream)?,)*))
             }
-            // write the dst gap as gamma
-            stream.write_gamma((dst - prev_dst) as _)?;
-            // write the payload
-            payload.to_bitstream(&mut stream)?;
-            (prev_src, prev_dst) = (src, dst);
         }
-        // flush the stream and reset the buffer
-        stream.flush()?;
-        self.last_batch_len = self.batch.len();
-        self.batch.clear();
-        self.num_batches += 1;
-        Ok(())
-    }
+    };
+}
 
-    /// Cancel all the files that were created
-    pub fn cancel_batches(&mut self) -> Result<()> {
-        for i in 0..self.num_batches {
-            let batch_name = self.dir.join(format!("{:06x}", i));
-            // It's OK if something is not OK here
-            std::fs::remove_file(batch_name)?;
+impl_tuple_payload!(A, B);
+impl_tuple_payload!(A, B, C);
+impl_tuple_payload!(A, B, C, D);
+
+/// Arrays are written element by element.
+impl<T: SortPairsPayload + Default, const N: usize> SortPairsPayload for [T; N] {
+    #[inline(always)]
+    fn to_bitstream<E: Endianness, B: WriteCodes<E>>(&self, bitstream: &mut B) -> Result<usize> {
+        let mut written_bits = 0;
+        for x in self {
+            written_bits += x.to_bitstream(bitstream)?;
         }
-        self.num_batches = 0;
-        self.last_batch_len = 0;
-        self.batch.clear();
-        Ok(())
+        Ok(written_bits)
     }
-
-    pub fn iter(&mut self) -> Result<KMergeIters<T, BatchIterator<T>>> {
-        self.dump()?;
-        Ok(KMergeIters::new((0..self.num_batches).map(|batch_idx| {
-            BatchIterator::new(
-                self.dir.join(format!("{:06x}", batch_idx)),
-                if batch_idx == self.num_batches - 1 {
-                    self.last_batch_len
-                } else {
-                    self.batch_size
-                },
-            )
-            .unwrap()
-        })))
+    #[inline(always)]
+    fn from_bitstream<E: Endianness, B: ReadCodes<E>>(bitstream: &mut B) -> Result<Self> {
+        let mut result = [T::default(); N];
+        for x in &mut result {
+            *x = T::from_bitstream(bitstream)?;
+        }
+        Ok(result)
     }
 }
 
-/// An iterator that can read the batch files generated by [`SortPairs`] and
-/// iterate over the triples
-#[derive(Debug)]
-pub struct BatchIterator<T: SortPairsPayload> {
-    file_path: PathBuf,
-    stream: BufferedBitStreamRead<LE, u64, FileBackend<u32, std::io::BufReader<std::fs::File>>>,
-    len: usize,
-    current: usize,
+/// The codec of the triples of [`SortPairs`]: the source is written as a gap
+/// from the previous source in γ code, the destination as a gap from the
+/// previous destination in γ code if the source did not change, and as it is
+/// otherwise, and then the payload follows.
+#[derive(Clone, Copy, Debug)]
+pub struct PairsCodec<T: SortPairsPayload> {
     prev_src: usize,
     prev_dst: usize,
     marker: PhantomData<T>,
 }
 
-impl<T: SortPairsPayload> BatchIterator<T> {
-    pub fn new<P: AsRef<std::path::Path>>(file_path: P, len: usize) -> Result<Self> {
-        let file_path = file_path.as_ref();
-        let file = std::io::BufReader::new(
-            std::fs::File::open(file_path)
-                .with_context(|| format!("Cannot open batch {}", file_path.to_string_lossy()))?,
-        );
-        let stream = <BufferedBitStreamRead<LE, u64, _>>::new(FileBackend::new(file));
-        Ok(BatchIterator {
-            file_path: file_path.to_owned(),
-            stream,
-            len,
-            current: 0,
+impl<T: SortPairsPayload> Default for PairsCodec<T> {
+    fn default() -> Self {
+        Self {
             prev_src: 0,
             prev_dst: 0,
             marker: PhantomData,
-        })
+        }
     }
 }
 
-impl<T: SortPairsPayload> Clone for BatchIterator<T> {
-    fn clone(&self) -> Self {
-        // we can't directly clone the stream, so we need to reopen the file
-        // and seek to the same position
-        let file = std::io::BufReader::new(std::fs::File::open(&self.file_path).unwrap());
-        let mut stream = <BufferedBitStreamRead<LE, u64, _>>::new(FileBackend::new(file));
-        stream.set_pos(self.stream.get_pos()).unwrap();
-        assert_eq!(stream.get_pos(), self.stream.get_pos());
-        BatchIterator {
-            file_path: self.file_path.clone(),
-            stream,
-            len: self.len,
-            current: self.current,
-            prev_src: self.prev_src,
-            prev_dst: self.prev_dst,
-            marker: PhantomData,
+impl<T: SortPairsPayload> RecordCodec<(usize, usize, T)> for PairsCodec<T> {
+    #[inline(always)]
+    fn write<E: Endianness, B: WriteCodes<E>>(
+        &mut self,
+        &(src, dst, payload): &(usize, usize, T),
+        bitstream: &mut B,
+    ) -> Result<usize> {
+        // write the src gap as gamma
+        let mut written_bits = bitstream.write_gamma((src - self.prev_src) as _)?;
+        if src != self.prev_src {
+            // Reset prev_y
+            self.prev_dst = 0;
         }
+        // write the dst gap as gamma
+        written_bits += bitstream.write_gamma((dst - self.prev_dst) as _)?;
+        // write the payload
+        written_bits += payload.to_bitstream(bitstream)?;
+        (self.prev_src, self.prev_dst) = (src, dst);
+        Ok(written_bits)
     }
-}
 
-unsafe impl<T: SortPairsPayload> SortedIterator for BatchIterator<T> {}
-
-impl<T: SortPairsPayload> Iterator for BatchIterator<T> {
-    type Item = (usize, usize, T);
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.current == self.len {
-            return None;
-        }
-        let src = self.prev_src + self.stream.read_gamma().unwrap() as usize;
+    #[inline(always)]
+    fn read<E: Endianness, B: ReadCodes<E>>(
+        &mut self,
+        bitstream: &mut B,
+    ) -> Result<(usize, usize, T)> {
+        let src = self.prev_src + bitstream.read_gamma()? as usize;
         if src != self.prev_src {
             // Reset prev_y
             self.prev_dst = 0;
         }
-        let dst = self.prev_dst + self.stream.read_gamma().unwrap() as usize;
-        let payload = T::from_bitstream(&mut self.stream).unwrap();
-        self.prev_src = src;
-        self.prev_dst = dst;
-        self.current += 1;
-        Some((src, dst, payload))
+        let dst = self.prev_dst + bitstream.read_gamma()? as usize;
+        let payload = T::from_bitstream(bitstream)?;
+        (self.prev_src, self.prev_dst) = (src, dst);
+        Ok((src, dst, payload))
     }
 }
 
-#[derive(Clone, Debug)]
-/// Private struct that can be used to sort triples based only on the nodes and
-/// ignoring the payload
-struct HeadTail<T: Copy, I: Iterator<Item = (usize, usize, T)> + SortedIterator> {
-    head: (usize, usize),
-    payload: T,
-    tail: I,
+/// Triples are sorted by source and destination, ignoring the payload.
+impl<T: SortPairsPayload> SortRecord for (usize, usize, T) {
+    type Key = (usize, usize);
+    type Codec = PairsCodec<T>;
+    #[inline(always)]
+    fn key(&self) -> Self::Key {
+        (self.0, self.1)
+    }
 }
 
-impl<T: Copy, I: Iterator<Item = (usize, usize, T)> + SortedIterator> PartialEq for HeadTail<T, I> {
-    fn eq(&self, other: &Self) -> bool {
-        self.head == other.head
-    }
+/// A struct that ingests paris of nodes and a generic payload and sort them
+/// in chunks of `batch_size` triples, then dumps them to disk.
+///
+/// This is an [`ExternalSorter`] of triples whose batches are merged by a
+/// [`KMergeIters`], so that duplicates can be removed or counted.
+pub struct SortPairs<T: SortPairsPayload = ()> {
+    sorter: ExternalSorter<(usize, usize, T)>,
 }
-impl<T: Copy, I: Iterator<Item = (usize, usize, T)> + SortedIterator> PartialOrd
-    for HeadTail<T, I>
-{
-    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
-        Some(self.head.cmp(&other.head))
+
+impl<T: SortPairsPayload> SortPairs<T> {
+    /// Create a new `SortPairs` with a given batch size
+    pub fn new<P: AsRef<Path>>(batch_size: usize, dir: P) -> Result<Self> {
+        Ok(SortPairs {
+            sorter: ExternalSorter::new(batch_size, dir)?,
+        })
     }
-}
 
-#[derive(Clone, Debug)]
-/// Merge K different sorted iterators
-pub struct KMergeIters<T: Copy, I: Iterator<Item = (usize, usize, T)> + SortedIterator> {
-    heap: KAryHeap<HeadTail<T, I>>,
-}
+    /// Spread the batches round-robin across the given directories, which
+    /// replace the one passed to the constructor.
+    ///
+    /// With one directory per scratch disk, I/O is parallelized and no single
+    /// volume has to hold all the batches. Must be called before pushing any
+    /// triple.
+    pub fn with_dirs<P: AsRef<Path>>(self, dirs: impl IntoIterator<Item = P>) -> Result<Self> {
+        Ok(SortPairs {
+            sorter: self.sorter.with_dirs(dirs)?,
+        })
+    }
 
-impl<T: Copy, I: Iterator<Item = (usize, usize, T)> + SortedIterator> KMergeIters<T, I> {
-    pub fn new(iters: impl Iterator<Item = I>) -> Self {
-        let mut heap = KAryHeap::with_capacity(iters.size_hint().1.unwrap_or(10));
-        for mut iter in iters {
-            match iter.next() {
-                None => {}
-                Some((src, dst, payload)) => {
-                    heap.push(HeadTail {
-                        head: (src, dst),
-                        payload,
-                        tail: iter,
-                    });
-                }
-            }
+    #[cfg(feature = "zstd")]
+    /// Compress the batches written to disk with zstd at the given level.
+    ///
+    /// This trades some CPU time for a large reduction of the temporary disk
+    /// space, as gaps between sorted pairs compress well. Must be called
+    /// before pushing any triple.
+    pub fn with_zstd(self, level: i32) -> Self {
+        SortPairs {
+            sorter: self.sorter.with_zstd(level),
         }
-        KMergeIters { heap }
+    }
+
+    /// Create a new `SortPairs` whose batch size is derived from a memory
+    /// budget of `memory_budget` bytes for the buffered triples.
+    ///
+    /// Each triple takes `size_of::<(usize, usize, T)>()` bytes in memory,
+    /// payload and padding included, so the budget is converted into a number
+    /// of triples. The budget is approximate, as it does not include the I/O
+    /// buffers used to write the batches (see
+    /// [`ExternalSorter::new_with_memory_budget`]).
+    pub fn new_with_memory_budget<P: AsRef<Path>>(memory_budget: usize, dir: P) -> Result<Self> {
+        Ok(SortPairs {
+            sorter: ExternalSorter::new_with_memory_budget(memory_budget, dir)?,
+        })
+    }
+
+    /// Return the number of bytes allocated for the batch buffers, including
+    /// the one that is being written in background.
+    pub fn memory_usage(&self) -> usize {
+        self.sorter.memory_usage()
+    }
+
+    /// Add a triple to the graph.
+    pub fn push(&mut self, x: usize, y: usize, t: T) -> Result<()> {
+        self.sorter.push((x, y, t))
+    }
+
+    /// Cancel all the files that were created
+    pub fn cancel_batches(&mut self) -> Result<()> {
+        self.sorter.cancel_batches()
+    }
+
+    /// Dump the current batch and return an iterator on each batch written
+    /// so far.
+    ///
+    /// This is useful to merge the batches of several `SortPairs`, for
+    /// example, one per thread, with a single [`KMergeIters`].
+    pub fn batches(&mut self) -> Result<Vec<BatchIterator<T>>> {
+        self.sorter.batches()
+    }
+
+    pub fn iter(&mut self) -> Result<KMergeIters<T, BatchIterator<T>>> {
+        Ok(KMergeIters::new(self.batches()?.into_iter()))
     }
 }
 
-impl<T: Copy, I: Iterator<Item = (usize, usize, T)> + SortedIterator> Iterator
-    for KMergeIters<T, I>
+/// An iterator that can read the batch files generated by [`SortPairs`] and
+/// iterate over the triples
+pub type BatchIterator<T> = RecordBatchIterator<(usize, usize, T)>;
+
+unsafe impl<T: SortPairsPayload> SortedIterator for BatchIterator<T> {}
+
+/// Merge K different sorted iterators of triples using a
+/// [`LoserTree`](super::LoserTree).
+///
+/// This is a [`RecordMerge`] comparing triples by their pair of nodes, so
+/// triples with the same pair are returned in the order of the iterators.
+pub type KMergeIters<T, I> = RecordMerge<(usize, usize, T), I>;
+
+impl<T: SortPairsPayload, I: Iterator<Item = (usize, usize, T)> + SortedIterator>
+    KMergeIters<T, I>
 {
-    type Item = (usize, usize, T);
+    /// Collapse duplicate pairs of nodes, keeping the payload of the first
+    /// occurrence (in the order in which the iterators were passed).
+    pub fn dedup(self) -> DedupIter<T, Self> {
+        DedupIter::new(self)
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.heap.is_empty() {
-            return None;
-        }
-        // Read the head of the heap
-        let head_tail = self.heap.peek_mut();
-        let (src, dst) = head_tail.head;
-        let result = (src, dst, head_tail.payload);
-        match head_tail.tail.next() {
-            None => {
-                // Remove the head of the heap if the iterator ended
-                self.heap.pop();
-            }
-            Some((src, dst, payload)) => {
-                // set the new values
-                head_tail.head = (src, dst);
-                head_tail.payload = payload;
-                // fix the heap
-                self.heap.bubble_down(0);
-            }
-        }
-        Some(result)
+    /// Collapse duplicate pairs of nodes, replacing the payload with the
+    /// number of occurrences of the pair.
+    pub fn count_dups(self) -> CountDupsIter<T, Self> {
+        CountDupsIter::new(self)
     }
 }
 
+unsafe impl<T: SortPairsPayload, I: Iterator<Item = (usize, usize, T)> + SortedIterator>
+    SortedIterator for KMergeIters<T, I>
+{
+}
+
 #[cfg(test)]
 #[test]
 pub fn test_push() -> Result<()> {
@@ -326,3 +423,91 @@ pub fn test_push() -> Result<()> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+#[test]
+pub fn test_memory_budget() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let triple_size = core::mem::size_of::<(usize, usize, ())>();
+    let mut sp = <SortPairs<()>>::new_with_memory_budget(20 * triple_size, dir.path())?;
+    assert_eq!(sp.memory_usage(), 20 * triple_size);
+    let n = 25;
+    for i in (0..n).rev() {
+        sp.push(i, i + 1, ())?;
+        assert!(sp.memory_usage() <= 20 * triple_size);
+    }
+    let iter = sp.iter()?;
+    assert_eq!(sp.sorter.batch_lens(), [10, 10, 5]);
+    for (i, (x, y, _)) in iter.enumerate() {
+        assert_eq!((x, y), (i, i + 1));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "zstd")]
+#[cfg(test)]
+#[test]
+pub fn test_zstd() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let mut sp = <SortPairs<()>>::new(10, dir.path())?.with_zstd(3);
+    let n = 25;
+    for i in (0..n).rev() {
+        sp.push(i, i + 1, ())?;
+    }
+    let mut iter = sp.iter()?;
+    iter.next();
+    let cloned = iter.clone();
+    for (i, (x, y, _)) in iter.enumerate() {
+        assert_eq!((x, y), (i + 1, i + 2));
+    }
+    for (i, (x, y, _)) in cloned.enumerate() {
+        assert_eq!((x, y), (i + 1, i + 2));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+pub fn test_multiple_dirs() -> Result<()> {
+    let dirs = [tempfile::tempdir()?, tempfile::tempdir()?];
+    let mut sp =
+        <SortPairs<()>>::new(10, dirs[0].path())?.with_dirs(dirs.iter().map(|dir| dir.path()))?;
+    let n = 25;
+    for i in (0..n).rev() {
+        sp.push(i, i + 1, ())?;
+    }
+    let iter = sp.iter()?;
+    // three batches, two in the first directory and one in the second
+    assert_eq!(std::fs::read_dir(dirs[0].path())?.count(), 2);
+    assert_eq!(std::fs::read_dir(dirs[1].path())?.count(), 1);
+    for (i, (x, y, _)) in iter.enumerate() {
+        assert_eq!((x, y), (i, i + 1));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+pub fn test_payloads() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    type Payload = ((u64, u64, i64, i64), (f32, f64), [u8; 3], (bool, usize, i8));
+    let mut sp = <SortPairs<Payload>>::new(7, dir.path())?;
+    let payload = |i: usize| -> Payload {
+        let k = i as u64;
+        (
+            (k, u64::MAX - k, -(i as i64), i64::MIN + i as i64),
+            (i as f32 / 3.0, -1.0 / k as f64),
+            [i as u8, u8::MAX, 0],
+            (i % 2 == 0, i, i8::MIN + i as i8),
+        )
+    };
+    let n = 25;
+    for i in (0..n).rev() {
+        sp.push(i, i + 1, payload(i))?;
+    }
+    for (i, (x, y, p)) in sp.iter()?.enumerate() {
+        assert_eq!((x, y), (i, i + 1));
+        assert_eq!(p, payload(i));
+    }
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use super::{BatchIterator, COOIterToGraph, DedupIter, KMergeIters, SortPairs};
+use anyhow::{ensure, Result};
+use dsi_progress_logger::ProgressLogger;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+/// The iterator on the pairs of nodes merged from the batches of a
+/// [`SortPairs`] without duplicates.
+pub type DedupPairs = core::iter::Map<
+    DedupIter<(), KMergeIters<(), BatchIterator<()>>>,
+    fn((usize, usize, ())) -> (usize, usize),
+>;
+
+/// A sequential graph view of arcs sorted out of core, as returned by
+/// [`sort_arcs`].
+pub type SortedArcsGraph = COOIterToGraph<SortedArcs<DedupPairs>>;
+
+/// An iterator on the triples or pairs merged from the batches of one or
+/// more [`SortPairs`], owning the temporary directory containing the
+/// batches.
+///
+/// The clones of the iterator, such as the ones made by each call to
+/// [`iter_nodes`](crate::traits::SequentialGraph::iter_nodes) on a
+/// [`COOIterToGraph`], reopen the batches, so the directory is shared by all
+/// the clones and deleted when the last one is dropped.
+#[derive(Debug, Clone)]
+pub struct SortedArcs<I> {
+    iter: I,
+    _dir: Arc<TempDir>,
+}
+
+impl<I: Iterator> SortedArcs<I> {
+    /// Wrap an iterator on batches stored in the directory `dir`.
+    pub fn new(iter: I, dir: TempDir) -> Self {
+        Self {
+            iter,
+            _dir: Arc::new(dir),
+        }
+    }
+}
+
+impl SortedArcs<DedupPairs> {
+    /// Merge the batches of arcs stored in the directory `dir`, removing
+    /// duplicate arcs.
+    pub fn dedup(batches: Vec<BatchIterator<()>>, dir: TempDir) -> Self {
+        let map: fn((usize, usize, ())) -> (usize, usize) = |(src, dst, _)| (src, dst);
+        Self::new(KMergeIters::new(batches.into_iter()).dedup().map(map), dir)
+    }
+}
+
+impl<I: Iterator> Iterator for SortedArcs<I> {
+    type Item = I::Item;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<I::Item> {
+        self.iter.next()
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Sort arcs given in any order and possibly with duplicates, and return a
+/// sequential graph view of them without duplicates.
+///
+/// The arcs are sorted by a [`SortPairs`] with batches of `batch_size`
+/// pairs in a temporary directory, which is owned by the result and deleted
+/// when the result and all its iterators are dropped.
+///
+/// If `num_nodes` is `None`, the number of nodes is one plus the largest
+/// node appearing in an arc; otherwise, all nodes must be smaller than
+/// `num_nodes`, and nodes without arcs past the largest one are kept.
+pub fn sort_arcs(
+    arcs: impl IntoIterator<Item = (usize, usize)>,
+    num_nodes: Option<usize>,
+    batch_size: usize,
+) -> Result<SortedArcsGraph> {
+    let dir = tempfile::tempdir()?;
+    let mut sorted = <SortPairs<()>>::new(batch_size, dir.path())?;
+
+    let mut pl = ProgressLogger::default();
+    pl.item_name = "arc";
+    pl.start("Sorting arcs...");
+    let mut max_node = None;
+    for (src, dst) in arcs {
+        if let Some(num_nodes) = num_nodes {
+            ensure!(
+                src < num_nodes && dst < num_nodes,
+                "The arc ({}, {}) is not between nodes smaller than {}",
+                src,
+                dst,
+                num_nodes
+            );
+        }
+        max_node = max_node.max(Some(src.max(dst)));
+        sorted.push(src, dst, ())?;
+        pl.light_update();
+    }
+    pl.done();
+
+    let num_nodes = num_nodes.unwrap_or(max_node.map_or(0, |max_node| max_node + 1));
+    Ok(COOIterToGraph::new(
+        num_nodes,
+        SortedArcs::dedup(sorted.batches()?, dir),
+    ))
+}
+
+#[cfg(test)]
+#[cfg_attr(test, test)]
+fn test_sort_arcs() -> Result<()> {
+    use crate::graph::vec_graph::VecGraph;
+    use crate::traits::SequentialGraph;
+    let arcs = [(3, 1), (0, 2), (3, 1), (1, 0), (0, 2), (2, 2)];
+    let graph = sort_arcs(arcs, None, 2)?;
+    assert_eq!(graph.num_nodes(), 4);
+    let expected = VecGraph::from_arc_list(&[(0, 2), (1, 0), (2, 2), (3, 1)]);
+    // each iteration reopens the batches
+    for _ in 0..2 {
+        assert_eq!(VecGraph::from_node_iter(graph.iter_nodes()), expected);
+    }
+    assert_eq!(sort_arcs(arcs, Some(6), 2)?.num_nodes(), 6);
+    assert!(sort_arcs(arcs, Some(3), 2).is_err());
+
+    // the batches are deleted with the last iterator
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().to_owned();
+    let mut sorted = <SortPairs<()>>::new(2, &path)?;
+    for (src, dst) in arcs {
+        sorted.push(src, dst, ())?;
+    }
+    let iter = SortedArcs::dedup(sorted.batches()?, dir);
+    let clone = iter.clone();
+    drop(iter);
+    assert!(path.exists());
+    assert_eq!(clone.count(), 4);
+    assert!(!path.exists());
+    Ok(())
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "mmap")]
+
 use std::{fs::File, io::BufWriter};
 use tempfile::NamedTempFile;
 
@@ -69,3 +69,5 @@ impl_fuzz_repr!(
     fuzz_bvcomp_and_read_zip,
     bvcomp_and_read
 );
+
+impl_fuzz_repr!(fuzz_random_access, fuzz_random_access_zip, random_access);
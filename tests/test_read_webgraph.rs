@@ -73,3 +73,51 @@ fn test_sequential_reading() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_load_seq_from_read() -> Result<()> {
+    let graph = webgraph::graph::bvgraph::load_seq("tests/data/cnr-2000")?;
+    let file = std::io::BufReader::new(std::fs::File::open("tests/data/cnr-2000.graph")?);
+    let stream_graph = webgraph::graph::bvgraph::load_seq_from_read("tests/data/cnr-2000", file)?;
+    assert_eq!(graph.num_nodes(), stream_graph.num_nodes());
+
+    let mut iter = graph.iter_nodes();
+    for (node_id, succ) in stream_graph.iter_nodes() {
+        let (true_node_id, true_succ) = iter.next().unwrap();
+        assert_eq!(node_id, true_node_id);
+        assert_eq!(succ.collect::<Vec<_>>(), true_succ.collect::<Vec<_>>());
+    }
+    assert!(iter.next().is_none());
+    Ok(())
+}
+
+#[test]
+fn test_load_seq_file() -> Result<()> {
+    let graph = webgraph::graph::bvgraph::load_seq("tests/data/cnr-2000")?;
+    let file_graph = webgraph::graph::bvgraph::load_seq_file("tests/data/cnr-2000")?;
+    assert_eq!(graph.num_nodes(), file_graph.num_nodes());
+    assert_eq!(graph.num_arcs_hint(), file_graph.num_arcs_hint());
+    // the file is opened again at each iteration
+    for _ in 0..2 {
+        let mut iter = graph.iter_nodes();
+        for (node_id, succ) in file_graph.iter_nodes() {
+            let (true_node_id, true_succ) = iter.next().unwrap();
+            assert_eq!(node_id, true_node_id);
+            assert_eq!(succ.collect::<Vec<_>>(), true_succ.collect::<Vec<_>>());
+        }
+        assert!(iter.next().is_none());
+    }
+
+    // readers can start at any bit offset
+    let builder = file_graph.get_codes_reader_builder();
+    let mut iter = graph.iter_nodes();
+    let mut offset = 0;
+    while let Some((_, succ)) = iter.next() {
+        let mut reader = builder.get_reader(offset)?;
+        assert_eq!(reader.read_outdegree() as usize, succ.count());
+        offset = iter.get_pos();
+    }
+
+    assert!(webgraph::graph::bvgraph::load_seq_file_le("tests/data/cnr-2000").is_err());
+    Ok(())
+}